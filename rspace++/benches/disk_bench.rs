@@ -0,0 +1,96 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rspace_plus_plus::keyvaluestore::{Durability, KeyValueStore, Storage};
+use rspace_plus_plus::lmdbstore::LmdbStore;
+#[cfg(feature = "redb")]
+use rspace_plus_plus::redbstore::RedbStore;
+use rspace_plus_plus::rtypes::rtypes::{Commit, Entry, Retrieve};
+use rspace_plus_plus::setup::Setup;
+use rspace_plus_plus::tuplespace::{self, TupleSpace};
+use std::path::Path;
+use std::time::Duration;
+
+// Number of unrelated channels already holding data before measuring. LMDB opens
+// with the default 1 MiB map, which fills up after about 2_000 entries
+const STORE_SIZES: [usize; 3] = [0, 100, 1_000];
+
+fn filler_retrieve(i: usize) -> Retrieve {
+    let entry = Entry {
+        email: format!("filler-{}@example.org", i),
+        ..Default::default()
+    };
+    Setup::create_retrieve(format!("filler-{}", i), entry, String::from("filler"))
+}
+
+// Every produce and consume commits on its own, so this measures durable commits
+fn bench_store<S: Storage>(c: &mut Criterion, name: &str, space: TupleSpace<S, Retrieve, Commit>) {
+    let setup = Setup::new();
+    let mut group = c.benchmark_group(format!("{} produce/consume on one channel", name));
+    let _ = space.clear();
+
+    let mut filled = 0;
+    for size in STORE_SIZES {
+        let mut txn = space.write_txn().unwrap();
+        for i in filled..size {
+            tuplespace::produce_in_txn(&mut txn, &filler_retrieve(i), false, &mut vec![]).unwrap();
+        }
+        txn.commit().unwrap();
+        filled = size;
+
+        let retrieve = Setup::create_retrieve(
+            String::from("friends"),
+            setup.alice.clone(),
+            Setup::get_city_field(setup.alice.clone()),
+        );
+        let commit = Setup::create_commit(
+            vec![String::from("friends")],
+            vec![setup.city_match_case.clone()],
+            String::from("I am the continuation, for now..."),
+        );
+
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            b.iter(|| {
+                black_box(space.produce(retrieve.clone(), false).unwrap());
+                black_box(space.consume(commit.clone(), false).unwrap());
+            })
+        });
+    }
+
+    group.finish();
+    let _ = space.clear();
+}
+
+const GROUP_COMMIT: Durability = Durability::GroupCommit {
+    ops: 100,
+    interval: Duration::from_millis(10),
+};
+
+fn bench_lmdb(c: &mut Criterion) {
+    let path = Path::new("target").join("DiskBenchLmdb");
+    for (name, durability) in [
+        ("lmdb", Durability::Sync),
+        ("lmdb group commit", GROUP_COMMIT),
+        ("lmdb no-sync", Durability::NoSync),
+    ] {
+        let store = LmdbStore::open_with_durability(&path, durability).unwrap();
+        bench_store(c, name, TupleSpace::new(store));
+    }
+}
+
+#[cfg(feature = "redb")]
+fn bench_redb(c: &mut Criterion) {
+    let path = Path::new("target").join("DiskBenchRedb");
+    for (name, durability) in [
+        ("redb", Durability::Sync),
+        ("redb group commit", GROUP_COMMIT),
+        ("redb no-sync", Durability::NoSync),
+    ] {
+        let store = RedbStore::open_with_durability(&path, durability).unwrap();
+        bench_store(c, name, TupleSpace::new(store));
+    }
+}
+
+#[cfg(not(feature = "redb"))]
+criterion_group!(benches, bench_lmdb);
+#[cfg(feature = "redb")]
+criterion_group!(benches, bench_lmdb, bench_redb);
+criterion_main!(benches);
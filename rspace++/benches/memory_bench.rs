@@ -0,0 +1,99 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rspace_plus_plus::memorystore::MemStore;
+use rspace_plus_plus::rtypes::rtypes::{Commit, Entry, Retrieve};
+use rspace_plus_plus::setup::Setup;
+use rspace_plus_plus::tuplespace::TupleSpace;
+use std::thread;
+use std::time::Instant;
+
+// Number of unrelated channels already holding data before measuring
+const STORE_SIZES: [usize; 4] = [0, 10_000, 100_000, 1_000_000];
+
+fn filler_retrieve(i: usize) -> Retrieve {
+    let entry = Entry {
+        email: format!("filler-{}@example.org", i),
+        ..Default::default()
+    };
+    Setup::create_retrieve(format!("filler-{}", i), entry, String::from("filler"))
+}
+
+fn bench_memseq(c: &mut Criterion) {
+    let setup = Setup::new();
+    let mut group = c.benchmark_group("memseq produce/consume on one channel");
+
+    for size in STORE_SIZES {
+        let memseq = TupleSpace::<MemStore, Retrieve, Commit>::new(MemStore::new());
+        for i in 0..size {
+            memseq.produce(filler_retrieve(i), false).unwrap();
+        }
+
+        let retrieve = Setup::create_retrieve(
+            String::from("friends"),
+            setup.alice.clone(),
+            Setup::get_city_field(setup.alice.clone()),
+        );
+        let commit = Setup::create_commit(
+            vec![String::from("friends")],
+            vec![setup.city_match_case.clone()],
+            String::from("I am the continuation, for now..."),
+        );
+
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            b.iter(|| {
+                black_box(memseq.produce(retrieve.clone(), false).unwrap());
+                black_box(memseq.consume(commit.clone(), false).unwrap());
+            })
+        });
+    }
+
+    group.finish();
+}
+
+// Threads producing and consuming on the same channel at once
+const THREADS: usize = 4;
+
+fn bench_memconc(c: &mut Criterion) {
+    let setup = Setup::new();
+    let mut group = c.benchmark_group("memconc produce/consume on one channel from 4 threads");
+
+    for size in STORE_SIZES {
+        let memconc = TupleSpace::<MemStore, Retrieve, Commit>::new(MemStore::concurrent());
+        for i in 0..size {
+            memconc.produce(filler_retrieve(i), false).unwrap();
+        }
+
+        let retrieve = Setup::create_retrieve(
+            String::from("friends"),
+            setup.alice.clone(),
+            Setup::get_city_field(setup.alice.clone()),
+        );
+        let commit = Setup::create_commit(
+            vec![String::from("friends")],
+            vec![setup.city_match_case.clone()],
+            String::from("I am the continuation, for now..."),
+        );
+
+        // each iteration is one produce and one consume per thread
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            b.iter_custom(|iters| {
+                let start = Instant::now();
+                thread::scope(|scope| {
+                    for _ in 0..THREADS {
+                        scope.spawn(|| {
+                            for _ in 0..iters {
+                                black_box(memconc.produce(retrieve.clone(), false).unwrap());
+                                black_box(memconc.consume(commit.clone(), false).unwrap());
+                            }
+                        });
+                    }
+                });
+                start.elapsed()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_memseq, bench_memconc);
+criterion_main!(benches);
@@ -6,7 +6,53 @@ fn main() {
     let mut prost_build = prost_build::Config::new();
     prost_build.message_attribute(".", "#[derive(Hash, serde::Serialize, serde::Deserialize)]");
     prost_build.message_attribute(".", "#[repr(C)]");
+    // oneofs are enums to prost, and its proto enums already derive Hash
+    // so New, and every Par holding one, can derive Hash
+    prost_build.btree_map(["New.injections"]);
+    // a ParWithRandom would make every TaggedContinuation as large as itself
+    prost_build.boxed("TaggedContinuation.tagged_cont.par_body");
+    for oneof in [
+        "ChannelChange.change",
+        "TaggedContinuation.tagged_cont",
+        "Var.var_instance",
+        "Expr.expr_instance",
+        "Connective.connective_instance",
+        "GUnforgeable.unf_instance",
+    ] {
+        prost_build.enum_attribute(
+            oneof,
+            "#[derive(Hash, serde::Serialize, serde::Deserialize)]",
+        );
+        prost_build.enum_attribute(oneof, "#[serde(rename_all = \"snake_case\")]");
+    }
+    compile(prost_build);
+}
+
+#[cfg(not(feature = "grpc"))]
+fn compile(mut prost_build: prost_build::Config) {
     prost_build
-        .compile_protos(&["src/main/protobuf/rtypes.proto"], &["src/"])
+        .compile_protos(
+            &[
+                "src/main/protobuf/rhotypes.proto",
+                "src/main/protobuf/rtypes.proto",
+            ],
+            &["src/main/protobuf/"],
+        )
+        .unwrap();
+}
+
+// The service shares the firefly package, so its messages and stubs land in rtypes too
+#[cfg(feature = "grpc")]
+fn compile(prost_build: prost_build::Config) {
+    tonic_build::configure()
+        .compile_with_config(
+            prost_build,
+            &[
+                "src/main/protobuf/rhotypes.proto",
+                "src/main/protobuf/rtypes.proto",
+                "src/main/protobuf/rspace_service.proto",
+            ],
+            &["src/main/protobuf/"],
+        )
         .unwrap();
 }
@@ -0,0 +1,243 @@
+#![allow(dead_code)]
+
+use crate::keyvaluestore::Storage;
+use crate::rtypes::rtypes::{Commit, OptionResult, Retrieve};
+use crate::tuplespace::{check_commit, TupleSpace};
+use std::collections::HashMap;
+use std::error::Error;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use std::task::{Context, Poll, Waker};
+
+/*
+Produce and consume for Tokio hosts. Each verb runs on tokio's blocking pool, so
+LMDB I/O never blocks the runtime. consume_waiting hands back a Fired future for
+a consume that found no match, which wakes once a produce fires its continuation,
+whether made through this facade, on the TupleSpace directly or in a Transaction,
+which wakes it when it commits.
+*/
+pub struct AsyncSpace<S: Storage> {
+    space: Arc<TupleSpace<S, Retrieve, Commit>>,
+    waiting: Arc<Waiting>,
+}
+
+impl<S: Storage> Clone for AsyncSpace<S> {
+    fn clone(&self) -> AsyncSpace<S> {
+        AsyncSpace {
+            space: self.space.clone(),
+            waiting: self.waiting.clone(),
+        }
+    }
+}
+
+impl<S> AsyncSpace<S>
+where
+    S: Storage + Send + Sync + 'static,
+{
+    pub fn new(space: TupleSpace<S, Retrieve, Commit>) -> AsyncSpace<S> {
+        let waiting = Arc::new(Waiting::default());
+        space.on_fired({
+            let waiting = waiting.clone();
+            move |key, result| waiting.fire(key, result)
+        });
+
+        AsyncSpace {
+            space: Arc::new(space),
+            waiting,
+        }
+    }
+
+    pub fn space(&self) -> &TupleSpace<S, Retrieve, Commit> {
+        &self.space
+    }
+
+    pub async fn produce(
+        &self,
+        retrieve: Retrieve,
+        persistent: bool,
+    ) -> Result<Option<OptionResult>, Box<dyn Error>> {
+        let space = self.space.clone();
+
+        blocking(move || {
+            space
+                .produce(retrieve, persistent)
+                .map_err(|err| err.to_string())
+        })
+        .await
+    }
+
+    pub async fn consume(
+        &self,
+        commit: Commit,
+        persistent: bool,
+    ) -> Result<Option<Vec<OptionResult>>, Box<dyn Error>> {
+        check_commit(&commit)?;
+        let space = self.space.clone();
+
+        blocking(move || {
+            space
+                .consume(commit, persistent)
+                .map_err(|err| err.to_string())
+        })
+        .await
+    }
+
+    /*
+    Consumes, and when nothing matches returns a future of what the stored
+    continuation gets once a produce fires it. A persistent continuation stays
+    stored but the future only sees its first firing. Dropping the future does not
+    withdraw the continuation.
+    */
+    pub async fn consume_waiting(
+        &self,
+        commit: Commit,
+        persistent: bool,
+    ) -> Result<Fired, Box<dyn Error>> {
+        check_commit(&commit)?;
+        let space = self.space.clone();
+        let waiting = self.waiting.clone();
+        let slot = Arc::new(Slot::default());
+
+        let consumed = blocking({
+            let slot = slot.clone();
+            move || {
+                // held while the keys go in, so a produce just after the commit waits for them
+                let mut waiters = waiting.lock();
+                let (results, keys) = space
+                    .consume_keyed(commit, persistent)
+                    .map_err(|err| err.to_string())?;
+                if results.is_none() {
+                    waiters.add(keys, &slot);
+                }
+                Ok(results)
+            }
+        })
+        .await?;
+        if let Some(results) = consumed {
+            slot.resolve(results);
+        }
+
+        Ok(Fired { slot })
+    }
+}
+
+// Runs f on tokio's blocking pool
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<T, Box<dyn Error>> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(Into::into)
+}
+
+/*
+Waiting consumes by the entry keys of their stored continuation, kept in memory
+only, so nothing about a waiter is stored and nothing stale outlives the process.
+A join waits on the key of every copy, whichever fires first resolves it and takes
+the others with it. The entry of a dropped future goes once its continuation fires.
+*/
+#[derive(Default)]
+struct Waiting {
+    waiters: Mutex<Waiters>,
+}
+
+#[derive(Default)]
+struct Waiters {
+    by_key: HashMap<Vec<u8>, u64>,
+    // each waiter's slot, with the keys it waits on
+    slots: HashMap<u64, (Weak<Slot>, Vec<Vec<u8>>)>,
+    next: u64,
+}
+
+impl Waiting {
+    fn lock(&self) -> MutexGuard<'_, Waiters> {
+        self.waiters.lock().unwrap()
+    }
+
+    fn fire(&self, key: &[u8], result: &OptionResult) {
+        let slot = self.lock().take(key);
+        if let Some(slot) = slot.and_then(|slot| slot.upgrade()) {
+            slot.resolve(vec![result.clone()]);
+        }
+    }
+}
+
+impl Waiters {
+    fn add(&mut self, keys: Vec<Vec<u8>>, slot: &Arc<Slot>) {
+        let waiter = self.next;
+        self.next += 1;
+        for key in &keys {
+            self.by_key.insert(key.clone(), waiter);
+        }
+        self.slots.insert(waiter, (Arc::downgrade(slot), keys));
+    }
+
+    // The slot waiting on key, no longer waiting on any of its keys
+    fn take(&mut self, key: &[u8]) -> Option<Weak<Slot>> {
+        let waiter = self.by_key.remove(key)?;
+        let (slot, keys) = self.slots.remove(&waiter)?;
+        for key in keys {
+            // a later waiter may have its continuation stored under the same key since
+            if self.by_key.get(&key) == Some(&waiter) {
+                self.by_key.remove(&key);
+            }
+        }
+        Some(slot)
+    }
+}
+
+#[derive(Default)]
+struct Slot {
+    state: Mutex<SlotState>,
+}
+
+#[derive(Default)]
+struct SlotState {
+    results: Option<Vec<OptionResult>>,
+    resolved: bool,
+    waker: Option<Waker>,
+}
+
+impl Slot {
+    // Only the first firing counts
+    fn resolve(&self, results: Vec<OptionResult>) {
+        let mut state = self.state.lock().unwrap();
+        if state.resolved {
+            return;
+        }
+        state.resolved = true;
+        state.results = Some(results);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+// What a waiting consume's continuation got, once it fired
+pub struct Fired {
+    slot: Arc<Slot>,
+}
+
+impl Fired {
+    // Whether it has fired, without waiting
+    pub fn is_fired(&self) -> bool {
+        self.slot.state.lock().unwrap().resolved
+    }
+}
+
+impl Future for Fired {
+    type Output = Vec<OptionResult>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Vec<OptionResult>> {
+        let mut state = self.slot.state.lock().unwrap();
+        match state.results.take() {
+            Some(results) => Poll::Ready(results),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use rspace_plus_plus::cli::{Format, Session};
+use rspace_plus_plus::dump::{dump, restore};
+use rspace_plus_plus::keyvaluestore::Storage;
+use rspace_plus_plus::lmdbstore::LmdbStore;
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, IsTerminal, Write};
+use std::path::PathBuf;
+use std::process;
+
+const USAGE: &str = "\
+usage: rspace-cli [--json] (<data dir> | --memory) [--script <file>]
+       rspace-cli dump <data dir> [--out <file>]
+       rspace-cli restore <data dir> [--in <file>]
+
+Opens the LMDB store in <data dir>, with its checkpoint history in <data dir>/history,
+or an empty one in memory. Commands come from the script, or from stdin. Type help
+for the commands.
+
+dump opens the store read-only and writes every datum and continuation as a JSON
+line, to the file or stdout. restore loads such lines, from the file or stdin, into
+a new or empty store.";
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let mut format = Format::Human;
+    let mut memory = false;
+    let mut dir: Option<PathBuf> = None;
+    let mut script: Option<PathBuf> = None;
+
+    let mut args = std::env::args().skip(1).peekable();
+    match args.peek().map(String::as_str) {
+        Some("dump") | Some("restore") => return offline(args),
+        _ => {}
+    }
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => format = Format::Json,
+            "--memory" => memory = true,
+            "--script" => script = Some(args.next().ok_or(USAGE)?.into()),
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            _ if arg.starts_with('-') || dir.is_some() => return Err(USAGE.into()),
+            _ => dir = Some(arg.into()),
+        }
+    }
+
+    match (dir, memory) {
+        (Some(dir), false) => drive(&Session::open(&dir, format)?, script),
+        (None, true) => drive(&Session::in_memory(format), script),
+        _ => Err(USAGE.into()),
+    }
+}
+
+fn drive<S: Storage, H: Storage>(
+    session: &Session<S, H>,
+    script: Option<PathBuf>,
+) -> Result<(), Box<dyn Error>> {
+    let mut stdout = io::stdout();
+
+    match script {
+        Some(script) => session.run(BufReader::new(File::open(script)?), &mut stdout, false),
+        None => {
+            let interactive = io::stdin().is_terminal();
+            session.run(io::stdin().lock(), &mut stdout, interactive)
+        }
+    }
+}
+
+fn offline(mut args: impl Iterator<Item = String>) -> Result<(), Box<dyn Error>> {
+    let command = args.next().ok_or(USAGE)?;
+    let dir: PathBuf = args.next().ok_or(USAGE)?.into();
+    let file: Option<PathBuf> = match (command.as_str(), args.next().as_deref()) {
+        ("dump", Some("--out")) | ("restore", Some("--in")) => {
+            Some(args.next().ok_or(USAGE)?.into())
+        }
+        (_, None) => None,
+        _ => return Err(USAGE.into()),
+    };
+    if args.next().is_some() {
+        return Err(USAGE.into());
+    }
+
+    if command == "dump" {
+        let store = LmdbStore::open_read_only(&dir)?;
+        let output: Box<dyn Write> = match file {
+            Some(file) => Box::new(File::create(file)?),
+            None => Box::new(io::stdout().lock()),
+        };
+        let lines = dump(&store, &mut BufWriter::new(output))?;
+        eprintln!("dumped {} entries", lines);
+    } else {
+        let store = LmdbStore::open(&dir)?;
+        let input: Box<dyn BufRead> = match file {
+            Some(file) => Box::new(BufReader::new(File::open(file)?)),
+            None => Box::new(io::stdin().lock()),
+        };
+        let restored = restore(&store, input)?;
+        eprintln!("restored {} entries", restored);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use rspace_plus_plus::grpc::RSpaceServer;
+use rspace_plus_plus::history::HistoryRepository;
+use rspace_plus_plus::lmdbstore::LmdbStore;
+use rspace_plus_plus::memorystore::MemStore;
+use rspace_plus_plus::tuplespace::TupleSpace;
+use std::error::Error;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::process;
+
+const USAGE: &str = "\
+usage: rspace-server (<data dir> | --memory) [--listen <addr>]
+
+Serves RSpaceService from rspace_service.proto over gRPC at <addr>, 127.0.0.1:50051
+by default, until interrupted. The store is the LMDB one in <data dir>, with its
+checkpoint history in <data dir>/history, or an empty one in memory.";
+
+const DEFAULT_LISTEN: &str = "127.0.0.1:50051";
+
+#[tokio::main]
+async fn main() {
+    if let Err(err) = run().await {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
+    let mut memory = false;
+    let mut dir: Option<PathBuf> = None;
+    let mut listen = String::from(DEFAULT_LISTEN);
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--memory" => memory = true,
+            "--listen" => listen = args.next().ok_or(USAGE)?,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            _ if arg.starts_with('-') || dir.is_some() => return Err(USAGE.into()),
+            _ => dir = Some(arg.into()),
+        }
+    }
+    if dir.is_some() == memory {
+        return Err(USAGE.into());
+    }
+    let addr: SocketAddr = listen
+        .parse()
+        .map_err(|err| format!("--listen {}: {}", listen, err))?;
+    let shutdown = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+
+    eprintln!("listening on {}", addr);
+    match dir {
+        Some(dir) => {
+            RSpaceServer::new(
+                TupleSpace::new(LmdbStore::open(&dir)?),
+                HistoryRepository::new(LmdbStore::open(&dir.join("history"))?),
+            )
+            .serve(addr, shutdown)
+            .await
+        }
+        None => {
+            RSpaceServer::new(
+                TupleSpace::new(MemStore::new()),
+                HistoryRepository::new(MemStore::new()),
+            )
+            .serve(addr, shutdown)
+            .await
+        }
+    }
+}
@@ -0,0 +1,454 @@
+#![allow(dead_code)]
+
+use crate::history::{from_hex, to_hex, HistoryRepository};
+use crate::hotstore::HistoryStore;
+use crate::keyvaluestore::Storage;
+use crate::lmdbstore::LmdbStore;
+use crate::memorystore::MemStore;
+use crate::rtypes::rtypes::{Address, Commit, Entry, Name, Retrieve};
+use crate::tuplespace::TupleSpace;
+use serde_json::{json, Value};
+use std::error::Error;
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+pub const HELP: &str = "\
+produce <channel> <match case> <entry json> [--persist]   store a datum, or fire the continuation it matches
+consume <channels> <patterns> <continuation> [--persist]  channels and patterns are comma separated
+peek <channel>                                            data and continuations stored at a channel
+dump                                                      everything stored, by channel set
+channels                                                  channels with anything stored
+checkpoint                                                record the current state, prints its root
+reset <root>                                              go back to a checkpoint
+stats                                                     counts and the current root
+verify [--repair]                                         check every key, fix what is wrong with --repair
+help
+quit";
+
+const COMMANDS: [&str; 10] = [
+    "produce",
+    "consume",
+    "peek",
+    "dump",
+    "channels",
+    "checkpoint",
+    "reset",
+    "stats",
+    "verify",
+    "help",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Human,
+    Json,
+}
+
+/*
+One space and its checkpoint history, driven by text commands from a REPL or a
+script. Every command answers with a JSON value, printed as it is or rendered for
+people. Entries are the ones Setup uses, given as JSON, fields left out stay empty.
+*/
+pub struct Session<S: Storage, H: Storage> {
+    space: TupleSpace<S, Retrieve, Commit>,
+    history: HistoryRepository<H>,
+    format: Format,
+}
+
+impl Session<LmdbStore, LmdbStore> {
+    // The space is the LMDB environment in dir, its history the one in dir/history
+    pub fn open(
+        dir: &Path,
+        format: Format,
+    ) -> Result<Session<LmdbStore, LmdbStore>, Box<dyn Error>> {
+        Ok(Session::new(
+            TupleSpace::new(LmdbStore::open(dir)?),
+            HistoryRepository::new(LmdbStore::open(&dir.join("history"))?),
+            format,
+        ))
+    }
+}
+
+impl Session<MemStore, MemStore> {
+    pub fn in_memory(format: Format) -> Session<MemStore, MemStore> {
+        Session::new(
+            TupleSpace::new(MemStore::new()),
+            HistoryRepository::new(MemStore::new()),
+            format,
+        )
+    }
+}
+
+impl<S: Storage, H: Storage> Session<S, H> {
+    pub fn new(
+        space: TupleSpace<S, Retrieve, Commit>,
+        history: HistoryRepository<H>,
+        format: Format,
+    ) -> Session<S, H> {
+        Session {
+            space,
+            history,
+            format,
+        }
+    }
+
+    /*
+    Runs commands until the input ends or says quit. Interactive runs prompt and
+    carry on after an error, scripts stop at the first one.
+    */
+    pub fn run(
+        &self,
+        input: impl BufRead,
+        output: &mut impl Write,
+        interactive: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        prompt(output, interactive)?;
+
+        for (number, line) in input.lines().enumerate() {
+            let line = line?;
+            if matches!(line.trim(), "quit" | "exit") {
+                break;
+            }
+
+            match self.execute(&line) {
+                Ok(Some(text)) => writeln!(output, "{}", text)?,
+                Ok(None) => {}
+                Err(err) if interactive => writeln!(output, "{}", self.error(err.as_ref()))?,
+                Err(err) => return Err(format!("line {}: {}", number + 1, err).into()),
+            }
+            prompt(output, interactive)?;
+        }
+
+        Ok(())
+    }
+
+    // What one line prints, none for blank lines and comments
+    pub fn execute(&self, line: &str) -> Result<Option<String>, Box<dyn Error>> {
+        if line.trim_start().starts_with('#') {
+            return Ok(None);
+        }
+        let words = split(line)?;
+        let (command, args) = match words.split_first() {
+            Some((command, args)) => (command.as_str(), args),
+            None => return Ok(None),
+        };
+        let (args, flag) = match command {
+            "produce" | "consume" => without_flag(args, "--persist"),
+            "verify" => without_flag(args, "--repair"),
+            _ => (args.to_vec(), false),
+        };
+
+        let value = match (command, args.as_slice()) {
+            ("produce", [channel, match_case, entry]) => {
+                self.produce(channel, match_case, entry, flag)?
+            }
+            ("consume", [channels, patterns, continuation]) => {
+                self.consume(channels, patterns, continuation, flag)?
+            }
+            ("peek", [channel]) => self.peek(channel)?,
+            ("dump", []) => self.dump()?,
+            ("channels", []) => json!(self.space.channels()?),
+            ("checkpoint", []) => {
+                let root = self.space.checkpoint(&self.history)?;
+                json!({ "root": to_hex(&root) })
+            }
+            ("reset", [root]) => {
+                let root = from_hex(root)?;
+                self.space.reset(&self.history, &root)?;
+                json!({ "root": to_hex(&root) })
+            }
+            ("stats", []) => self.stats()?,
+            ("verify", []) => self.verify(flag)?,
+            ("help", []) => return Ok(Some(HELP.to_string())),
+            (command, _) if COMMANDS.contains(&command) => {
+                return Err(format!("wrong arguments for {}, see help", command).into())
+            }
+            (command, _) => return Err(format!("unknown command {}, see help", command).into()),
+        };
+
+        Ok(Some(match self.format {
+            Format::Json => value.to_string(),
+            Format::Human => human(command, &value),
+        }))
+    }
+
+    pub fn error(&self, err: &dyn Error) -> String {
+        match self.format {
+            Format::Json => json!({ "error": err.to_string() }).to_string(),
+            Format::Human => format!("error: {}", err),
+        }
+    }
+
+    fn produce(
+        &self,
+        channel: &str,
+        match_case: &str,
+        entry: &str,
+        persistent: bool,
+    ) -> Result<Value, Box<dyn Error>> {
+        let retrieve = Retrieve {
+            chan: channel.to_string(),
+            data: Some(parse_entry(entry)?),
+            match_case: match_case.to_string(),
+            ..Default::default()
+        };
+
+        Ok(json!({ "matched": self.space.produce(retrieve, persistent)? }))
+    }
+
+    fn consume(
+        &self,
+        channels: &str,
+        patterns: &str,
+        continuation: &str,
+        persistent: bool,
+    ) -> Result<Value, Box<dyn Error>> {
+        let channels: Vec<String> = channels.split(',').map(String::from).collect();
+        let patterns: Vec<String> = patterns.split(',').map(String::from).collect();
+        if channels.len() != patterns.len() {
+            return Err("channel and pattern vectors are not equal length!".into());
+        }
+        let commit = Commit {
+            channels,
+            patterns,
+            continuation: Some(continuation.into()),
+            ..Default::default()
+        };
+
+        Ok(json!({ "matched": self.space.consume(commit, persistent)? }))
+    }
+
+    fn peek(&self, channel: &str) -> Result<Value, Box<dyn Error>> {
+        let entries = self.space.read_channel(channel)?;
+
+        Ok(json!({
+            "channel": channel,
+            "data": entries.data,
+            "continuations": entries.continuations,
+        }))
+    }
+
+    fn dump(&self) -> Result<Value, Box<dyn Error>> {
+        let rows: Vec<Value> = self
+            .space
+            .to_map()?
+            .into_iter()
+            .map(|(channels, entries)| {
+                json!({
+                    "channels": channels,
+                    "data": entries.data,
+                    "continuations": entries.continuations,
+                })
+            })
+            .collect();
+
+        Ok(json!(rows))
+    }
+
+    fn stats(&self) -> Result<Value, Box<dyn Error>> {
+        let channels = self.space.channels()?;
+        let (mut data, mut continuations) = (0, 0);
+        for channel in &channels {
+            let entries = self.space.read_channel(channel)?;
+            data += entries.data.len();
+            continuations += entries.continuations.len();
+        }
+
+        Ok(json!({
+            "channels": channels.len(),
+            "data": data,
+            "continuations": continuations,
+            "entries": self.space.entry_count()?,
+            "root": to_hex(&self.space.root()?),
+            "checkpoints": self.history.roots()?.len(),
+        }))
+    }
+
+    fn verify(&self, repair: bool) -> Result<Value, Box<dyn Error>> {
+        let report = self.space.verify(repair)?;
+        let problems: Vec<Value> = report
+            .problems
+            .iter()
+            .map(|problem| {
+                json!({
+                    "key": to_hex(&problem.key),
+                    "kind": problem.kind().as_str_name(),
+                    "detail": problem.detail,
+                    "repaired": problem.repaired,
+                })
+            })
+            .collect();
+
+        Ok(json!({ "keys": report.keys, "problems": problems }))
+    }
+}
+
+fn prompt(output: &mut impl Write, interactive: bool) -> Result<(), Box<dyn Error>> {
+    if interactive {
+        write!(output, "> ")?;
+        output.flush()?;
+    }
+    Ok(())
+}
+
+// Words separated by whitespace. Single quotes keep everything, double quotes allow \" and \\
+fn split(line: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut words = vec![];
+    let mut word: Option<String> = None;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err("unterminated ' quote".into()),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\')) => word.push(c),
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => return Err("unterminated \" quote".into()),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err("unterminated \" quote".into()),
+                    }
+                }
+            }
+            c if c.is_whitespace() => words.extend(word.take()),
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+
+    Ok(words)
+}
+
+fn without_flag(args: &[String], flag: &str) -> (Vec<String>, bool) {
+    let rest: Vec<String> = args.iter().filter(|arg| *arg != flag).cloned().collect();
+    let found = rest.len() != args.len();
+    (rest, found)
+}
+
+// Fields left out of the JSON keep their defaults
+fn parse_entry(json: &str) -> Result<Entry, Box<dyn Error>> {
+    let mut entry = serde_json::to_value(Entry {
+        name: Some(Name::default()),
+        address: Some(Address::default()),
+        ..Default::default()
+    })?;
+    merge(&mut entry, serde_json::from_str(json)?);
+
+    Ok(serde_json::from_value(entry)?)
+}
+
+fn merge(base: &mut Value, patch: Value) {
+    match (base, patch) {
+        (Value::Object(base), Value::Object(patch)) => {
+            for (key, value) in patch {
+                merge(base.entry(key).or_insert(Value::Null), value);
+            }
+        }
+        (base, patch) => *base = patch,
+    }
+}
+
+fn human(command: &str, value: &Value) -> String {
+    let mut lines = vec![];
+
+    match command {
+        "produce" | "consume" => match &value["matched"] {
+            Value::Null => lines.push(String::from("stored")),
+            Value::Array(results) => {
+                lines.extend(results.iter().map(|result| format!("matched {}", result)))
+            }
+            result => lines.push(format!("matched {}", result)),
+        },
+        "peek" => {
+            entry_lines(&mut lines, value, "");
+            if lines.is_empty() {
+                lines.push(String::from("nothing stored"));
+            }
+        }
+        "dump" => {
+            for row in value.as_array().into_iter().flatten() {
+                let channels: Vec<&str> = row["channels"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Value::as_str)
+                    .collect();
+                lines.push(channels.join(", "));
+                entry_lines(&mut lines, row, "  ");
+            }
+            if lines.is_empty() {
+                lines.push(String::from("nothing stored"));
+            }
+        }
+        "verify" => {
+            let problems = value["problems"].as_array().cloned().unwrap_or_default();
+            lines.push(format!(
+                "{} keys, {} problems",
+                value["keys"],
+                problems.len()
+            ));
+            for problem in problems {
+                lines.push(format!(
+                    "{} {}: {}{}",
+                    problem["kind"].as_str().unwrap_or_default(),
+                    problem["key"].as_str().unwrap_or_default(),
+                    problem["detail"].as_str().unwrap_or_default(),
+                    if problem["repaired"] == true {
+                        " (repaired)"
+                    } else {
+                        ""
+                    }
+                ));
+            }
+        }
+        "channels" => {
+            lines.extend(
+                value
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Value::as_str)
+                    .map(String::from),
+            );
+            if lines.is_empty() {
+                lines.push(String::from("no channels"));
+            }
+        }
+        _ => {
+            for (key, value) in value.as_object().into_iter().flatten() {
+                match value {
+                    Value::String(text) => lines.push(format!("{}: {}", key, text)),
+                    value => lines.push(format!("{}: {}", key, value)),
+                }
+            }
+        }
+    }
+
+    lines.join("\n")
+}
+
+fn entry_lines(lines: &mut Vec<String>, value: &Value, indent: &str) {
+    for datum in value["data"].as_array().into_iter().flatten() {
+        lines.push(format!("{}data {}", indent, datum));
+    }
+    for continuation in value["continuations"].as_array().into_iter().flatten() {
+        lines.push(format!("{}continuation {}", indent, continuation));
+    }
+}
@@ -0,0 +1,110 @@
+#![allow(dead_code)]
+
+use crate::keyvaluestore::{KeyValueStore, Storage};
+use crate::rtypes::rtypes::{CommitContinuation, RetrieveContinuation};
+use crate::tuplespace::{list_channels, put_channel, read_channel, ChannelEntries};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::io::{BufRead, Write};
+
+/*
+One stored datum or continuation, as a line of a dump. A continuation waiting on
+several channels is stored, and dumped, once per channel. persistent repeats the
+payload's flag so a dump can be grepped by it.
+*/
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DumpLine {
+    pub channel: String,
+    pub persistent: bool,
+    #[serde(flatten)]
+    pub entry: DumpEntry,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "payload", rename_all = "snake_case")]
+pub enum DumpEntry {
+    Data(RetrieveContinuation),
+    Continuation(CommitContinuation),
+}
+
+/*
+Writes every entry of the store as a JSON line, by channel, data before
+continuations, each in key order. Indexes are left out, restore rebuilds them.
+Returns the number of lines written.
+*/
+pub fn dump<S: Storage>(store: &S, output: &mut impl Write) -> Result<usize, Box<dyn Error>> {
+    let txn = store.read_txn()?;
+    let mut lines = 0;
+
+    for channel in list_channels(&txn)? {
+        let entries = read_channel(&txn, &channel)?;
+        let data = entries.data.into_iter().map(|datum| DumpLine {
+            channel: channel.clone(),
+            persistent: datum.persistent,
+            entry: DumpEntry::Data(datum),
+        });
+        let continuations = entries.continuations.into_iter().map(|k| DumpLine {
+            channel: channel.clone(),
+            persistent: k.persistent,
+            entry: DumpEntry::Continuation(k),
+        });
+
+        for line in data.chain(continuations) {
+            serde_json::to_writer(&mut *output, &line)?;
+            writeln!(output)?;
+            lines += 1;
+        }
+    }
+    output.flush()?;
+
+    Ok(lines)
+}
+
+/*
+Loads a dump into an empty store, in one transaction, so a bad line leaves the
+store empty. Blank lines are skipped. Returns the number of entries restored.
+*/
+pub fn restore<S: Storage>(store: &S, input: impl BufRead) -> Result<usize, Box<dyn Error>> {
+    if !store.is_empty()? {
+        return Err("can only restore into an empty store".into());
+    }
+
+    let mut txn = store.write_txn()?;
+    let mut restored = 0;
+
+    for (number, line) in input.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let line: DumpLine =
+            serde_json::from_str(&line).map_err(|err| format!("line {}: {}", number + 1, err))?;
+        restore_line(&mut txn, line).map_err(|err| format!("line {}: {}", number + 1, err))?;
+        restored += 1;
+    }
+    txn.commit()?;
+
+    Ok(restored)
+}
+
+fn restore_line(txn: &mut impl KeyValueStore, line: DumpLine) -> Result<(), Box<dyn Error>> {
+    let mut entries = ChannelEntries::default();
+    let persistent = match line.entry {
+        DumpEntry::Data(datum) => {
+            entries.data.push(datum);
+            entries.data[0].persistent
+        }
+        DumpEntry::Continuation(k) => {
+            if !k.channels.is_empty() && !k.channels.contains(&line.channel) {
+                return Err(format!("continuation does not wait on {}", line.channel).into());
+            }
+            entries.continuations.push(k);
+            entries.continuations[0].persistent
+        }
+    };
+    if persistent != line.persistent {
+        return Err("persistent does not match the payload".into());
+    }
+
+    put_channel(txn, &line.channel, &entries)
+}
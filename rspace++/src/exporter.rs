@@ -0,0 +1,190 @@
+#![allow(dead_code)]
+
+use crate::keyvaluestore::{KeyValueRead, Storage};
+use crate::rtypes::rtypes::{ExportChunk, KeyValue, ProofNode};
+use crate::trie::{leaf_hash, split_depth, Subtree, TrieBuilder};
+use blake2::digest::consts::U32;
+use blake2::{Blake2b, Digest};
+use std::error::Error;
+
+pub type Blake2b256Hash = [u8; 32];
+
+/*
+A store's root is the root of the radix trie over its key-value pairs, see trie.rs,
+so two stores holding the same pairs have the same root whatever wrote them. The
+empty store's root is the hash of nothing.
+See RSpaceExporter.scala in rspace/
+*/
+pub fn state_root(txn: &impl KeyValueRead) -> Result<Blake2b256Hash, Box<dyn Error>> {
+    let mut builder = TrieBuilder::new();
+    let mut last: Option<Vec<u8>> = None;
+    txn.iterate_prefix(&[], &mut |key, value| {
+        let depth = last.as_ref().map_or(0, |last| split_depth(last, key));
+        builder.push(leaf_hash(key, value), depth, &mut |_, _, _| {});
+        last = Some(key.to_vec());
+        Ok(true)
+    })?;
+
+    Ok(builder.finish(&mut |_, _, _| {}))
+}
+
+/*
+Serves chunks of one snapshot of a store, every node asking for the same chunk gets the same bytes.
+It keeps the read transaction it was made with, the first key of every chunk and
+the subtrees that prove each chunk, see ExportChunk in rtypes.proto. A chunk's pairs
+are read from storage when it is asked for.
+On the memory store the snapshot is the map's read lock, so commits to it wait until
+the exporter is dropped.
+*/
+pub struct RSpaceExporter<'a> {
+    txn: Box<dyn KeyValueRead + 'a>,
+    root: Blake2b256Hash,
+    total: usize,
+    starts: Vec<Vec<u8>>,
+    before: Vec<Vec<ProofNode>>,
+    after: Vec<Vec<ProofNode>>,
+    chunk_size: usize,
+}
+
+impl<'a> RSpaceExporter<'a> {
+    pub fn new<S: Storage>(
+        store: &'a S,
+        chunk_size: usize,
+    ) -> Result<RSpaceExporter<'a>, Box<dyn Error>> {
+        if chunk_size == 0 {
+            return Err("chunk size must be positive".into());
+        }
+
+        let txn = store.read_txn()?;
+        let mut starts = vec![];
+        let mut before = vec![];
+        let mut after = vec![];
+        let mut on_branch = |depth, left: &Subtree, right: &Subtree| {
+            prove(chunk_size, depth, left, right, &mut before, &mut after)
+        };
+        let mut builder = TrieBuilder::new();
+        let mut last: Option<Vec<u8>> = None;
+        let mut total = 0;
+        txn.iterate_prefix(&[], &mut |key, value| {
+            if total % chunk_size == 0 {
+                starts.push(key.to_vec());
+            }
+            let depth = last.as_ref().map_or(0, |last| split_depth(last, key));
+            builder.push(leaf_hash(key, value), depth, &mut on_branch);
+            last = Some(key.to_vec());
+            total += 1;
+            Ok(true)
+        })?;
+        let root = builder.finish(&mut on_branch);
+
+        before.resize(starts.len(), vec![]);
+        after.resize(starts.len(), vec![]);
+        // recorded as the branches were made, innermost first
+        for nodes in &mut before {
+            nodes.reverse();
+        }
+
+        Ok(RSpaceExporter {
+            txn: Box::new(txn),
+            root,
+            total,
+            starts,
+            before,
+            after,
+            chunk_size,
+        })
+    }
+
+    pub fn root(&self) -> Blake2b256Hash {
+        self.root
+    }
+
+    pub fn chunk_count(&self) -> usize {
+        self.starts.len()
+    }
+
+    pub fn chunk(&self, index: usize) -> Result<Option<ExportChunk>, Box<dyn Error>> {
+        let Some(first) = self.starts.get(index) else {
+            return Ok(None);
+        };
+        let start = index * self.chunk_size;
+        let len = self.chunk_size.min(self.total - start);
+
+        let mut pairs = Vec::with_capacity(len);
+        self.txn.iterate_from(first, &mut |key, value| {
+            pairs.push(KeyValue {
+                key: key.to_vec(),
+                value: value.to_vec(),
+            });
+            Ok(pairs.len() < len)
+        })?;
+        if pairs.len() != len {
+            return Err("store changed under the exporter's snapshot".into());
+        }
+
+        Ok(Some(ExportChunk {
+            root: self.root.to_vec(),
+            total: self.total as u64,
+            start: start as u64,
+            pairs,
+            before: self.before[index].clone(),
+            after: self.after[index].clone(),
+        }))
+    }
+
+    pub fn chunks(&self) -> impl Iterator<Item = Result<ExportChunk, Box<dyn Error>>> + '_ {
+        (0..self.chunk_count()).filter_map(|index| self.chunk(index).transpose())
+    }
+}
+
+/*
+A branch joining the leaves [left.start, left.end) and [left.end, right.end) proves
+the chunks that start inside right with left, and the chunks that end inside left,
+or just at its end, with right. See verify_range in importer.rs
+*/
+fn prove(
+    chunk_size: usize,
+    depth: u64,
+    left: &Subtree,
+    right: &Subtree,
+    before: &mut Vec<Vec<ProofNode>>,
+    after: &mut Vec<Vec<ProofNode>>,
+) {
+    let proof = |subtree: &Subtree| ProofNode {
+        hash: subtree.hash.to_vec(),
+        depth,
+    };
+
+    // chunks are cut at every multiple of chunk_size, chunk n starts at the nth cut
+    for cut in left.end.div_ceil(chunk_size)..right.end.div_ceil(chunk_size) {
+        grow(before, cut).push(proof(left));
+    }
+    for cut in (left.start + 1).div_ceil(chunk_size)..=left.end / chunk_size {
+        grow(after, cut - 1).push(proof(right));
+    }
+}
+
+fn grow(proofs: &mut Vec<Vec<ProofNode>>, index: usize) -> &mut Vec<ProofNode> {
+    if proofs.len() <= index {
+        proofs.resize(index + 1, vec![]);
+    }
+    &mut proofs[index]
+}
+
+// Every pair the store holds, in key order
+pub(crate) fn read_pairs(txn: &impl KeyValueRead) -> Result<Vec<KeyValue>, Box<dyn Error>> {
+    let mut pairs = vec![];
+    txn.iterate_prefix(&[], &mut |key, value| {
+        pairs.push(KeyValue {
+            key: key.to_vec(),
+            value: value.to_vec(),
+        });
+        Ok(true)
+    })?;
+
+    Ok(pairs)
+}
+
+pub(crate) fn hash(bytes: &[u8]) -> Blake2b256Hash {
+    Blake2b::<U32>::digest(bytes).into()
+}
@@ -0,0 +1,166 @@
+#![allow(dead_code)]
+
+use crate::exporter::{hash, Blake2b256Hash};
+use crate::history::{
+    from_node_key, read_node, to_hash, to_hex, HistoryRepository, NODE_PREFIX, ROOT_PREFIX,
+};
+use crate::keyvaluestore::{KeyValueRead, KeyValueStore, Storage};
+use crate::trie::Node;
+use std::collections::HashSet;
+use std::error::Error;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GcProgress {
+    Marking { reachable: usize },
+    Sweeping { remaining: usize },
+    Done { reclaimed: usize },
+}
+
+/*
+Reclaims the history nodes no live root reaches, along with the records of roots
+that are not live. Work is done in steps of at most budget nodes, each step in its
+own short transaction on the history store, so checkpoints can go on in between and
+produce/consume, which never touch history, are not held up at all.
+Nodes checkpointed after the collection started are never reclaimed by it.
+*/
+pub struct GarbageCollector<'a, S: Storage> {
+    history: &'a HistoryRepository<S>,
+    live: HashSet<Blake2b256Hash>,
+    to_visit: Vec<Blake2b256Hash>,
+    reachable: HashSet<Blake2b256Hash>,
+    // Keys to delete, known once marking is done
+    to_sweep: Option<Vec<Vec<u8>>>,
+    reclaimed: usize,
+}
+
+impl<'a, S: Storage> GarbageCollector<'a, S> {
+    // Fails if a live root is not in history, or another collection is running
+    pub fn new(
+        history: &'a HistoryRepository<S>,
+        live: &[Blake2b256Hash],
+    ) -> Result<GarbageCollector<'a, S>, Box<dyn Error>> {
+        for root in live {
+            if !history.contains(root)? {
+                return Err(format!("live root {} is not in history", to_hex(root)).into());
+            }
+        }
+
+        let mut written = history.written()?;
+        if written.is_some() {
+            return Err("a collection is already running".into());
+        }
+        *written = Some(HashSet::new());
+
+        Ok(GarbageCollector {
+            history,
+            live: live.iter().copied().collect(),
+            to_visit: live
+                .iter()
+                .filter(|root| **root != hash(&[]))
+                .copied()
+                .collect(),
+            reachable: HashSet::new(),
+            to_sweep: None,
+            reclaimed: 0,
+        })
+    }
+
+    pub fn step(&mut self, budget: usize) -> Result<GcProgress, Box<dyn Error>> {
+        if budget == 0 {
+            return Err("budget must be positive".into());
+        }
+
+        match self.to_sweep {
+            None => self.mark(budget),
+            Some(_) => self.sweep(budget),
+        }
+    }
+
+    // Steps until done, returns how many nodes were reclaimed
+    pub fn run(mut self, budget: usize) -> Result<usize, Box<dyn Error>> {
+        loop {
+            if let GcProgress::Done { reclaimed } = self.step(budget)? {
+                return Ok(reclaimed);
+            }
+        }
+    }
+
+    fn mark(&mut self, budget: usize) -> Result<GcProgress, Box<dyn Error>> {
+        let txn = self.history.store().read_txn()?;
+
+        for _ in 0..budget {
+            let node_hash = match self.to_visit.pop() {
+                Some(node_hash) => node_hash,
+                None => break,
+            };
+            if !self.reachable.insert(node_hash) {
+                continue;
+            }
+            if let Node::Branch(_, left, right) = read_node(&txn, &node_hash)? {
+                self.to_visit.push(right);
+                self.to_visit.push(left);
+            }
+        }
+
+        if !self.to_visit.is_empty() {
+            return Ok(GcProgress::Marking {
+                reachable: self.reachable.len(),
+            });
+        }
+
+        let mut to_sweep = vec![];
+        txn.iterate_prefix(NODE_PREFIX, &mut |key, _| {
+            if !self.reachable.contains(&from_node_key(key)?) {
+                to_sweep.push(key.to_vec());
+            }
+            Ok(true)
+        })?;
+        txn.iterate_prefix(ROOT_PREFIX, &mut |key, value| {
+            if !self.live.contains(&to_hash(value)?) {
+                to_sweep.push(key.to_vec());
+            }
+            Ok(true)
+        })?;
+        let remaining = to_sweep.len();
+        self.to_sweep = Some(to_sweep);
+
+        Ok(GcProgress::Sweeping { remaining })
+    }
+
+    fn sweep(&mut self, budget: usize) -> Result<GcProgress, Box<dyn Error>> {
+        let to_sweep = self.to_sweep.get_or_insert_with(Vec::new);
+        let batch = to_sweep.split_off(to_sweep.len().saturating_sub(budget));
+
+        let mut wtxn = self.history.store().write_txn()?;
+        {
+            // a checkpoint adds to written before it writes, and writers take turns
+            let written = self.history.written()?;
+            let written = written.as_ref().ok_or("the collection is not registered")?;
+            for key in batch {
+                if key.starts_with(NODE_PREFIX) {
+                    if written.contains(&from_node_key(&key)?) {
+                        continue;
+                    }
+                    self.reclaimed += 1;
+                }
+                wtxn.delete(&key)?;
+            }
+        }
+        wtxn.commit()?;
+
+        Ok(match self.to_sweep.as_ref().map_or(0, Vec::len) {
+            0 => GcProgress::Done {
+                reclaimed: self.reclaimed,
+            },
+            remaining => GcProgress::Sweeping { remaining },
+        })
+    }
+}
+
+impl<S: Storage> Drop for GarbageCollector<'_, S> {
+    fn drop(&mut self) {
+        if let Ok(mut written) = self.history.written() {
+            *written = None;
+        }
+    }
+}
@@ -0,0 +1,274 @@
+#![allow(dead_code)]
+// every handler returns tonic's Status, which is large
+#![allow(clippy::result_large_err)]
+
+use crate::history::{to_hash, to_hex, HistoryRepository};
+use crate::hotstore::HistoryStore;
+use crate::keyvaluestore::Storage;
+use crate::rtypes::rtypes::r_space_service_server::{RSpaceService, RSpaceServiceServer};
+use crate::rtypes::rtypes::{
+    CheckpointRequest, CheckpointResponse, Commit, ConsumeRequest, ConsumeResponse, InstallRequest,
+    InstallResponse, PeekRequest, PeekResponse, ProduceRequest, ProduceResponse, ResetRequest,
+    ResetResponse, Retrieve,
+};
+use crate::tuplespace::{check_commit, TupleSpace};
+use std::error::Error;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
+use tonic::transport::Server;
+use tonic::{Request, Response, Status, Streaming};
+
+// One streamed request applied to the space
+type Apply<S, Req, Res> = fn(&TupleSpace<S, Retrieve, Commit>, Req) -> Result<Res, Status>;
+
+// How many streamed responses may wait for a slow client before the stream stops reading
+const STREAM_BUFFER: usize = 64;
+
+/*
+Serves one space and its checkpoint history as RSpaceService, see
+rspace_service.proto. Every call runs on tokio's blocking pool, the space does
+its own locking.
+*/
+pub struct RSpaceServer<S: Storage, H: Storage> {
+    space: Arc<TupleSpace<S, Retrieve, Commit>>,
+    history: Arc<HistoryRepository<H>>,
+}
+
+impl<S, H> RSpaceServer<S, H>
+where
+    S: Storage + Send + Sync + 'static,
+    H: Storage + Send + Sync + 'static,
+{
+    pub fn new(
+        space: TupleSpace<S, Retrieve, Commit>,
+        history: HistoryRepository<H>,
+    ) -> RSpaceServer<S, H> {
+        RSpaceServer {
+            space: Arc::new(space),
+            history: Arc::new(history),
+        }
+    }
+
+    pub fn into_service(self) -> RSpaceServiceServer<RSpaceServer<S, H>> {
+        RSpaceServiceServer::new(self)
+    }
+
+    // Serves at addr until shutdown resolves
+    pub async fn serve(
+        self,
+        addr: SocketAddr,
+        shutdown: impl std::future::Future<Output = ()>,
+    ) -> Result<(), Box<dyn Error>> {
+        Server::builder()
+            .add_service(self.into_service())
+            .serve_with_shutdown(addr, shutdown)
+            .await?;
+
+        Ok(())
+    }
+
+    // Runs f against the space on the blocking pool
+    async fn blocking<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&TupleSpace<S, Retrieve, Commit>, &HistoryRepository<H>) -> Result<T, Status>
+            + Send
+            + 'static,
+    ) -> Result<T, Status> {
+        let (space, history) = (self.space.clone(), self.history.clone());
+        tokio::task::spawn_blocking(move || f(&space, &history))
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?
+    }
+
+    /*
+    Applies each request of an inbound stream in turn and streams back its
+    response. The stream ends with the first error, in the request or in applying it.
+    */
+    fn respond_to_stream<Req, Res>(
+        &self,
+        mut inbound: Streaming<Req>,
+        apply: Apply<S, Req, Res>,
+    ) -> ReceiverStream<Result<Res, Status>>
+    where
+        Req: Send + 'static,
+        Res: Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel(STREAM_BUFFER);
+        let space = self.space.clone();
+
+        tokio::spawn(async move {
+            while let Some(request) = inbound.next().await {
+                let response = match request {
+                    Ok(request) => {
+                        let space = space.clone();
+                        tokio::task::spawn_blocking(move || apply(&space, request))
+                            .await
+                            .unwrap_or_else(|err| Err(Status::internal(err.to_string())))
+                    }
+                    Err(status) => Err(status),
+                };
+                let failed = response.is_err();
+                if sender.send(response).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        ReceiverStream::new(receiver)
+    }
+}
+
+fn produce<S: Storage>(
+    space: &TupleSpace<S, Retrieve, Commit>,
+    request: ProduceRequest,
+) -> Result<ProduceResponse, Status> {
+    let retrieve = request
+        .retrieve
+        .ok_or_else(|| Status::invalid_argument("retrieve is missing"))?;
+
+    Ok(ProduceResponse {
+        result: space
+            .produce(retrieve, request.persistent)
+            .map_err(internal)?,
+    })
+}
+
+fn consume<S: Storage>(
+    space: &TupleSpace<S, Retrieve, Commit>,
+    request: ConsumeRequest,
+) -> Result<ConsumeResponse, Status> {
+    let commit = request
+        .commit
+        .ok_or_else(|| Status::invalid_argument("commit is missing"))?;
+    check_commit(&commit).map_err(|err| Status::invalid_argument(err.to_string()))?;
+
+    Ok(ConsumeResponse {
+        results: space
+            .consume(commit, request.persistent)
+            .map_err(internal)?
+            .unwrap_or_default(),
+    })
+}
+
+fn internal(err: Box<dyn Error>) -> Status {
+    Status::internal(err.to_string())
+}
+
+#[tonic::async_trait]
+impl<S, H> RSpaceService for RSpaceServer<S, H>
+where
+    S: Storage + Send + Sync + 'static,
+    H: Storage + Send + Sync + 'static,
+{
+    async fn produce(
+        &self,
+        request: Request<ProduceRequest>,
+    ) -> Result<Response<ProduceResponse>, Status> {
+        let request = request.into_inner();
+        let response = self
+            .blocking(move |space, _| produce(space, request))
+            .await?;
+
+        Ok(Response::new(response))
+    }
+
+    async fn consume(
+        &self,
+        request: Request<ConsumeRequest>,
+    ) -> Result<Response<ConsumeResponse>, Status> {
+        let request = request.into_inner();
+        let response = self
+            .blocking(move |space, _| consume(space, request))
+            .await?;
+
+        Ok(Response::new(response))
+    }
+
+    async fn peek(&self, request: Request<PeekRequest>) -> Result<Response<PeekResponse>, Status> {
+        let channel = request.into_inner().channel;
+        let entries = self
+            .blocking(move |space, _| space.read_channel(&channel).map_err(internal))
+            .await?;
+
+        Ok(Response::new(PeekResponse {
+            data: entries.data,
+            continuations: entries.continuations,
+        }))
+    }
+
+    async fn install(
+        &self,
+        request: Request<InstallRequest>,
+    ) -> Result<Response<InstallResponse>, Status> {
+        let commit = request
+            .into_inner()
+            .commit
+            .ok_or_else(|| Status::invalid_argument("commit is missing"))?;
+        self.blocking(move |space, _| {
+            space
+                .install(commit)
+                .map_err(|err| Status::failed_precondition(err.to_string()))
+        })
+        .await?;
+
+        Ok(Response::new(InstallResponse {}))
+    }
+
+    async fn checkpoint(
+        &self,
+        _request: Request<CheckpointRequest>,
+    ) -> Result<Response<CheckpointResponse>, Status> {
+        let root = self
+            .blocking(|space, history| space.checkpoint(history).map_err(internal))
+            .await?;
+
+        Ok(Response::new(CheckpointResponse {
+            root: root.to_vec(),
+        }))
+    }
+
+    async fn reset(
+        &self,
+        request: Request<ResetRequest>,
+    ) -> Result<Response<ResetResponse>, Status> {
+        let root = to_hash(&request.into_inner().root)
+            .map_err(|_| Status::invalid_argument("root is not a 32 byte hash"))?;
+        self.blocking(move |space, history| {
+            if !history.contains(&root).map_err(internal)? {
+                return Err(Status::not_found(format!(
+                    "no checkpoint {}",
+                    to_hex(&root)
+                )));
+            }
+            space.reset(history, &root).map_err(internal)
+        })
+        .await?;
+
+        Ok(Response::new(ResetResponse {}))
+    }
+
+    type ProduceStreamStream = ReceiverStream<Result<ProduceResponse, Status>>;
+
+    async fn produce_stream(
+        &self,
+        request: Request<Streaming<ProduceRequest>>,
+    ) -> Result<Response<Self::ProduceStreamStream>, Status> {
+        Ok(Response::new(
+            self.respond_to_stream(request.into_inner(), produce),
+        ))
+    }
+
+    type ConsumeStreamStream = ReceiverStream<Result<ConsumeResponse, Status>>;
+
+    async fn consume_stream(
+        &self,
+        request: Request<Streaming<ConsumeRequest>>,
+    ) -> Result<Response<Self::ConsumeStreamStream>, Status> {
+        Ok(Response::new(
+            self.respond_to_stream(request.into_inner(), consume),
+        ))
+    }
+}
@@ -0,0 +1,386 @@
+#![allow(dead_code)]
+
+use crate::exporter::{hash, Blake2b256Hash};
+use crate::keyvaluestore::{KeyValueRead, KeyValueStore, Storage};
+use crate::rtypes::rtypes::KeyValue;
+use crate::tracked::Changes;
+use crate::trie::{self, encode_branch, encode_leaf, key_bit, split_depth, Node, TrieBuilder};
+use std::collections::{BTreeSet, HashSet};
+use std::error::Error;
+use std::sync::{Mutex, MutexGuard};
+
+pub(crate) const NODE_PREFIX: &[u8] = b"node-";
+pub(crate) const ROOT_PREFIX: &[u8] = b"root-";
+
+/*
+Checkpoints of a store, kept as the nodes of its trie (see trie.rs). A node is
+stored under its hash and holds exactly the bytes that hash covers, so checkpoints
+share the leaves and subtrees they have in common, and a checkpoint after a few
+changes only adds the nodes on their paths. Roots are recorded in the order they
+were checkpointed.
+See HistoryRepository.scala in rspace/history/
+*/
+pub struct HistoryRepository<S: Storage> {
+    store: S,
+    // Nodes checkpointed while a collection runs, see gc.rs
+    written: Mutex<Option<HashSet<Blake2b256Hash>>>,
+}
+
+impl<S: Storage> HistoryRepository<S> {
+    pub fn new(store: S) -> HistoryRepository<S> {
+        HistoryRepository {
+            store,
+            written: Mutex::new(None),
+        }
+    }
+
+    /*
+    Stores the trie of the pairs txn holds and records its root as the latest
+    checkpoint. If changes has a base that is in history, only the paths of its keys
+    are updated from there, with their values read from txn. Otherwise, or while a
+    collection runs, every pair is read and every node written, see gc.rs
+    */
+    pub fn checkpoint(
+        &self,
+        txn: &impl KeyValueRead,
+        changes: &Changes,
+    ) -> Result<Blake2b256Hash, Box<dyn Error>> {
+        let mut wtxn = self.store.write_txn()?;
+        // held until the commit, a collection's sweep takes it after its own write transaction too
+        let mut written = self.written()?;
+
+        let updated = match changes.base {
+            Some(base) if written.is_none() && contains(&wtxn, &base)? => {
+                // a collection stopped part way may have taken nodes under a root it left
+                update(&mut wtxn, txn, base, &changes.keys).ok()
+            }
+            _ => None,
+        };
+        let root = match updated {
+            Some(root) => root,
+            None => rebuild(&mut wtxn, txn, written.as_mut())?,
+        };
+
+        let seq = last_seq(&wtxn)?.map_or(0, |seq| seq + 1);
+        wtxn.put(
+            &[ROOT_PREFIX, format!("{:020}", seq).as_bytes()].concat(),
+            &root,
+        )?;
+        wtxn.commit()?;
+
+        Ok(root)
+    }
+
+    // Recorded roots, oldest first
+    pub fn roots(&self) -> Result<Vec<Blake2b256Hash>, Box<dyn Error>> {
+        let txn = self.store.read_txn()?;
+        let mut roots = vec![];
+        txn.iterate_prefix(ROOT_PREFIX, &mut |_, value| {
+            roots.push(to_hash(value)?);
+            Ok(true)
+        })?;
+
+        Ok(roots)
+    }
+
+    pub fn contains(&self, root: &Blake2b256Hash) -> Result<bool, Box<dyn Error>> {
+        contains(&self.store.read_txn()?, root)
+    }
+
+    // The pairs a checkpoint held, in key order. Fails if a node is missing or does not match its hash
+    pub fn pairs(&self, root: &Blake2b256Hash) -> Result<Vec<KeyValue>, Box<dyn Error>> {
+        let mut pairs = vec![];
+        if *root == hash(&[]) {
+            return Ok(pairs);
+        }
+
+        let txn = self.store.read_txn()?;
+        let mut to_visit = vec![*root];
+        while let Some(node_hash) = to_visit.pop() {
+            match read_node(&txn, &node_hash)? {
+                Node::Leaf(key, value) => pairs.push(KeyValue { key, value }),
+                Node::Branch(_, left, right) => {
+                    to_visit.push(right);
+                    to_visit.push(left);
+                }
+            }
+        }
+
+        Ok(pairs)
+    }
+
+    pub fn is_empty(&self) -> Result<bool, Box<dyn Error>> {
+        self.store.is_empty()
+    }
+
+    pub fn flush(&self) -> Result<(), Box<dyn Error>> {
+        self.store.flush()
+    }
+
+    pub(crate) fn store(&self) -> &S {
+        &self.store
+    }
+
+    pub(crate) fn written(
+        &self,
+    ) -> Result<MutexGuard<'_, Option<HashSet<Blake2b256Hash>>>, Box<dyn Error>> {
+        Ok(self.written.lock().map_err(|err| err.to_string())?)
+    }
+}
+
+pub(crate) fn read_node(
+    txn: &impl KeyValueRead,
+    node_hash: &Blake2b256Hash,
+) -> Result<Node, Box<dyn Error>> {
+    let bytes = txn
+        .get(&node_key(node_hash))?
+        .ok_or_else(|| format!("node {} is not in history", to_hex(node_hash)))?;
+    if hash(&bytes) != *node_hash {
+        return Err(format!("node {} does not match its hash", to_hex(node_hash)).into());
+    }
+
+    Ok(trie::decode(&bytes).ok_or_else(|| format!("node {} is malformed", to_hex(node_hash)))?)
+}
+
+fn contains(txn: &impl KeyValueRead, root: &Blake2b256Hash) -> Result<bool, Box<dyn Error>> {
+    Ok(*root == hash(&[]) || txn.get(&node_key(root))?.is_some())
+}
+
+// Updates the paths of keys from base, nothing is written unless all of them are read
+fn update(
+    wtxn: &mut impl KeyValueStore,
+    txn: &impl KeyValueRead,
+    base: Blake2b256Hash,
+    keys: &BTreeSet<Vec<u8>>,
+) -> Result<Blake2b256Hash, Box<dyn Error>> {
+    let mut changed = Trie::Stored(base);
+    for key in keys {
+        match txn.get(key)? {
+            Some(value) => changed.insert(&*wtxn, key, value)?,
+            None => changed.remove(&*wtxn, key)?,
+        }
+    }
+
+    changed.store(wtxn)
+}
+
+// Written over even when present, a collection may have taken it since
+fn put_node(
+    wtxn: &mut impl KeyValueStore,
+    written: &mut Option<&mut HashSet<Blake2b256Hash>>,
+    node: &[u8],
+) -> Result<Blake2b256Hash, Box<dyn Error>> {
+    let node_hash = hash(node);
+    if let Some(written) = written {
+        written.insert(node_hash);
+    }
+    wtxn.put(&node_key(&node_hash), node)?;
+    Ok(node_hash)
+}
+
+// Writes the whole trie of the pairs txn holds
+fn rebuild(
+    wtxn: &mut impl KeyValueStore,
+    txn: &impl KeyValueRead,
+    mut written: Option<&mut HashSet<Blake2b256Hash>>,
+) -> Result<Blake2b256Hash, Box<dyn Error>> {
+    let mut builder = TrieBuilder::new();
+    let mut branches = vec![];
+    let mut last: Option<Vec<u8>> = None;
+    txn.iterate_prefix(&[], &mut |key, value| {
+        let depth = last.as_ref().map_or(0, |last| split_depth(last, key));
+        let leaf = put_node(wtxn, &mut written, &encode_leaf(key, value))?;
+        builder.push(leaf, depth, &mut |depth, left, right| {
+            branches.push(encode_branch(depth, &left.hash, &right.hash))
+        });
+        for branch in branches.drain(..) {
+            put_node(wtxn, &mut written, &branch)?;
+        }
+        last = Some(key.to_vec());
+        Ok(true)
+    })?;
+    let root = builder.finish(&mut |depth, left, right| {
+        branches.push(encode_branch(depth, &left.hash, &right.hash))
+    });
+    for branch in branches {
+        put_node(wtxn, &mut written, &branch)?;
+    }
+
+    Ok(root)
+}
+
+/*
+The part of a stored trie a checkpoint changes. Nodes are read as the changed keys'
+paths reach them, everything off those paths stays behind its hash.
+*/
+enum Trie {
+    Empty,
+    Stored(Blake2b256Hash),
+    Leaf(Vec<u8>, Vec<u8>),
+    Branch(u64, Box<Trie>, Box<Trie>),
+}
+
+impl Trie {
+    fn load(&mut self, txn: &impl KeyValueRead) -> Result<(), Box<dyn Error>> {
+        if let Trie::Stored(node_hash) = self {
+            *self = if *node_hash == hash(&[]) {
+                Trie::Empty
+            } else {
+                match read_node(txn, node_hash)? {
+                    Node::Leaf(key, value) => Trie::Leaf(key, value),
+                    Node::Branch(depth, left, right) => Trie::Branch(
+                        depth,
+                        Box::new(Trie::Stored(left)),
+                        Box::new(Trie::Stored(right)),
+                    ),
+                }
+            };
+        }
+
+        Ok(())
+    }
+
+    // The key of the leaf key's bits lead to, where key would be if it is not there
+    fn nearest(
+        &mut self,
+        txn: &impl KeyValueRead,
+        key: &[u8],
+    ) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+        self.load(txn)?;
+        match self {
+            Trie::Leaf(leaf_key, _) => Ok(Some(leaf_key.clone())),
+            Trie::Branch(depth, left, right) => match key_bit(key, *depth) {
+                true => right.nearest(txn, key),
+                false => left.nearest(txn, key),
+            },
+            _ => Ok(None),
+        }
+    }
+
+    fn insert(
+        &mut self,
+        txn: &impl KeyValueRead,
+        key: &[u8],
+        value: Vec<u8>,
+    ) -> Result<(), Box<dyn Error>> {
+        match self.nearest(txn, key)? {
+            None => {
+                *self = Trie::Leaf(key.to_vec(), value);
+                Ok(())
+            }
+            Some(nearest) if nearest == key => self.insert_at(txn, key, value, None),
+            Some(nearest) => self.insert_at(txn, key, value, Some(split_depth(key, &nearest))),
+        }
+    }
+
+    // Replaces the value of key, or with split adds it in a new branch at that depth
+    fn insert_at(
+        &mut self,
+        txn: &impl KeyValueRead,
+        key: &[u8],
+        value: Vec<u8>,
+        split: Option<u64>,
+    ) -> Result<(), Box<dyn Error>> {
+        self.load(txn)?;
+        match (self, split) {
+            (Trie::Branch(depth, left, right), split)
+                if split.is_none_or(|split| *depth < split) =>
+            {
+                match key_bit(key, *depth) {
+                    true => right.insert_at(txn, key, value, split),
+                    false => left.insert_at(txn, key, value, split),
+                }
+            }
+            (Trie::Leaf(_, leaf_value), None) => {
+                *leaf_value = value;
+                Ok(())
+            }
+            (node, Some(split)) => {
+                let old = Box::new(std::mem::replace(node, Trie::Empty));
+                let leaf = Box::new(Trie::Leaf(key.to_vec(), value));
+                *node = match key_bit(key, split) {
+                    true => Trie::Branch(split, old, leaf),
+                    false => Trie::Branch(split, leaf, old),
+                };
+                Ok(())
+            }
+            (_, None) => Err("a changed key is off its own path".into()),
+        }
+    }
+
+    // A branch left with one child is replaced by it
+    fn remove(&mut self, txn: &impl KeyValueRead, key: &[u8]) -> Result<(), Box<dyn Error>> {
+        self.load(txn)?;
+        match self {
+            Trie::Leaf(leaf_key, _) if leaf_key == key => *self = Trie::Empty,
+            Trie::Branch(depth, left, right) => {
+                let (child, other) = match key_bit(key, *depth) {
+                    true => (right, left),
+                    false => (left, right),
+                };
+                child.remove(txn, key)?;
+                if let Trie::Empty = **child {
+                    *self = std::mem::replace(&mut **other, Trie::Empty);
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    // Writes the nodes that changed, returns the root
+    fn store(self, wtxn: &mut impl KeyValueStore) -> Result<Blake2b256Hash, Box<dyn Error>> {
+        match self {
+            Trie::Empty => Ok(hash(&[])),
+            Trie::Stored(node_hash) => Ok(node_hash),
+            Trie::Leaf(key, value) => put_node(wtxn, &mut None, &encode_leaf(&key, &value)),
+            Trie::Branch(depth, left, right) => {
+                let left = left.store(wtxn)?;
+                let right = right.store(wtxn)?;
+                put_node(wtxn, &mut None, &encode_branch(depth, &left, &right))
+            }
+        }
+    }
+}
+
+fn last_seq(txn: &impl KeyValueRead) -> Result<Option<u64>, Box<dyn Error>> {
+    let mut last = None;
+    txn.iterate_prefix(ROOT_PREFIX, &mut |key, _| {
+        last = Some(std::str::from_utf8(&key[ROOT_PREFIX.len()..])?.parse::<u64>()?);
+        Ok(true)
+    })?;
+
+    Ok(last)
+}
+
+pub(crate) fn node_key(node_hash: &Blake2b256Hash) -> Vec<u8> {
+    [NODE_PREFIX, to_hex(node_hash).as_bytes()].concat()
+}
+
+pub(crate) fn from_node_key(key: &[u8]) -> Result<Blake2b256Hash, Box<dyn Error>> {
+    let hex = key
+        .strip_prefix(NODE_PREFIX)
+        .ok_or_else(|| format!("{} is not a node key", String::from_utf8_lossy(key)))?;
+    from_hex(std::str::from_utf8(hex)?)
+}
+
+pub(crate) fn to_hash(bytes: &[u8]) -> Result<Blake2b256Hash, Box<dyn Error>> {
+    Ok(bytes.try_into()?)
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn from_hex(hex: &str) -> Result<Blake2b256Hash, Box<dyn Error>> {
+    if hex.len() != 64 || !hex.is_ascii() {
+        return Err(format!("{} is not a 32 byte hex hash", hex).into());
+    }
+    let mut bytes = [0u8; 32];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16)?;
+    }
+
+    Ok(bytes)
+}
@@ -0,0 +1,211 @@
+#![allow(dead_code)]
+
+use crate::keyvaluestore::{KeyValueStore, Storage};
+use crate::memorystore::MemStore;
+use crate::rho;
+use crate::rtypes::rtypes;
+use crate::tuplespace::{self, ChannelEntries};
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+use std::error::Error;
+use tracing::{debug, debug_span};
+
+/*
+One change a checkpoint writes to the history store. Inserts replace everything
+stored for that channel and kind, deletes remove it.
+See HotStoreTrieAction.scala in rspace/
+*/
+#[derive(Clone, Debug, PartialEq)]
+#[allow(clippy::enum_variant_names)]
+pub enum HotStoreTrieAction {
+    TrieInsertProduce {
+        channel: String,
+        data: Vec<rtypes::RetrieveContinuation>,
+    },
+    TrieInsertConsume {
+        channel: String,
+        continuations: Vec<rtypes::CommitContinuation>,
+    },
+    TrieInsertJoins {
+        channel: String,
+        joins: Vec<Vec<String>>,
+    },
+    TrieDeleteProduce {
+        channel: String,
+    },
+    TrieDeleteConsume {
+        channel: String,
+    },
+    TrieDeleteJoins {
+        channel: String,
+    },
+}
+
+// The cold store a hot store reads through on a miss and writes back to at checkpoint
+pub trait HistoryStore {
+    fn read_channel(&self, channel: &str) -> Result<ChannelEntries, Box<dyn Error>>;
+    fn apply_actions(&self, actions: &[HotStoreTrieAction]) -> Result<(), Box<dyn Error>>;
+}
+
+/*
+Working set of the channels touched during a block. A channel is copied from the
+history store the first time it is touched, and every produce and consume after
+that only works on the copy in memory. Nothing reaches the history store until
+checkpoint, which writes the difference as HotStoreTrieActions.
+See HotStore.scala in rspace/
+*/
+pub struct HotStore<'a, H: HistoryStore> {
+    history: &'a H,
+    // channels as they were read from the history store
+    history_cache: DashMap<String, ChannelEntries>,
+    // copies of those channels, updated by produce and consume
+    cache: MemStore,
+}
+
+impl<'a, H: HistoryStore> HotStore<'a, H> {
+    pub fn new(history: &'a H) -> HotStore<'a, H> {
+        HotStore {
+            history,
+            history_cache: DashMap::new(),
+            cache: MemStore::new(),
+        }
+    }
+
+    pub fn consume(
+        &self,
+        commit: &rtypes::Commit,
+        persistent: bool,
+    ) -> Result<Option<Vec<rtypes::OptionResult>>, Box<dyn Error>> {
+        for channel in &rho::named_commit(commit).channels {
+            self.load(channel)?;
+        }
+
+        let mut txn = self.cache.write_txn()?;
+        let results = tuplespace::consume_in_txn(&mut txn, commit, persistent, &mut vec![])?;
+        txn.commit()?;
+        Ok(results)
+    }
+
+    pub fn produce(
+        &self,
+        retrieve: &rtypes::Retrieve,
+        persistent: bool,
+    ) -> Result<Option<rtypes::OptionResult>, Box<dyn Error>> {
+        self.load(&rho::named_retrieve(retrieve).chan)?;
+
+        let mut txn = self.cache.write_txn()?;
+        let result = tuplespace::produce_in_txn(&mut txn, retrieve, persistent, &mut vec![])?;
+        txn.commit()?;
+        Ok(result)
+    }
+
+    pub fn get_data(
+        &self,
+        channel: &str,
+    ) -> Result<Vec<rtypes::RetrieveContinuation>, Box<dyn Error>> {
+        Ok(self.read_channel(channel)?.data)
+    }
+
+    pub fn get_continuations(
+        &self,
+        channel: &str,
+    ) -> Result<Vec<rtypes::CommitContinuation>, Box<dyn Error>> {
+        Ok(self.read_channel(channel)?.continuations)
+    }
+
+    pub fn get_joins(&self, channel: &str) -> Result<Vec<Vec<String>>, Box<dyn Error>> {
+        Ok(self.read_channel(channel)?.joins())
+    }
+
+    // Actions that turn the history store into the cached state, ordered by channel
+    pub fn changes(&self) -> Result<Vec<HotStoreTrieAction>, Box<dyn Error>> {
+        let mut channels: Vec<String> = self
+            .history_cache
+            .iter()
+            .map(|entry| entry.key().clone())
+            .collect();
+        channels.sort();
+
+        let mut actions = vec![];
+        for channel in channels {
+            let before = match self.history_cache.get(&channel) {
+                Some(entries) => entries.clone(),
+                None => continue,
+            };
+            let after = tuplespace::read_channel(&self.cache.read_txn()?, &channel)?;
+
+            if after.data != before.data {
+                actions.push(if after.data.is_empty() {
+                    HotStoreTrieAction::TrieDeleteProduce {
+                        channel: channel.clone(),
+                    }
+                } else {
+                    HotStoreTrieAction::TrieInsertProduce {
+                        channel: channel.clone(),
+                        data: after.data.clone(),
+                    }
+                });
+            }
+
+            if after.continuations != before.continuations {
+                actions.push(if after.continuations.is_empty() {
+                    HotStoreTrieAction::TrieDeleteConsume {
+                        channel: channel.clone(),
+                    }
+                } else {
+                    HotStoreTrieAction::TrieInsertConsume {
+                        channel: channel.clone(),
+                        continuations: after.continuations.clone(),
+                    }
+                });
+            }
+
+            let joins = after.joins();
+            if joins != before.joins() {
+                actions.push(if joins.is_empty() {
+                    HotStoreTrieAction::TrieDeleteJoins { channel }
+                } else {
+                    HotStoreTrieAction::TrieInsertJoins { channel, joins }
+                });
+            }
+        }
+
+        Ok(actions)
+    }
+
+    // Writes the changes to the history store and starts an empty working set
+    pub fn checkpoint(&self) -> Result<Vec<HotStoreTrieAction>, Box<dyn Error>> {
+        let _entered = debug_span!("checkpoint").entered();
+
+        let actions = self.changes()?;
+        debug!(actions = actions.len(), "checkpointed");
+        self.history.apply_actions(&actions)?;
+        self.clear()?;
+        Ok(actions)
+    }
+
+    // Drops the working set without writing it
+    pub fn clear(&self) -> Result<(), Box<dyn Error>> {
+        self.history_cache.clear();
+        self.cache.clear()
+    }
+
+    fn read_channel(&self, channel: &str) -> Result<ChannelEntries, Box<dyn Error>> {
+        self.load(channel)?;
+        tuplespace::read_channel(&self.cache.read_txn()?, channel)
+    }
+
+    fn load(&self, channel: &str) -> Result<(), Box<dyn Error>> {
+        // holding the entry keeps a second load of the channel from copying it again
+        if let Entry::Vacant(vacant) = self.history_cache.entry(channel.to_string()) {
+            let entries = self.history.read_channel(channel)?;
+
+            let mut txn = self.cache.write_txn()?;
+            tuplespace::put_channel(&mut txn, channel, &entries)?;
+            txn.commit()?;
+
+            vacant.insert(entries);
+        }
+        Ok(())
+    }
+}
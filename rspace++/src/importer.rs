@@ -0,0 +1,120 @@
+#![allow(dead_code)]
+
+use crate::exporter::{hash, state_root, Blake2b256Hash};
+use crate::history::to_hash;
+use crate::keyvaluestore::{KeyValueRead, KeyValueStore, Storage};
+use crate::rtypes::rtypes::ExportChunk;
+use crate::trie::{leaf_hash, split_depth, Subtree, TrieBuilder};
+use std::error::Error;
+
+/*
+Fills an empty store from the chunks of an RSpaceExporter. Every chunk is checked
+against the expected root before it is written, and chunks have to come in order.
+The whole import is one write transaction that only commits once finish finds the
+root reproduced, so an import that fails or is dropped leaves the store empty to retry.
+See RSpaceImporter.scala in rspace/
+*/
+pub struct RSpaceImporter<'a, S: Storage + 'a> {
+    txn: S::WriteTxn<'a>,
+    root: Blake2b256Hash,
+    imported: u64,
+    last: Option<Vec<u8>>,
+}
+
+impl<'a, S: Storage> RSpaceImporter<'a, S> {
+    pub fn new(
+        store: &'a S,
+        root: Blake2b256Hash,
+    ) -> Result<RSpaceImporter<'a, S>, Box<dyn Error>> {
+        let txn = store.write_txn()?;
+        let mut empty = true;
+        txn.iterate_prefix(&[], &mut |_, _| {
+            empty = false;
+            Ok(false)
+        })?;
+        if !empty {
+            return Err("can only import into an empty store".into());
+        }
+
+        Ok(RSpaceImporter {
+            txn,
+            root,
+            imported: 0,
+            last: None,
+        })
+    }
+
+    pub fn import_chunk(&mut self, chunk: &ExportChunk) -> Result<(), Box<dyn Error>> {
+        if chunk.root != self.root {
+            return Err("chunk is for a different root".into());
+        }
+        if chunk.start != self.imported {
+            return Err(format!(
+                "expected the chunk starting at {}, got {}",
+                self.imported, chunk.start
+            )
+            .into());
+        }
+        verify_range(chunk, &self.root)?;
+        if self.last.as_ref() >= chunk.pairs.first().map(|pair| &pair.key) {
+            return Err("chunk pairs come before the ones imported".into());
+        }
+
+        for pair in &chunk.pairs {
+            self.txn.put(&pair.key, &pair.value)?;
+        }
+
+        self.imported += chunk.pairs.len() as u64;
+        self.last = chunk.pairs.last().map(|pair| pair.key.clone());
+        Ok(())
+    }
+
+    // Checks the store now holds everything and has the root it was exported with, then commits
+    pub fn finish(self) -> Result<(), Box<dyn Error>> {
+        let root = state_root(&self.txn)?;
+        if root != self.root {
+            return Err("imported store does not reproduce the root".into());
+        }
+
+        self.txn.commit()
+    }
+}
+
+// Recomputes the root from the chunk's pairs and the subtrees around them, see prove in exporter.rs
+fn verify_range(chunk: &ExportChunk, root: &Blake2b256Hash) -> Result<(), Box<dyn Error>> {
+    if chunk.pairs.is_empty() || chunk.start + chunk.pairs.len() as u64 > chunk.total {
+        return Err("chunk pairs are out of range".into());
+    }
+
+    let mut builder = TrieBuilder::new();
+    let mut on_branch = |_, _: &Subtree, _: &Subtree| {};
+    let mut depth = 0;
+    for node in &chunk.before {
+        builder.push(to_hash(&node.hash)?, depth, &mut on_branch);
+        depth = node.depth;
+    }
+    for (i, pair) in chunk.pairs.iter().enumerate() {
+        if i > 0 {
+            let previous = &chunk.pairs[i - 1].key;
+            if *previous >= pair.key {
+                return Err("chunk pairs are not in key order".into());
+            }
+            depth = split_depth(previous, &pair.key);
+        }
+        builder.push(leaf_hash(&pair.key, &pair.value), depth, &mut on_branch);
+    }
+    for node in &chunk.after {
+        builder.push(to_hash(&node.hash)?, node.depth, &mut on_branch);
+    }
+
+    if builder.finish(&mut on_branch) != *root {
+        return Err("chunk does not match the root".into());
+    }
+
+    Ok(())
+}
+
+// Root an importer expects when the exporter's store was empty
+pub fn empty_root() -> Blake2b256Hash {
+    hash(&[])
+}
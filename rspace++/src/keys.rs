@@ -0,0 +1,166 @@
+#![allow(dead_code)]
+
+use crate::exporter::{hash, Blake2b256Hash};
+
+/*
+Key layout of a tuplespace store. A key starts with a tag byte saying what it
+holds, variable length fields follow prefixed with their length as a big endian
+u32, and an entry id closes it as a big endian u64. No field can run into the
+next, so whatever a channel name or match key holds, one key is never a prefix
+of another kind's or another channel's. Channels are keyed by the Blake2b256
+hash of their name.
+
+[CHANNEL][channel hash] -> the channel name, while the channel holds entries
+[DATA|CONTINUATION][channel hash][id] -> the encoded entry
+[INDEX][DATA|CONTINUATION][channel hash][match key][id] -> key of the entry
+
+An entry's id is the start of the Blake2b256 hash of its encoded value followed
+by which copy of that value at the channel it is, see entry_id. Identical
+entries are stored apart, and ids are the same in every build and process.
+*/
+pub const CHANNEL: u8 = 0;
+pub const DATA: u8 = 1;
+pub const CONTINUATION: u8 = 2;
+pub const INDEX: u8 = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Kind {
+    Data,
+    Continuation,
+}
+
+impl Kind {
+    fn tag(self) -> u8 {
+        match self {
+            Kind::Data => DATA,
+            Kind::Continuation => CONTINUATION,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Option<Kind> {
+        match tag {
+            DATA => Some(Kind::Data),
+            CONTINUATION => Some(Kind::Continuation),
+            _ => None,
+        }
+    }
+}
+
+// A key taken apart, see parse
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Key {
+    Channel(Blake2b256Hash),
+    Entry(Kind, Blake2b256Hash, u64),
+    Index(Kind, Blake2b256Hash, String, u64),
+}
+
+pub fn channel_hash(channel: &str) -> Blake2b256Hash {
+    hash(channel.as_bytes())
+}
+
+pub fn channel_key(channel_hash: &Blake2b256Hash) -> Vec<u8> {
+    let mut key = vec![CHANNEL];
+    push_field(&mut key, channel_hash);
+    key
+}
+
+// Every entry of one kind at a channel is under it
+pub fn entry_prefix(kind: Kind, channel_hash: &Blake2b256Hash) -> Vec<u8> {
+    let mut key = vec![kind.tag()];
+    push_field(&mut key, channel_hash);
+    key
+}
+
+pub fn entry_key(kind: Kind, channel_hash: &Blake2b256Hash, id: u64) -> Vec<u8> {
+    let mut key = entry_prefix(kind, channel_hash);
+    key.extend(id.to_be_bytes());
+    key
+}
+
+// The id of the copy'th entry at a channel encoded as value: six bytes of hash, two of copy
+pub fn entry_id(value: &[u8], copy: u16) -> u64 {
+    let mut id = [0; 8];
+    id[..6].copy_from_slice(&hash(value)[..6]);
+    id[6..].copy_from_slice(&copy.to_be_bytes());
+    u64::from_be_bytes(id)
+}
+
+// Which copy an id is, see entry_id
+pub fn entry_copy(id: u64) -> u16 {
+    id as u16
+}
+
+// Every index key of one kind at a channel is under it
+pub fn index_channel_prefix(kind: Kind, channel_hash: &Blake2b256Hash) -> Vec<u8> {
+    let mut key = vec![INDEX, kind.tag()];
+    push_field(&mut key, channel_hash);
+    key
+}
+
+// The index keys of the entries at a channel stored under match_key
+pub fn index_prefix(kind: Kind, channel_hash: &Blake2b256Hash, match_key: &str) -> Vec<u8> {
+    let mut key = index_channel_prefix(kind, channel_hash);
+    push_field(&mut key, match_key.as_bytes());
+    key
+}
+
+pub fn index_key(kind: Kind, channel_hash: &Blake2b256Hash, match_key: &str, id: u64) -> Vec<u8> {
+    let mut key = index_prefix(kind, channel_hash, match_key);
+    key.extend(id.to_be_bytes());
+    key
+}
+
+// None unless key is exactly one of the layouts above
+pub fn parse(key: &[u8]) -> Option<Key> {
+    let (&tag, rest) = key.split_first()?;
+    let mut fields = Fields(rest);
+
+    let parsed = match tag {
+        CHANNEL => Key::Channel(fields.hash()?),
+        INDEX => {
+            let kind = Kind::from_tag(fields.byte()?)?;
+            let channel_hash = fields.hash()?;
+            let match_key = String::from_utf8(fields.field()?.to_vec()).ok()?;
+            Key::Index(kind, channel_hash, match_key, fields.id()?)
+        }
+        _ => Key::Entry(Kind::from_tag(tag)?, fields.hash()?, fields.id()?),
+    };
+
+    fields.0.is_empty().then_some(parsed)
+}
+
+fn push_field(key: &mut Vec<u8>, field: &[u8]) {
+    key.extend((field.len() as u32).to_be_bytes());
+    key.extend(field);
+}
+
+// What is left of a key being parsed
+struct Fields<'a>(&'a [u8]);
+
+impl<'a> Fields<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Some(taken)
+    }
+
+    fn byte(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn field(&mut self) -> Option<&'a [u8]> {
+        let len = u32::from_be_bytes(self.take(4)?.try_into().ok()?);
+        self.take(len as usize)
+    }
+
+    fn hash(&mut self) -> Option<Blake2b256Hash> {
+        self.field()?.try_into().ok()
+    }
+
+    fn id(&mut self) -> Option<u64> {
+        Some(u64::from_be_bytes(self.take(8)?.try_into().ok()?))
+    }
+}
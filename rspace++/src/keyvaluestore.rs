@@ -0,0 +1,225 @@
+#![allow(dead_code)]
+
+use std::error::Error;
+use std::fmt;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+// Called on each key-value pair in turn, returns false to stop iterating
+pub type Visitor<'f> = dyn FnMut(&[u8], &[u8]) -> Result<bool, Box<dyn Error>> + 'f;
+
+/*
+Ordered key-value access the tuplespace logic is written against. Read and write
+transactions of every storage backend implement it. Keys are bytes compared
+lexicographically, see keys.rs for the layout.
+See KeyValueStore.scala in shared/
+*/
+pub trait KeyValueRead {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Box<dyn Error>>;
+
+    // Calls f on every pair whose key starts with prefix, in key order
+    fn iterate_prefix(&self, prefix: &[u8], f: &mut Visitor) -> Result<(), Box<dyn Error>>;
+
+    // Calls f on every pair whose key is start or comes after it, in key order
+    fn iterate_from(&self, start: &[u8], f: &mut Visitor) -> Result<(), Box<dyn Error>> {
+        self.iterate_prefix(&[], &mut |key, value| match key < start {
+            true => Ok(true),
+            false => f(key, value),
+        })
+    }
+}
+
+pub trait KeyValueStore: KeyValueRead {
+    fn put(&mut self, key: &[u8], value: &[u8]) -> Result<(), Box<dyn Error>>;
+
+    fn delete(&mut self, key: &[u8]) -> Result<(), Box<dyn Error>>;
+
+    // Makes every put and delete visible at once. Dropping the store instead discards them
+    fn commit(self) -> Result<(), Box<dyn Error>>
+    where
+        Self: Sized;
+}
+
+/*
+A storage backend. Write transactions are serializable, so a produce or consume
+never sees another one half applied: either only one is open at a time, or a
+commit that could not have come after the ones before it fails with Conflict.
+Read transactions only see committed writes. Whether the data survives a restart
+is up to the backend.
+*/
+pub trait Storage {
+    type ReadTxn<'a>: KeyValueRead
+    where
+        Self: 'a;
+    type WriteTxn<'a>: KeyValueStore
+    where
+        Self: 'a;
+
+    fn read_txn(&self) -> Result<Self::ReadTxn<'_>, Box<dyn Error>>;
+
+    fn write_txn(&self) -> Result<Self::WriteTxn<'_>, Box<dyn Error>>;
+
+    fn is_empty(&self) -> Result<bool, Box<dyn Error>>;
+
+    fn clear(&self) -> Result<(), Box<dyn Error>>;
+
+    // Forces every committed write to disk, whatever the durability
+    fn flush(&self) -> Result<(), Box<dyn Error>>;
+}
+
+// A write transaction read something another one changed and committed first. Nothing
+// of it was written, so running it again is safe
+#[derive(Debug)]
+pub struct Conflict;
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "write transaction conflicted with a concurrent commit")
+    }
+}
+
+impl Error for Conflict {}
+
+// When a disk backend's commits reach the disk
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Durability {
+    // Every commit is synced before it returns
+    #[default]
+    Sync,
+    // Commits are synced together once ops of them pile up or interval has passed
+    // since the last sync. A crash loses at most the commits since then
+    GroupCommit {
+        ops: usize,
+        interval: Duration,
+    },
+    // Syncing is left to the OS and to flush. For tests and bulk loads
+    NoSync,
+}
+
+// Counts commits since the last sync, so a backend knows when the next one is due
+pub struct SyncPolicy {
+    durability: Durability,
+    pending: Mutex<(usize, Instant)>,
+}
+
+impl SyncPolicy {
+    pub fn new(durability: Durability) -> SyncPolicy {
+        SyncPolicy {
+            durability,
+            pending: Mutex::new((0, Instant::now())),
+        }
+    }
+
+    pub fn durability(&self) -> Durability {
+        self.durability
+    }
+
+    // Records one commit, returns whether it has to be synced
+    pub fn commit_is_due(&self) -> Result<bool, Box<dyn Error>> {
+        let mut pending = self.pending.lock().map_err(|err| err.to_string())?;
+        pending.0 += 1;
+
+        let due = match self.durability {
+            Durability::Sync => true,
+            Durability::GroupCommit { ops, interval } => {
+                pending.0 >= ops || pending.1.elapsed() >= interval
+            }
+            Durability::NoSync => false,
+        };
+        if due {
+            *pending = (0, Instant::now());
+        }
+
+        Ok(due)
+    }
+
+    // Commits not yet synced
+    pub fn pending(&self) -> Result<usize, Box<dyn Error>> {
+        Ok(self.pending.lock().map_err(|err| err.to_string())?.0)
+    }
+
+    pub fn synced(&self) -> Result<(), Box<dyn Error>> {
+        *self.pending.lock().map_err(|err| err.to_string())? = (0, Instant::now());
+        Ok(())
+    }
+
+    // How long until the pending commits are due by the interval alone, None if never
+    pub fn due_in(&self) -> Result<Option<Duration>, Box<dyn Error>> {
+        let pending = self.pending.lock().map_err(|err| err.to_string())?;
+        Ok(match self.durability {
+            Durability::GroupCommit { interval, .. } if pending.0 > 0 => {
+                Some(interval.saturating_sub(pending.1.elapsed()))
+            }
+            Durability::GroupCommit { interval, .. } => Some(interval),
+            _ => None,
+        })
+    }
+}
+
+/*
+Syncs a group commit store once its interval has passed with commits pending,
+so the last commits before the store goes idle do not wait for the next one.
+Stops when dropped.
+*/
+pub struct Flusher {
+    stop: Arc<(Mutex<bool>, Condvar)>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Flusher {
+    // None unless sync is a group commit with an interval, flush has to call sync.synced
+    pub fn spawn(
+        sync: Arc<SyncPolicy>,
+        flush: impl Fn() -> Result<(), Box<dyn Error>> + Send + 'static,
+    ) -> Option<Flusher> {
+        match sync.durability() {
+            Durability::GroupCommit { interval, .. } if !interval.is_zero() => {}
+            _ => return None,
+        }
+
+        let stop = Arc::new((Mutex::new(false), Condvar::new()));
+        let thread = thread::spawn({
+            let stop = stop.clone();
+            move || {
+                let (stopped, wake) = &*stop;
+                let Ok(mut stopped) = stopped.lock() else {
+                    return;
+                };
+                while !*stopped {
+                    match sync.due_in() {
+                        Ok(Some(wait)) if wait.is_zero() => {
+                            if let Err(err) = flush() {
+                                warn!(%err, "background sync failed");
+                            }
+                        }
+                        Ok(Some(wait)) => match wake.wait_timeout(stopped, wait) {
+                            Ok((guard, _)) => stopped = guard,
+                            Err(_) => return,
+                        },
+                        _ => return,
+                    }
+                }
+            }
+        });
+
+        Some(Flusher {
+            stop,
+            thread: Some(thread),
+        })
+    }
+}
+
+impl Drop for Flusher {
+    fn drop(&mut self) {
+        let (stopped, wake) = &*self.stop;
+        if let Ok(mut stopped) = stopped.lock() {
+            *stopped = true;
+            wake.notify_one();
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -1,16 +1,48 @@
-pub mod diskconc;
-pub mod diskseq;
-pub mod memconc;
-pub mod memseq;
+#[cfg(feature = "async")]
+pub mod asyncspace;
+pub mod cli;
+pub mod dump;
+pub mod exporter;
+pub mod gc;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod history;
+pub mod hotstore;
+pub mod importer;
+pub mod keys;
+pub mod keyvaluestore;
+pub mod lmdbstore;
+pub mod logging;
+pub mod matcher;
+pub mod memorystore;
+pub mod merger;
+pub mod metrics;
+#[cfg(feature = "redb")]
+pub mod redbstore;
+pub mod rho;
 pub mod rspace;
 pub mod rtypes;
 pub mod setup;
-
+pub mod trace;
+pub mod tracked;
+pub mod transaction;
+pub mod trie;
+pub mod tuplespace;
+pub mod verify;
+pub mod watch;
+
+use logging::LogCallback;
+use metrics::InMemoryMetrics;
 use prost::Message;
 use rspace::RSpace;
-use rtypes::rtypes::{Commit, Retrieve};
-use serde_json;
-use std::ffi::{c_char, CStr, CString};
+use rtypes::rtypes::{
+    ChannelList, Commit, ContinuationList, DataList, JoinList, ResultList, Retrieve, Row, Store,
+    StoreMap,
+};
+use std::ffi::{c_char, CStr};
+use std::sync::Arc;
+use std::thread;
+use watch::WatchCallback;
 
 #[repr(C)]
 pub struct Space {
@@ -24,539 +56,701 @@ pub extern "C" fn space_new() -> *mut Space {
     }))
 }
 
+// Verbs. A get returns an encoded OptionResult and a put an encoded ResultList,
+// writing its length to len, or null when the space stored what it was given. A verb
+// that fails returns null too, and sets error. Free the result with space_free_bytes
+
 // Verb Set 1
+/// # Safety
+/// rspace is a live pointer from space_new, rdata_ptr points at rdata_len readable bytes, len at a writable usize and error at a writable bool
 #[no_mangle]
-pub extern "C" fn space_get_once_durable_concurrent(
+pub unsafe extern "C" fn space_get_once_durable_concurrent(
     rspace: *mut Space,
     rdata_ptr: *const u8,
     rdata_len: usize,
-) -> *const c_char {
-    unsafe {
+    len: *mut usize,
+    error: *mut bool,
+) -> *const u8 {
+    let result = unsafe {
         let rdata_buf = std::slice::from_raw_parts(rdata_ptr, rdata_len);
-        let rdata = Retrieve::decode(rdata_buf).unwrap();
-
-        let result_option = (*rspace).rspace.get_once_durable_concurrent(rdata);
-
-        if result_option.is_some() {
-            let result = result_option.unwrap();
-            let result_string = serde_json::to_string(&result).unwrap();
-            let c_string = std::ffi::CString::new(result_string).expect("Failed to create CString");
-            c_string.into_raw()
-        } else {
-            // let result_string = "";
-            // let c_string = std::ffi::CString::new(result_string).expect("Failed to create CString");
-            // c_string.into_raw()
-            std::ptr::null()
-        }
-
-        // &result as *const OptionResult // return type *const OptionResult for Rust. ?? for Scala
-
-        // Box::into_raw(Box::new(result)) // return type *mut Option<OptionResult> for Rust. ?? for Scala
+        Retrieve::decode(rdata_buf)
+            .map_err(Into::into)
+            .and_then(|rdata| (*rspace).rspace.get_once_durable_concurrent(rdata))
+    };
 
-        // 40 as *const i32 // return type *const i32 for Rust. Int for Scala
-
-        // let hello_string = "Hello from Rust!".to_string();
-        // let c_string = std::ffi::CString::new(hello_string).expect("Failed to create CString");
-        // c_string.into_raw() // return type *const c_char for Rust. String for Scala
-
-        // hello_string.as_ptr() as *const u8 // return type *const u8 for Rust. Pointer, Unit, or Long for Scala
+    unsafe {
+        *error = result.is_err();
+    }
+    match result {
+        Ok(Some(result)) => to_raw_bytes(&result, len),
+        _ => std::ptr::null(),
     }
 }
 
+/// # Safety
+/// rspace is a live pointer from space_new, rdata_ptr points at rdata_len readable bytes, len at a writable usize and error at a writable bool
 #[no_mangle]
-pub extern "C" fn space_get_once_non_durable_concurrent(
+pub unsafe extern "C" fn space_get_once_durable_sequential(
     rspace: *mut Space,
     rdata_ptr: *const u8,
     rdata_len: usize,
-) -> *const c_char {
-    unsafe {
+    len: *mut usize,
+    error: *mut bool,
+) -> *const u8 {
+    let result = unsafe {
         let rdata_buf = std::slice::from_raw_parts(rdata_ptr, rdata_len);
-        let rdata = Retrieve::decode(rdata_buf).unwrap();
+        Retrieve::decode(rdata_buf)
+            .map_err(Into::into)
+            .and_then(|rdata| (*rspace).rspace.get_once_durable_sequential(rdata))
+    };
 
-        let result_option = (*rspace).rspace.get_once_non_durable_concurrent(rdata);
-
-        if result_option.is_some() {
-            let result = result_option.unwrap();
-            let result_string = serde_json::to_string(&result).unwrap();
-            let c_string = std::ffi::CString::new(result_string).expect("Failed to create CString");
-            c_string.into_raw()
-        } else {
-            std::ptr::null()
-        }
+    unsafe {
+        *error = result.is_err();
+    }
+    match result {
+        Ok(Some(result)) => to_raw_bytes(&result, len),
+        _ => std::ptr::null(),
     }
 }
 
+/// # Safety
+/// rspace is a live pointer from space_new, rdata_ptr points at rdata_len readable bytes, len at a writable usize and error at a writable bool
 #[no_mangle]
-pub extern "C" fn space_get_once_durable_sequential(
+pub unsafe extern "C" fn space_get_once_non_durable_concurrent(
     rspace: *mut Space,
     rdata_ptr: *const u8,
     rdata_len: usize,
-) -> *const c_char {
-    unsafe {
+    len: *mut usize,
+    error: *mut bool,
+) -> *const u8 {
+    let result = unsafe {
         let rdata_buf = std::slice::from_raw_parts(rdata_ptr, rdata_len);
-        let rdata = Retrieve::decode(rdata_buf).unwrap();
-
-        let result_option = (*rspace).rspace.get_once_durable_sequential(rdata);
+        Retrieve::decode(rdata_buf)
+            .map_err(Into::into)
+            .and_then(|rdata| (*rspace).rspace.get_once_non_durable_concurrent(rdata))
+    };
 
-        if result_option.is_some() {
-            let result = result_option.unwrap();
-            let result_string = serde_json::to_string(&result).unwrap();
-            let c_string = std::ffi::CString::new(result_string).expect("Failed to create CString");
-            c_string.into_raw()
-        } else {
-            std::ptr::null()
-        }
+    unsafe {
+        *error = result.is_err();
+    }
+    match result {
+        Ok(Some(result)) => to_raw_bytes(&result, len),
+        _ => std::ptr::null(),
     }
 }
 
+/// # Safety
+/// rspace is a live pointer from space_new, rdata_ptr points at rdata_len readable bytes, len at a writable usize and error at a writable bool
 #[no_mangle]
-pub extern "C" fn space_get_once_non_durable_sequential(
+pub unsafe extern "C" fn space_get_once_non_durable_sequential(
     rspace: *mut Space,
     rdata_ptr: *const u8,
     rdata_len: usize,
-) -> *const c_char {
-    unsafe {
+    len: *mut usize,
+    error: *mut bool,
+) -> *const u8 {
+    let result = unsafe {
         let rdata_buf = std::slice::from_raw_parts(rdata_ptr, rdata_len);
-        let rdata = Retrieve::decode(rdata_buf).unwrap();
-
-        let result_option = (*rspace).rspace.get_once_non_durable_sequential(rdata);
+        Retrieve::decode(rdata_buf)
+            .map_err(Into::into)
+            .and_then(|rdata| (*rspace).rspace.get_once_non_durable_sequential(rdata))
+    };
 
-        if result_option.is_some() {
-            let result = result_option.unwrap();
-            let result_string = serde_json::to_string(&result).unwrap();
-            let c_string = std::ffi::CString::new(result_string).expect("Failed to create CString");
-            c_string.into_raw()
-        } else {
-            std::ptr::null()
-        }
+    unsafe {
+        *error = result.is_err();
+    }
+    match result {
+        Ok(Some(result)) => to_raw_bytes(&result, len),
+        _ => std::ptr::null(),
     }
 }
 
 // Verb Set 2
+/// # Safety
+/// rspace is a live pointer from space_new, rdata_ptr points at rdata_len readable bytes, len at a writable usize and error at a writable bool
 #[no_mangle]
-pub extern "C" fn space_get_always_durable_concurrent(
+pub unsafe extern "C" fn space_get_always_durable_concurrent(
     rspace: *mut Space,
     rdata_ptr: *const u8,
     rdata_len: usize,
-) -> *const c_char {
-    unsafe {
+    len: *mut usize,
+    error: *mut bool,
+) -> *const u8 {
+    let result = unsafe {
         let rdata_buf = std::slice::from_raw_parts(rdata_ptr, rdata_len);
-        let rdata = Retrieve::decode(rdata_buf).unwrap();
-
-        let result_option = (*rspace).rspace.get_always_durable_concurrent(rdata);
+        Retrieve::decode(rdata_buf)
+            .map_err(Into::into)
+            .and_then(|rdata| (*rspace).rspace.get_always_durable_concurrent(rdata))
+    };
 
-        if result_option.is_some() {
-            let result = result_option.unwrap();
-            let result_string = serde_json::to_string(&result).unwrap();
-            let c_string = std::ffi::CString::new(result_string).expect("Failed to create CString");
-            c_string.into_raw()
-        } else {
-            std::ptr::null()
-        }
+    unsafe {
+        *error = result.is_err();
+    }
+    match result {
+        Ok(Some(result)) => to_raw_bytes(&result, len),
+        _ => std::ptr::null(),
     }
 }
 
+/// # Safety
+/// rspace is a live pointer from space_new, rdata_ptr points at rdata_len readable bytes, len at a writable usize and error at a writable bool
 #[no_mangle]
-pub extern "C" fn space_get_always_non_durable_concurrent(
+pub unsafe extern "C" fn space_get_always_durable_sequential(
     rspace: *mut Space,
     rdata_ptr: *const u8,
     rdata_len: usize,
-) -> *const c_char {
-    unsafe {
+    len: *mut usize,
+    error: *mut bool,
+) -> *const u8 {
+    let result = unsafe {
         let rdata_buf = std::slice::from_raw_parts(rdata_ptr, rdata_len);
-        let rdata = Retrieve::decode(rdata_buf).unwrap();
+        Retrieve::decode(rdata_buf)
+            .map_err(Into::into)
+            .and_then(|rdata| (*rspace).rspace.get_always_durable_sequential(rdata))
+    };
 
-        let result_option = (*rspace).rspace.get_always_non_durable_concurrent(rdata);
-
-        if result_option.is_some() {
-            let result = result_option.unwrap();
-            let result_string = serde_json::to_string(&result).unwrap();
-            let c_string = std::ffi::CString::new(result_string).expect("Failed to create CString");
-            c_string.into_raw()
-        } else {
-            std::ptr::null()
-        }
+    unsafe {
+        *error = result.is_err();
+    }
+    match result {
+        Ok(Some(result)) => to_raw_bytes(&result, len),
+        _ => std::ptr::null(),
     }
 }
 
+/// # Safety
+/// rspace is a live pointer from space_new, rdata_ptr points at rdata_len readable bytes, len at a writable usize and error at a writable bool
 #[no_mangle]
-pub extern "C" fn space_get_always_durable_sequential(
+pub unsafe extern "C" fn space_get_always_non_durable_concurrent(
     rspace: *mut Space,
     rdata_ptr: *const u8,
     rdata_len: usize,
-) -> *const c_char {
-    unsafe {
+    len: *mut usize,
+    error: *mut bool,
+) -> *const u8 {
+    let result = unsafe {
         let rdata_buf = std::slice::from_raw_parts(rdata_ptr, rdata_len);
-        let rdata = Retrieve::decode(rdata_buf).unwrap();
+        Retrieve::decode(rdata_buf)
+            .map_err(Into::into)
+            .and_then(|rdata| (*rspace).rspace.get_always_non_durable_concurrent(rdata))
+    };
 
-        let result_option = (*rspace).rspace.get_always_durable_sequential(rdata);
-
-        if result_option.is_some() {
-            let result = result_option.unwrap();
-            let result_string = serde_json::to_string(&result).unwrap();
-            let c_string = std::ffi::CString::new(result_string).expect("Failed to create CString");
-            c_string.into_raw()
-        } else {
-            std::ptr::null()
-        }
+    unsafe {
+        *error = result.is_err();
+    }
+    match result {
+        Ok(Some(result)) => to_raw_bytes(&result, len),
+        _ => std::ptr::null(),
     }
 }
 
+/// # Safety
+/// rspace is a live pointer from space_new, rdata_ptr points at rdata_len readable bytes, len at a writable usize and error at a writable bool
 #[no_mangle]
-pub extern "C" fn space_get_always_non_durable_sequential(
+pub unsafe extern "C" fn space_get_always_non_durable_sequential(
     rspace: *mut Space,
     rdata_ptr: *const u8,
     rdata_len: usize,
-) -> *const c_char {
-    unsafe {
+    len: *mut usize,
+    error: *mut bool,
+) -> *const u8 {
+    let result = unsafe {
         let rdata_buf = std::slice::from_raw_parts(rdata_ptr, rdata_len);
-        let rdata = Retrieve::decode(rdata_buf).unwrap();
-
-        let result_option = (*rspace).rspace.get_always_non_durable_sequential(rdata);
+        Retrieve::decode(rdata_buf)
+            .map_err(Into::into)
+            .and_then(|rdata| (*rspace).rspace.get_always_non_durable_sequential(rdata))
+    };
 
-        if result_option.is_some() {
-            let result = result_option.unwrap();
-            let result_string = serde_json::to_string(&result).unwrap();
-            let c_string = std::ffi::CString::new(result_string).expect("Failed to create CString");
-            c_string.into_raw()
-        } else {
-            std::ptr::null()
-        }
+    unsafe {
+        *error = result.is_err();
+    }
+    match result {
+        Ok(Some(result)) => to_raw_bytes(&result, len),
+        _ => std::ptr::null(),
     }
 }
 
 // Verb Set 3
+/// # Safety
+/// rspace is a live pointer from space_new, cdata_ptr points at cdata_len readable bytes, len at a writable usize and error at a writable bool
 #[no_mangle]
-pub extern "C" fn space_put_once_durable_concurrent(
+pub unsafe extern "C" fn space_put_once_durable_concurrent(
     rspace: *mut Space,
     cdata_ptr: *const u8,
     cdata_len: usize,
-) -> *const *const c_char {
-    unsafe {
+    len: *mut usize,
+    error: *mut bool,
+) -> *const u8 {
+    let result = unsafe {
         let cdata_buf = std::slice::from_raw_parts(cdata_ptr, cdata_len);
-        let cdata = Commit::decode(cdata_buf).unwrap();
-
-        let result_option = (*rspace).rspace.put_once_durable_concurrent(cdata);
-
-        if result_option.is_some() {
-            let results = result_option.unwrap();
-            let mut result_array: Vec<String> = vec![];
-
-            for res in results {
-                let result_string = serde_json::to_string(&res).unwrap();
-                result_array.push(result_string);
-            }
+        Commit::decode(cdata_buf)
+            .map_err(Into::into)
+            .and_then(|cdata| (*rspace).rspace.put_once_durable_concurrent(cdata))
+    };
 
-            let mut ptrs: Vec<_> = result_array
-                .into_iter()
-                .map(|s| {
-                    CString::new(s)
-                        .expect("Failed to create CString")
-                        .into_raw()
-                })
-                .collect();
-            ptrs.push(std::ptr::null_mut());
-            let boxed_ptrs = ptrs.into_boxed_slice();
-
-            Box::into_raw(boxed_ptrs) as *const *const c_char // return type *const *const c_char for Rust. Array[String] for Scala
-        } else {
-            std::ptr::null()
-        }
-
-        // Box::into_raw(Box::new(result_option)) return type *mut Option<Vec<OptionResult>> for Rust. ?? for Scala
+    unsafe {
+        *error = result.is_err();
+    }
+    match result {
+        Ok(Some(results)) => to_raw_bytes(&ResultList { results }, len),
+        _ => std::ptr::null(),
     }
 }
 
+/// # Safety
+/// rspace is a live pointer from space_new, cdata_ptr points at cdata_len readable bytes, len at a writable usize and error at a writable bool
 #[no_mangle]
-pub extern "C" fn space_put_once_non_durable_concurrent(
+pub unsafe extern "C" fn space_put_once_durable_sequential(
     rspace: *mut Space,
     cdata_ptr: *const u8,
     cdata_len: usize,
-) -> *const *const c_char {
-    unsafe {
+    len: *mut usize,
+    error: *mut bool,
+) -> *const u8 {
+    let result = unsafe {
         let cdata_buf = std::slice::from_raw_parts(cdata_ptr, cdata_len);
-        let cdata = Commit::decode(cdata_buf).unwrap();
+        Commit::decode(cdata_buf)
+            .map_err(Into::into)
+            .and_then(|cdata| (*rspace).rspace.put_once_durable_sequential(cdata))
+    };
 
-        let result_option = (*rspace).rspace.put_once_non_durable_concurrent(cdata);
-
-        if result_option.is_some() {
-            let results = result_option.unwrap();
-            let mut result_array: Vec<String> = vec![];
-
-            for res in results {
-                let result_string = serde_json::to_string(&res).unwrap();
-                result_array.push(result_string);
-            }
-
-            let mut ptrs: Vec<_> = result_array
-                .into_iter()
-                .map(|s| {
-                    CString::new(s)
-                        .expect("Failed to create CString")
-                        .into_raw()
-                })
-                .collect();
-            ptrs.push(std::ptr::null_mut());
-            let boxed_ptrs = ptrs.into_boxed_slice();
-
-            Box::into_raw(boxed_ptrs) as *const *const c_char
-        } else {
-            std::ptr::null()
-        }
+    unsafe {
+        *error = result.is_err();
+    }
+    match result {
+        Ok(Some(results)) => to_raw_bytes(&ResultList { results }, len),
+        _ => std::ptr::null(),
     }
 }
 
+/// # Safety
+/// rspace is a live pointer from space_new, cdata_ptr points at cdata_len readable bytes, len at a writable usize and error at a writable bool
 #[no_mangle]
-pub extern "C" fn space_put_once_durable_sequential(
+pub unsafe extern "C" fn space_put_once_non_durable_concurrent(
     rspace: *mut Space,
     cdata_ptr: *const u8,
     cdata_len: usize,
-) -> *const *const c_char {
-    unsafe {
+    len: *mut usize,
+    error: *mut bool,
+) -> *const u8 {
+    let result = unsafe {
         let cdata_buf = std::slice::from_raw_parts(cdata_ptr, cdata_len);
-        let cdata = Commit::decode(cdata_buf).unwrap();
-
-        let result_option = (*rspace).rspace.put_once_durable_sequential(cdata);
-
-        if result_option.is_some() {
-            let results = result_option.unwrap();
-            let mut result_array: Vec<String> = vec![];
-
-            for res in results {
-                let result_string = serde_json::to_string(&res).unwrap();
-                result_array.push(result_string);
-            }
+        Commit::decode(cdata_buf)
+            .map_err(Into::into)
+            .and_then(|cdata| (*rspace).rspace.put_once_non_durable_concurrent(cdata))
+    };
 
-            let mut ptrs: Vec<_> = result_array
-                .into_iter()
-                .map(|s| {
-                    CString::new(s)
-                        .expect("Failed to create CString")
-                        .into_raw()
-                })
-                .collect();
-            ptrs.push(std::ptr::null_mut());
-            let boxed_ptrs = ptrs.into_boxed_slice();
-
-            Box::into_raw(boxed_ptrs) as *const *const c_char
-        } else {
-            std::ptr::null()
-        }
+    unsafe {
+        *error = result.is_err();
+    }
+    match result {
+        Ok(Some(results)) => to_raw_bytes(&ResultList { results }, len),
+        _ => std::ptr::null(),
     }
 }
 
+/// # Safety
+/// rspace is a live pointer from space_new, cdata_ptr points at cdata_len readable bytes, len at a writable usize and error at a writable bool
 #[no_mangle]
-pub extern "C" fn space_put_once_non_durable_sequential(
+pub unsafe extern "C" fn space_put_once_non_durable_sequential(
     rspace: *mut Space,
     cdata_ptr: *const u8,
     cdata_len: usize,
-) -> *const *const c_char {
-    unsafe {
+    len: *mut usize,
+    error: *mut bool,
+) -> *const u8 {
+    let result = unsafe {
         let cdata_buf = std::slice::from_raw_parts(cdata_ptr, cdata_len);
-        let cdata = Commit::decode(cdata_buf).unwrap();
-
-        let result_option = (*rspace).rspace.put_once_non_durable_sequential(cdata);
+        Commit::decode(cdata_buf)
+            .map_err(Into::into)
+            .and_then(|cdata| (*rspace).rspace.put_once_non_durable_sequential(cdata))
+    };
 
-        if result_option.is_some() {
-            let results = result_option.unwrap();
-            let mut result_array: Vec<String> = vec![];
-
-            for res in results {
-                let result_string = serde_json::to_string(&res).unwrap();
-                result_array.push(result_string);
-            }
-
-            let mut ptrs: Vec<_> = result_array
-                .into_iter()
-                .map(|s| {
-                    CString::new(s)
-                        .expect("Failed to create CString")
-                        .into_raw()
-                })
-                .collect();
-            ptrs.push(std::ptr::null_mut());
-            let boxed_ptrs = ptrs.into_boxed_slice();
-
-            Box::into_raw(boxed_ptrs) as *const *const c_char
-        } else {
-            std::ptr::null()
-        }
+    unsafe {
+        *error = result.is_err();
+    }
+    match result {
+        Ok(Some(results)) => to_raw_bytes(&ResultList { results }, len),
+        _ => std::ptr::null(),
     }
 }
 
 // Verb Set 4
+/// # Safety
+/// rspace is a live pointer from space_new, cdata_ptr points at cdata_len readable bytes, len at a writable usize and error at a writable bool
 #[no_mangle]
-pub extern "C" fn space_put_always_durable_concurrent(
+pub unsafe extern "C" fn space_put_always_durable_concurrent(
     rspace: *mut Space,
     cdata_ptr: *const u8,
     cdata_len: usize,
-) -> *const *const c_char {
-    unsafe {
+    len: *mut usize,
+    error: *mut bool,
+) -> *const u8 {
+    let result = unsafe {
         let cdata_buf = std::slice::from_raw_parts(cdata_ptr, cdata_len);
-        let cdata = Commit::decode(cdata_buf).unwrap();
-
-        let result_option = (*rspace).rspace.put_always_durable_concurrent(cdata);
-
-        if result_option.is_some() {
-            let results = result_option.unwrap();
-            let mut result_array: Vec<String> = vec![];
-
-            for res in results {
-                let result_string = serde_json::to_string(&res).unwrap();
-                result_array.push(result_string);
-            }
+        Commit::decode(cdata_buf)
+            .map_err(Into::into)
+            .and_then(|cdata| (*rspace).rspace.put_always_durable_concurrent(cdata))
+    };
 
-            let mut ptrs: Vec<_> = result_array
-                .into_iter()
-                .map(|s| {
-                    CString::new(s)
-                        .expect("Failed to create CString")
-                        .into_raw()
-                })
-                .collect();
-            ptrs.push(std::ptr::null_mut());
-            let boxed_ptrs = ptrs.into_boxed_slice();
-
-            Box::into_raw(boxed_ptrs) as *const *const c_char
-        } else {
-            std::ptr::null()
-        }
+    unsafe {
+        *error = result.is_err();
+    }
+    match result {
+        Ok(Some(results)) => to_raw_bytes(&ResultList { results }, len),
+        _ => std::ptr::null(),
     }
 }
 
+/// # Safety
+/// rspace is a live pointer from space_new, cdata_ptr points at cdata_len readable bytes, len at a writable usize and error at a writable bool
 #[no_mangle]
-pub extern "C" fn space_put_always_non_durable_concurrent(
+pub unsafe extern "C" fn space_put_always_durable_sequential(
     rspace: *mut Space,
     cdata_ptr: *const u8,
     cdata_len: usize,
-) -> *const *const c_char {
-    unsafe {
+    len: *mut usize,
+    error: *mut bool,
+) -> *const u8 {
+    let result = unsafe {
         let cdata_buf = std::slice::from_raw_parts(cdata_ptr, cdata_len);
-        let cdata = Commit::decode(cdata_buf).unwrap();
-
-        let result_option = (*rspace).rspace.put_always_non_durable_concurrent(cdata);
+        Commit::decode(cdata_buf)
+            .map_err(Into::into)
+            .and_then(|cdata| (*rspace).rspace.put_always_durable_sequential(cdata))
+    };
 
-        if result_option.is_some() {
-            let results = result_option.unwrap();
-            let mut result_array: Vec<String> = vec![];
-
-            for res in results {
-                let result_string = serde_json::to_string(&res).unwrap();
-                result_array.push(result_string);
-            }
-
-            let mut ptrs: Vec<_> = result_array
-                .into_iter()
-                .map(|s| {
-                    CString::new(s)
-                        .expect("Failed to create CString")
-                        .into_raw()
-                })
-                .collect();
-            ptrs.push(std::ptr::null_mut());
-            let boxed_ptrs = ptrs.into_boxed_slice();
-
-            Box::into_raw(boxed_ptrs) as *const *const c_char
-        } else {
-            std::ptr::null()
-        }
+    unsafe {
+        *error = result.is_err();
+    }
+    match result {
+        Ok(Some(results)) => to_raw_bytes(&ResultList { results }, len),
+        _ => std::ptr::null(),
     }
 }
 
+/// # Safety
+/// rspace is a live pointer from space_new, cdata_ptr points at cdata_len readable bytes, len at a writable usize and error at a writable bool
 #[no_mangle]
-pub extern "C" fn space_put_always_durable_sequential(
+pub unsafe extern "C" fn space_put_always_non_durable_concurrent(
     rspace: *mut Space,
     cdata_ptr: *const u8,
     cdata_len: usize,
-) -> *const *const c_char {
-    unsafe {
+    len: *mut usize,
+    error: *mut bool,
+) -> *const u8 {
+    let result = unsafe {
         let cdata_buf = std::slice::from_raw_parts(cdata_ptr, cdata_len);
-        let cdata = Commit::decode(cdata_buf).unwrap();
-
-        let result_option = (*rspace).rspace.put_always_durable_sequential(cdata);
-
-        if result_option.is_some() {
-            let results = result_option.unwrap();
-            let mut result_array: Vec<String> = vec![];
-
-            for res in results {
-                let result_string = serde_json::to_string(&res).unwrap();
-                result_array.push(result_string);
-            }
+        Commit::decode(cdata_buf)
+            .map_err(Into::into)
+            .and_then(|cdata| (*rspace).rspace.put_always_non_durable_concurrent(cdata))
+    };
 
-            let mut ptrs: Vec<_> = result_array
-                .into_iter()
-                .map(|s| {
-                    CString::new(s)
-                        .expect("Failed to create CString")
-                        .into_raw()
-                })
-                .collect();
-            ptrs.push(std::ptr::null_mut());
-            let boxed_ptrs = ptrs.into_boxed_slice();
-
-            Box::into_raw(boxed_ptrs) as *const *const c_char
-        } else {
-            std::ptr::null()
-        }
+    unsafe {
+        *error = result.is_err();
+    }
+    match result {
+        Ok(Some(results)) => to_raw_bytes(&ResultList { results }, len),
+        _ => std::ptr::null(),
     }
 }
 
+/// # Safety
+/// rspace is a live pointer from space_new, cdata_ptr points at cdata_len readable bytes, len at a writable usize and error at a writable bool
 #[no_mangle]
-pub extern "C" fn space_put_always_non_durable_sequential(
+pub unsafe extern "C" fn space_put_always_non_durable_sequential(
     rspace: *mut Space,
     cdata_ptr: *const u8,
     cdata_len: usize,
-) -> *const *const c_char {
-    unsafe {
+    len: *mut usize,
+    error: *mut bool,
+) -> *const u8 {
+    let result = unsafe {
         let cdata_buf = std::slice::from_raw_parts(cdata_ptr, cdata_len);
-        let cdata = Commit::decode(cdata_buf).unwrap();
-
-        let result_option = (*rspace).rspace.put_always_non_durable_sequential(cdata);
-
-        if result_option.is_some() {
-            let results = result_option.unwrap();
-            let mut result_array: Vec<String> = vec![];
+        Commit::decode(cdata_buf)
+            .map_err(Into::into)
+            .and_then(|cdata| (*rspace).rspace.put_always_non_durable_sequential(cdata))
+    };
 
-            for res in results {
-                let result_string = serde_json::to_string(&res).unwrap();
-                result_array.push(result_string);
-            }
-
-            let mut ptrs: Vec<_> = result_array
-                .into_iter()
-                .map(|s| {
-                    CString::new(s)
-                        .expect("Failed to create CString")
-                        .into_raw()
-                })
-                .collect();
-            ptrs.push(std::ptr::null_mut());
-            let boxed_ptrs = ptrs.into_boxed_slice();
-
-            Box::into_raw(boxed_ptrs) as *const *const c_char
-        } else {
-            std::ptr::null()
-        }
+    unsafe {
+        *error = result.is_err();
+    }
+    match result {
+        Ok(Some(results)) => to_raw_bytes(&ResultList { results }, len),
+        _ => std::ptr::null(),
     }
 }
 
+/// # Safety
+/// rspace is a live pointer from space_new
 #[no_mangle]
-pub extern "C" fn is_empty(rspace: *mut Space) -> bool {
+pub unsafe extern "C" fn is_empty(rspace: *mut Space) -> bool {
     unsafe { (*rspace).rspace.is_empty() }
 }
 
+/// # Safety
+/// rspace is a live pointer from space_new and channel a nul-terminated UTF-8 string
 #[no_mangle]
-pub extern "C" fn space_print(rspace: *mut Space, channel: *const c_char) -> () {
+pub unsafe extern "C" fn space_print(rspace: *mut Space, channel: *const c_char) {
     unsafe {
         let channel_str = CStr::from_ptr(channel).to_str().unwrap();
-        (*rspace).rspace.print_store(channel_str)
+        (*rspace).rspace.print_store(channel_str);
     }
 }
 
+/// # Safety
+/// rspace is a live pointer from space_new
 #[no_mangle]
-pub extern "C" fn space_clear(rspace: *mut Space) -> () {
+pub unsafe extern "C" fn space_flush(rspace: *mut Space) -> bool {
+    unsafe { (*rspace).rspace.flush().is_ok() }
+}
+
+/// # Safety
+/// rspace is a live pointer from space_new
+#[no_mangle]
+pub unsafe extern "C" fn space_clear(rspace: *mut Space) {
     unsafe {
         (*rspace).rspace.clear_store();
     }
 }
+
+// Inspection. Each call returns an encoded protobuf message and writes its length
+// to len, or returns null. Free the message with space_free_bytes
+fn to_raw_bytes(message: &impl Message, len: *mut usize) -> *const u8 {
+    let buf = message.encode_to_vec().into_boxed_slice();
+    unsafe {
+        *len = buf.len();
+    }
+    Box::into_raw(buf) as *const u8
+}
+
+/// # Safety
+/// ptr and len are a message returned by one of the calls here, not freed yet
+#[no_mangle]
+pub unsafe extern "C" fn space_free_bytes(ptr: *mut u8, len: usize) {
+    unsafe {
+        drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(ptr, len)));
+    }
+}
+
+/// # Safety
+/// rspace is a live pointer from space_new and len points at a writable usize
+#[no_mangle]
+pub unsafe extern "C" fn space_channels(
+    rspace: *mut Space,
+    store: i32,
+    len: *mut usize,
+) -> *const u8 {
+    let channels = Store::from_i32(store)
+        .ok_or_else(|| "unknown store".into())
+        .and_then(|store| unsafe { (*rspace).rspace.channels(store) });
+
+    match channels {
+        Ok(channels) => to_raw_bytes(&ChannelList { channels }, len),
+        Err(_) => std::ptr::null(),
+    }
+}
+
+/// # Safety
+/// rspace is a live pointer from space_new, channel a NUL terminated string and len points at a writable usize
+#[no_mangle]
+pub unsafe extern "C" fn space_get_data(
+    rspace: *mut Space,
+    store: i32,
+    channel: *const c_char,
+    len: *mut usize,
+) -> *const u8 {
+    let data = Store::from_i32(store)
+        .ok_or_else(|| "unknown store".into())
+        .and_then(|store| unsafe {
+            let channel_str = CStr::from_ptr(channel).to_str()?;
+            (*rspace).rspace.get_data(store, channel_str)
+        });
+
+    match data {
+        Ok(data) => to_raw_bytes(&DataList { data }, len),
+        Err(_) => std::ptr::null(),
+    }
+}
+
+// channels is an encoded ChannelList
+/// # Safety
+/// rspace is a live pointer from space_new, channels_ptr points at channels_len readable bytes and len at a writable usize
+#[no_mangle]
+pub unsafe extern "C" fn space_get_waiting_continuations(
+    rspace: *mut Space,
+    store: i32,
+    channels_ptr: *const u8,
+    channels_len: usize,
+    len: *mut usize,
+) -> *const u8 {
+    let continuations = Store::from_i32(store)
+        .ok_or_else(|| "unknown store".into())
+        .and_then(|store| unsafe {
+            let channels_buf = std::slice::from_raw_parts(channels_ptr, channels_len);
+            let channels = ChannelList::decode(channels_buf)?.channels;
+            (*rspace).rspace.get_waiting_continuations(store, &channels)
+        });
+
+    match continuations {
+        Ok(continuations) => to_raw_bytes(&ContinuationList { continuations }, len),
+        Err(_) => std::ptr::null(),
+    }
+}
+
+/// # Safety
+/// rspace is a live pointer from space_new, channel a NUL terminated string and len points at a writable usize
+#[no_mangle]
+pub unsafe extern "C" fn space_get_joins(
+    rspace: *mut Space,
+    store: i32,
+    channel: *const c_char,
+    len: *mut usize,
+) -> *const u8 {
+    let joins = Store::from_i32(store)
+        .ok_or_else(|| "unknown store".into())
+        .and_then(|store| unsafe {
+            let channel_str = CStr::from_ptr(channel).to_str()?;
+            (*rspace).rspace.get_joins(store, channel_str)
+        });
+
+    match joins {
+        Ok(joins) => to_raw_bytes(
+            &JoinList {
+                joins: joins
+                    .into_iter()
+                    .map(|channels| ChannelList { channels })
+                    .collect(),
+            },
+            len,
+        ),
+        Err(_) => std::ptr::null(),
+    }
+}
+
+/// # Safety
+/// rspace is a live pointer from space_new and len points at a writable usize
+#[no_mangle]
+pub unsafe extern "C" fn space_to_map(
+    rspace: *mut Space,
+    store: i32,
+    len: *mut usize,
+) -> *const u8 {
+    let map = Store::from_i32(store)
+        .ok_or_else(|| "unknown store".into())
+        .and_then(|store| unsafe { (*rspace).rspace.to_map(store) });
+
+    match map {
+        Ok(map) => to_raw_bytes(
+            &StoreMap {
+                rows: map
+                    .into_iter()
+                    .map(|(channels, entries)| Row {
+                        channels,
+                        data: entries.data,
+                        continuations: entries.continuations,
+                    })
+                    .collect(),
+            },
+            len,
+        ),
+        Err(_) => std::ptr::null(),
+    }
+}
+
+// Encoded VerifyReport of one store, repair fixes what it reports
+/// # Safety
+/// rspace is a live pointer from space_new and len points at a writable usize
+#[no_mangle]
+pub unsafe extern "C" fn space_verify(
+    rspace: *mut Space,
+    store: i32,
+    repair: bool,
+    len: *mut usize,
+) -> *const u8 {
+    let report = Store::from_i32(store)
+        .ok_or_else(|| "unknown store".into())
+        .and_then(|store| unsafe { (*rspace).rspace.verify(store, repair) });
+
+    match report {
+        Ok(report) => to_raw_bytes(&report, len),
+        Err(_) => std::ptr::null(),
+    }
+}
+
+// Starts keeping metrics in memory, for space_metrics_snapshot. Returns false if it could not
+#[no_mangle]
+pub extern "C" fn space_enable_metrics() -> bool {
+    metrics::recorder().is_some() || metrics::set_recorder(Arc::new(InMemoryMetrics::new())).is_ok()
+}
+
+// Encoded MetricsSnapshot, with the store sizes as of now
+/// # Safety
+/// rspace is a live pointer from space_new and len points at a writable usize
+#[no_mangle]
+pub unsafe extern "C" fn space_metrics_snapshot(rspace: *mut Space, len: *mut usize) -> *const u8 {
+    match unsafe { (*rspace).rspace.record_store_sizes() } {
+        Ok(()) => to_raw_bytes(&metrics::snapshot(), len),
+        Err(_) => std::ptr::null(),
+    }
+}
+
+// Log filter in EnvFilter syntax, e.g. "rspace_plus_plus=debug". Returns false if it does not parse
+/// # Safety
+/// filter is a NUL terminated string
+#[no_mangle]
+pub unsafe extern "C" fn space_set_log_filter(filter: *const c_char) -> bool {
+    let filter = unsafe { CStr::from_ptr(filter) }.to_str();
+    filter.is_ok_and(|filter| logging::set_filter(filter).is_ok())
+}
+
+/*
+Calls callback with each encoded ChannelChange at channel, on a thread of its own,
+until space_unwatch. The bytes are only valid during the call. Returns the
+watch's id, 0 if it could not start.
+*/
+/// # Safety
+/// rspace is a live pointer from space_new that outlives the watch, channel a NUL terminated string
+#[no_mangle]
+pub unsafe extern "C" fn space_watch(
+    rspace: *mut Space,
+    channel: *const c_char,
+    callback: WatchCallback,
+) -> u64 {
+    let channel = match unsafe { CStr::from_ptr(channel) }.to_str() {
+        Ok(channel) => channel,
+        Err(_) => return 0,
+    };
+    let watch = unsafe { (*rspace).rspace.watch(channel) };
+    let id = watch.id();
+    let channel = channel.to_string();
+
+    let spawned = thread::Builder::new()
+        .name(format!("watch-{}", id))
+        .spawn(move || {
+            for change in watch {
+                let bytes = change.to_proto(&channel).encode_to_vec();
+                callback(bytes.as_ptr(), bytes.len());
+            }
+        });
+    match spawned {
+        Ok(_) => id,
+        Err(_) => {
+            unsafe { (*rspace).rspace.unwatch(id) };
+            0
+        }
+    }
+}
+
+// Stops the watch with id, its callback still gets changes queued before this
+/// # Safety
+/// rspace is a live pointer from space_new
+#[no_mangle]
+pub unsafe extern "C" fn space_unwatch(rspace: *mut Space, id: u64) -> bool {
+    unsafe { (*rspace).rspace.unwatch(id) }
+}
+
+// Log lines go to callback from now on, or back to stderr for null
+#[no_mangle]
+pub extern "C" fn space_set_log_callback(callback: Option<LogCallback>) -> bool {
+    logging::set_callback(callback).is_ok()
+}
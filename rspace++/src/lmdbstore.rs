@@ -0,0 +1,289 @@
+#![allow(dead_code)]
+
+use crate::keyvaluestore::{
+    Durability, Flusher, KeyValueRead, KeyValueStore, Storage, SyncPolicy, Visitor,
+};
+use crate::metrics;
+use heed::flags::Flags;
+use heed::types::*;
+use heed::{Database, Env, EnvOpenOptions, RoTxn, RwTxn};
+use std::error::Error;
+use std::fs;
+use std::ops::Bound;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+
+/*
+Durable storage: one LMDB environment per directory. With Sync durability LMDB
+syncs each commit itself, data pages before the meta page that points at them.
+Otherwise the environment is opened without syncing on commit and the store syncs
+it as its durability says.
+*/
+pub struct LmdbStore {
+    env: Env,
+    db: Database<ByteSlice, SerdeBincode<Vec<u8>>>,
+    sync: Arc<SyncPolicy>,
+    // whether LMDB syncs each commit, else committed does as sync says
+    lmdb_syncs: bool,
+    // syncs a group commit store gone idle
+    _flusher: Option<Flusher>,
+}
+
+// Transactions report how long they were open as lmdb.read_txn and lmdb.write_txn, see metrics.rs
+pub struct LmdbReadTxn<'a> {
+    txn: RoTxn<'a>,
+    db: Database<ByteSlice, SerdeBincode<Vec<u8>>>,
+    started: Instant,
+}
+
+pub struct LmdbWriteTxn<'a> {
+    txn: RwTxn<'a, 'a>,
+    db: Database<ByteSlice, SerdeBincode<Vec<u8>>>,
+    store: &'a LmdbStore,
+    started: Instant,
+}
+
+impl LmdbStore {
+    pub fn open(path: &Path) -> Result<LmdbStore, Box<dyn Error>> {
+        LmdbStore::open_with_durability(path, Durability::Sync)
+    }
+
+    pub fn open_with_durability(
+        path: &Path,
+        durability: Durability,
+    ) -> Result<LmdbStore, Box<dyn Error>> {
+        fs::create_dir_all(path)?;
+        let mut options = EnvOpenOptions::new();
+        options.max_dbs(2);
+        /*
+        heed shares one environment per path, so a path already open in this process
+        keeps the flags it was first opened with, and the store syncs it itself. NoTls
+        ties a read transaction to its handle instead of the thread, so an exporter can
+        keep its snapshot while the same thread reads again
+        */
+        let shared = heed::env_closing_event(path.canonicalize()?).is_some();
+        let lmdb_syncs = durability == Durability::Sync && !shared;
+        unsafe {
+            if !lmdb_syncs {
+                options.flag(Flags::MdbNoSync);
+            }
+            options.flag(Flags::MdbNoTls);
+        }
+        let env = options.open(path)?;
+        let db = env.create_database(Some("entries"))?;
+
+        let sync = Arc::new(SyncPolicy::new(durability));
+        let flusher = Flusher::spawn(sync.clone(), {
+            let (env, sync) = (env.clone(), sync.clone());
+            move || {
+                env.force_sync()?;
+                sync.synced()
+            }
+        });
+
+        Ok(LmdbStore {
+            env,
+            db,
+            sync,
+            lmdb_syncs,
+            _flusher: flusher,
+        })
+    }
+
+    /*
+    Opens an existing store without the right to write to it, for looking at a store
+    another process owns. Write transactions on it fail, unless this process already
+    opened the path writable: heed hands back that environment instead.
+    */
+    pub fn open_read_only(path: &Path) -> Result<LmdbStore, Box<dyn Error>> {
+        if !path.is_dir() {
+            return Err(format!("no store at {}", path.display()).into());
+        }
+        let mut options = EnvOpenOptions::new();
+        options.max_dbs(2);
+        unsafe {
+            options.flag(Flags::MdbRdOnly);
+            options.flag(Flags::MdbNoTls);
+        }
+        let env = options.open(path)?;
+        let db = env
+            .open_database(Some("entries"))?
+            .ok_or_else(|| format!("no entries database in {}", path.display()))?;
+
+        Ok(LmdbStore {
+            env,
+            db,
+            sync: Arc::new(SyncPolicy::new(Durability::NoSync)),
+            lmdb_syncs: false,
+            _flusher: None,
+        })
+    }
+
+    pub fn durability(&self) -> Durability {
+        self.sync.durability()
+    }
+
+    // Commits not yet synced
+    pub fn pending(&self) -> Result<usize, Box<dyn Error>> {
+        self.sync.pending()
+    }
+
+    fn committed(&self) -> Result<(), Box<dyn Error>> {
+        if self.sync.commit_is_due()? && !self.lmdb_syncs {
+            self.env.force_sync()?;
+        }
+
+        Ok(())
+    }
+}
+
+// Whatever is still unsynced gets synced when the store goes away
+impl Drop for LmdbStore {
+    fn drop(&mut self) {
+        if self.sync.pending().unwrap_or(0) > 0 {
+            let _ = self.flush();
+        }
+    }
+}
+
+impl Storage for LmdbStore {
+    type ReadTxn<'a> = LmdbReadTxn<'a>;
+    type WriteTxn<'a> = LmdbWriteTxn<'a>;
+
+    fn read_txn(&self) -> Result<LmdbReadTxn<'_>, Box<dyn Error>> {
+        Ok(LmdbReadTxn {
+            txn: self.env.read_txn()?,
+            db: self.db,
+            started: Instant::now(),
+        })
+    }
+
+    fn write_txn(&self) -> Result<LmdbWriteTxn<'_>, Box<dyn Error>> {
+        Ok(LmdbWriteTxn {
+            txn: self.env.write_txn()?,
+            db: self.db,
+            store: self,
+            started: Instant::now(),
+        })
+    }
+
+    fn is_empty(&self) -> Result<bool, Box<dyn Error>> {
+        let rtxn = self.env.read_txn()?;
+        Ok(self.db.is_empty(&rtxn)?)
+    }
+
+    fn clear(&self) -> Result<(), Box<dyn Error>> {
+        let mut wtxn = self.env.write_txn()?;
+        self.db.clear(&mut wtxn)?;
+        wtxn.commit()?;
+
+        self.committed()
+    }
+
+    fn flush(&self) -> Result<(), Box<dyn Error>> {
+        self.env.force_sync()?;
+        self.sync.synced()
+    }
+}
+
+fn get(
+    db: &Database<ByteSlice, SerdeBincode<Vec<u8>>>,
+    txn: &RoTxn,
+    key: &[u8],
+) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+    Ok(db.get(txn, key)?)
+}
+
+// Pairs in key order as LMDB hands them out
+type Pairs<'t> = Box<dyn Iterator<Item = heed::Result<(&'t [u8], Vec<u8>)>> + 't>;
+
+fn iterate_prefix(
+    db: &Database<ByteSlice, SerdeBincode<Vec<u8>>>,
+    txn: &RoTxn,
+    prefix: &[u8],
+    f: &mut Visitor,
+) -> Result<(), Box<dyn Error>> {
+    // LMDB rejects an empty key, so the empty prefix walks the whole database
+    let entries: Pairs = match prefix {
+        [] => Box::new(db.iter(txn)?),
+        _ => Box::new(db.prefix_iter(txn, prefix)?),
+    };
+    for entry in entries {
+        let (key, value) = entry?;
+        if !f(key, &value)? {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn iterate_from(
+    db: &Database<ByteSlice, SerdeBincode<Vec<u8>>>,
+    txn: &RoTxn,
+    start: &[u8],
+    f: &mut Visitor,
+) -> Result<(), Box<dyn Error>> {
+    let entries: Pairs = match start {
+        [] => Box::new(db.iter(txn)?),
+        _ => Box::new(db.range(txn, &(Bound::Included(start), Bound::Unbounded))?),
+    };
+    for entry in entries {
+        let (key, value) = entry?;
+        if !f(key, &value)? {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+impl Drop for LmdbReadTxn<'_> {
+    fn drop(&mut self) {
+        metrics::record("lmdb.read_txn", self.started.elapsed());
+    }
+}
+
+impl KeyValueRead for LmdbReadTxn<'_> {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+        get(&self.db, &self.txn, key)
+    }
+
+    fn iterate_prefix(&self, prefix: &[u8], f: &mut Visitor) -> Result<(), Box<dyn Error>> {
+        iterate_prefix(&self.db, &self.txn, prefix, f)
+    }
+
+    fn iterate_from(&self, start: &[u8], f: &mut Visitor) -> Result<(), Box<dyn Error>> {
+        iterate_from(&self.db, &self.txn, start, f)
+    }
+}
+
+impl KeyValueRead for LmdbWriteTxn<'_> {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+        get(&self.db, &self.txn, key)
+    }
+
+    fn iterate_prefix(&self, prefix: &[u8], f: &mut Visitor) -> Result<(), Box<dyn Error>> {
+        iterate_prefix(&self.db, &self.txn, prefix, f)
+    }
+}
+
+impl KeyValueStore for LmdbWriteTxn<'_> {
+    fn put(&mut self, key: &[u8], value: &[u8]) -> Result<(), Box<dyn Error>> {
+        self.db.put(&mut self.txn, key, &value.to_vec())?;
+        Ok(())
+    }
+
+    fn delete(&mut self, key: &[u8]) -> Result<(), Box<dyn Error>> {
+        self.db.delete(&mut self.txn, key)?;
+        Ok(())
+    }
+
+    fn commit(self) -> Result<(), Box<dyn Error>> {
+        self.txn.commit()?;
+        let committed = self.store.committed();
+        metrics::record("lmdb.write_txn", self.started.elapsed());
+        committed
+    }
+}
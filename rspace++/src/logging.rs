@@ -0,0 +1,144 @@
+#![allow(dead_code)]
+
+use std::error::Error;
+use std::ffi::{c_char, CString};
+use std::fmt::{self, Write};
+use std::sync::{Mutex, RwLock};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{reload, EnvFilter, Layer, Registry};
+
+// Level 1 (error) to 5 (trace), the event's target and the formatted line
+pub type LogCallback = extern "C" fn(level: i32, target: *const c_char, message: *const c_char);
+
+pub const DEFAULT_FILTER: &str = "warn";
+
+static FILTER: Mutex<Option<reload::Handle<EnvFilter, Registry>>> = Mutex::new(None);
+static CALLBACK: RwLock<Option<LogCallback>> = RwLock::new(None);
+
+/*
+Spans and events from produce, consume and checkpoint go to the registered
+callback, or to stderr when there is none. The filter takes EnvFilter directives,
+like "rspace_plus_plus=debug", and can be changed while running. The first call
+here installs the global subscriber.
+*/
+pub fn set_filter(directives: &str) -> Result<(), Box<dyn Error>> {
+    let filter = EnvFilter::try_new(directives)?;
+    let mut handle = FILTER.lock().map_err(|err| err.to_string())?;
+
+    match handle.as_ref() {
+        Some(handle) => handle.reload(filter)?,
+        None => {
+            let (layer, reload_handle) = reload::Layer::new(filter);
+            tracing::subscriber::set_global_default(
+                Registry::default().with(layer).with(ForwardLayer),
+            )?;
+            *handle = Some(reload_handle);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn set_callback(callback: Option<LogCallback>) -> Result<(), Box<dyn Error>> {
+    *CALLBACK.write().map_err(|err| err.to_string())? = callback;
+
+    let installed = FILTER.lock().map_err(|err| err.to_string())?.is_some();
+    if !installed {
+        set_filter(DEFAULT_FILTER)?;
+    }
+
+    Ok(())
+}
+
+fn level_number(level: &Level) -> i32 {
+    match *level {
+        Level::ERROR => 1,
+        Level::WARN => 2,
+        Level::INFO => 3,
+        Level::DEBUG => 4,
+        Level::TRACE => 5,
+    }
+}
+
+fn forward(level: &Level, target: &str, line: &str) {
+    let callback = CALLBACK.read().ok().and_then(|callback| *callback);
+
+    match callback {
+        Some(callback) => {
+            // interior NULs would cut the strings short on the other side
+            let target = CString::new(target.replace('\0', " ")).unwrap_or_default();
+            let line = CString::new(line.replace('\0', " ")).unwrap_or_default();
+            callback(level_number(level), target.as_ptr(), line.as_ptr());
+        }
+        None => eprintln!("{} {}: {}", level, target, line),
+    }
+}
+
+// Formats an event as its enclosing spans with their fields, root first, then its own fields
+struct ForwardLayer;
+
+// A span's fields as formatted so far
+struct SpanFields(String);
+
+impl<S> Layer<S> for ForwardLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            let mut fields = String::new();
+            attrs.record(&mut FieldWriter(&mut fields));
+            span.extensions_mut().insert(SpanFields(fields));
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(SpanFields(fields)) = span.extensions_mut().get_mut::<SpanFields>() {
+                values.record(&mut FieldWriter(fields));
+            }
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut line = String::new();
+
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope.from_root() {
+                line.push_str(span.name());
+                if let Some(SpanFields(fields)) = span.extensions().get::<SpanFields>() {
+                    if !fields.is_empty() {
+                        let _ = write!(line, "{{{}}}", fields);
+                    }
+                }
+                line.push_str(": ");
+            }
+        }
+        event.record(&mut FieldWriter(&mut line));
+
+        forward(event.metadata().level(), event.metadata().target(), &line);
+    }
+}
+
+// Appends fields as name=value, the message as it is
+struct FieldWriter<'a>(&'a mut String);
+
+impl Visit for FieldWriter<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record_debug(field, &format_args!("{}", value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if !self.0.is_empty() && !self.0.ends_with(": ") {
+            self.0.push(' ');
+        }
+        let _ = match field.name() {
+            "message" => write!(self.0, "{:?}", value),
+            name => write!(self.0, "{}={:?}", name, value),
+        };
+    }
+}
@@ -1,13 +1,14 @@
 package rspacePlusPlus
 
 import com.sun.jna._
+import com.sun.jna.ptr.{ByteByReference, LongByReference}
+import firefly.rtypes.{OptionResult, ResultList}
 import java.nio.charset.StandardCharsets
 import java.nio.ByteBuffer
 
 final case class _Name(first: String, last: String)
 final case class _Address(street: String, city: String, state: String, zip: String)
 final case class _Entry(name: _Name, address: _Address, email: String, phone: String)
-final case class Pres_Class(continuation: String, data: _Entry)
 
 final case class Result[C, A](
     channel: C,
@@ -35,6 +36,17 @@ final case class ContResult[C, P, K](
 //   val y: Int = 0
 // }
 
+// Gets each log line, level is 1 (error) to 5 (trace)
+trait LogCallback extends Callback {
+  def invoke(level: Int, target: String, message: String): Unit
+}
+
+// Gets each change at a watched channel, an encoded ChannelChange of len bytes
+// only valid during the call
+trait WatchCallback extends Callback {
+  def invoke(change: Pointer, len: Long): Unit
+}
+
 /** The interface for RSpace++
   *
   */
@@ -43,104 +55,206 @@ trait RSpacePlusPlus[F[_]] extends Library {
   def is_empty(rspace: Pointer): Boolean
   def space_print(rspace: Pointer, channel: String): Unit
   def space_clear(rspace: Pointer): Unit
+  def space_flush(rspace: Pointer): Boolean
+
+  // Inspection, store is a Store from rtypes.proto. Each returns an encoded protobuf
+  // message of len bytes, or null, to be freed with space_free_bytes
+  def space_channels(rspace: Pointer, store: Int, len: LongByReference): Pointer
+  def space_get_data(rspace: Pointer, store: Int, channel: String, len: LongByReference): Pointer
+  def space_get_waiting_continuations(
+      rspace: Pointer,
+      store: Int,
+      channels: Array[Byte],
+      channels_len: Int,
+      len: LongByReference
+  ): Pointer
+  def space_get_joins(rspace: Pointer, store: Int, channel: String, len: LongByReference): Pointer
+  def space_to_map(rspace: Pointer, store: Int, len: LongByReference): Pointer
+  def space_free_bytes(ptr: Pointer, len: Long): Unit
+
+  // Integrity check of one store, returns an encoded VerifyReport, repair fixes what it reports
+  def space_verify(rspace: Pointer, store: Int, repair: Boolean, len: LongByReference): Pointer
+
+  // Metrics, kept in memory once enabled. Returns an encoded MetricsSnapshot from rtypes.proto
+  // of len bytes, or null, to be freed with space_free_bytes
+  def space_enable_metrics(): Boolean
+  def space_metrics_snapshot(rspace: Pointer, len: LongByReference): Pointer
+
+  // Logging, filter takes EnvFilter directives like "rspace_plus_plus=debug"
+  def space_set_log_filter(filter: String): Boolean
+  def space_set_log_callback(callback: LogCallback): Boolean
+
+  // Watching a channel in every store, space_watch returns the id to unwatch with, or 0
+  def space_watch(rspace: Pointer, channel: String, callback: WatchCallback): Long
+  def space_unwatch(rspace: Pointer, id: Long): Boolean
+
+  // Verbs, a get returns an encoded OptionResult and a put an encoded ResultList of len
+  // bytes, or null when the space stored what it was given. A verb that fails returns null
+  // and sets error. See getResult and putResults
 
   // Verb Set 1
   def space_get_once_durable_concurrent(
       rspace: Pointer,
       retrieve: Array[Byte],
-      retrieve_len: Int
-  ): String
+      retrieve_len: Int,
+      len: LongByReference,
+      error: ByteByReference
+  ): Pointer
 
   def space_get_once_non_durable_concurrent(
       rspace: Pointer,
       retrieve: Array[Byte],
-      retrieve_len: Int
-  ): String
+      retrieve_len: Int,
+      len: LongByReference,
+      error: ByteByReference
+  ): Pointer
 
   def space_get_once_durable_sequential(
       rspace: Pointer,
       retrieve: Array[Byte],
-      retrieve_len: Int
-  ): String
+      retrieve_len: Int,
+      len: LongByReference,
+      error: ByteByReference
+  ): Pointer
 
   def space_get_once_non_durable_sequential(
       rspace: Pointer,
       retrieve: Array[Byte],
-      retrieve_len: Int
-  ): String
+      retrieve_len: Int,
+      len: LongByReference,
+      error: ByteByReference
+  ): Pointer
 
   // Verb Set 2
   def space_get_always_durable_concurrent(
       rspace: Pointer,
       retrieve: Array[Byte],
-      retrieve_len: Int
-  ): String
+      retrieve_len: Int,
+      len: LongByReference,
+      error: ByteByReference
+  ): Pointer
 
   def space_get_always_non_durable_concurrent(
       rspace: Pointer,
       retrieve: Array[Byte],
-      retrieve_len: Int
-  ): String
+      retrieve_len: Int,
+      len: LongByReference,
+      error: ByteByReference
+  ): Pointer
 
   def space_get_always_durable_sequential(
       rspace: Pointer,
       retrieve: Array[Byte],
-      retrieve_len: Int
-  ): String
+      retrieve_len: Int,
+      len: LongByReference,
+      error: ByteByReference
+  ): Pointer
 
   def space_get_always_non_durable_sequential(
       rspace: Pointer,
       retrieve: Array[Byte],
-      retrieve_len: Int
-  ): String
+      retrieve_len: Int,
+      len: LongByReference,
+      error: ByteByReference
+  ): Pointer
 
   // Verb Set 3
   def space_put_once_durable_concurrent(
       rspace: Pointer,
       commit: Array[Byte],
-      commit_len: Int
-  ): Array[String]
+      commit_len: Int,
+      len: LongByReference,
+      error: ByteByReference
+  ): Pointer
 
   def space_put_once_non_durable_concurrent(
       rspace: Pointer,
       commit: Array[Byte],
-      commit_len: Int
-  ): Array[String]
+      commit_len: Int,
+      len: LongByReference,
+      error: ByteByReference
+  ): Pointer
 
   def space_put_once_durable_sequential(
       rspace: Pointer,
       commit: Array[Byte],
-      commit_len: Int
-  ): Array[String]
+      commit_len: Int,
+      len: LongByReference,
+      error: ByteByReference
+  ): Pointer
 
   def space_put_once_non_durable_sequential(
       rspace: Pointer,
       commit: Array[Byte],
-      commit_len: Int
-  ): Array[String]
+      commit_len: Int,
+      len: LongByReference,
+      error: ByteByReference
+  ): Pointer
 
   // Verb Set 4
   def space_put_always_durable_concurrent(
       rspace: Pointer,
       commit: Array[Byte],
-      commit_len: Int
-  ): Array[String]
+      commit_len: Int,
+      len: LongByReference,
+      error: ByteByReference
+  ): Pointer
 
   def space_put_always_non_durable_concurrent(
       rspace: Pointer,
       commit: Array[Byte],
-      commit_len: Int
-  ): Array[String]
+      commit_len: Int,
+      len: LongByReference,
+      error: ByteByReference
+  ): Pointer
 
   def space_put_always_durable_sequential(
       rspace: Pointer,
       commit: Array[Byte],
-      commit_len: Int
-  ): Array[String]
+      commit_len: Int,
+      len: LongByReference,
+      error: ByteByReference
+  ): Pointer
 
   def space_put_always_non_durable_sequential(
       rspace: Pointer,
       commit: Array[Byte],
-      commit_len: Int
-  ): Array[String]
+      commit_len: Int,
+      len: LongByReference,
+      error: ByteByReference
+  ): Pointer
+}
+
+object RSpacePlusPlus {
+  // Copies out a message of len bytes returned by lib, and frees it
+  def takeBytes(lib: RSpacePlusPlus[Array], ptr: Pointer, len: LongByReference): Array[Byte] = {
+    val bytes = ptr.getByteArray(0, len.getValue.toInt)
+    lib.space_free_bytes(ptr, len.getValue)
+    bytes
+  }
+
+  // What a get returned, None when the datum was stored. Throws if the get failed
+  def getResult(
+      lib: RSpacePlusPlus[Array],
+      ptr: Pointer,
+      len: LongByReference,
+      error: ByteByReference
+  ): Option[OptionResult] = {
+    checkError(error)
+    Option(ptr).map(ptr => OptionResult.parseFrom(takeBytes(lib, ptr, len)))
+  }
+
+  // What a put returned, None when the continuation was stored. Throws if the put failed
+  def putResults(
+      lib: RSpacePlusPlus[Array],
+      ptr: Pointer,
+      len: LongByReference,
+      error: ByteByReference
+  ): Option[Seq[OptionResult]] = {
+    checkError(error)
+    Option(ptr).map(ptr => ResultList.parseFrom(takeBytes(lib, ptr, len)).results)
+  }
+
+  private def checkError(error: ByteByReference): Unit =
+    if (error.getValue != 0) throw new RuntimeException("rspace++ verb failed, see its log")
 }
@@ -1,10 +1,9 @@
 package rspacePlusPlus
 
 import com.sun.jna._
+import com.sun.jna.ptr.{ByteByReference, LongByReference}
 import java.nio.charset.StandardCharsets
 import java.nio.ByteBuffer
-import io.circe.parser._
-import io.circe.generic.auto._
 
 import firefly.rtypes.{Address, Commit, Entry, Name, OptionResult, Retrieve}
 
@@ -32,17 +31,24 @@ object Main extends Library {
     val setup    = Setup.apply();
 
     val channel = "friends"
+    val len     = new LongByReference()
+    val error   = new ByteByReference()
 
     // Consume
     val commit1 =
-      Commit(Seq("friends"), Seq(setup.cityMatchCase), "I am the continuation, for now...");
+      Commit(Seq("friends"), Seq(setup.cityMatchCase), Some(setup.continuation));
     val commit1_buf = commit1.toByteArray;
-    val cres1       = lib.space_put_once_durable_concurrent(spacePtr, commit1_buf, commit1_buf.length);
+    val cres1 = RSpacePlusPlus.putResults(
+      lib,
+      lib.space_put_once_durable_concurrent(spacePtr, commit1_buf, commit1_buf.length, len, error),
+      len,
+      error
+    );
     println(cres1)
 
     // Consume
     // val commit2 =
-    //   Commit(Seq("friends"), Seq(setup.cityPattern), "I am the continuation, for now...");
+    //   Commit(Seq("friends"), Seq(setup.cityPattern), Some(setup.continuation));
     // val commit2_buf = commit2.toByteArray;
     // val cres2       = lib.space_put_once_durable_concurrent(spacePtr, commit2_buf, commit2_buf.length);
     // println(cres2)
@@ -50,7 +56,12 @@ object Main extends Library {
     // Produce
     val retrieve1     = Retrieve("friends", Some(setup.alice), getCityField(setup.alice));
     val retrieve1_buf = retrieve1.toByteArray;
-    val pres1         = lib.space_get_once_durable_concurrent(spacePtr, retrieve1_buf, retrieve1_buf.length);
+    val pres1 = RSpacePlusPlus.getResult(
+      lib,
+      lib.space_get_once_durable_concurrent(spacePtr, retrieve1_buf, retrieve1_buf.length, len, error),
+      len,
+      error
+    );
     println(pres1);
 
     // Produce
@@ -62,11 +73,9 @@ object Main extends Library {
     lib.space_print(spacePtr, channel)
     lib.space_clear(spacePtr)
 
-    val pres1_obj = decode[Pres_Class](pres1)
-
-    pres1_obj match {
-      case Right(p)  => println(p)
-      case Left(err) => println(err)
+    pres1 match {
+      case Some(result) => println(result.continuation)
+      case None         => println("stored")
     }
   }
 }
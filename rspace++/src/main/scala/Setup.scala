@@ -1,6 +1,7 @@
 package rspacePlusPlus
 
-import firefly.rtypes.{Address, Entry, Name}
+import com.google.protobuf.ByteString
+import firefly.rtypes.{Address, Entry, Name, TaggedContinuation}
 
 final case class Setup(
     cityMatchCase: String,
@@ -10,7 +11,8 @@ final case class Setup(
     bob: Entry,
     carol: Entry,
     dan: Entry,
-    erin: Entry
+    erin: Entry,
+    continuation: TaggedContinuation
 )
 
 object Setup {
@@ -48,7 +50,12 @@ object Setup {
       bob,
       carol,
       dan,
-      erin
+      erin,
+      TaggedContinuation(
+        TaggedContinuation.TaggedCont.Opaque(
+          ByteString.copyFromUtf8("I am the continuation, for now...")
+        )
+      )
     )
   }
 }
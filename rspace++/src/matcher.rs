@@ -0,0 +1,1343 @@
+#![allow(dead_code)]
+
+use crate::rtypes::rtypes::connective::ConnectiveInstance;
+use crate::rtypes::rtypes::expr::ExprInstance;
+use crate::rtypes::rtypes::g_unforgeable::UnfInstance;
+use crate::rtypes::rtypes::var::VarInstance;
+use crate::rtypes::rtypes::{
+    BindPattern, Bundle, Connective, EList, EMap, ESet, Expr, GUnforgeable, KeyValuePair,
+    ListParWithRandom, Match, MatchCase, New, Par, Receive, ReceiveBind, Send, Var,
+};
+use std::cmp;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Add;
+
+/*
+Rholang's spatial matcher, see SpatialMatcher.scala in rholang/interpreter/matcher/.
+A pattern's free variables are bound, by level, to what they capture. Where the
+Scala matcher backtracks through a stream of states, here each state a match can
+end in is handed to a callback, which returns true once it needs no more.
+Locally free sets take no part in equality, as with AlwaysEqual in models/
+*/
+pub type FreeMap = BTreeMap<i32, Par>;
+
+// Called with each state a match ends in, returns true to stop looking
+type Found<'a> = &'a mut dyn FnMut(FreeMap) -> bool;
+
+pub fn spatial_match(target: &Par, pattern: &Par) -> Option<FreeMap> {
+    first(|found| match_par(target, pattern, FreeMap::new(), found))
+}
+
+/*
+A datum's pars against a receive's bind pattern, see matchListPar in
+rholang/interpreter/storage/. The bindings are the pattern's freeCount pars by
+level, Nil where nothing was bound, with the data's randomState
+*/
+pub fn match_list_par(
+    pattern: &BindPattern,
+    data: &ListParWithRandom,
+) -> Option<ListParWithRandom> {
+    let mut matched = None;
+    fold_match(
+        &data.pars,
+        &pattern.patterns,
+        pattern.remainder.as_ref(),
+        FreeMap::new(),
+        &mut |free_map, caught| {
+            matched = Some((free_map, caught));
+            true
+        },
+    );
+    let (mut free_map, caught) = matched?;
+
+    if let Some(level) = free_var(pattern.remainder.as_ref()) {
+        free_map.insert(level, list_par(caught));
+    }
+    Some(ListParWithRandom {
+        pars: (0..pattern.free_count)
+            .map(|level| free_map.remove(&level).unwrap_or_default())
+            .collect(),
+        random_state: data.random_state.clone(),
+    })
+}
+
+// The par with every locally free set cleared, as equality sees it
+pub fn erase_locally_free(par: &mut Par) {
+    par.locally_free.clear();
+    par.sends.iter_mut().for_each(erase_send);
+    par.receives.iter_mut().for_each(erase_receive);
+    par.news.iter_mut().for_each(erase_new);
+    par.exprs.iter_mut().for_each(erase_expr);
+    par.matches.iter_mut().for_each(erase_match);
+    par.bundles.iter_mut().for_each(erase_bundle);
+    par.connectives.iter_mut().for_each(erase_connective);
+}
+
+fn first(search: impl FnOnce(Found) -> bool) -> Option<FreeMap> {
+    let mut result = None;
+    search(&mut |state| {
+        result = Some(state);
+        true
+    });
+    result
+}
+
+/*
+What the matcher pairs up, see HasLocallyFree.scala in models/. A term with
+locally free variables refers to binders outside it, so no free variable can
+capture it
+*/
+trait Term: Clone + PartialEq {
+    fn connective_used(&self) -> bool;
+    fn has_locally_free(&self) -> bool;
+    fn erase(&mut self);
+    fn spatial_match(target: &Self, pattern: &Self, state: FreeMap, found: Found) -> bool;
+}
+
+fn same<T: Term>(target: &T, pattern: &T) -> bool {
+    if target == pattern {
+        return true;
+    }
+    let (mut target, mut pattern) = (target.clone(), pattern.clone());
+    target.erase();
+    pattern.erase();
+    target == pattern
+}
+
+fn locally_free(bits: &[u8]) -> bool {
+    bits.iter().any(|byte| *byte != 0)
+}
+
+fn free_var(var: Option<&Var>) -> Option<i32> {
+    match var.and_then(|var| var.var_instance.as_ref()) {
+        Some(VarInstance::FreeVar(level)) => Some(*level),
+        _ => None,
+    }
+}
+
+fn is_wildcard(var: Option<&Var>) -> bool {
+    matches!(
+        var.and_then(|var| var.var_instance.as_ref()),
+        Some(VarInstance::Wildcard(_))
+    )
+}
+
+fn expr_var(expr: &Expr) -> Option<&Var> {
+    match &expr.expr_instance {
+        Some(ExprInstance::EVarBody(evar)) => evar.v.as_ref(),
+        _ => None,
+    }
+}
+
+// Exprs other than free variables and wildcards
+fn no_frees(exprs: &[Expr]) -> Vec<Expr> {
+    exprs
+        .iter()
+        .filter(|expr| {
+            let var = expr_var(expr);
+            free_var(var).is_none() && !is_wildcard(var)
+        })
+        .cloned()
+        .collect()
+}
+
+fn list_par(ps: Vec<Par>) -> Par {
+    Par {
+        exprs: vec![Expr {
+            expr_instance: Some(ExprInstance::EListBody(EList {
+                ps,
+                ..Default::default()
+            })),
+        }],
+        ..Default::default()
+    }
+}
+
+// A missing Par is Nil
+fn match_opt(target: &Option<Par>, pattern: &Option<Par>, state: FreeMap, found: Found) -> bool {
+    let nil = Par::default();
+    match_par(
+        target.as_ref().unwrap_or(&nil),
+        pattern.as_ref().unwrap_or(&nil),
+        state,
+        found,
+    )
+}
+
+/*
+Connectives first, each on a part of the target its bounds allow, then what is
+left against the rest of the pattern. The first free variable among the
+pattern's exprs captures whatever no other term of the pattern took
+*/
+fn match_par(target: &Par, pattern: &Par, state: FreeMap, found: Found) -> bool {
+    if !pattern.connective_used {
+        return same(target, pattern) && found(state);
+    }
+
+    let var_level = pattern
+        .exprs
+        .iter()
+        .find_map(|expr| free_var(expr_var(expr)));
+    let wildcard = pattern.exprs.iter().any(|expr| is_wildcard(expr_var(expr)));
+
+    let count = ParCount::without_frees(pattern);
+    let max_rem = if wildcard || var_level.is_some() {
+        ParCount::MAX
+    } else {
+        count
+    };
+    let bounds: Vec<_> = pattern
+        .connectives
+        .iter()
+        .map(ParCount::min_max_connective)
+        .collect();
+    // what the connectives after each one, and the rest of the pattern, still need
+    let mut remainders = vec![(count, max_rem); bounds.len()];
+    for i in (0..bounds.len().saturating_sub(1)).rev() {
+        remainders[i] = (
+            bounds[i + 1].0 + remainders[i + 1].0,
+            bounds[i + 1].1 + remainders[i + 1].1,
+        );
+    }
+
+    match_connectives(
+        target,
+        &pattern.connectives,
+        &bounds,
+        &remainders,
+        state,
+        &mut |rest, state| match match_rest(rest, pattern, var_level, wildcard, state) {
+            Some(state) => found(state),
+            None => false,
+        },
+    )
+}
+
+fn match_connectives(
+    target: &Par,
+    connectives: &[Connective],
+    bounds: &[(ParCount, ParCount)],
+    remainders: &[(ParCount, ParCount)],
+    state: FreeMap,
+    found: &mut dyn FnMut(&Par, FreeMap) -> bool,
+) -> bool {
+    let Some((connective, later)) = connectives.split_first() else {
+        return found(target, state);
+    };
+    let ((min, max), (min_prune, max_prune)) = (bounds[0], remainders[0]);
+
+    sub_pars(target, min, max, min_prune, max_prune, &mut |sub, rest| {
+        match_connective(&sub, connective, state.clone(), &mut |state| {
+            match_connectives(&rest, later, &bounds[1..], &remainders[1..], state, found)
+        })
+    })
+}
+
+fn match_rest(
+    rest: &Par,
+    pattern: &Par,
+    var_level: Option<i32>,
+    wildcard: bool,
+    state: FreeMap,
+) -> Option<FreeMap> {
+    let state = list_match_single(
+        &rest.sends,
+        &pattern.sends,
+        |par, sends| par.sends = sends,
+        var_level,
+        wildcard,
+        state,
+    )?;
+    let state = list_match_single(
+        &rest.receives,
+        &pattern.receives,
+        |par, receives| par.receives = receives,
+        var_level,
+        wildcard,
+        state,
+    )?;
+    let state = list_match_single(
+        &rest.news,
+        &pattern.news,
+        |par, news| par.news = news,
+        var_level,
+        wildcard,
+        state,
+    )?;
+    let state = list_match_single(
+        &rest.exprs,
+        &no_frees(&pattern.exprs),
+        |par, exprs| par.exprs = exprs,
+        var_level,
+        wildcard,
+        state,
+    )?;
+    let state = list_match_single(
+        &rest.matches,
+        &pattern.matches,
+        |par, matches| par.matches = matches,
+        var_level,
+        wildcard,
+        state,
+    )?;
+    let state = list_match_single(
+        &rest.bundles,
+        &pattern.bundles,
+        |par, bundles| par.bundles = bundles,
+        var_level,
+        wildcard,
+        state,
+    )?;
+    list_match_single(
+        &rest.unforgeables,
+        &pattern.unforgeables,
+        |par, unforgeables| par.unforgeables = unforgeables,
+        var_level,
+        wildcard,
+        state,
+    )
+}
+
+fn match_connective(target: &Par, connective: &Connective, state: FreeMap, found: Found) -> bool {
+    let Some(instance) = &connective.connective_instance else {
+        return false;
+    };
+    let single_expr = single_expr(target).and_then(|expr| expr.expr_instance.as_ref());
+
+    match instance {
+        ConnectiveInstance::ConnAndBody(body) => match_all(target, &body.ps, state, found),
+        // the first alternative that matches is taken, and binds nothing
+        ConnectiveInstance::ConnOrBody(body) => {
+            body.ps.iter().any(|pattern| {
+                first(|any| match_par(target, pattern, state.clone(), any)).is_some()
+            }) && found(state)
+        }
+        ConnectiveInstance::ConnNotBody(pattern) => {
+            first(|any| match_par(target, pattern, state.clone(), any)).is_none() && found(state)
+        }
+        // substituted before matching
+        ConnectiveInstance::VarRefBody(_) => false,
+        ConnectiveInstance::ConnBool(_) => {
+            matches!(single_expr, Some(ExprInstance::GBool(_))) && found(state)
+        }
+        ConnectiveInstance::ConnInt(_) => {
+            matches!(single_expr, Some(ExprInstance::GInt(_))) && found(state)
+        }
+        ConnectiveInstance::ConnString(_) => {
+            matches!(single_expr, Some(ExprInstance::GString(_))) && found(state)
+        }
+        ConnectiveInstance::ConnUri(_) => {
+            matches!(single_expr, Some(ExprInstance::GUri(_))) && found(state)
+        }
+        ConnectiveInstance::ConnByteArray(_) => {
+            matches!(single_expr, Some(ExprInstance::GByteArray(_))) && found(state)
+        }
+    }
+}
+
+fn match_all(target: &Par, patterns: &[Par], state: FreeMap, found: Found) -> bool {
+    match patterns.split_first() {
+        None => found(state),
+        Some((pattern, rest)) => match_par(target, pattern, state, &mut |state| {
+            match_all(target, rest, state, found)
+        }),
+    }
+}
+
+fn single_expr(par: &Par) -> Option<&Expr> {
+    if par.sends.is_empty()
+        && par.receives.is_empty()
+        && par.news.is_empty()
+        && par.matches.is_empty()
+        && par.bundles.is_empty()
+        && par.exprs.len() == 1
+    {
+        return par.exprs.first();
+    }
+    None
+}
+
+/*
+Targets and patterns pairwise in order. Targets left over go to a free variable
+remainder, and are handed on with the state
+*/
+fn fold_match<T: Term>(
+    targets: &[T],
+    patterns: &[T],
+    remainder: Option<&Var>,
+    state: FreeMap,
+    found: &mut dyn FnMut(FreeMap, Vec<T>) -> bool,
+) -> bool {
+    match (targets.split_first(), patterns.split_first()) {
+        (None, None) => found(state, vec![]),
+        (None, Some(_)) => false,
+        (Some(_), None) => {
+            if free_var(remainder).is_some() {
+                !targets.iter().any(Term::has_locally_free) && found(state, targets.to_vec())
+            } else {
+                is_wildcard(remainder) && found(state, vec![])
+            }
+        }
+        (Some((target, targets)), Some((pattern, patterns))) => {
+            T::spatial_match(target, pattern, state, &mut |state| {
+                fold_match(targets, patterns, remainder, state, found)
+            })
+        }
+    }
+}
+
+/*
+One match of every pattern to a distinct target, in any order. Targets left
+over go to the remainder variable at level, or are dropped for a wildcard.
+Takes the first matching found, as listMatchSingle_ does
+*/
+fn list_match_single<T: Term>(
+    targets: &[T],
+    patterns: &[T],
+    merger: fn(&mut Par, Vec<T>),
+    remainder: Option<i32>,
+    wildcard: bool,
+    state: FreeMap,
+) -> Option<FreeMap> {
+    let exact = !wildcard && remainder.is_none();
+    if (exact && patterns.len() != targets.len()) || patterns.len() > targets.len() {
+        return None;
+    }
+    if patterns.is_empty() && targets.is_empty() && remainder.is_none() {
+        return Some(state);
+    }
+    if let (true, Some(level)) = (patterns.is_empty(), remainder) {
+        if targets.iter().any(Term::has_locally_free) {
+            return None;
+        }
+        return Some(handle_remainder(targets.to_vec(), level, merger, state));
+    }
+
+    // None stands for a slot of the remainder
+    let slots = remainder.map_or(0, |_| targets.len() - patterns.len());
+    let all: Vec<Option<&T>> = (0..slots)
+        .map(|_| None)
+        .chain(patterns.iter().map(Some))
+        .collect();
+
+    let mut memo = HashMap::new();
+    let matched = bipartite_match(all.len(), targets.len(), &mut |p, t| {
+        memo.entry((p, t))
+            .or_insert_with(|| match all[p] {
+                Some(pattern) if !pattern.connective_used() => {
+                    same(&targets[t], pattern).then(|| state.clone())
+                }
+                Some(pattern) => {
+                    first(|found| T::spatial_match(&targets[t], pattern, state.clone(), found))
+                }
+                None => (!targets[t].has_locally_free()).then(|| state.clone()),
+            })
+            .clone()
+    })?;
+
+    // every match started from state, what they bound must not overlap
+    let mut updated = state.clone();
+    for (_, _, free_map) in &matched {
+        for (level, par) in free_map {
+            if !state.contains_key(level) && updated.contains_key(level) {
+                return None;
+            }
+            updated.insert(*level, par.clone());
+        }
+    }
+
+    // like the Scala matcher, targets equal to one the remainder took are all kept
+    let taken: Vec<&T> = matched
+        .iter()
+        .filter(|(_, pattern, _)| all[*pattern].is_none())
+        .map(|(target, _, _)| &targets[*target])
+        .collect();
+    let rest: Vec<T> = targets
+        .iter()
+        .filter(|target| taken.iter().any(|taken| same(*target, *taken)))
+        .cloned()
+        .collect();
+
+    match remainder {
+        None => (wildcard || rest.is_empty()).then_some(updated),
+        Some(level) => Some(handle_remainder(rest, level, merger, updated)),
+    }
+}
+
+fn handle_remainder<T>(
+    targets: Vec<T>,
+    level: i32,
+    merger: fn(&mut Par, Vec<T>),
+    mut state: FreeMap,
+) -> FreeMap {
+    let mut par = state.remove(&level).unwrap_or_default();
+    merger(&mut par, targets);
+    state.insert(level, par);
+    state
+}
+
+/*
+Assigns each pattern a target it matches, no target twice, see
+MaximumBipartiteMatch.scala. Patterns are placed in order, each trying targets
+in order and moving an earlier pattern on to another target when it has to.
+Returns (target, pattern, result) for each pattern, or None if one is left out
+*/
+fn bipartite_match<R: Clone>(
+    patterns: usize,
+    targets: usize,
+    matches: &mut dyn FnMut(usize, usize) -> Option<R>,
+) -> Option<Vec<(usize, usize, R)>> {
+    let mut assignment = Assignment {
+        matches,
+        claimed: vec![None; targets],
+        seen: vec![false; targets],
+    };
+
+    for pattern in 0..patterns {
+        assignment.seen.fill(false);
+        if !assignment.find(pattern, 0) {
+            return None;
+        }
+    }
+    Some(
+        assignment
+            .claimed
+            .into_iter()
+            .enumerate()
+            .filter_map(|(target, claim)| {
+                claim.map(|(pattern, _, result)| (target, pattern, result))
+            })
+            .collect(),
+    )
+}
+
+struct Assignment<'a, R> {
+    matches: &'a mut dyn FnMut(usize, usize) -> Option<R>,
+    // by target, the pattern holding it, where that pattern's search reached, and its result
+    claimed: Vec<Option<(usize, usize, R)>>,
+    seen: Vec<bool>,
+}
+
+impl<R: Clone> Assignment<'_, R> {
+    fn find(&mut self, pattern: usize, from: usize) -> bool {
+        for target in from..self.claimed.len() {
+            if self.seen[target] {
+                continue;
+            }
+            if let Some(result) = (self.matches)(pattern, target) {
+                self.seen[target] = true;
+                return self.claim(target, pattern, result);
+            }
+        }
+        false
+    }
+
+    fn claim(&mut self, target: usize, pattern: usize, result: R) -> bool {
+        match self.claimed[target].clone() {
+            Some((previous, previous_from, _)) if !self.find(previous, previous_from) => {
+                self.find(pattern, target)
+            }
+            _ => {
+                self.claimed[target] = Some((pattern, target, result));
+                true
+            }
+        }
+    }
+}
+
+// How many terms of each kind a part of a par can hold, see ParCount.scala
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct ParCount {
+    sends: i32,
+    receives: i32,
+    news: i32,
+    exprs: i32,
+    matches: i32,
+    unforgeables: i32,
+    bundles: i32,
+}
+
+impl ParCount {
+    const ZERO: ParCount = ParCount::all(0);
+    const MAX: ParCount = ParCount::all(i32::MAX);
+    const ONE_EXPR: ParCount = ParCount {
+        exprs: 1,
+        ..ParCount::ZERO
+    };
+
+    const fn all(n: i32) -> ParCount {
+        ParCount {
+            sends: n,
+            receives: n,
+            news: n,
+            exprs: n,
+            matches: n,
+            unforgeables: n,
+            bundles: n,
+        }
+    }
+
+    fn of(par: &Par) -> ParCount {
+        ParCount {
+            sends: par.sends.len() as i32,
+            receives: par.receives.len() as i32,
+            news: par.news.len() as i32,
+            exprs: par.exprs.len() as i32,
+            matches: par.matches.len() as i32,
+            unforgeables: par.unforgeables.len() as i32,
+            bundles: par.bundles.len() as i32,
+        }
+    }
+
+    fn without_frees(par: &Par) -> ParCount {
+        ParCount {
+            exprs: no_frees(&par.exprs).len() as i32,
+            ..ParCount::of(par)
+        }
+    }
+
+    fn zip(self, other: ParCount, op: fn(i32, i32) -> i32) -> ParCount {
+        ParCount {
+            sends: op(self.sends, other.sends),
+            receives: op(self.receives, other.receives),
+            news: op(self.news, other.news),
+            exprs: op(self.exprs, other.exprs),
+            matches: op(self.matches, other.matches),
+            unforgeables: op(self.unforgeables, other.unforgeables),
+            bundles: op(self.bundles, other.bundles),
+        }
+    }
+
+    fn min(self, other: ParCount) -> ParCount {
+        self.zip(other, cmp::min)
+    }
+
+    fn max(self, other: ParCount) -> ParCount {
+        self.zip(other, cmp::max)
+    }
+
+    fn min_max(par: &Par) -> (ParCount, ParCount) {
+        let count = ParCount::without_frees(par);
+        let wildcard = par.exprs.iter().any(|expr| {
+            let var = expr_var(expr);
+            free_var(var).is_some() || is_wildcard(var)
+        });
+        let init = (count, if wildcard { ParCount::MAX } else { count });
+
+        par.connectives.iter().fold(init, |(min, max), connective| {
+            let (cmin, cmax) = ParCount::min_max_connective(connective);
+            (min + cmin, max + cmax)
+        })
+    }
+
+    fn min_max_connective(connective: &Connective) -> (ParCount, ParCount) {
+        match &connective.connective_instance {
+            Some(ConnectiveInstance::ConnAndBody(body)) => {
+                body.ps.iter().map(ParCount::min_max).fold(
+                    (ParCount::ZERO, ParCount::MAX),
+                    |(min, max), (pmin, pmax)| (min.max(pmin), max.min(pmax)),
+                )
+            }
+            Some(ConnectiveInstance::ConnOrBody(body)) => {
+                body.ps.iter().map(ParCount::min_max).fold(
+                    (ParCount::MAX, ParCount::ZERO),
+                    |(min, max), (pmin, pmax)| (min.min(pmin), max.max(pmax)),
+                )
+            }
+            Some(ConnectiveInstance::ConnNotBody(_)) => (ParCount::ZERO, ParCount::MAX),
+            Some(ConnectiveInstance::VarRefBody(_)) | None => (ParCount::ZERO, ParCount::ZERO),
+            Some(_) => (ParCount::ONE_EXPR, ParCount::ONE_EXPR),
+        }
+    }
+}
+
+impl Add for ParCount {
+    type Output = ParCount;
+
+    fn add(self, other: ParCount) -> ParCount {
+        self.zip(other, i32::saturating_add)
+    }
+}
+
+/*
+Each way of splitting par in two, the first part holding between min and max
+terms of each kind and leaving between min_prune and max_prune for the second.
+In the order of subPars in ParSpatialMatcherUtils.scala
+*/
+fn sub_pars(
+    par: &Par,
+    min: ParCount,
+    max: ParCount,
+    min_prune: ParCount,
+    max_prune: ParCount,
+    found: &mut dyn FnMut(Par, Par) -> bool,
+) -> bool {
+    let size = ParCount::of(par);
+    let lo = min.max(size.zip(max_prune, |size, prune| size - prune));
+    let hi = max.min(size.zip(min_prune, |size, prune| size - prune));
+
+    subsets(&par.sends, lo.sends, hi.sends, &mut |sends, rest_sends| {
+        subsets(
+            &par.receives,
+            lo.receives,
+            hi.receives,
+            &mut |receives, rest_receives| {
+                subsets(&par.news, lo.news, hi.news, &mut |news, rest_news| {
+                    subsets(&par.exprs, lo.exprs, hi.exprs, &mut |exprs, rest_exprs| {
+                        subsets(
+                            &par.matches,
+                            lo.matches,
+                            hi.matches,
+                            &mut |matches, rest_matches| {
+                                subsets(
+                                    &par.unforgeables,
+                                    lo.unforgeables,
+                                    hi.unforgeables,
+                                    &mut |unforgeables, rest_unforgeables| {
+                                        subsets(
+                                            &par.bundles,
+                                            lo.bundles,
+                                            hi.bundles,
+                                            &mut |bundles, rest_bundles| {
+                                                let sub = Par {
+                                                    sends: sends.clone(),
+                                                    receives: receives.clone(),
+                                                    news: news.clone(),
+                                                    exprs: exprs.clone(),
+                                                    matches: matches.clone(),
+                                                    unforgeables: unforgeables.clone(),
+                                                    bundles,
+                                                    ..Default::default()
+                                                };
+                                                let rest = Par {
+                                                    sends: rest_sends.clone(),
+                                                    receives: rest_receives.clone(),
+                                                    news: rest_news.clone(),
+                                                    exprs: rest_exprs.clone(),
+                                                    matches: rest_matches.clone(),
+                                                    unforgeables: rest_unforgeables.clone(),
+                                                    bundles: rest_bundles,
+                                                    ..Default::default()
+                                                };
+                                                found(sub, rest)
+                                            },
+                                        )
+                                    },
+                                )
+                            },
+                        )
+                    })
+                })
+            },
+        )
+    })
+}
+
+// Each subset of items with min to max of them, and its complement, see minMaxSubsets
+fn subsets<A: Clone>(
+    items: &[A],
+    min: i32,
+    max: i32,
+    found: &mut dyn FnMut(Vec<A>, Vec<A>) -> bool,
+) -> bool {
+    sized_subsets(items, min, max, &mut |subset, complement, _| {
+        found(subset, complement)
+    })
+}
+
+fn sized_subsets<A: Clone>(
+    items: &[A],
+    min: i32,
+    max: i32,
+    found: &mut dyn FnMut(Vec<A>, Vec<A>, i32) -> bool,
+) -> bool {
+    if max < 0 || min > max {
+        return false;
+    }
+    if min <= 0 {
+        if max == 0 {
+            return found(vec![], items.to_vec(), 0);
+        }
+        return at_most(items, max, found);
+    }
+
+    let Some((head, rest)) = items.split_first() else {
+        return false;
+    };
+    sized_subsets(rest, min - 1, max, &mut |subset, complement, count| {
+        if count == max {
+            found(subset, prepend(head, complement), count)
+        } else if count == min - 1 {
+            found(prepend(head, subset), complement, min)
+        } else {
+            found(subset.clone(), prepend(head, complement.clone()), count)
+                || found(prepend(head, subset), complement, count + 1)
+        }
+    })
+}
+
+fn at_most<A: Clone>(
+    items: &[A],
+    max: i32,
+    found: &mut dyn FnMut(Vec<A>, Vec<A>, i32) -> bool,
+) -> bool {
+    let Some((head, rest)) = items.split_first() else {
+        return found(vec![], vec![], 0);
+    };
+    found(vec![], items.to_vec(), 0)
+        || at_most(rest, max, &mut |subset, complement, count| {
+            if count == max {
+                found(subset, prepend(head, complement), count)
+            } else if subset.is_empty() {
+                found(vec![head.clone()], complement, 1)
+            } else {
+                found(subset.clone(), prepend(head, complement.clone()), count)
+                    || found(prepend(head, subset), complement, count + 1)
+            }
+        })
+}
+
+fn prepend<A: Clone>(head: &A, mut rest: Vec<A>) -> Vec<A> {
+    rest.insert(0, head.clone());
+    rest
+}
+
+impl Term for Par {
+    fn connective_used(&self) -> bool {
+        self.connective_used
+    }
+
+    fn has_locally_free(&self) -> bool {
+        locally_free(&self.locally_free)
+    }
+
+    fn erase(&mut self) {
+        erase_locally_free(self);
+    }
+
+    fn spatial_match(target: &Par, pattern: &Par, state: FreeMap, found: Found) -> bool {
+        match_par(target, pattern, state, found)
+    }
+}
+
+impl Term for Send {
+    fn connective_used(&self) -> bool {
+        self.connective_used
+    }
+
+    fn has_locally_free(&self) -> bool {
+        locally_free(&self.locally_free)
+    }
+
+    fn erase(&mut self) {
+        erase_send(self);
+    }
+
+    fn spatial_match(target: &Send, pattern: &Send, state: FreeMap, found: Found) -> bool {
+        target.persistent == pattern.persistent
+            && match_opt(&target.chan, &pattern.chan, state, &mut |state| {
+                fold_match(&target.data, &pattern.data, None, state, &mut |state, _| {
+                    found(state)
+                })
+            })
+    }
+}
+
+impl Term for Receive {
+    fn connective_used(&self) -> bool {
+        self.connective_used
+    }
+
+    fn has_locally_free(&self) -> bool {
+        locally_free(&self.locally_free)
+    }
+
+    fn erase(&mut self) {
+        erase_receive(self);
+    }
+
+    fn spatial_match(target: &Receive, pattern: &Receive, state: FreeMap, found: Found) -> bool {
+        if target.persistent != pattern.persistent {
+            return false;
+        }
+        match list_match_single(&target.binds, &pattern.binds, |_, _| {}, None, false, state) {
+            Some(state) => match_opt(&target.body, &pattern.body, state, found),
+            None => false,
+        }
+    }
+}
+
+impl Term for ReceiveBind {
+    fn connective_used(&self) -> bool {
+        self.source
+            .as_ref()
+            .is_some_and(|source| source.connective_used)
+    }
+
+    fn has_locally_free(&self) -> bool {
+        self.source.as_ref().is_some_and(Term::has_locally_free)
+            || self.patterns.iter().any(Term::has_locally_free)
+    }
+
+    fn erase(&mut self) {
+        self.patterns.iter_mut().for_each(erase_locally_free);
+        self.source.iter_mut().for_each(erase_locally_free);
+    }
+
+    fn spatial_match(
+        target: &ReceiveBind,
+        pattern: &ReceiveBind,
+        state: FreeMap,
+        found: Found,
+    ) -> bool {
+        target.patterns.len() == pattern.patterns.len()
+            && target
+                .patterns
+                .iter()
+                .zip(&pattern.patterns)
+                .all(|(target, pattern)| same(target, pattern))
+            && match_opt(&target.source, &pattern.source, state, found)
+    }
+}
+
+impl Term for New {
+    fn connective_used(&self) -> bool {
+        self.p.as_ref().is_some_and(|p| p.connective_used)
+    }
+
+    fn has_locally_free(&self) -> bool {
+        locally_free(&self.locally_free)
+    }
+
+    fn erase(&mut self) {
+        erase_new(self);
+    }
+
+    fn spatial_match(target: &New, pattern: &New, state: FreeMap, found: Found) -> bool {
+        target.bind_count == pattern.bind_count && match_opt(&target.p, &pattern.p, state, found)
+    }
+}
+
+impl Term for Match {
+    fn connective_used(&self) -> bool {
+        self.connective_used
+    }
+
+    fn has_locally_free(&self) -> bool {
+        locally_free(&self.locally_free)
+    }
+
+    fn erase(&mut self) {
+        erase_match(self);
+    }
+
+    fn spatial_match(target: &Match, pattern: &Match, state: FreeMap, found: Found) -> bool {
+        match_opt(&target.target, &pattern.target, state, &mut |state| {
+            fold_match(
+                &target.cases,
+                &pattern.cases,
+                None,
+                state,
+                &mut |state, _| found(state),
+            )
+        })
+    }
+}
+
+impl Term for MatchCase {
+    fn connective_used(&self) -> bool {
+        self.source
+            .as_ref()
+            .is_some_and(|source| source.connective_used)
+    }
+
+    fn has_locally_free(&self) -> bool {
+        self.source.as_ref().is_some_and(Term::has_locally_free)
+            || self.pattern.as_ref().is_some_and(Term::has_locally_free)
+    }
+
+    fn erase(&mut self) {
+        self.pattern.iter_mut().for_each(erase_locally_free);
+        self.source.iter_mut().for_each(erase_locally_free);
+    }
+
+    fn spatial_match(
+        target: &MatchCase,
+        pattern: &MatchCase,
+        state: FreeMap,
+        found: Found,
+    ) -> bool {
+        let nil = Par::default();
+        same(
+            target.pattern.as_ref().unwrap_or(&nil),
+            pattern.pattern.as_ref().unwrap_or(&nil),
+        ) && match_opt(&target.source, &pattern.source, state, found)
+    }
+}
+
+// Bundles are only ever equal or not, nothing inside them is matched
+impl Term for Bundle {
+    fn connective_used(&self) -> bool {
+        false
+    }
+
+    fn has_locally_free(&self) -> bool {
+        self.body.as_ref().is_some_and(Term::has_locally_free)
+    }
+
+    fn erase(&mut self) {
+        erase_bundle(self);
+    }
+
+    fn spatial_match(target: &Bundle, pattern: &Bundle, state: FreeMap, found: Found) -> bool {
+        same(target, pattern) && found(state)
+    }
+}
+
+impl Term for GUnforgeable {
+    fn connective_used(&self) -> bool {
+        false
+    }
+
+    fn has_locally_free(&self) -> bool {
+        false
+    }
+
+    fn erase(&mut self) {}
+
+    fn spatial_match(
+        target: &GUnforgeable,
+        pattern: &GUnforgeable,
+        state: FreeMap,
+        found: Found,
+    ) -> bool {
+        let equal = match (&target.unf_instance, &pattern.unf_instance) {
+            (Some(UnfInstance::GPrivateBody(t)), Some(UnfInstance::GPrivateBody(p))) => t == p,
+            (Some(UnfInstance::GDeployerIdBody(t)), Some(UnfInstance::GDeployerIdBody(p))) => {
+                t == p
+            }
+            _ => false,
+        };
+        equal && found(state)
+    }
+}
+
+impl Term for KeyValuePair {
+    fn connective_used(&self) -> bool {
+        self.key.as_ref().is_some_and(|key| key.connective_used)
+            || self
+                .value
+                .as_ref()
+                .is_some_and(|value| value.connective_used)
+    }
+
+    fn has_locally_free(&self) -> bool {
+        self.key.as_ref().is_some_and(Term::has_locally_free)
+            || self.value.as_ref().is_some_and(Term::has_locally_free)
+    }
+
+    fn erase(&mut self) {
+        self.key.iter_mut().for_each(erase_locally_free);
+        self.value.iter_mut().for_each(erase_locally_free);
+    }
+
+    fn spatial_match(
+        target: &KeyValuePair,
+        pattern: &KeyValuePair,
+        state: FreeMap,
+        found: Found,
+    ) -> bool {
+        match_opt(&target.key, &pattern.key, state, &mut |state| {
+            match_opt(&target.value, &pattern.value, state, found)
+        })
+    }
+}
+
+impl Term for Expr {
+    fn connective_used(&self) -> bool {
+        match &self.expr_instance {
+            Some(ExprInstance::EListBody(list)) => list.connective_used,
+            Some(ExprInstance::ETupleBody(tuple)) => tuple.connective_used,
+            Some(ExprInstance::ESetBody(set)) => set.connective_used,
+            Some(ExprInstance::EMapBody(map)) => map.connective_used,
+            Some(ExprInstance::EMethodBody(method)) => method.connective_used,
+            Some(ExprInstance::EVarBody(evar)) => matches!(
+                evar.v.as_ref().and_then(|v| v.var_instance.as_ref()),
+                Some(VarInstance::FreeVar(_) | VarInstance::Wildcard(_))
+            ),
+            Some(ExprInstance::ENotBody(not)) => used(&not.p),
+            Some(ExprInstance::ENegBody(neg)) => used(&neg.p),
+            Some(ExprInstance::EMatchesBody(matches)) => used(&matches.target),
+            instance => instance
+                .as_ref()
+                .and_then(operands)
+                .is_some_and(|(p1, p2)| used(p1) || used(p2)),
+        }
+    }
+
+    fn has_locally_free(&self) -> bool {
+        match &self.expr_instance {
+            Some(ExprInstance::EListBody(list)) => locally_free(&list.locally_free),
+            Some(ExprInstance::ETupleBody(tuple)) => locally_free(&tuple.locally_free),
+            Some(ExprInstance::ESetBody(set)) => locally_free(&set.locally_free),
+            Some(ExprInstance::EMapBody(map)) => locally_free(&map.locally_free),
+            Some(ExprInstance::EMethodBody(method)) => locally_free(&method.locally_free),
+            Some(ExprInstance::EVarBody(evar)) => matches!(
+                evar.v.as_ref().and_then(|v| v.var_instance.as_ref()),
+                Some(VarInstance::BoundVar(_))
+            ),
+            Some(ExprInstance::ENotBody(not)) => free(&not.p),
+            Some(ExprInstance::ENegBody(neg)) => free(&neg.p),
+            Some(ExprInstance::EMatchesBody(matches)) => {
+                free(&matches.target) || free(&matches.pattern)
+            }
+            instance => instance
+                .as_ref()
+                .and_then(operands)
+                .is_some_and(|(p1, p2)| free(p1) || free(p2)),
+        }
+    }
+
+    fn erase(&mut self) {
+        erase_expr(self);
+    }
+
+    fn spatial_match(target: &Expr, pattern: &Expr, state: FreeMap, found: Found) -> bool {
+        let (Some(target), Some(pattern)) = (&target.expr_instance, &pattern.expr_instance) else {
+            return false;
+        };
+
+        match (target, pattern) {
+            (ExprInstance::EListBody(target), ExprInstance::EListBody(pattern)) => fold_match(
+                &target.ps,
+                &pattern.ps,
+                pattern.remainder.as_ref(),
+                state,
+                &mut |mut state, caught| {
+                    if let Some(level) = free_var(pattern.remainder.as_ref()) {
+                        state.insert(level, list_par(caught));
+                    }
+                    found(state)
+                },
+            ),
+            (ExprInstance::ETupleBody(target), ExprInstance::ETupleBody(pattern)) => {
+                fold_match(&target.ps, &pattern.ps, None, state, &mut |state, _| {
+                    found(state)
+                })
+            }
+            (ExprInstance::ESetBody(target), ExprInstance::ESetBody(pattern)) => {
+                match list_match_single(
+                    &target.ps,
+                    &pattern.ps,
+                    |par, ps| par.exprs = vec![set_expr(ps)],
+                    free_var(pattern.remainder.as_ref()),
+                    is_wildcard(pattern.remainder.as_ref()),
+                    state,
+                ) {
+                    Some(state) => found(state),
+                    None => false,
+                }
+            }
+            (ExprInstance::EMapBody(target), ExprInstance::EMapBody(pattern)) => {
+                match list_match_single(
+                    &target.kvs,
+                    &pattern.kvs,
+                    |par, kvs| par.exprs = vec![map_expr(kvs)],
+                    free_var(pattern.remainder.as_ref()),
+                    is_wildcard(pattern.remainder.as_ref()),
+                    state,
+                ) {
+                    Some(state) => found(state),
+                    None => false,
+                }
+            }
+            (ExprInstance::EVarBody(target), ExprInstance::EVarBody(pattern)) => {
+                target == pattern && found(state)
+            }
+            (ExprInstance::ENotBody(target), ExprInstance::ENotBody(pattern)) => {
+                match_opt(&target.p, &pattern.p, state, found)
+            }
+            (ExprInstance::ENegBody(target), ExprInstance::ENegBody(pattern)) => {
+                match_opt(&target.p, &pattern.p, state, found)
+            }
+            (target, pattern) => {
+                if std::mem::discriminant(target) != std::mem::discriminant(pattern) {
+                    return false;
+                }
+                match (matched_operands(target), matched_operands(pattern)) {
+                    (Some((t1, t2)), Some((p1, p2))) => {
+                        match_opt(t1, p1, state, &mut |state| match_opt(t2, p2, state, found))
+                    }
+                    _ => false,
+                }
+            }
+        }
+    }
+}
+
+fn used(par: &Option<Par>) -> bool {
+    par.as_ref().is_some_and(|par| par.connective_used)
+}
+
+fn free(par: &Option<Par>) -> bool {
+    par.as_ref().is_some_and(Term::has_locally_free)
+}
+
+// Both sides of a binary operator
+fn operands(instance: &ExprInstance) -> Option<(&Option<Par>, &Option<Par>)> {
+    use crate::rtypes::rtypes::{
+        EAnd, EDiv, EEq, EGt, EGte, ELt, ELte, EMinus, EMinusMinus, EMod, EMult, ENeq, EOr,
+        EPercentPercent, EPlus, EPlusPlus,
+    };
+    match instance {
+        ExprInstance::EMultBody(EMult { p1, p2 })
+        | ExprInstance::EDivBody(EDiv { p1, p2 })
+        | ExprInstance::EModBody(EMod { p1, p2 })
+        | ExprInstance::EPlusBody(EPlus { p1, p2 })
+        | ExprInstance::EMinusBody(EMinus { p1, p2 })
+        | ExprInstance::ELtBody(ELt { p1, p2 })
+        | ExprInstance::ELteBody(ELte { p1, p2 })
+        | ExprInstance::EGtBody(EGt { p1, p2 })
+        | ExprInstance::EGteBody(EGte { p1, p2 })
+        | ExprInstance::EEqBody(EEq { p1, p2 })
+        | ExprInstance::ENeqBody(ENeq { p1, p2 })
+        | ExprInstance::EAndBody(EAnd { p1, p2 })
+        | ExprInstance::EOrBody(EOr { p1, p2 })
+        | ExprInstance::EPercentPercentBody(EPercentPercent { p1, p2 })
+        | ExprInstance::EPlusPlusBody(EPlusPlus { p1, p2 })
+        | ExprInstance::EMinusMinusBody(EMinusMinus { p1, p2 }) => Some((p1, p2)),
+        _ => None,
+    }
+}
+
+// The operators the Scala matcher looks inside, others only match as equal terms
+fn matched_operands(instance: &ExprInstance) -> Option<(&Option<Par>, &Option<Par>)> {
+    match instance {
+        ExprInstance::EMultBody(_)
+        | ExprInstance::EDivBody(_)
+        | ExprInstance::EModBody(_)
+        | ExprInstance::EPercentPercentBody(_)
+        | ExprInstance::EPlusBody(_)
+        | ExprInstance::EPlusPlusBody(_)
+        | ExprInstance::EMinusMinusBody(_) => operands(instance),
+        _ => None,
+    }
+}
+
+fn set_expr(ps: Vec<Par>) -> Expr {
+    Expr {
+        expr_instance: Some(ExprInstance::ESetBody(ESet {
+            connective_used: ps.iter().any(|p| p.connective_used),
+            ps,
+            ..Default::default()
+        })),
+    }
+}
+
+fn map_expr(kvs: Vec<KeyValuePair>) -> Expr {
+    Expr {
+        expr_instance: Some(ExprInstance::EMapBody(EMap {
+            connective_used: kvs.iter().any(Term::connective_used),
+            kvs,
+            ..Default::default()
+        })),
+    }
+}
+
+fn erase_send(send: &mut Send) {
+    send.locally_free.clear();
+    send.chan.iter_mut().for_each(erase_locally_free);
+    send.data.iter_mut().for_each(erase_locally_free);
+}
+
+fn erase_receive(receive: &mut Receive) {
+    receive.locally_free.clear();
+    receive.binds.iter_mut().for_each(Term::erase);
+    receive.body.iter_mut().for_each(erase_locally_free);
+}
+
+fn erase_new(new: &mut New) {
+    new.locally_free.clear();
+    new.p.iter_mut().for_each(erase_locally_free);
+    new.injections.values_mut().for_each(erase_locally_free);
+}
+
+fn erase_match(m: &mut Match) {
+    m.locally_free.clear();
+    m.target.iter_mut().for_each(erase_locally_free);
+    m.cases.iter_mut().for_each(Term::erase);
+}
+
+fn erase_bundle(bundle: &mut Bundle) {
+    bundle.body.iter_mut().for_each(erase_locally_free);
+}
+
+fn erase_connective(connective: &mut Connective) {
+    match &mut connective.connective_instance {
+        Some(ConnectiveInstance::ConnAndBody(body) | ConnectiveInstance::ConnOrBody(body)) => {
+            body.ps.iter_mut().for_each(erase_locally_free)
+        }
+        Some(ConnectiveInstance::ConnNotBody(p)) => erase_locally_free(p),
+        _ => {}
+    }
+}
+
+fn erase_expr(expr: &mut Expr) {
+    use crate::rtypes::rtypes::{
+        EAnd, EDiv, EEq, EGt, EGte, ELt, ELte, EMinus, EMinusMinus, EMod, EMult, ENeq, EOr,
+        EPercentPercent, EPlus, EPlusPlus,
+    };
+    let Some(instance) = &mut expr.expr_instance else {
+        return;
+    };
+
+    match instance {
+        ExprInstance::ENotBody(not) => not.p.iter_mut().for_each(erase_locally_free),
+        ExprInstance::ENegBody(neg) => neg.p.iter_mut().for_each(erase_locally_free),
+        ExprInstance::EListBody(list) => {
+            list.locally_free.clear();
+            list.ps.iter_mut().for_each(erase_locally_free);
+        }
+        ExprInstance::ETupleBody(tuple) => {
+            tuple.locally_free.clear();
+            tuple.ps.iter_mut().for_each(erase_locally_free);
+        }
+        ExprInstance::ESetBody(set) => {
+            set.locally_free.clear();
+            set.ps.iter_mut().for_each(erase_locally_free);
+        }
+        ExprInstance::EMapBody(map) => {
+            map.locally_free.clear();
+            map.kvs.iter_mut().for_each(Term::erase);
+        }
+        ExprInstance::EMethodBody(method) => {
+            method.locally_free.clear();
+            method.target.iter_mut().for_each(erase_locally_free);
+            method.arguments.iter_mut().for_each(erase_locally_free);
+        }
+        ExprInstance::EMatchesBody(matches) => {
+            matches.target.iter_mut().for_each(erase_locally_free);
+            matches.pattern.iter_mut().for_each(erase_locally_free);
+        }
+        ExprInstance::EMultBody(EMult { p1, p2 })
+        | ExprInstance::EDivBody(EDiv { p1, p2 })
+        | ExprInstance::EModBody(EMod { p1, p2 })
+        | ExprInstance::EPlusBody(EPlus { p1, p2 })
+        | ExprInstance::EMinusBody(EMinus { p1, p2 })
+        | ExprInstance::ELtBody(ELt { p1, p2 })
+        | ExprInstance::ELteBody(ELte { p1, p2 })
+        | ExprInstance::EGtBody(EGt { p1, p2 })
+        | ExprInstance::EGteBody(EGte { p1, p2 })
+        | ExprInstance::EEqBody(EEq { p1, p2 })
+        | ExprInstance::ENeqBody(ENeq { p1, p2 })
+        | ExprInstance::EAndBody(EAnd { p1, p2 })
+        | ExprInstance::EOrBody(EOr { p1, p2 })
+        | ExprInstance::EPercentPercentBody(EPercentPercent { p1, p2 })
+        | ExprInstance::EPlusPlusBody(EPlusPlus { p1, p2 })
+        | ExprInstance::EMinusMinusBody(EMinusMinus { p1, p2 }) => {
+            p1.iter_mut().for_each(erase_locally_free);
+            p2.iter_mut().for_each(erase_locally_free);
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,357 @@
+#![allow(dead_code)]
+
+use crate::keyvaluestore::{Conflict, KeyValueRead, KeyValueStore, Storage, Visitor};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::ops::Bound;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, RwLock, RwLockReadGuard};
+
+type Map = BTreeMap<Vec<u8>, Vec<u8>>;
+type Pairs = Vec<(Vec<u8>, Vec<u8>)>;
+
+/*
+Non-durable storage: an ordered map in memory. A write transaction keeps its
+changes to the side while readers see committed data.
+
+Sequential, a write transaction holds the writer lock until it commits or is
+dropped, the same way an LMDB write transaction does. Concurrent, any number of
+them run at once and each records what it read of the map. Whenever another
+commit happened since, the next read and the commit check those reads against
+the map as it is then and fail with Conflict if any of them changed. So a
+transaction only ever sees one state of the map and the result is as if they
+had run one after the other.
+*/
+#[derive(Default)]
+pub struct MemStore {
+    map: RwLock<Map>,
+    // commits so far, changed under the map's write lock only
+    version: AtomicU64,
+    writer: Mutex<()>,
+    concurrent: bool,
+}
+
+pub struct MemReadTxn<'a> {
+    map: RwLockReadGuard<'a, Map>,
+}
+
+pub struct MemWriteTxn<'a> {
+    map: &'a RwLock<Map>,
+    version: &'a AtomicU64,
+    // None marks a deleted key
+    writes: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+    // held by a sequential transaction only
+    _writer: Option<MutexGuard<'a, ()>>,
+    // kept by a concurrent transaction only
+    reads: Option<Mutex<Reads>>,
+}
+
+struct Reads {
+    // the version all of reads were last checked at
+    version: u64,
+    reads: Vec<Read>,
+}
+
+// What a concurrent write transaction saw of the committed map
+enum Read {
+    Get(Vec<u8>, Option<Vec<u8>>),
+    // the committed pairs under prefix, up to until if the iteration stopped before the end
+    Prefix {
+        prefix: Vec<u8>,
+        pairs: Pairs,
+        until: Option<Vec<u8>>,
+    },
+}
+
+impl MemStore {
+    pub fn new() -> MemStore {
+        MemStore::default()
+    }
+
+    // Write transactions run at once and conflict on commit, see above
+    pub fn concurrent() -> MemStore {
+        MemStore {
+            concurrent: true,
+            ..MemStore::default()
+        }
+    }
+}
+
+impl Storage for MemStore {
+    type ReadTxn<'a> = MemReadTxn<'a>;
+    type WriteTxn<'a> = MemWriteTxn<'a>;
+
+    fn read_txn(&self) -> Result<MemReadTxn<'_>, Box<dyn Error>> {
+        Ok(MemReadTxn {
+            map: self.map.read().map_err(|err| err.to_string())?,
+        })
+    }
+
+    fn write_txn(&self) -> Result<MemWriteTxn<'_>, Box<dyn Error>> {
+        let (writer, reads) = if self.concurrent {
+            let reads = Reads {
+                version: self.version.load(Ordering::Acquire),
+                reads: vec![],
+            };
+            (None, Some(Mutex::new(reads)))
+        } else {
+            (
+                Some(self.writer.lock().map_err(|err| err.to_string())?),
+                None,
+            )
+        };
+
+        Ok(MemWriteTxn {
+            map: &self.map,
+            version: &self.version,
+            writes: BTreeMap::new(),
+            _writer: writer,
+            reads,
+        })
+    }
+
+    fn is_empty(&self) -> Result<bool, Box<dyn Error>> {
+        Ok(self.map.read().map_err(|err| err.to_string())?.is_empty())
+    }
+
+    // Waits for the open write transaction, like any other writer. Concurrent ones conflict instead
+    fn clear(&self) -> Result<(), Box<dyn Error>> {
+        let _writer = self.writer.lock().map_err(|err| err.to_string())?;
+        let mut map = self.map.write().map_err(|err| err.to_string())?;
+        map.clear();
+        self.version.fetch_add(1, Ordering::Release);
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+}
+
+// Committed pairs a write transaction copies out per read lock, see iterate_prefix
+const BATCH: usize = 256;
+
+fn range_from<'m, V>(
+    map: &'m BTreeMap<Vec<u8>, V>,
+    prefix: &'m [u8],
+) -> impl Iterator<Item = (&'m Vec<u8>, &'m V)> {
+    range_after(map, prefix, Bound::Included(prefix))
+}
+
+fn range_after<'m, V>(
+    map: &'m BTreeMap<Vec<u8>, V>,
+    prefix: &'m [u8],
+    start: Bound<&'m [u8]>,
+) -> impl Iterator<Item = (&'m Vec<u8>, &'m V)> {
+    map.range::<[u8], _>((start, Bound::Unbounded))
+        .take_while(move |(key, _)| key.starts_with(prefix))
+}
+
+impl KeyValueRead for MemReadTxn<'_> {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+        Ok(self.map.get(key).cloned())
+    }
+
+    fn iterate_prefix(&self, prefix: &[u8], f: &mut Visitor) -> Result<(), Box<dyn Error>> {
+        for (key, value) in range_from(&self.map, prefix) {
+            if !f(key, value)? {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn iterate_from(&self, start: &[u8], f: &mut Visitor) -> Result<(), Box<dyn Error>> {
+        for (key, value) in range_after(&self.map, &[], Bound::Included(start)) {
+            if !f(key, value)? {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl MemWriteTxn<'_> {
+    /*
+    Records read as what this transaction saw of map, which is locked. Fails with
+    Conflict instead if map no longer holds what it saw before. Returns the
+    read's index, for a prefix read to grow as it goes on.
+    */
+    fn record(&self, map: &Map, read: Read) -> Result<Option<usize>, Box<dyn Error>> {
+        let Some(reads) = &self.reads else {
+            return Ok(None);
+        };
+        let mut reads = reads.lock().map_err(|err| err.to_string())?;
+        validate(&mut reads, map, self.version.load(Ordering::Acquire))?;
+        reads.reads.push(read);
+        Ok(Some(reads.reads.len() - 1))
+    }
+
+    // Adds a batch of committed pairs to the prefix read at index
+    fn extend(
+        &self,
+        map: &Map,
+        index: Option<usize>,
+        batch: &[(Vec<u8>, Vec<u8>)],
+        exhausted: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        let (Some(reads), Some(index)) = (&self.reads, index) else {
+            return Ok(());
+        };
+        let mut reads = reads.lock().map_err(|err| err.to_string())?;
+        validate(&mut reads, map, self.version.load(Ordering::Acquire))?;
+        if let Read::Prefix { pairs, until, .. } = &mut reads.reads[index] {
+            pairs.extend_from_slice(batch);
+            *until = match exhausted {
+                true => None,
+                false => pairs.last().map(|(key, _)| key.clone()),
+            };
+        }
+        Ok(())
+    }
+
+    /*
+    Merges the committed pairs with the ones written so far, the latter winning.
+    The committed ones are copied out a batch at a time and f runs with the map
+    unlocked, so it can read through this transaction again.
+    */
+    fn merge_prefix(&self, prefix: &[u8], f: &mut Visitor) -> Result<(), Box<dyn Error>> {
+        let mut written = range_from(&self.writes, prefix).peekable();
+        let mut last: Option<Vec<u8>> = None;
+        let mut index = None;
+
+        loop {
+            let (batch, exhausted) = {
+                let map = self.map.read().map_err(|err| err.to_string())?;
+                let start = match &last {
+                    Some(key) => Bound::Excluded(key.as_slice()),
+                    None => Bound::Included(prefix),
+                };
+                let batch: Pairs = range_after(&map, prefix, start)
+                    .take(BATCH)
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect();
+                let exhausted = batch.len() < BATCH;
+
+                if last.is_none() {
+                    let read = Read::Prefix {
+                        prefix: prefix.to_vec(),
+                        pairs: vec![],
+                        until: Some(prefix.to_vec()),
+                    };
+                    index = self.record(&map, read)?;
+                }
+                self.extend(&map, index, &batch, exhausted)?;
+                (batch, exhausted)
+            };
+
+            for (key, value) in &batch {
+                // written keys up to this one come first, a write to it replaces it
+                let mut replaced = false;
+                while let Some((written_key, write)) =
+                    written.next_if(|(written_key, _)| *written_key <= key)
+                {
+                    replaced = written_key == key;
+                    if let Some(write) = write {
+                        if !f(written_key, write)? {
+                            return Ok(());
+                        }
+                    }
+                }
+                if !replaced && !f(key, value)? {
+                    return Ok(());
+                }
+            }
+
+            if exhausted {
+                break;
+            }
+            last = batch.into_iter().last().map(|(key, _)| key);
+        }
+
+        for (key, write) in written {
+            if let Some(value) = write {
+                if !f(key, value)? {
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl KeyValueRead for MemWriteTxn<'_> {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+        if let Some(write) = self.writes.get(key) {
+            return Ok(write.clone());
+        }
+        let map = self.map.read().map_err(|err| err.to_string())?;
+        let value = map.get(key).cloned();
+
+        self.record(&map, Read::Get(key.to_vec(), value.clone()))?;
+        Ok(value)
+    }
+
+    fn iterate_prefix(&self, prefix: &[u8], f: &mut Visitor) -> Result<(), Box<dyn Error>> {
+        self.merge_prefix(prefix, f)
+    }
+}
+
+// Fails with Conflict if a commit since reads were checked changed any of them
+fn validate(reads: &mut Reads, map: &Map, version: u64) -> Result<(), Box<dyn Error>> {
+    if reads.version != version {
+        if !reads.reads.iter().all(|read| unchanged(map, read)) {
+            return Err(Conflict.into());
+        }
+        reads.version = version;
+    }
+    Ok(())
+}
+
+// Whether map still holds what read saw
+fn unchanged(map: &Map, read: &Read) -> bool {
+    match read {
+        Read::Get(key, value) => map.get(key) == value.as_ref(),
+        Read::Prefix {
+            prefix,
+            pairs,
+            until,
+        } => range_from(map, prefix)
+            .take_while(|(key, _)| until.as_ref().is_none_or(|until| *key <= until))
+            .eq(pairs.iter().map(|(key, value)| (key, value))),
+    }
+}
+
+impl KeyValueStore for MemWriteTxn<'_> {
+    fn put(&mut self, key: &[u8], value: &[u8]) -> Result<(), Box<dyn Error>> {
+        self.writes.insert(key.to_vec(), Some(value.to_vec()));
+        Ok(())
+    }
+
+    fn delete(&mut self, key: &[u8]) -> Result<(), Box<dyn Error>> {
+        self.writes.insert(key.to_vec(), None);
+        Ok(())
+    }
+
+    fn commit(self) -> Result<(), Box<dyn Error>> {
+        let mut map = self.map.write().map_err(|err| err.to_string())?;
+        let version = self.version.load(Ordering::Acquire);
+        if let Some(reads) = self.reads {
+            let mut reads = reads.into_inner().map_err(|err| err.to_string())?;
+            validate(&mut reads, &map, version)?;
+        }
+
+        for (key, write) in self.writes {
+            match write {
+                Some(value) => map.insert(key, value),
+                None => map.remove(&key),
+            };
+        }
+        self.version.store(version + 1, Ordering::Release);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,362 @@
+#![allow(dead_code)]
+
+use crate::exporter::Blake2b256Hash;
+use crate::hotstore::{HistoryStore, HotStoreTrieAction};
+use crate::keyvaluestore::Storage;
+use crate::rtypes::rtypes;
+use crate::trace::Event;
+use crate::tuplespace::{self, TupleSpace};
+use std::collections::BTreeMap;
+use std::error::Error;
+
+// How one event log changed a channel, relative to the base it ran on
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChannelChange {
+    pub added_data: Vec<rtypes::RetrieveContinuation>,
+    pub removed_data: Vec<rtypes::RetrieveContinuation>,
+    pub added_continuations: Vec<rtypes::CommitContinuation>,
+    pub removed_continuations: Vec<rtypes::CommitContinuation>,
+}
+
+// An entry a log took out of its base, and the channels of the event that took it
+#[derive(Clone, Debug, PartialEq)]
+struct Taken<T> {
+    channel: String,
+    value: T,
+    event_channels: Vec<String>,
+}
+
+// An entry a log left stored, and the channels of the event that stored it
+#[derive(Clone, Debug, PartialEq)]
+struct Created<T> {
+    channel: String,
+    value: T,
+    event_channels: Vec<String>,
+}
+
+/*
+What one event log did to its base: the entries it took out, the entries it left
+behind and the resulting change per channel. Entries stored and taken within the
+same log cancel out.
+See EventLogIndex.scala in rspace/merger/
+*/
+#[derive(Clone, Debug, Default)]
+pub struct EventLogIndex {
+    taken_data: Vec<Taken<rtypes::RetrieveContinuation>>,
+    taken_continuations: Vec<Taken<rtypes::CommitContinuation>>,
+    created_data: Vec<Created<rtypes::RetrieveContinuation>>,
+    created_continuations: Vec<Created<rtypes::CommitContinuation>>,
+}
+
+impl EventLogIndex {
+    pub fn new(events: &[Event]) -> EventLogIndex {
+        let mut index = EventLogIndex::default();
+
+        for event in events {
+            let event_channels = event.channels();
+            match event {
+                Event::Produce { channel, datum } => index.created_data.push(Created {
+                    channel: channel.clone(),
+                    value: (**datum).clone(),
+                    event_channels,
+                }),
+                Event::Consume { continuations } => {
+                    for (channel, ccdata) in continuations {
+                        index.created_continuations.push(Created {
+                            channel: channel.clone(),
+                            value: ccdata.clone(),
+                            event_channels: event_channels.clone(),
+                        });
+                    }
+                }
+                Event::Comm {
+                    data,
+                    continuations,
+                } => {
+                    for (channel, rcdata) in data.iter().filter(|(_, rcdata)| !rcdata.persistent) {
+                        take(
+                            &mut index.created_data,
+                            &mut index.taken_data,
+                            channel,
+                            rcdata,
+                            &event_channels,
+                        );
+                    }
+                    for (channel, ccdata) in continuations
+                        .iter()
+                        .filter(|(_, ccdata)| !ccdata.persistent)
+                    {
+                        take(
+                            &mut index.created_continuations,
+                            &mut index.taken_continuations,
+                            channel,
+                            ccdata,
+                            &event_channels,
+                        );
+                    }
+                }
+            }
+        }
+
+        index
+    }
+
+    // Net change per channel, ordered by channel
+    pub fn changes(&self) -> BTreeMap<String, ChannelChange> {
+        let mut changes: BTreeMap<String, ChannelChange> = BTreeMap::new();
+
+        for created in &self.created_data {
+            let change = changes.entry(created.channel.clone()).or_default();
+            change.added_data.push(created.value.clone());
+        }
+        for created in &self.created_continuations {
+            let change = changes.entry(created.channel.clone()).or_default();
+            change.added_continuations.push(created.value.clone());
+        }
+        for taken in &self.taken_data {
+            let change = changes.entry(taken.channel.clone()).or_default();
+            change.removed_data.push(taken.value.clone());
+        }
+        for taken in &self.taken_continuations {
+            let change = changes.entry(taken.channel.clone()).or_default();
+            change.removed_continuations.push(taken.value.clone());
+        }
+
+        changes
+    }
+}
+
+// Taking an entry the log stored itself cancels the store, otherwise it comes out of the base
+fn take<T: Clone + PartialEq>(
+    created: &mut Vec<Created<T>>,
+    taken: &mut Vec<Taken<T>>,
+    channel: &str,
+    value: &T,
+    event_channels: &[String],
+) {
+    match created
+        .iter()
+        .position(|entry| entry.channel == channel && &entry.value == value)
+    {
+        Some(position) => {
+            created.remove(position);
+        }
+        None => taken.push(Taken {
+            channel: channel.to_string(),
+            value: value.clone(),
+            event_channels: event_channels.to_vec(),
+        }),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConflictReason {
+    // Both logs took the same non-persistent entry out of the base
+    SameEntryTaken,
+    // One log left a datum where the other left a continuation it matches,
+    // run one after the other they would have fired
+    PotentialComm,
+}
+
+// Channels of the two events that conflict, one from each log
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Conflict {
+    pub left: Vec<String>,
+    pub right: Vec<String>,
+    pub reason: ConflictReason,
+}
+
+// See MergingLogic.scala in rspace/merger/
+pub fn conflicts(left: &EventLogIndex, right: &EventLogIndex) -> Vec<Conflict> {
+    let mut conflicts = vec![];
+
+    for (l, r) in pairs(&left.taken_data, &right.taken_data) {
+        if l.channel == r.channel && l.value == r.value {
+            conflicts.push(conflict(
+                &l.event_channels,
+                &r.event_channels,
+                ConflictReason::SameEntryTaken,
+            ));
+        }
+    }
+    for (l, r) in pairs(&left.taken_continuations, &right.taken_continuations) {
+        if l.channel == r.channel && l.value == r.value {
+            conflicts.push(conflict(
+                &l.event_channels,
+                &r.event_channels,
+                ConflictReason::SameEntryTaken,
+            ));
+        }
+    }
+
+    for (datum, continuation) in pairs(&left.created_data, &right.created_continuations) {
+        if could_match(datum, continuation) {
+            conflicts.push(conflict(
+                &datum.event_channels,
+                &continuation.event_channels,
+                ConflictReason::PotentialComm,
+            ));
+        }
+    }
+    for (continuation, datum) in pairs(&left.created_continuations, &right.created_data) {
+        if could_match(datum, continuation) {
+            conflicts.push(conflict(
+                &continuation.event_channels,
+                &datum.event_channels,
+                ConflictReason::PotentialComm,
+            ));
+        }
+    }
+
+    conflicts.sort();
+    conflicts.dedup();
+    conflicts
+}
+
+fn pairs<'a, L, R>(left: &'a [L], right: &'a [R]) -> impl Iterator<Item = (&'a L, &'a R)> {
+    left.iter()
+        .flat_map(move |l| right.iter().map(move |r| (l, r)))
+}
+
+// The store's own test for firing: same channel and match key, and the pars fit the bind pattern
+fn could_match(
+    datum: &Created<rtypes::RetrieveContinuation>,
+    continuation: &Created<rtypes::CommitContinuation>,
+) -> bool {
+    datum.channel == continuation.channel
+        && datum.value.match_case == continuation.value.pattern
+        && tuplespace::bind(
+            continuation.value.bind_pattern.as_ref(),
+            datum.value.pars.as_ref(),
+        )
+        .is_some()
+}
+
+fn conflict(left: &[String], right: &[String], reason: ConflictReason) -> Conflict {
+    Conflict {
+        left: left.to_vec(),
+        right: right.to_vec(),
+        reason,
+    }
+}
+
+// Both logs' changes, once they are known not to conflict
+pub fn merged_changes(
+    left: &EventLogIndex,
+    right: &EventLogIndex,
+) -> BTreeMap<String, ChannelChange> {
+    let mut changes = left.changes();
+
+    for (channel, change) in right.changes() {
+        let merged = changes.entry(channel).or_default();
+        merged.added_data.extend(change.added_data);
+        merged.removed_data.extend(change.removed_data);
+        merged
+            .added_continuations
+            .extend(change.added_continuations);
+        merged
+            .removed_continuations
+            .extend(change.removed_continuations);
+    }
+
+    changes
+}
+
+/*
+Actions that apply changes to what history holds. Fails if a removed entry is
+not there, which means the logs did not run on this base.
+See StateChangeMerger.scala in rspace/merger/
+*/
+pub fn change_actions(
+    history: &impl HistoryStore,
+    changes: &BTreeMap<String, ChannelChange>,
+) -> Result<Vec<HotStoreTrieAction>, Box<dyn Error>> {
+    let mut actions = vec![];
+
+    for (channel, change) in changes {
+        let entries = history.read_channel(channel)?;
+
+        if !change.added_data.is_empty() || !change.removed_data.is_empty() {
+            let data = apply(entries.data, &change.removed_data, &change.added_data)?;
+            actions.push(if data.is_empty() {
+                HotStoreTrieAction::TrieDeleteProduce {
+                    channel: channel.clone(),
+                }
+            } else {
+                HotStoreTrieAction::TrieInsertProduce {
+                    channel: channel.clone(),
+                    data,
+                }
+            });
+        }
+
+        if !change.added_continuations.is_empty() || !change.removed_continuations.is_empty() {
+            let continuations = apply(
+                entries.continuations,
+                &change.removed_continuations,
+                &change.added_continuations,
+            )?;
+            actions.push(if continuations.is_empty() {
+                HotStoreTrieAction::TrieDeleteConsume {
+                    channel: channel.clone(),
+                }
+            } else {
+                HotStoreTrieAction::TrieInsertConsume {
+                    channel: channel.clone(),
+                    continuations,
+                }
+            });
+        }
+    }
+
+    Ok(actions)
+}
+
+fn apply<T: Clone + PartialEq + std::fmt::Debug>(
+    mut entries: Vec<T>,
+    removed: &[T],
+    added: &[T],
+) -> Result<Vec<T>, Box<dyn Error>> {
+    for value in removed {
+        let position = entries
+            .iter()
+            .position(|entry| entry == value)
+            .ok_or_else(|| format!("{:?} is not in the base", value))?;
+        entries.remove(position);
+    }
+    entries.extend(added.iter().cloned());
+
+    Ok(entries)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MergeOutcome {
+    // The root of base after both logs' changes
+    Merged(Blake2b256Hash),
+    // Nothing was applied
+    Conflicting(Vec<Conflict>),
+}
+
+// Merges two logs that ran on what base holds now, writing the result into base
+pub fn merge<
+    S: Storage,
+    D: Clone + std::hash::Hash + std::fmt::Debug + std::default::Default + prost::Message,
+    K: Clone + std::hash::Hash + std::fmt::Debug + std::default::Default + prost::Message,
+>(
+    base: &TupleSpace<S, D, K>,
+    left: &[Event],
+    right: &[Event],
+) -> Result<MergeOutcome, Box<dyn Error>> {
+    let left = EventLogIndex::new(left);
+    let right = EventLogIndex::new(right);
+
+    let conflicts = conflicts(&left, &right);
+    if !conflicts.is_empty() {
+        return Ok(MergeOutcome::Conflicting(conflicts));
+    }
+
+    let actions = change_actions(base, &merged_changes(&left, &right))?;
+    base.apply_actions(&actions)?;
+
+    Ok(MergeOutcome::Merged(base.root()?))
+}
@@ -0,0 +1,221 @@
+#![allow(dead_code)]
+
+use crate::rtypes::rtypes;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+/*
+Where counters, gauges and latencies go. Nothing is recorded until a recorder is
+set, after which every verb, match and LMDB transaction reports to it.
+See Metrics.scala in shared/metrics/
+*/
+pub trait Metrics: Send + Sync {
+    fn increment_counter(&self, name: &str, delta: u64);
+
+    fn set_gauge(&self, name: &str, value: i64);
+
+    fn record(&self, name: &str, value: Duration);
+
+    // Everything recorded so far, recorders that only forward elsewhere have nothing to show
+    fn snapshot(&self) -> rtypes::MetricsSnapshot {
+        rtypes::MetricsSnapshot::default()
+    }
+}
+
+static RECORDER: RwLock<Option<Arc<dyn Metrics>>> = RwLock::new(None);
+
+pub fn set_recorder(recorder: Arc<dyn Metrics>) -> Result<(), Box<dyn Error>> {
+    *RECORDER.write().map_err(|err| err.to_string())? = Some(recorder);
+    Ok(())
+}
+
+pub fn clear_recorder() -> Result<(), Box<dyn Error>> {
+    *RECORDER.write().map_err(|err| err.to_string())? = None;
+    Ok(())
+}
+
+pub fn recorder() -> Option<Arc<dyn Metrics>> {
+    RECORDER.read().ok()?.clone()
+}
+
+pub fn increment_counter(name: &str, delta: u64) {
+    if let Some(recorder) = recorder() {
+        recorder.increment_counter(name, delta);
+    }
+}
+
+pub fn set_gauge(name: &str, value: i64) {
+    if let Some(recorder) = recorder() {
+        recorder.set_gauge(name, value);
+    }
+}
+
+pub fn record(name: &str, value: Duration) {
+    if let Some(recorder) = recorder() {
+        recorder.record(name, value);
+    }
+}
+
+pub fn snapshot() -> rtypes::MetricsSnapshot {
+    recorder()
+        .map(|recorder| recorder.snapshot())
+        .unwrap_or_default()
+}
+
+/*
+Times one verb call, named like rspace.produce.once.durable. On finish it counts
+the call under .calls, the match under .hit or .miss, and the latency under .latency
+*/
+pub struct VerbTimer {
+    name: String,
+    started: Instant,
+}
+
+impl VerbTimer {
+    pub fn start(verb: &str, persistent: bool, durable: bool) -> VerbTimer {
+        VerbTimer {
+            name: format!(
+                "rspace.{}.{}.{}",
+                verb,
+                if persistent { "always" } else { "once" },
+                if durable { "durable" } else { "non_durable" }
+            ),
+            started: Instant::now(),
+        }
+    }
+
+    pub fn finish(self, matched: bool) {
+        self.record(if matched { "hit" } else { "miss" });
+    }
+
+    // A verb that failed counts as neither hit nor miss
+    pub fn fail(self) {
+        self.record("error");
+    }
+
+    fn record(self, outcome: &str) {
+        if let Some(recorder) = recorder() {
+            recorder.record(&format!("{}.latency", self.name), self.started.elapsed());
+            recorder.increment_counter(&format!("{}.calls", self.name), 1);
+            recorder.increment_counter(&format!("{}.{}", self.name, outcome), 1);
+        }
+    }
+}
+
+// Upper bounds of the latency buckets, in microseconds. Anything slower lands in the last bucket
+const BUCKETS: [u64; 10] = [
+    10,
+    50,
+    100,
+    500,
+    1_000,
+    5_000,
+    10_000,
+    50_000,
+    100_000,
+    u64::MAX,
+];
+
+// Keeps everything in memory, for tests and for space_metrics_snapshot
+#[derive(Default)]
+pub struct InMemoryMetrics {
+    counters: Mutex<BTreeMap<String, u64>>,
+    gauges: Mutex<BTreeMap<String, i64>>,
+    histograms: Mutex<BTreeMap<String, rtypes::Histogram>>,
+}
+
+impl InMemoryMetrics {
+    pub fn new() -> InMemoryMetrics {
+        InMemoryMetrics::default()
+    }
+
+    pub fn counter(&self, name: &str) -> u64 {
+        self.counters
+            .lock()
+            .map_or(0, |counters| counters.get(name).copied().unwrap_or(0))
+    }
+
+    pub fn gauge(&self, name: &str) -> Option<i64> {
+        self.gauges.lock().ok()?.get(name).copied()
+    }
+
+    pub fn histogram(&self, name: &str) -> Option<rtypes::Histogram> {
+        self.histograms.lock().ok()?.get(name).cloned()
+    }
+}
+
+impl Metrics for InMemoryMetrics {
+    fn increment_counter(&self, name: &str, delta: u64) {
+        if let Ok(mut counters) = self.counters.lock() {
+            *counters.entry(name.to_string()).or_insert(0) += delta;
+        }
+    }
+
+    fn set_gauge(&self, name: &str, value: i64) {
+        if let Ok(mut gauges) = self.gauges.lock() {
+            gauges.insert(name.to_string(), value);
+        }
+    }
+
+    fn record(&self, name: &str, value: Duration) {
+        let micros = value.as_micros().min(u64::MAX as u128) as u64;
+
+        if let Ok(mut histograms) = self.histograms.lock() {
+            let histogram =
+                histograms
+                    .entry(name.to_string())
+                    .or_insert_with(|| rtypes::Histogram {
+                        name: name.to_string(),
+                        min_micros: u64::MAX,
+                        buckets: BUCKETS
+                            .iter()
+                            .map(|&le_micros| rtypes::HistogramBucket {
+                                le_micros,
+                                count: 0,
+                            })
+                            .collect(),
+                        ..Default::default()
+                    });
+            histogram.count += 1;
+            histogram.sum_micros = histogram.sum_micros.saturating_add(micros);
+            histogram.min_micros = histogram.min_micros.min(micros);
+            histogram.max_micros = histogram.max_micros.max(micros);
+            if let Some(bucket) = histogram
+                .buckets
+                .iter_mut()
+                .find(|bucket| micros <= bucket.le_micros)
+            {
+                bucket.count += 1;
+            }
+        }
+    }
+
+    fn snapshot(&self) -> rtypes::MetricsSnapshot {
+        rtypes::MetricsSnapshot {
+            counters: self.counters.lock().map_or(vec![], |counters| {
+                counters
+                    .iter()
+                    .map(|(name, &value)| rtypes::Counter {
+                        name: name.clone(),
+                        value,
+                    })
+                    .collect()
+            }),
+            gauges: self.gauges.lock().map_or(vec![], |gauges| {
+                gauges
+                    .iter()
+                    .map(|(name, &value)| rtypes::Gauge {
+                        name: name.clone(),
+                        value,
+                    })
+                    .collect()
+            }),
+            histograms: self
+                .histograms
+                .lock()
+                .map_or(vec![], |histograms| histograms.values().cloned().collect()),
+        }
+    }
+}
@@ -0,0 +1,294 @@
+#![allow(dead_code)]
+
+use crate::keyvaluestore::{
+    Durability, Flusher, KeyValueRead, KeyValueStore, Storage, SyncPolicy, Visitor,
+};
+use redb::{
+    Database, ReadTransaction, ReadableTable, ReadableTableMetadata, TableDefinition,
+    WriteTransaction,
+};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::marker::PhantomData;
+use std::ops::Bound;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+const ENTRIES: TableDefinition<&[u8], &[u8]> = TableDefinition::new("entries");
+
+// Pairs a write transaction reads per opening of the table, see iterate_prefix
+const BATCH: usize = 256;
+
+/*
+redb locks its file, so every store opened on the same directory shares one
+Database. The last of them to be dropped closes it and takes it out.
+*/
+static OPENED_DATABASES: OnceLock<Mutex<HashMap<PathBuf, Arc<Database>>>> = OnceLock::new();
+
+/*
+Durable storage on redb, a pure-Rust embedded store. Unlike LMDB it needs no
+preset map size. Only built with the "redb" feature.
+*/
+pub struct RedbStore {
+    // taken only by drop
+    db: Option<Arc<Database>>,
+    path: PathBuf,
+    sync: Arc<SyncPolicy>,
+    // syncs a group commit store gone idle, without keeping its database open
+    flusher: Option<Flusher>,
+}
+
+pub struct RedbReadTxn<'a> {
+    txn: ReadTransaction,
+    phantom: PhantomData<&'a RedbStore>,
+}
+
+pub struct RedbWriteTxn<'a> {
+    txn: WriteTransaction,
+    store: &'a RedbStore,
+}
+
+impl RedbStore {
+    pub fn open(path: &Path) -> Result<RedbStore, Box<dyn Error>> {
+        RedbStore::open_with_durability(path, Durability::Sync)
+    }
+
+    pub fn open_with_durability(
+        path: &Path,
+        durability: Durability,
+    ) -> Result<RedbStore, Box<dyn Error>> {
+        fs::create_dir_all(path)?;
+        let path = fs::canonicalize(path)?;
+
+        let mut opened = OPENED_DATABASES
+            .get_or_init(Default::default)
+            .lock()
+            .map_err(|err| err.to_string())?;
+        let db = match opened.get(&path) {
+            Some(db) => db.clone(),
+            None => {
+                let db = Arc::new(Database::create(path.join("data.redb"))?);
+
+                // reads expect the table to exist
+                let txn = db.begin_write()?;
+                txn.open_table(ENTRIES)?;
+                txn.commit()?;
+
+                opened.insert(path.clone(), db.clone());
+                db
+            }
+        };
+
+        let sync = Arc::new(SyncPolicy::new(durability));
+        let flusher = Flusher::spawn(sync.clone(), {
+            let (db, sync) = (Arc::downgrade(&db), sync.clone());
+            move || match db.upgrade() {
+                Some(db) => flush(&db, &sync),
+                None => Ok(()),
+            }
+        });
+
+        Ok(RedbStore {
+            db: Some(db),
+            path,
+            sync,
+            flusher,
+        })
+    }
+
+    pub fn durability(&self) -> Durability {
+        self.sync.durability()
+    }
+
+    fn db(&self) -> &Database {
+        self.db.as_ref().expect("database is open until drop")
+    }
+
+    // Commits not yet synced
+    pub fn pending(&self) -> Result<usize, Box<dyn Error>> {
+        self.sync.pending()
+    }
+
+    /*
+    redb decides at commit time whether a transaction waits for the disk. Commits
+    that do not are only persisted by the next one that does, and redb frees pages
+    only then, so a no-sync store grows until it is flushed.
+    */
+    fn commit(&self, mut txn: WriteTransaction) -> Result<(), Box<dyn Error>> {
+        if self.sync.commit_is_due()? {
+            txn.set_durability(redb::Durability::Immediate);
+        } else {
+            txn.set_durability(redb::Durability::None);
+        }
+        txn.commit()?;
+
+        Ok(())
+    }
+}
+
+// Whatever is still unsynced gets synced when the store goes away
+impl Drop for RedbStore {
+    fn drop(&mut self) {
+        if self.sync.pending().unwrap_or(0) > 0 {
+            let _ = self.flush();
+        }
+        self.flusher.take();
+
+        // closed under the lock, so an open of the same path never finds the file still locked
+        let Ok(mut opened) = OPENED_DATABASES.get_or_init(Default::default).lock() else {
+            return;
+        };
+        if let Some(db) = self.db.take() {
+            if Arc::strong_count(&db) == 2 {
+                opened.remove(&self.path);
+            }
+            drop(db);
+        }
+    }
+}
+
+impl Storage for RedbStore {
+    type ReadTxn<'a> = RedbReadTxn<'a>;
+    type WriteTxn<'a> = RedbWriteTxn<'a>;
+
+    fn read_txn(&self) -> Result<RedbReadTxn<'_>, Box<dyn Error>> {
+        Ok(RedbReadTxn {
+            txn: self.db().begin_read()?,
+            phantom: PhantomData,
+        })
+    }
+
+    fn write_txn(&self) -> Result<RedbWriteTxn<'_>, Box<dyn Error>> {
+        Ok(RedbWriteTxn {
+            txn: self.db().begin_write()?,
+            store: self,
+        })
+    }
+
+    fn is_empty(&self) -> Result<bool, Box<dyn Error>> {
+        let txn = self.db().begin_read()?;
+        let table = txn.open_table(ENTRIES)?;
+        Ok(table.is_empty()?)
+    }
+
+    fn clear(&self) -> Result<(), Box<dyn Error>> {
+        let txn = self.db().begin_write()?;
+        txn.open_table(ENTRIES)?.retain(|_, _| false)?;
+        self.commit(txn)
+    }
+
+    fn flush(&self) -> Result<(), Box<dyn Error>> {
+        flush(self.db(), &self.sync)
+    }
+}
+
+// An immediate commit also persists every commit before it
+fn flush(db: &Database, sync: &SyncPolicy) -> Result<(), Box<dyn Error>> {
+    let mut txn = db.begin_write()?;
+    txn.set_durability(redb::Durability::Immediate);
+    txn.commit()?;
+
+    sync.synced()
+}
+
+fn get(
+    table: &impl ReadableTable<&'static [u8], &'static [u8]>,
+    key: &[u8],
+) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+    Ok(table.get(key)?.map(|value| value.value().to_vec()))
+}
+
+fn iterate_prefix(
+    table: &impl ReadableTable<&'static [u8], &'static [u8]>,
+    prefix: &[u8],
+    f: &mut Visitor,
+) -> Result<(), Box<dyn Error>> {
+    for entry in table.range(prefix..)? {
+        let (key, value) = entry?;
+        if !key.value().starts_with(prefix) || !f(key.value(), value.value())? {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+impl KeyValueRead for RedbReadTxn<'_> {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+        get(&self.txn.open_table(ENTRIES)?, key)
+    }
+
+    fn iterate_prefix(&self, prefix: &[u8], f: &mut Visitor) -> Result<(), Box<dyn Error>> {
+        iterate_prefix(&self.txn.open_table(ENTRIES)?, prefix, f)
+    }
+
+    fn iterate_from(&self, start: &[u8], f: &mut Visitor) -> Result<(), Box<dyn Error>> {
+        for entry in self.txn.open_table(ENTRIES)?.range(start..)? {
+            let (key, value) = entry?;
+            if !f(key.value(), value.value())? {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl KeyValueRead for RedbWriteTxn<'_> {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+        get(&self.txn.open_table(ENTRIES)?, key)
+    }
+
+    /*
+    A write transaction can only have the table open once, so f may not run while
+    the table is open to iterate. Pairs are read a batch at a time with the table
+    closed in between, each batch starting after the last key of the one before.
+    */
+    fn iterate_prefix(&self, prefix: &[u8], f: &mut Visitor) -> Result<(), Box<dyn Error>> {
+        let mut start = Bound::Included(prefix.to_vec());
+
+        loop {
+            let mut batch = Vec::with_capacity(BATCH);
+            {
+                let table = self.txn.open_table(ENTRIES)?;
+                let range =
+                    table.range::<&[u8]>((start.as_ref().map(Vec::as_slice), Bound::Unbounded))?;
+                for entry in range.take(BATCH) {
+                    let (key, value) = entry?;
+                    if !key.value().starts_with(prefix) {
+                        break;
+                    }
+                    batch.push((key.value().to_vec(), value.value().to_vec()));
+                }
+            }
+            let exhausted = batch.len() < BATCH;
+
+            for (key, value) in &batch {
+                if !f(key, value)? {
+                    return Ok(());
+                }
+            }
+            match batch.pop() {
+                Some((key, _)) if !exhausted => start = Bound::Excluded(key),
+                _ => return Ok(()),
+            }
+        }
+    }
+}
+
+impl KeyValueStore for RedbWriteTxn<'_> {
+    fn put(&mut self, key: &[u8], value: &[u8]) -> Result<(), Box<dyn Error>> {
+        self.txn.open_table(ENTRIES)?.insert(key, value)?;
+        Ok(())
+    }
+
+    fn delete(&mut self, key: &[u8]) -> Result<(), Box<dyn Error>> {
+        self.txn.open_table(ENTRIES)?.remove(key)?;
+        Ok(())
+    }
+
+    fn commit(self) -> Result<(), Box<dyn Error>> {
+        self.store.commit(self.txn)
+    }
+}
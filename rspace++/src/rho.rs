@@ -0,0 +1,79 @@
+#![allow(dead_code)]
+
+use crate::history::to_hex;
+use crate::rtypes::rtypes::{
+    BindPattern, Commit, ListParWithRandom, Par, Retrieve, TaggedContinuation,
+};
+use blake2::digest::consts::U32;
+use blake2::{Blake2b, Digest};
+use prost::Message;
+use std::borrow::Cow;
+
+/*
+Rholang sends and receives, see rhotypes.proto. A Par channel is stored under the
+hex Blake2b256 hash of its encoding, as StableHashProvider hashes channels in
+rspace/, so the same name from Scala and from Rust is the same channel here.
+*/
+pub fn channel_name(channel: &Par) -> String {
+    to_hex(&Blake2b::<U32>::digest(channel.encode_to_vec()))
+}
+
+// A send of pars on channel
+pub fn retrieve(channel: Par, pars: ListParWithRandom, match_case: &str) -> Retrieve {
+    Retrieve {
+        chan: channel_name(&channel),
+        data: None,
+        match_case: match_case.to_string(),
+        channel: Some(channel),
+        pars: Some(pars),
+    }
+}
+
+// A receive on channels, with a bind pattern for each
+pub fn commit(
+    channels: Vec<Par>,
+    patterns: Vec<String>,
+    bind_patterns: Vec<BindPattern>,
+    continuation: TaggedContinuation,
+) -> Commit {
+    Commit {
+        channels: channels.iter().map(channel_name).collect(),
+        patterns,
+        continuation: Some(continuation),
+        channel_pars: channels,
+        bind_patterns,
+    }
+}
+
+// The send with chan named after its Par channel, as is when it has none
+pub fn named_retrieve(retrieve: &Retrieve) -> Cow<'_, Retrieve> {
+    match &retrieve.channel {
+        Some(channel) => {
+            let chan = channel_name(channel);
+            if chan == retrieve.chan {
+                return Cow::Borrowed(retrieve);
+            }
+            Cow::Owned(Retrieve {
+                chan,
+                ..retrieve.clone()
+            })
+        }
+        None => Cow::Borrowed(retrieve),
+    }
+}
+
+// The receive with channels named after its Par channels, as is when it has none
+pub fn named_commit(commit: &Commit) -> Cow<'_, Commit> {
+    if commit.channel_pars.is_empty() {
+        return Cow::Borrowed(commit);
+    }
+
+    let channels: Vec<String> = commit.channel_pars.iter().map(channel_name).collect();
+    if channels == commit.channels {
+        return Cow::Borrowed(commit);
+    }
+    Cow::Owned(Commit {
+        channels,
+        ..commit.clone()
+    })
+}
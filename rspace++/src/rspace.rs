@@ -1,17 +1,57 @@
-use crate::diskconc::DiskConcDB;
-use crate::diskseq::DiskSeqDB;
-use crate::memconc::MemConcDB;
-use crate::memseq::MemSeqDB;
+use crate::exporter::{Blake2b256Hash, RSpaceExporter};
+use crate::history::HistoryRepository;
+use crate::hotstore::HotStore;
+use crate::keyvaluestore::{Durability, Storage};
+use crate::lmdbstore::LmdbStore;
+use crate::memorystore::MemStore;
+use crate::metrics::{self, VerbTimer};
 use crate::rtypes::rtypes;
+use crate::trace::Event;
+use crate::transaction::{Comm, Transaction};
+use crate::tuplespace::{ChannelEntries, TupleSpace};
+use crate::watch::Watch;
+use std::collections::BTreeMap;
 use std::error::Error;
+use std::path::Path;
+
+// Runs the same call on whichever of the four stores is asked for
+macro_rules! on_store {
+    ($self:ident, $store:expr, $space:ident => $call:expr) => {
+        match $store {
+            rtypes::Store::MemSeq => {
+                let $space = &$self.memseq;
+                $call
+            }
+            rtypes::Store::MemConc => {
+                let $space = &$self.memconc;
+                $call
+            }
+            rtypes::Store::DiskSeq => {
+                let $space = &$self.diskseq;
+                $call
+            }
+            rtypes::Store::DiskConc => {
+                let $space = &$self.diskconc;
+                $call
+            }
+        }
+    };
+}
+
+const STORES: [rtypes::Store; 4] = [
+    rtypes::Store::MemSeq,
+    rtypes::Store::MemConc,
+    rtypes::Store::DiskSeq,
+    rtypes::Store::DiskConc,
+];
 
 // See https://docs.google.com/document/d/1yWdvJwsq4Ft7elzKBM0dehh4RFoQ-vXt-1TAUTLLxMY/edit
 #[repr(C)]
-pub struct RSpace<D: prost::Message, K: prost::Message> {
-    diskseq: DiskSeqDB<D, K>,
-    diskconc: DiskConcDB<D, K>,
-    memseq: MemSeqDB<D, K>,
-    memconc: MemConcDB<D, K>,
+pub struct RSpace<D: prost::Message, K: prost::Message, S: Storage = LmdbStore> {
+    diskseq: TupleSpace<S, D, K>,
+    diskconc: TupleSpace<S, D, K>,
+    memseq: TupleSpace<MemStore, D, K>,
+    memconc: TupleSpace<MemStore, D, K>,
 }
 
 impl<
@@ -20,10 +60,29 @@ impl<
     > RSpace<D, K>
 {
     pub fn create() -> Result<RSpace<D, K>, Box<dyn Error>> {
-        let ds = DiskSeqDB::create().unwrap();
-        let dc = DiskConcDB::create().unwrap();
-        let ms = MemSeqDB::create().unwrap();
-        let mc = MemConcDB::create().unwrap();
+        RSpace::create_with_durability(Durability::Sync)
+    }
+
+    pub fn create_with_durability(durability: Durability) -> Result<RSpace<D, K>, Box<dyn Error>> {
+        RSpace::create_with_storage(
+            LmdbStore::open_with_durability(&Path::new("target").join("DiskSeqDB"), durability)?,
+            LmdbStore::open_with_durability(&Path::new("target").join("DiskConcDB"), durability)?,
+        )
+    }
+}
+
+impl<
+        D: Clone + std::hash::Hash + std::fmt::Debug + std::default::Default + prost::Message,
+        K: Clone + std::hash::Hash + std::fmt::Debug + std::default::Default + prost::Message,
+        S: Storage,
+    > RSpace<D, K, S>
+{
+    // The durable verbs go to diskseq and diskconc, which can be any storage backend
+    pub fn create_with_storage(diskseq: S, diskconc: S) -> Result<RSpace<D, K, S>, Box<dyn Error>> {
+        let ds = TupleSpace::new(diskseq);
+        let dc = TupleSpace::new(diskconc);
+        let ms = TupleSpace::new(MemStore::new());
+        let mc = TupleSpace::new(MemStore::concurrent());
 
         Ok(RSpace {
             diskseq: ds,
@@ -34,116 +93,183 @@ impl<
     }
 
     // Verb Set 1
-    pub fn get_once_durable_concurrent(&self, rdata: rtypes::Retrieve) -> Option<rtypes::OptionResult> {
-        return self.diskconc.produce(rdata, false);
+    pub fn get_once_durable_concurrent(
+        &self,
+        rdata: rtypes::Retrieve,
+    ) -> Result<Option<rtypes::OptionResult>, Box<dyn Error>> {
+        Self::produce_on(&self.diskconc, true, rdata, false)
     }
 
     pub fn get_once_non_durable_concurrent(
         &self,
         rdata: rtypes::Retrieve,
-    ) -> Option<rtypes::OptionResult> {
-        return self.memconc.produce(rdata, false);
+    ) -> Result<Option<rtypes::OptionResult>, Box<dyn Error>> {
+        Self::produce_on(&self.memconc, false, rdata, false)
     }
 
-    pub fn get_once_durable_sequential(&self, rdata: rtypes::Retrieve) -> Option<rtypes::OptionResult> {
-        return self.diskseq.produce(rdata, false);
+    pub fn get_once_durable_sequential(
+        &self,
+        rdata: rtypes::Retrieve,
+    ) -> Result<Option<rtypes::OptionResult>, Box<dyn Error>> {
+        Self::produce_on(&self.diskseq, true, rdata, false)
     }
 
     pub fn get_once_non_durable_sequential(
         &self,
         rdata: rtypes::Retrieve,
-    ) -> Option<rtypes::OptionResult> {
-        return self.memseq.produce(rdata, false);
+    ) -> Result<Option<rtypes::OptionResult>, Box<dyn Error>> {
+        Self::produce_on(&self.memseq, false, rdata, false)
     }
 
     // Verb Set 2
     pub fn get_always_durable_concurrent(
         &self,
         rdata: rtypes::Retrieve,
-    ) -> Option<rtypes::OptionResult> {
-        return self.diskconc.produce(rdata, true);
+    ) -> Result<Option<rtypes::OptionResult>, Box<dyn Error>> {
+        Self::produce_on(&self.diskconc, true, rdata, true)
     }
 
     pub fn get_always_non_durable_concurrent(
         &self,
         rdata: rtypes::Retrieve,
-    ) -> Option<rtypes::OptionResult> {
-        return self.memconc.produce(rdata, true);
+    ) -> Result<Option<rtypes::OptionResult>, Box<dyn Error>> {
+        Self::produce_on(&self.memconc, false, rdata, true)
     }
 
     pub fn get_always_durable_sequential(
         &self,
         rdata: rtypes::Retrieve,
-    ) -> Option<rtypes::OptionResult> {
-        return self.diskseq.produce(rdata, true);
+    ) -> Result<Option<rtypes::OptionResult>, Box<dyn Error>> {
+        Self::produce_on(&self.diskseq, true, rdata, true)
     }
 
     pub fn get_always_non_durable_sequential(
         &self,
         rdata: rtypes::Retrieve,
-    ) -> Option<rtypes::OptionResult> {
-        return self.memseq.produce(rdata, true);
+    ) -> Result<Option<rtypes::OptionResult>, Box<dyn Error>> {
+        Self::produce_on(&self.memseq, false, rdata, true)
     }
 
     // Verb Set 3
     pub fn put_once_durable_concurrent(
         &self,
         cdata: rtypes::Commit,
-    ) -> Option<Vec<rtypes::OptionResult>> {
-        return self.diskconc.consume(cdata, false);
+    ) -> Result<Option<Vec<rtypes::OptionResult>>, Box<dyn Error>> {
+        Self::consume_on(&self.diskconc, true, cdata, false)
     }
 
     pub fn put_once_non_durable_concurrent(
         &self,
         cdata: rtypes::Commit,
-    ) -> Option<Vec<rtypes::OptionResult>> {
-        return self.memconc.consume(cdata, false);
+    ) -> Result<Option<Vec<rtypes::OptionResult>>, Box<dyn Error>> {
+        Self::consume_on(&self.memconc, false, cdata, false)
     }
 
     pub fn put_once_durable_sequential(
         &self,
         cdata: rtypes::Commit,
-    ) -> Option<Vec<rtypes::OptionResult>> {
-        return self.diskseq.consume(cdata, false);
+    ) -> Result<Option<Vec<rtypes::OptionResult>>, Box<dyn Error>> {
+        Self::consume_on(&self.diskseq, true, cdata, false)
     }
 
     pub fn put_once_non_durable_sequential(
         &self,
         cdata: rtypes::Commit,
-    ) -> Option<Vec<rtypes::OptionResult>> {
-        return self.memseq.consume(cdata, false);
+    ) -> Result<Option<Vec<rtypes::OptionResult>>, Box<dyn Error>> {
+        Self::consume_on(&self.memseq, false, cdata, false)
     }
 
     // Verb Set 4
     pub fn put_always_durable_concurrent(
         &self,
         cdata: rtypes::Commit,
-    ) -> Option<Vec<rtypes::OptionResult>> {
-        return self.diskconc.consume(cdata, true);
+    ) -> Result<Option<Vec<rtypes::OptionResult>>, Box<dyn Error>> {
+        Self::consume_on(&self.diskconc, true, cdata, true)
     }
 
     pub fn put_always_non_durable_concurrent(
         &self,
         cdata: rtypes::Commit,
-    ) -> Option<Vec<rtypes::OptionResult>> {
-        return self.memconc.consume(cdata, true);
+    ) -> Result<Option<Vec<rtypes::OptionResult>>, Box<dyn Error>> {
+        Self::consume_on(&self.memconc, false, cdata, true)
     }
 
     pub fn put_always_durable_sequential(
         &self,
         cdata: rtypes::Commit,
-    ) -> Option<Vec<rtypes::OptionResult>> {
-        return self.diskseq.consume(cdata, true);
+    ) -> Result<Option<Vec<rtypes::OptionResult>>, Box<dyn Error>> {
+        Self::consume_on(&self.diskseq, true, cdata, true)
     }
 
     pub fn put_always_non_durable_sequential(
         &self,
         cdata: rtypes::Commit,
-    ) -> Option<Vec<rtypes::OptionResult>> {
-        return self.memseq.consume(cdata, true);
+    ) -> Result<Option<Vec<rtypes::OptionResult>>, Box<dyn Error>> {
+        Self::consume_on(&self.memseq, false, cdata, true)
     }
 
-    pub fn print_data(&self, channel: &str) -> () {
+    // Every verb is timed and counted, see VerbTimer in metrics.rs
+    fn produce_on<T: Storage>(
+        space: &TupleSpace<T, D, K>,
+        durable: bool,
+        rdata: rtypes::Retrieve,
+        persistent: bool,
+    ) -> Result<Option<rtypes::OptionResult>, Box<dyn Error>> {
+        let timer = VerbTimer::start("produce", persistent, durable);
+        let result = space.produce(rdata, persistent);
+        match &result {
+            Ok(result) => timer.finish(result.is_some()),
+            Err(_) => timer.fail(),
+        }
+        result
+    }
+
+    fn consume_on<T: Storage>(
+        space: &TupleSpace<T, D, K>,
+        durable: bool,
+        cdata: rtypes::Commit,
+        persistent: bool,
+    ) -> Result<Option<Vec<rtypes::OptionResult>>, Box<dyn Error>> {
+        let timer = VerbTimer::start("consume", persistent, durable);
+        let result = space.consume(cdata, persistent);
+        match &result {
+            Ok(result) => timer.finish(result.is_some()),
+            Err(_) => timer.fail(),
+        }
+        result
+    }
+
+    // Produces and consumes made through the transaction only take effect once it commits
+    pub fn begin_transaction(&self) -> Transaction<'_, D, K, S> {
+        Transaction::new(&self.diskseq, &self.diskconc, &self.memseq, &self.memconc)
+    }
+
+    // Commits if f returns Ok and rolls back if it returns Err. Returns every COMM fired inside it
+    pub fn transaction<F>(&self, f: F) -> Result<Vec<Comm>, Box<dyn Error>>
+    where
+        F: FnOnce(&mut Transaction<D, K, S>) -> Result<(), Box<dyn Error>>,
+    {
+        let mut tx = self.begin_transaction();
+
+        match f(&mut tx) {
+            Ok(()) => tx.commit(),
+            Err(err) => {
+                tx.rollback();
+                Err(err)
+            }
+        }
+    }
+
+    // Hot stores cache the durable channels they touch until checkpoint writes them back
+    pub fn hot_store_diskseq(&self) -> HotStore<'_, TupleSpace<S, D, K>> {
+        HotStore::new(&self.diskseq)
+    }
+
+    pub fn hot_store_diskconc(&self) -> HotStore<'_, TupleSpace<S, D, K>> {
+        HotStore::new(&self.diskconc)
+    }
+
+    pub fn print_data(&self, channel: &str) {
         let _ = self.memseq.print_channel(channel);
         //let _ = self.memconc.print_channel(channel);
         // let _ = self.diskseq.print_channel(channel);
@@ -151,7 +277,7 @@ impl<
     }
 
     // TODO: Remove the need to pass in channel. Should be able to print entire store
-    pub fn print_store(&self, channel: &str) -> () {
+    pub fn print_store(&self, channel: &str) {
         println!("\n*** IN-MEMORY SEQUENTIAL ***");
         let _ = self.memseq.print_channel(channel);
 
@@ -173,23 +299,159 @@ impl<
     }
 
     pub fn is_memseq_empty(&self) -> bool {
-        let memseq_is_empty = self.memseq.is_empty();
-        return memseq_is_empty;
+        self.memseq.is_empty()
     }
     pub fn is_memconc_empty(&self) -> bool {
-        let memconc_is_empty = self.memconc.is_empty();
-        return memconc_is_empty;
+        self.memconc.is_empty()
     }
     pub fn is_diskseq_empty(&self) -> bool {
-        let diskseq_is_empty = self.diskseq.is_empty();
-        return diskseq_is_empty;
+        self.diskseq.is_empty()
     }
     pub fn is_diskconc_empty(&self) -> bool {
-        let diskconc_is_empty = self.diskconc.is_empty();
-        return diskconc_is_empty;
+        self.diskconc.is_empty()
+    }
+
+    // Inspection of one store, see ISpace.scala in rspace/
+    pub fn channels(&self, store: rtypes::Store) -> Result<Vec<String>, Box<dyn Error>> {
+        on_store!(self, store, space => space.channels())
+    }
+
+    pub fn get_data(
+        &self,
+        store: rtypes::Store,
+        channel: &str,
+    ) -> Result<Vec<rtypes::RetrieveContinuation>, Box<dyn Error>> {
+        on_store!(self, store, space => space.get_data(channel))
+    }
+
+    pub fn get_waiting_continuations(
+        &self,
+        store: rtypes::Store,
+        channels: &[String],
+    ) -> Result<Vec<rtypes::CommitContinuation>, Box<dyn Error>> {
+        on_store!(self, store, space => space.get_waiting_continuations(channels))
+    }
+
+    pub fn get_joins(
+        &self,
+        store: rtypes::Store,
+        channel: &str,
+    ) -> Result<Vec<Vec<String>>, Box<dyn Error>> {
+        on_store!(self, store, space => space.get_joins(channel))
+    }
+
+    pub fn to_map(
+        &self,
+        store: rtypes::Store,
+    ) -> Result<BTreeMap<Vec<String>, ChannelEntries>, Box<dyn Error>> {
+        on_store!(self, store, space => space.to_map())
+    }
+
+    // Event log of one store, see trace.rs
+    pub fn start_event_log(&self, store: rtypes::Store) {
+        on_store!(self, store, space => space.start_event_log())
+    }
+
+    pub fn take_event_log(&self, store: rtypes::Store) -> Vec<Event> {
+        on_store!(self, store, space => space.take_event_log())
+    }
+
+    // Changes at channel in any of the four stores from now on, see watch.rs
+    pub fn watch(&self, channel: &str) -> Watch {
+        let (watch, sender) = Watch::new(channel);
+        for store in STORES {
+            on_store!(self, store, space => space.watchers().add(channel, watch.id(), sender.clone()));
+        }
+        watch
+    }
+
+    // Ends the watch with id, false if there was none
+    pub fn unwatch(&self, id: u64) -> bool {
+        let mut removed = false;
+        for store in STORES {
+            removed |= on_store!(self, store, space => space.unwatch(id));
+        }
+        removed
+    }
+
+    // State export of one store, see exporter.rs
+    pub fn root(&self, store: rtypes::Store) -> Result<Blake2b256Hash, Box<dyn Error>> {
+        on_store!(self, store, space => space.root())
+    }
+
+    pub fn exporter(
+        &self,
+        store: rtypes::Store,
+        chunk_size: usize,
+    ) -> Result<RSpaceExporter<'_>, Box<dyn Error>> {
+        on_store!(self, store, space => space.exporter(chunk_size))
+    }
+
+    // Fills an empty store with the chunks exported at root, in order
+    pub fn import(
+        &self,
+        store: rtypes::Store,
+        root: Blake2b256Hash,
+        chunks: impl IntoIterator<Item = rtypes::ExportChunk>,
+    ) -> Result<(), Box<dyn Error>> {
+        on_store!(self, store, space => {
+            let mut importer = space.importer(root)?;
+            for chunk in chunks {
+                importer.import_chunk(&chunk)?;
+            }
+            importer.finish()
+        })
+    }
+
+    // Integrity check of one store, see verify.rs
+    pub fn verify(
+        &self,
+        store: rtypes::Store,
+        repair: bool,
+    ) -> Result<rtypes::VerifyReport, Box<dyn Error>> {
+        on_store!(self, store, space => space.verify(repair))
+    }
+
+    // Checkpoint history of one store, see history.rs
+    pub fn checkpoint<H: Storage>(
+        &self,
+        store: rtypes::Store,
+        history: &HistoryRepository<H>,
+    ) -> Result<Blake2b256Hash, Box<dyn Error>> {
+        on_store!(self, store, space => space.checkpoint(history))
+    }
+
+    pub fn reset<H: Storage>(
+        &self,
+        store: rtypes::Store,
+        history: &HistoryRepository<H>,
+        root: &Blake2b256Hash,
+    ) -> Result<(), Box<dyn Error>> {
+        on_store!(self, store, space => space.reset(history, root))
+    }
+
+    // Sets the rspace.store.<store>.entries gauges, see metrics.rs
+    pub fn record_store_sizes(&self) -> Result<(), Box<dyn Error>> {
+        for (name, store) in [
+            ("memseq", rtypes::Store::MemSeq),
+            ("memconc", rtypes::Store::MemConc),
+            ("diskseq", rtypes::Store::DiskSeq),
+            ("diskconc", rtypes::Store::DiskConc),
+        ] {
+            let entries = on_store!(self, store, space => space.entry_count())?;
+            metrics::set_gauge(&format!("rspace.store.{}.entries", name), entries as i64);
+        }
+
+        Ok(())
+    }
+
+    // Forces the durable verbs' outstanding writes to disk
+    pub fn flush(&self) -> Result<(), Box<dyn Error>> {
+        self.diskseq.flush()?;
+        self.diskconc.flush()
     }
 
-    pub fn clear_store(&self) -> () {
+    pub fn clear_store(&self) {
         let _ = self.memseq.clear();
         let _ = self.memconc.clear();
         let _ = self.diskseq.clear();
@@ -1,3 +1,64 @@
+#![allow(dead_code)]
+
+#[allow(clippy::module_inception, clippy::enum_variant_names)]
 pub mod rtypes {
     include!(concat!(env!("OUT_DIR"), "/firefly.rs"));
 }
+
+use prost::Message;
+use rtypes::tagged_continuation::TaggedCont;
+use rtypes::{ParWithRandom, TaggedContinuation};
+use std::fmt;
+
+impl TaggedContinuation {
+    // A Rholang continuation, see rhotypes.proto
+    pub fn par_body(par_with_random: ParWithRandom) -> TaggedContinuation {
+        TaggedContinuation {
+            tagged_cont: Some(TaggedCont::ParBody(Box::new(par_with_random))),
+        }
+    }
+
+    pub fn scala_body_ref(system_process: i64) -> TaggedContinuation {
+        TaggedContinuation {
+            tagged_cont: Some(TaggedCont::ScalaBodyRef(system_process)),
+        }
+    }
+
+    pub fn opaque(bytes: impl Into<Vec<u8>>) -> TaggedContinuation {
+        TaggedContinuation {
+            tagged_cont: Some(TaggedCont::Opaque(bytes.into())),
+        }
+    }
+}
+
+// Text is stored as opaque bytes, for tools and tests that only need to tell continuations apart
+impl From<&str> for TaggedContinuation {
+    fn from(text: &str) -> TaggedContinuation {
+        TaggedContinuation::opaque(text.as_bytes())
+    }
+}
+
+impl From<String> for TaggedContinuation {
+    fn from(text: String) -> TaggedContinuation {
+        TaggedContinuation::opaque(text.into_bytes())
+    }
+}
+
+// Opaque text as itself, anything else by kind
+impl fmt::Display for TaggedContinuation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.tagged_cont {
+            Some(TaggedCont::ParBody(par_body)) => {
+                write!(f, "par_body ({} bytes)", par_body.encoded_len())
+            }
+            Some(TaggedCont::ScalaBodyRef(system_process)) => {
+                write!(f, "scala_body_ref {}", system_process)
+            }
+            Some(TaggedCont::Opaque(bytes)) => match std::str::from_utf8(bytes) {
+                Ok(text) => write!(f, "{}", text),
+                Err(_) => write!(f, "opaque ({} bytes)", bytes.len()),
+            },
+            None => write!(f, "empty"),
+        }
+    }
+}
@@ -1,7 +1,8 @@
 import org.scalatest.funsuite.AnyFunSuite
 import rspacePlusPlus.{RSpacePlusPlus, Setup}
-import firefly.rtypes.{Commit, Entry, Retrieve}
+import firefly.rtypes.{Commit, Entry, OptionResult, Retrieve}
 import com.sun.jna._
+import com.sun.jna.ptr.{ByteByReference, LongByReference}
 import java.io.File
 
 class MemSeqTest extends AnyFunSuite {
@@ -22,23 +23,31 @@ class MemSeqTest extends AnyFunSuite {
 
   val spacePtr = lib.space_new();
   val setup    = Setup.apply();
+  val len      = new LongByReference()
+  val error    = new ByteByReference()
+
+  def getResult(ptr: Pointer): Option[OptionResult] =
+    RSpacePlusPlus.getResult(lib, ptr, len, error)
+
+  def putResults(ptr: Pointer): Option[Seq[OptionResult]] =
+    RSpacePlusPlus.putResults(lib, ptr, len, error)
 
   // In-Memory Sequential
   test("MemSeqProduceMatch") {
     // Consume
     val commit =
-      Commit(Seq("friends"), Seq(setup.cityMatchCase), "I am the continuation, for now...");
+      Commit(Seq("friends"), Seq(setup.cityMatchCase), Some(setup.continuation));
     val commit_buf = commit.toByteArray;
-    val cres       = lib.space_put_once_non_durable_sequential(spacePtr, commit_buf, commit_buf.length);
+    val cres       = putResults(lib.space_put_once_non_durable_sequential(spacePtr, commit_buf, commit_buf.length, len, error));
 
     // Produce
     val retrieve     = Retrieve("friends", Some(setup.alice), getCityField(setup.alice));
     val retrieve_buf = retrieve.toByteArray;
     val pres =
-      lib.space_get_once_non_durable_sequential(spacePtr, retrieve_buf, retrieve_buf.length);
+      getResult(lib.space_get_once_non_durable_sequential(spacePtr, retrieve_buf, retrieve_buf.length, len, error));
 
-    assert(cres == null)
-    assert(!pres.isEmpty())
+    assert(cres.isEmpty)
+    assert(pres.isDefined)
     assert(lib.is_empty(spacePtr));
     lib.space_clear(spacePtr);
   }
@@ -46,18 +55,18 @@ class MemSeqTest extends AnyFunSuite {
   test("MemSeqProduceNoMatch") {
     // Consume
     val commit =
-      Commit(Seq("friends"), Seq(setup.cityMatchCase), "I am the continuation, for now...");
+      Commit(Seq("friends"), Seq(setup.cityMatchCase), Some(setup.continuation));
     val commit_buf = commit.toByteArray;
-    val cres       = lib.space_put_once_non_durable_sequential(spacePtr, commit_buf, commit_buf.length);
+    val cres       = putResults(lib.space_put_once_non_durable_sequential(spacePtr, commit_buf, commit_buf.length, len, error));
 
     // Produce
     val retrieve     = Retrieve("friends", Some(setup.carol), getCityField(setup.carol));
     val retrieve_buf = retrieve.toByteArray;
     val pres =
-      lib.space_get_once_non_durable_sequential(spacePtr, retrieve_buf, retrieve_buf.length);
+      getResult(lib.space_get_once_non_durable_sequential(spacePtr, retrieve_buf, retrieve_buf.length, len, error));
 
-    assert(cres == null)
-    assert(pres == null)
+    assert(cres.isEmpty)
+    assert(pres.isEmpty)
     assert(!lib.is_empty(spacePtr));
     lib.space_clear(spacePtr);
   }
@@ -67,16 +76,16 @@ class MemSeqTest extends AnyFunSuite {
     val retrieve     = Retrieve("friends", Some(setup.bob), getLastNameField(setup.bob));
     val retrieve_buf = retrieve.toByteArray;
     val pres =
-      lib.space_get_once_non_durable_sequential(spacePtr, retrieve_buf, retrieve_buf.length);
+      getResult(lib.space_get_once_non_durable_sequential(spacePtr, retrieve_buf, retrieve_buf.length, len, error));
 
     // Consume
     val commit =
-      Commit(Seq("friends"), Seq(setup.nameMatchCase), "I am the continuation, for now...");
+      Commit(Seq("friends"), Seq(setup.nameMatchCase), Some(setup.continuation));
     val commit_buf = commit.toByteArray;
-    val cres       = lib.space_put_once_non_durable_sequential(spacePtr, commit_buf, commit_buf.length);
+    val cres       = putResults(lib.space_put_once_non_durable_sequential(spacePtr, commit_buf, commit_buf.length, len, error));
 
-    assert(pres == null)
-    assert(!cres.isEmpty)
+    assert(pres.isEmpty)
+    assert(cres.isDefined)
     assert(lib.is_empty(spacePtr));
     lib.space_clear(spacePtr);
   }
@@ -86,28 +95,28 @@ class MemSeqTest extends AnyFunSuite {
     val retrieve1     = Retrieve("colleagues", Some(setup.dan), getStateField(setup.dan));
     val retrieve1_buf = retrieve1.toByteArray;
     val pres1 =
-      lib.space_get_once_non_durable_sequential(spacePtr, retrieve1_buf, retrieve1_buf.length);
+      getResult(lib.space_get_once_non_durable_sequential(spacePtr, retrieve1_buf, retrieve1_buf.length, len, error));
 
     // Produce
     val retrieve2     = Retrieve("friends", Some(setup.erin), getStateField(setup.erin));
     val retrieve2_buf = retrieve2.toByteArray;
     val pres2 =
-      lib.space_get_once_non_durable_sequential(spacePtr, retrieve2_buf, retrieve2_buf.length);
+      getResult(lib.space_get_once_non_durable_sequential(spacePtr, retrieve2_buf, retrieve2_buf.length, len, error));
 
     // Consume
     val commit =
       Commit(
         Seq("friends", "colleagues"),
         Seq(setup.stateMatchCase, setup.stateMatchCase),
-        "I am the continuation, for now..."
+        Some(setup.continuation)
       );
     val commit_buf = commit.toByteArray;
-    val cres       = lib.space_put_once_non_durable_sequential(spacePtr, commit_buf, commit_buf.length);
+    val cres       = putResults(lib.space_put_once_non_durable_sequential(spacePtr, commit_buf, commit_buf.length, len, error));
 
-    assert(pres1 == null)
-    assert(pres2 == null)
-    assert(!cres.isEmpty)
-    assert(cres.length == 2)
+    assert(pres1.isEmpty)
+    assert(pres2.isEmpty)
+    assert(cres.isDefined)
+    assert(cres.get.length == 2)
     assert(lib.is_empty(spacePtr));
     lib.space_clear(spacePtr);
   }
@@ -118,22 +127,22 @@ class MemSeqTest extends AnyFunSuite {
       Commit(
         Seq("friends"),
         Seq(setup.cityMatchCase),
-        "I am the continuation, for now..."
+        Some(setup.continuation)
       );
     val commit_buf = commit.toByteArray;
     val cres =
-      lib.space_put_always_non_durable_sequential(spacePtr, commit_buf, commit_buf.length);
+      putResults(lib.space_put_always_non_durable_sequential(spacePtr, commit_buf, commit_buf.length, len, error));
 
-    assert(cres == null)
+    assert(cres.isEmpty)
     assert(!lib.is_empty(spacePtr));
 
     // Produce
     val retrieve1     = Retrieve("friends", Some(setup.alice), getCityField(setup.alice));
     val retrieve1_buf = retrieve1.toByteArray;
     val pres =
-      lib.space_get_once_non_durable_sequential(spacePtr, retrieve1_buf, retrieve1_buf.length);
+      getResult(lib.space_get_once_non_durable_sequential(spacePtr, retrieve1_buf, retrieve1_buf.length, len, error));
 
-    assert(!pres.isEmpty())
+    assert(pres.isDefined)
     assert(!lib.is_empty(spacePtr));
     lib.space_clear(spacePtr);
   }
@@ -143,65 +152,65 @@ class MemSeqTest extends AnyFunSuite {
     val retrieve1     = Retrieve("friends", Some(setup.alice), getCityField(setup.alice));
     val retrieve1_buf = retrieve1.toByteArray;
     val pres1 =
-      lib.space_get_once_non_durable_sequential(spacePtr, retrieve1_buf, retrieve1_buf.length);
+      getResult(lib.space_get_once_non_durable_sequential(spacePtr, retrieve1_buf, retrieve1_buf.length, len, error));
 
-    assert(pres1 == null)
+    assert(pres1.isEmpty)
 
     // Produce
     val retrieve2     = Retrieve("friends", Some(setup.bob), getCityField(setup.alice));
     val retrieve2_buf = retrieve2.toByteArray;
     val pres2 =
-      lib.space_get_once_non_durable_sequential(spacePtr, retrieve2_buf, retrieve2_buf.length);
+      getResult(lib.space_get_once_non_durable_sequential(spacePtr, retrieve2_buf, retrieve2_buf.length, len, error));
 
-    assert(pres2 == null)
+    assert(pres2.isEmpty)
 
     // Consume
     val commit1 =
       Commit(
         Seq("friends"),
         Seq(setup.cityMatchCase),
-        "I am the continuation, for now..."
+        Some(setup.continuation)
       );
     val commit1_buf = commit1.toByteArray;
     val cres1 =
-      lib.space_put_always_non_durable_sequential(spacePtr, commit1_buf, commit1_buf.length);
+      putResults(lib.space_put_always_non_durable_sequential(spacePtr, commit1_buf, commit1_buf.length, len, error));
 
-    assert(cres1.length == 1)
+    assert(cres1.get.length == 1)
     assert(!lib.is_empty(spacePtr));
 
     val commit2 =
       Commit(
         Seq("friends"),
         Seq(setup.cityMatchCase),
-        "I am the continuation, for now..."
+        Some(setup.continuation)
       );
     val commit2_buf = commit2.toByteArray;
     val cres2 =
-      lib.space_put_always_non_durable_sequential(spacePtr, commit2_buf, commit2_buf.length);
+      putResults(lib.space_put_always_non_durable_sequential(spacePtr, commit2_buf, commit2_buf.length, len, error));
 
-    assert(cres2.length == 1)
+    assert(cres2.get.length == 1)
     assert(lib.is_empty(spacePtr));
 
     val commit3 =
       Commit(
         Seq("friends"),
         Seq(setup.cityMatchCase),
-        "I am the continuation, for now..."
+        Some(setup.continuation)
       );
     val commit3_buf = commit3.toByteArray;
     val cres3 =
-      lib.space_put_always_non_durable_sequential(spacePtr, commit3_buf, commit3_buf.length);
+      putResults(lib.space_put_always_non_durable_sequential(spacePtr, commit3_buf, commit3_buf.length, len, error));
 
-    assert(cres3 == null)
+    assert(cres3.isEmpty)
     assert(!lib.is_empty(spacePtr));
 
     // Produce
     val retrieve3     = Retrieve("friends", Some(setup.alice), getCityField(setup.alice));
     val retrieve3_buf = retrieve3.toByteArray;
     val pres3 =
-      lib.space_get_once_non_durable_sequential(spacePtr, retrieve3_buf, retrieve3_buf.length);
+      getResult(lib.space_get_once_non_durable_sequential(spacePtr, retrieve3_buf, retrieve3_buf.length, len, error));
 
-    assert(!pres3.isEmpty())
+    assert(pres3.isDefined)
     assert(!lib.is_empty(spacePtr));
     lib.space_clear(spacePtr);
   }
@@ -211,9 +220,9 @@ class MemSeqTest extends AnyFunSuite {
     val retrieve     = Retrieve("friends", Some(setup.alice), getCityField(setup.alice));
     val retrieve_buf = retrieve.toByteArray;
     val pres =
-      lib.space_get_always_non_durable_sequential(spacePtr, retrieve_buf, retrieve_buf.length);
+      getResult(lib.space_get_always_non_durable_sequential(spacePtr, retrieve_buf, retrieve_buf.length, len, error));
 
-    assert(pres == null)
+    assert(pres.isEmpty)
     assert(!lib.is_empty(spacePtr));
 
     // Consume
@@ -221,13 +230,13 @@ class MemSeqTest extends AnyFunSuite {
       Commit(
         Seq("friends"),
         Seq(setup.cityMatchCase),
-        "I am the continuation, for now..."
+        Some(setup.continuation)
       );
     val commit_buf = commit.toByteArray;
-    val cres       = lib.space_put_once_non_durable_sequential(spacePtr, commit_buf, commit_buf.length);
+    val cres       = putResults(lib.space_put_once_non_durable_sequential(spacePtr, commit_buf, commit_buf.length, len, error));
 
-    assert(!cres.isEmpty)
-    assert(cres.length == 1)
+    assert(cres.isDefined)
+    assert(cres.get.length == 1)
     assert(!lib.is_empty(spacePtr));
     lib.space_clear(spacePtr);
   }
@@ -238,43 +247,43 @@ class MemSeqTest extends AnyFunSuite {
       Commit(
         Seq("friends"),
         Seq(setup.cityMatchCase),
-        "I am the continuation, for now..."
+        Some(setup.continuation)
       );
     val commit1_buf = commit1.toByteArray;
     val cres1 =
-      lib.space_put_once_non_durable_sequential(spacePtr, commit1_buf, commit1_buf.length);
+      putResults(lib.space_put_once_non_durable_sequential(spacePtr, commit1_buf, commit1_buf.length, len, error));
 
-    assert(cres1 == null)
+    assert(cres1.isEmpty)
     assert(!lib.is_empty(spacePtr));
 
     // Produce
     val retrieve1     = Retrieve("friends", Some(setup.alice), getCityField(setup.alice));
     val retrieve1_buf = retrieve1.toByteArray;
     val pres1 =
-      lib.space_get_always_non_durable_sequential(spacePtr, retrieve1_buf, retrieve1_buf.length);
+      getResult(lib.space_get_always_non_durable_sequential(spacePtr, retrieve1_buf, retrieve1_buf.length, len, error));
 
-    assert(!pres1.isEmpty())
+    assert(pres1.isDefined)
     assert(lib.is_empty(spacePtr));
 
     // Produce
     val retrieve2     = Retrieve("friends", Some(setup.alice), getCityField(setup.alice));
     val retrieve2_buf = retrieve2.toByteArray;
     val pres2 =
-      lib.space_get_always_non_durable_sequential(spacePtr, retrieve2_buf, retrieve2_buf.length);
+      getResult(lib.space_get_always_non_durable_sequential(spacePtr, retrieve2_buf, retrieve2_buf.length, len, error));
 
     // Consume
     val commit2 =
       Commit(
         Seq("friends"),
         Seq(setup.cityMatchCase),
-        "I am the continuation, for now..."
+        Some(setup.continuation)
       );
     val commit2_buf = commit2.toByteArray;
     val cres2 =
-      lib.space_put_once_non_durable_sequential(spacePtr, commit2_buf, commit2_buf.length);
+      putResults(lib.space_put_once_non_durable_sequential(spacePtr, commit2_buf, commit2_buf.length, len, error));
 
-    assert(pres2 == null)
-    assert(!cres2.isEmpty)
+    assert(pres2.isEmpty)
+    assert(cres2.isDefined)
     assert(!lib.is_empty(spacePtr));
     lib.space_clear(spacePtr);
   }
@@ -1,8 +1,9 @@
 import org.scalatest.funsuite.AnyFunSuite
 import org.scalatest.matchers.should.Matchers
 import rspacePlusPlus.{RSpacePlusPlus, Setup}
-import firefly.rtypes.{Commit, Entry, Retrieve}
+import firefly.rtypes.{Commit, Entry, OptionResult, Retrieve}
 import com.sun.jna._
+import com.sun.jna.ptr.{ByteByReference, LongByReference}
 import java.io.File
 
 /**
@@ -27,6 +28,14 @@ class RSpaceTest extends AnyFunSuite with Matchers {
 
   val spacePtr = lib.space_new();
   val setup    = Setup.apply();
+  val len      = new LongByReference()
+  val error    = new ByteByReference()
+
+  def getResult(ptr: Pointer): Option[OptionResult] =
+    RSpacePlusPlus.getResult(lib, ptr, len, error)
+
+  def putResults(ptr: Pointer): Option[Seq[OptionResult]] =
+    RSpacePlusPlus.putResults(lib, ptr, len, error)
 
   /**
 	  * Original test created two duplicate channels
@@ -41,33 +50,33 @@ class RSpaceTest extends AnyFunSuite with Matchers {
       Commit(
         Seq("friends", "friends"),
         Seq(setup.cityMatchCase, setup.cityMatchCase),
-        "I am the continuation, for now..."
+        Some(setup.continuation)
       );
     val commit1_buf = commit1.toByteArray;
-    val cres        = lib.space_put_once_non_durable_sequential(spacePtr, commit1_buf, commit1_buf.length);
+    val cres        = putResults(lib.space_put_once_non_durable_sequential(spacePtr, commit1_buf, commit1_buf.length, len, error));
 
-    assert(cres == null)
+    assert(cres.isEmpty)
 
     // Produce
     val retrieve1     = Retrieve("friends", Some(setup.bob), getCityField(setup.bob));
     val retrieve1_buf = retrieve1.toByteArray;
     val pres1 =
-      lib.space_get_once_non_durable_sequential(spacePtr, retrieve1_buf, retrieve1_buf.length);
+      getResult(lib.space_get_once_non_durable_sequential(spacePtr, retrieve1_buf, retrieve1_buf.length, len, error));
 
     // Should be null
-    assert(!pres1.isEmpty())
+    assert(pres1.isDefined)
 
     // Produce
     val retrieve2     = Retrieve("friends", Some(setup.bob), getCityField(setup.bob));
     val retrieve2_buf = retrieve2.toByteArray;
     val pres2 =
-      lib.space_get_once_non_durable_sequential(spacePtr, retrieve2_buf, retrieve2_buf.length);
+      getResult(lib.space_get_once_non_durable_sequential(spacePtr, retrieve2_buf, retrieve2_buf.length, len, error));
 
     // Should be not null
-    assert(pres2 == null)
+    assert(pres2.isEmpty)
 
     // Length should be 2
-    assert(cres == null)
+    assert(cres.isEmpty)
 
     // Should be empty
     assert(!lib.is_empty(spacePtr));
@@ -81,30 +90,30 @@ class RSpaceTest extends AnyFunSuite with Matchers {
     val retrieve1     = Retrieve("friends", Some(setup.bob), getCityField(setup.bob));
     val retrieve1_buf = retrieve1.toByteArray;
     val pres1 =
-      lib.space_get_once_non_durable_sequential(spacePtr, retrieve1_buf, retrieve1_buf.length);
+      getResult(lib.space_get_once_non_durable_sequential(spacePtr, retrieve1_buf, retrieve1_buf.length, len, error));
 
-    assert(pres1 == null)
+    assert(pres1.isEmpty)
 
     // Produce
     val retrieve2     = Retrieve("friends", Some(setup.bob), getCityField(setup.bob));
     val retrieve2_buf = retrieve2.toByteArray;
     val pres2 =
-      lib.space_get_once_non_durable_sequential(spacePtr, retrieve2_buf, retrieve2_buf.length);
+      getResult(lib.space_get_once_non_durable_sequential(spacePtr, retrieve2_buf, retrieve2_buf.length, len, error));
 
-    assert(pres2 == null)
+    assert(pres2.isEmpty)
 
     // Consume
     val commit1 =
       Commit(
         Seq("friends", "friends"),
         Seq(setup.cityMatchCase, setup.cityMatchCase),
-        "I am the continuation, for now..."
+        Some(setup.continuation)
       );
     val commit1_buf = commit1.toByteArray;
-    val cres        = lib.space_put_once_non_durable_sequential(spacePtr, commit1_buf, commit1_buf.length);
+    val cres        = putResults(lib.space_put_once_non_durable_sequential(spacePtr, commit1_buf, commit1_buf.length, len, error));
 
     // Should be length 2
-    assert(cres.length == 1)
+    assert(cres.get.length == 1)
 
   }
   lib.space_clear(spacePtr);
@@ -0,0 +1,50 @@
+#![allow(dead_code)]
+
+use crate::rtypes::rtypes;
+
+/*
+What a produce or consume did to the space, in the order it happened. A merger
+replays two of these logs against their common base.
+See Event.scala in rspace/trace/
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    // A datum stored at a channel
+    Produce {
+        channel: String,
+        datum: Box<rtypes::RetrieveContinuation>,
+    },
+    // A continuation stored at every channel of a consume, one copy per channel
+    Consume {
+        continuations: Vec<(String, rtypes::CommitContinuation)>,
+    },
+    // A match, with the stored data and continuations it took. Persistent ones stay stored
+    Comm {
+        data: Vec<(String, rtypes::RetrieveContinuation)>,
+        continuations: Vec<(String, rtypes::CommitContinuation)>,
+    },
+}
+
+impl Event {
+    // Every channel the event touched, in order
+    pub fn channels(&self) -> Vec<String> {
+        let mut channels: Vec<String> = match self {
+            Event::Produce { channel, .. } => vec![channel.clone()],
+            Event::Consume { continuations } => continuations
+                .iter()
+                .map(|(channel, _)| channel.clone())
+                .collect(),
+            Event::Comm {
+                data,
+                continuations,
+            } => data
+                .iter()
+                .map(|(channel, _)| channel.clone())
+                .chain(continuations.iter().map(|(channel, _)| channel.clone()))
+                .collect(),
+        };
+        channels.sort();
+        channels.dedup();
+        channels
+    }
+}
@@ -0,0 +1,200 @@
+#![allow(dead_code)]
+
+use crate::exporter::Blake2b256Hash;
+use crate::keyvaluestore::{KeyValueRead, KeyValueStore, Storage, Visitor};
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::sync::{Mutex, MutexGuard};
+
+/*
+Storage that remembers which keys commits changed since the last checkpoint, so the
+next one only has to update their paths in the trie, see checkpoint in history.rs.
+A commit records its keys both before and after it commits, so whichever side of it
+a checkpoint takes its snapshot on, the keys are in what that checkpoint or the next
+one updates. No lock is held while the backend commits or clears, those may wait
+for its own writer lock.
+*/
+// Changed keys kept at most between checkpoints
+const MAX_KEYS: usize = 1 << 16;
+
+pub struct Tracked<S: Storage> {
+    inner: S,
+    changes: Mutex<Changes>,
+}
+
+// What changed since the root the store last held, nothing is known without one
+#[derive(Default)]
+pub struct Changes {
+    pub base: Option<Blake2b256Hash>,
+    pub keys: BTreeSet<Vec<u8>>,
+    // moves on with every snapshot and every write that replaces the base
+    epoch: u64,
+}
+
+pub struct TrackedWriteTxn<'a, S: Storage + 'a> {
+    txn: S::WriteTxn<'a>,
+    changes: &'a Mutex<Changes>,
+    keys: BTreeSet<Vec<u8>>,
+}
+
+impl<S: Storage> Tracked<S> {
+    pub fn new(inner: S) -> Tracked<S> {
+        Tracked {
+            inner,
+            changes: Mutex::new(Changes::default()),
+        }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /*
+    A read transaction and what changed between base and it, which is taken. Give
+    the root checkpointed from it to checkpointed, along with the epoch returned
+    */
+    pub fn snapshot(&self) -> Result<(S::ReadTxn<'_>, Changes, u64), Box<dyn Error>> {
+        let mut changes = self.changes()?;
+        let txn = self.inner.read_txn()?;
+        changes.epoch += 1;
+        let epoch = changes.epoch;
+        let taken = Changes {
+            base: changes.base.take(),
+            keys: std::mem::take(&mut changes.keys),
+            epoch,
+        };
+
+        Ok((txn, taken, epoch))
+    }
+
+    // The store held root at the snapshot of epoch. Lost if a later snapshot or reset came first
+    pub fn checkpointed(&self, root: Blake2b256Hash, epoch: u64) -> Result<(), Box<dyn Error>> {
+        let mut changes = self.changes()?;
+        if changes.epoch == epoch {
+            changes.base = Some(root);
+        }
+
+        Ok(())
+    }
+
+    fn changes(&self) -> Result<MutexGuard<'_, Changes>, Box<dyn Error>> {
+        lock(&self.changes)
+    }
+}
+
+fn lock(changes: &Mutex<Changes>) -> Result<MutexGuard<'_, Changes>, Box<dyn Error>> {
+    Ok(changes.lock().map_err(|err| err.to_string())?)
+}
+
+fn record(
+    changes: &Mutex<Changes>,
+    keys: impl IntoIterator<Item = Vec<u8>>,
+) -> Result<(), Box<dyn Error>> {
+    let mut changes = lock(changes)?;
+    changes.keys.extend(keys);
+    // past this many, or with no checkpoint to take them, reading everything is no worse
+    if changes.keys.len() > MAX_KEYS {
+        changes.keys.clear();
+        changes.base = None;
+        changes.epoch += 1;
+    }
+
+    Ok(())
+}
+
+// Starts the changes over from base, and keeps checkpoints under way from setting theirs
+fn replace_base(
+    changes: &Mutex<Changes>,
+    base: Option<Blake2b256Hash>,
+) -> Result<(), Box<dyn Error>> {
+    let mut changes = lock(changes)?;
+    if base.is_none() {
+        changes.keys.clear();
+    }
+    changes.base = base;
+    changes.epoch += 1;
+
+    Ok(())
+}
+
+impl<'a, S: Storage> TrackedWriteTxn<'a, S> {
+    /*
+    Commits what replaced everything the store held with the pairs of root. A
+    snapshot taken meanwhile has no base, and commits after it are recorded as usual
+    */
+    pub fn commit_at(self, root: Blake2b256Hash) -> Result<(), Box<dyn Error>> {
+        replace_base(self.changes, None)?;
+        self.txn.commit()?;
+        replace_base(self.changes, Some(root))
+    }
+}
+
+impl<S: Storage> KeyValueRead for TrackedWriteTxn<'_, S> {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+        self.txn.get(key)
+    }
+
+    fn iterate_prefix(&self, prefix: &[u8], f: &mut Visitor) -> Result<(), Box<dyn Error>> {
+        self.txn.iterate_prefix(prefix, f)
+    }
+
+    fn iterate_from(&self, start: &[u8], f: &mut Visitor) -> Result<(), Box<dyn Error>> {
+        self.txn.iterate_from(start, f)
+    }
+}
+
+impl<S: Storage> KeyValueStore for TrackedWriteTxn<'_, S> {
+    fn put(&mut self, key: &[u8], value: &[u8]) -> Result<(), Box<dyn Error>> {
+        self.keys.insert(key.to_vec());
+        self.txn.put(key, value)
+    }
+
+    fn delete(&mut self, key: &[u8]) -> Result<(), Box<dyn Error>> {
+        self.keys.insert(key.to_vec());
+        self.txn.delete(key)
+    }
+
+    fn commit(self) -> Result<(), Box<dyn Error>> {
+        record(self.changes, self.keys.iter().cloned())?;
+        self.txn.commit()?;
+        record(self.changes, self.keys)
+    }
+}
+
+impl<S: Storage> Storage for Tracked<S> {
+    type ReadTxn<'a>
+        = S::ReadTxn<'a>
+    where
+        S: 'a;
+    type WriteTxn<'a>
+        = TrackedWriteTxn<'a, S>
+    where
+        S: 'a;
+
+    fn read_txn(&self) -> Result<Self::ReadTxn<'_>, Box<dyn Error>> {
+        self.inner.read_txn()
+    }
+
+    fn write_txn(&self) -> Result<Self::WriteTxn<'_>, Box<dyn Error>> {
+        Ok(TrackedWriteTxn {
+            txn: self.inner.write_txn()?,
+            changes: &self.changes,
+            keys: BTreeSet::new(),
+        })
+    }
+
+    fn is_empty(&self) -> Result<bool, Box<dyn Error>> {
+        self.inner.is_empty()
+    }
+
+    // Leaves the store without a base, so the next checkpoint starts over
+    fn clear(&self) -> Result<(), Box<dyn Error>> {
+        replace_base(&self.changes, None)?;
+        self.inner.clear()?;
+        replace_base(&self.changes, None)
+    }
+
+    fn flush(&self) -> Result<(), Box<dyn Error>> {
+        self.inner.flush()
+    }
+}
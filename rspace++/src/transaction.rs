@@ -0,0 +1,376 @@
+use crate::keyvaluestore::{KeyValueStore, Storage};
+use crate::lmdbstore::LmdbStore;
+use crate::memorystore::MemStore;
+use crate::metrics::VerbTimer;
+use crate::rho;
+use crate::rtypes::rtypes::{self, Store};
+use crate::trace::Event;
+use crate::tracked::TrackedWriteTxn;
+use crate::tuplespace::{self, KeyedResult, TupleSpace};
+use std::error::Error;
+use tracing::debug_span;
+
+// A produce or consume that found its match and fired a continuation
+#[derive(Clone, Debug, PartialEq)]
+pub struct Comm {
+    pub channels: Vec<String>,
+    pub results: Vec<rtypes::OptionResult>,
+}
+
+/*
+Groups produces and consumes into one atomic unit: either all of them apply or none do.
+Every verb runs inside one write transaction, opened on first use.
+
+The stores are independent and can't commit together, so a transaction keeps to the
+store its first verb used; a verb on any other store fails. A verb that fails may
+have written part of what it does, so once one has, commit refuses and the
+transaction can only roll back.
+While a transaction holds a write transaction, any other write to the same store,
+including through RSpace itself, waits until it commits or rolls back. On the
+concurrent memory store the commit can instead fail with Conflict, having written nothing.
+Verbs are timed and traced like RSpace's own.
+*/
+pub struct Transaction<'a, D: prost::Message, K: prost::Message, S: Storage + 'a = LmdbStore> {
+    diskseq: &'a TupleSpace<S, D, K>,
+    diskconc: &'a TupleSpace<S, D, K>,
+    memseq: &'a TupleSpace<MemStore, D, K>,
+    memconc: &'a TupleSpace<MemStore, D, K>,
+    pending: Option<Pending<'a, D, K, S>>,
+    failed: bool,
+    comms: Vec<Comm>,
+}
+
+/*
+The write transaction on the store a transaction keeps to, with the events it will
+log and the continuations whose on_fired hooks it will run once committed
+*/
+struct Pending<'a, D: prost::Message, K: prost::Message, S: Storage + 'a> {
+    store: Store,
+    txn: Txn<'a, D, K, S>,
+    events: Vec<Event>,
+    fired: Vec<KeyedResult>,
+}
+
+enum Txn<'a, D: prost::Message, K: prost::Message, S: Storage + 'a> {
+    Disk(&'a TupleSpace<S, D, K>, TrackedWriteTxn<'a, S>),
+    Mem(
+        &'a TupleSpace<MemStore, D, K>,
+        TrackedWriteTxn<'a, MemStore>,
+    ),
+}
+
+impl<
+        'a,
+        D: Clone + std::hash::Hash + std::fmt::Debug + std::default::Default + prost::Message,
+        K: Clone + std::hash::Hash + std::fmt::Debug + std::default::Default + prost::Message,
+        S: Storage,
+    > Transaction<'a, D, K, S>
+{
+    pub fn new(
+        diskseq: &'a TupleSpace<S, D, K>,
+        diskconc: &'a TupleSpace<S, D, K>,
+        memseq: &'a TupleSpace<MemStore, D, K>,
+        memconc: &'a TupleSpace<MemStore, D, K>,
+    ) -> Transaction<'a, D, K, S> {
+        Transaction {
+            diskseq,
+            diskconc,
+            memseq,
+            memconc,
+            pending: None,
+            failed: false,
+            comms: vec![],
+        }
+    }
+
+    // Verb Set 1
+    pub fn get_once_durable_concurrent(
+        &mut self,
+        rdata: rtypes::Retrieve,
+    ) -> Result<Option<rtypes::OptionResult>, Box<dyn Error>> {
+        let result = self.produce(Store::DiskConc, &rdata, false)?;
+        Ok(self.record_produce(&rdata, result))
+    }
+
+    pub fn get_once_non_durable_concurrent(
+        &mut self,
+        rdata: rtypes::Retrieve,
+    ) -> Result<Option<rtypes::OptionResult>, Box<dyn Error>> {
+        let result = self.produce(Store::MemConc, &rdata, false)?;
+        Ok(self.record_produce(&rdata, result))
+    }
+
+    pub fn get_once_durable_sequential(
+        &mut self,
+        rdata: rtypes::Retrieve,
+    ) -> Result<Option<rtypes::OptionResult>, Box<dyn Error>> {
+        let result = self.produce(Store::DiskSeq, &rdata, false)?;
+        Ok(self.record_produce(&rdata, result))
+    }
+
+    pub fn get_once_non_durable_sequential(
+        &mut self,
+        rdata: rtypes::Retrieve,
+    ) -> Result<Option<rtypes::OptionResult>, Box<dyn Error>> {
+        let result = self.produce(Store::MemSeq, &rdata, false)?;
+        Ok(self.record_produce(&rdata, result))
+    }
+
+    // Verb Set 2
+    pub fn get_always_durable_concurrent(
+        &mut self,
+        rdata: rtypes::Retrieve,
+    ) -> Result<Option<rtypes::OptionResult>, Box<dyn Error>> {
+        let result = self.produce(Store::DiskConc, &rdata, true)?;
+        Ok(self.record_produce(&rdata, result))
+    }
+
+    pub fn get_always_non_durable_concurrent(
+        &mut self,
+        rdata: rtypes::Retrieve,
+    ) -> Result<Option<rtypes::OptionResult>, Box<dyn Error>> {
+        let result = self.produce(Store::MemConc, &rdata, true)?;
+        Ok(self.record_produce(&rdata, result))
+    }
+
+    pub fn get_always_durable_sequential(
+        &mut self,
+        rdata: rtypes::Retrieve,
+    ) -> Result<Option<rtypes::OptionResult>, Box<dyn Error>> {
+        let result = self.produce(Store::DiskSeq, &rdata, true)?;
+        Ok(self.record_produce(&rdata, result))
+    }
+
+    pub fn get_always_non_durable_sequential(
+        &mut self,
+        rdata: rtypes::Retrieve,
+    ) -> Result<Option<rtypes::OptionResult>, Box<dyn Error>> {
+        let result = self.produce(Store::MemSeq, &rdata, true)?;
+        Ok(self.record_produce(&rdata, result))
+    }
+
+    // Verb Set 3
+    pub fn put_once_durable_concurrent(
+        &mut self,
+        cdata: rtypes::Commit,
+    ) -> Result<Option<Vec<rtypes::OptionResult>>, Box<dyn Error>> {
+        let results = self.consume(Store::DiskConc, &cdata, false)?;
+        Ok(self.record_consume(&cdata, results))
+    }
+
+    pub fn put_once_non_durable_concurrent(
+        &mut self,
+        cdata: rtypes::Commit,
+    ) -> Result<Option<Vec<rtypes::OptionResult>>, Box<dyn Error>> {
+        let results = self.consume(Store::MemConc, &cdata, false)?;
+        Ok(self.record_consume(&cdata, results))
+    }
+
+    pub fn put_once_durable_sequential(
+        &mut self,
+        cdata: rtypes::Commit,
+    ) -> Result<Option<Vec<rtypes::OptionResult>>, Box<dyn Error>> {
+        let results = self.consume(Store::DiskSeq, &cdata, false)?;
+        Ok(self.record_consume(&cdata, results))
+    }
+
+    pub fn put_once_non_durable_sequential(
+        &mut self,
+        cdata: rtypes::Commit,
+    ) -> Result<Option<Vec<rtypes::OptionResult>>, Box<dyn Error>> {
+        let results = self.consume(Store::MemSeq, &cdata, false)?;
+        Ok(self.record_consume(&cdata, results))
+    }
+
+    // Verb Set 4
+    pub fn put_always_durable_concurrent(
+        &mut self,
+        cdata: rtypes::Commit,
+    ) -> Result<Option<Vec<rtypes::OptionResult>>, Box<dyn Error>> {
+        let results = self.consume(Store::DiskConc, &cdata, true)?;
+        Ok(self.record_consume(&cdata, results))
+    }
+
+    pub fn put_always_non_durable_concurrent(
+        &mut self,
+        cdata: rtypes::Commit,
+    ) -> Result<Option<Vec<rtypes::OptionResult>>, Box<dyn Error>> {
+        let results = self.consume(Store::MemConc, &cdata, true)?;
+        Ok(self.record_consume(&cdata, results))
+    }
+
+    pub fn put_always_durable_sequential(
+        &mut self,
+        cdata: rtypes::Commit,
+    ) -> Result<Option<Vec<rtypes::OptionResult>>, Box<dyn Error>> {
+        let results = self.consume(Store::DiskSeq, &cdata, true)?;
+        Ok(self.record_consume(&cdata, results))
+    }
+
+    pub fn put_always_non_durable_sequential(
+        &mut self,
+        cdata: rtypes::Commit,
+    ) -> Result<Option<Vec<rtypes::OptionResult>>, Box<dyn Error>> {
+        let results = self.consume(Store::MemSeq, &cdata, true)?;
+        Ok(self.record_consume(&cdata, results))
+    }
+
+    // COMMs fired so far
+    pub fn comms(&self) -> &[Comm] {
+        &self.comms
+    }
+
+    pub fn commit(self) -> Result<Vec<Comm>, Box<dyn Error>> {
+        if self.failed {
+            return Err("a verb of this transaction failed, it can only roll back".into());
+        }
+        if let Some(pending) = self.pending {
+            match pending.txn {
+                Txn::Disk(space, txn) => finish(space, txn, pending.events, pending.fired)?,
+                Txn::Mem(space, txn) => finish(space, txn, pending.events, pending.fired)?,
+            }
+        }
+
+        Ok(self.comms)
+    }
+
+    // Dropping the write transaction discards everything done inside it
+    pub fn rollback(self) {}
+
+    fn produce(
+        &mut self,
+        store: Store,
+        rdata: &rtypes::Retrieve,
+        persistent: bool,
+    ) -> Result<Option<rtypes::OptionResult>, Box<dyn Error>> {
+        let _entered = debug_span!("produce", channel = %rdata.chan, persistent).entered();
+        let timer = VerbTimer::start("produce", persistent, is_durable(store));
+        let result = self.pending(store).and_then(|pending| {
+            let matched = match &mut pending.txn {
+                Txn::Disk(_, txn) => {
+                    tuplespace::produce_keyed_in_txn(txn, rdata, persistent, &mut pending.events)?
+                }
+                Txn::Mem(_, txn) => {
+                    tuplespace::produce_keyed_in_txn(txn, rdata, persistent, &mut pending.events)?
+                }
+            };
+            Ok(matched.map(|(key, result)| {
+                pending.fired.push((key, result.clone()));
+                result
+            }))
+        });
+        match &result {
+            Ok(result) => timer.finish(result.is_some()),
+            Err(_) => self.fail(timer),
+        }
+        result
+    }
+
+    fn consume(
+        &mut self,
+        store: Store,
+        cdata: &rtypes::Commit,
+        persistent: bool,
+    ) -> Result<Option<Vec<rtypes::OptionResult>>, Box<dyn Error>> {
+        let _entered = debug_span!("consume", channels = ?cdata.channels, persistent).entered();
+        let timer = VerbTimer::start("consume", persistent, is_durable(store));
+        let result = self
+            .pending(store)
+            .and_then(|pending| match &mut pending.txn {
+                Txn::Disk(_, txn) => {
+                    tuplespace::consume_in_txn(txn, cdata, persistent, &mut pending.events)
+                }
+                Txn::Mem(_, txn) => {
+                    tuplespace::consume_in_txn(txn, cdata, persistent, &mut pending.events)
+                }
+            });
+        match &result {
+            Ok(result) => timer.finish(result.is_some()),
+            Err(_) => self.fail(timer),
+        }
+        result
+    }
+
+    fn fail(&mut self, timer: VerbTimer) {
+        timer.fail();
+        self.failed = true;
+    }
+
+    // The write transaction on store, opened on first use
+    fn pending(&mut self, store: Store) -> Result<&mut Pending<'a, D, K, S>, Box<dyn Error>> {
+        if let Some(pending) = &self.pending {
+            if pending.store != store {
+                return Err(format!(
+                    "a transaction keeps to one store, this one to {:?} and not {:?}",
+                    pending.store, store
+                )
+                .into());
+            }
+        }
+        if self.pending.is_none() {
+            let txn = match store {
+                Store::DiskSeq => Txn::Disk(self.diskseq, self.diskseq.write_txn()?),
+                Store::DiskConc => Txn::Disk(self.diskconc, self.diskconc.write_txn()?),
+                Store::MemSeq => Txn::Mem(self.memseq, self.memseq.write_txn()?),
+                Store::MemConc => Txn::Mem(self.memconc, self.memconc.write_txn()?),
+            };
+            self.pending = Some(Pending {
+                store,
+                txn,
+                events: vec![],
+                fired: vec![],
+            });
+        }
+        Ok(self.pending.as_mut().unwrap())
+    }
+
+    fn record_produce(
+        &mut self,
+        rdata: &rtypes::Retrieve,
+        result: Option<rtypes::OptionResult>,
+    ) -> Option<rtypes::OptionResult> {
+        if let Some(result) = &result {
+            self.comms.push(Comm {
+                channels: vec![rho::named_retrieve(rdata).chan.clone()],
+                results: vec![result.clone()],
+            });
+        }
+        result
+    }
+
+    fn record_consume(
+        &mut self,
+        cdata: &rtypes::Commit,
+        results: Option<Vec<rtypes::OptionResult>>,
+    ) -> Option<Vec<rtypes::OptionResult>> {
+        if let Some(results) = &results {
+            self.comms.push(Comm {
+                channels: rho::named_commit(cdata).channels.clone(),
+                results: results.clone(),
+            });
+        }
+        results
+    }
+}
+
+fn is_durable(store: Store) -> bool {
+    matches!(store, Store::DiskSeq | Store::DiskConc)
+}
+
+fn finish<
+    S: Storage,
+    D: Clone + std::hash::Hash + std::fmt::Debug + std::default::Default + prost::Message,
+    K: Clone + std::hash::Hash + std::fmt::Debug + std::default::Default + prost::Message,
+>(
+    space: &TupleSpace<S, D, K>,
+    txn: TrackedWriteTxn<'_, S>,
+    events: Vec<Event>,
+    fired: Vec<KeyedResult>,
+) -> Result<(), Box<dyn Error>> {
+    txn.commit()?;
+    space.log_events(events);
+    for (key, result) in &fired {
+        space.fire(key, result);
+    }
+    Ok(())
+}
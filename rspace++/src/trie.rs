@@ -0,0 +1,161 @@
+#![allow(dead_code)]
+
+use crate::exporter::{hash, Blake2b256Hash};
+
+const LEAF: u8 = 0;
+const BRANCH: u8 = 1;
+
+/*
+A store's Merkle tree is a binary radix trie over its keys. Keys are read as bits,
+each byte as a 1 and then its eight bits, with a 0 after the last, so no key is a
+prefix of another and the trie's leaves are in key order. A branch sits at the
+first bit its keys differ at, keys with a 0 there on the left. The shape only
+depends on which keys there are, so changing one key only changes the branches on
+its path.
+See RadixTree.scala in rspace/history/
+*/
+pub(crate) fn key_bit(key: &[u8], bit: u64) -> bool {
+    match key.get((bit / 9) as usize) {
+        None => false,
+        Some(_) if bit.is_multiple_of(9) => true,
+        Some(byte) => byte >> (8 - bit % 9) & 1 == 1,
+    }
+}
+
+// The first bit two different keys differ at
+pub(crate) fn split_depth(a: &[u8], b: &[u8]) -> u64 {
+    let common = a.iter().zip(b).take_while(|(a, b)| a == b).count();
+    let depth = 9 * common as u64;
+    match (a.get(common), b.get(common)) {
+        (Some(a), Some(b)) => depth + 1 + (a ^ b).leading_zeros() as u64,
+        // the shorter key has its closing 0 where the other has a 1
+        _ => depth,
+    }
+}
+
+// The bytes leaf_hash covers
+pub(crate) fn encode_leaf(key: &[u8], value: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![LEAF];
+    bytes.extend((key.len() as u64).to_be_bytes());
+    bytes.extend(key);
+    bytes.extend(value);
+    bytes
+}
+
+// The bytes branch_hash covers
+pub(crate) fn encode_branch(depth: u64, left: &Blake2b256Hash, right: &Blake2b256Hash) -> Vec<u8> {
+    let mut bytes = vec![BRANCH];
+    bytes.extend(depth.to_be_bytes());
+    bytes.extend(left);
+    bytes.extend(right);
+    bytes
+}
+
+pub(crate) fn leaf_hash(key: &[u8], value: &[u8]) -> Blake2b256Hash {
+    hash(&encode_leaf(key, value))
+}
+
+pub(crate) fn branch_hash(
+    depth: u64,
+    left: &Blake2b256Hash,
+    right: &Blake2b256Hash,
+) -> Blake2b256Hash {
+    hash(&encode_branch(depth, left, right))
+}
+
+pub(crate) enum Node {
+    Leaf(Vec<u8>, Vec<u8>),
+    Branch(u64, Blake2b256Hash, Blake2b256Hash),
+}
+
+// Inverse of encode_leaf and encode_branch
+pub(crate) fn decode(bytes: &[u8]) -> Option<Node> {
+    match bytes.split_first()? {
+        (&LEAF, rest) if rest.len() >= 8 => {
+            let (len, rest) = rest.split_at(8);
+            let len = u64::from_be_bytes(len.try_into().ok()?) as usize;
+            if rest.len() < len {
+                return None;
+            }
+            let (key, value) = rest.split_at(len);
+            Some(Node::Leaf(key.to_vec(), value.to_vec()))
+        }
+        (&BRANCH, rest) if rest.len() == 72 => Some(Node::Branch(
+            u64::from_be_bytes(rest[..8].try_into().ok()?),
+            rest[8..40].try_into().ok()?,
+            rest[40..].try_into().ok()?,
+        )),
+        _ => None,
+    }
+}
+
+// A finished subtree and the items it spans, counted from the first one pushed
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Subtree {
+    pub hash: Blake2b256Hash,
+    pub start: usize,
+    pub end: usize,
+}
+
+/*
+Builds a trie bottom up from the subtrees it is made of, pushed in key order. Each
+comes with the depth it splits from the one before; for two leaves that is
+split_depth of their keys. Once the next subtree shows how deep they split, the
+ones still open on the right are joined, calling on_branch with each new branch.
+*/
+#[derive(Default)]
+pub(crate) struct TrieBuilder {
+    // subtrees not joined yet, each with the depth it splits from the next
+    spine: Vec<(Subtree, u64)>,
+    last: Option<Subtree>,
+    pushed: usize,
+}
+
+// Called with a new branch's depth and its two children
+pub(crate) type OnBranch<'f> = dyn FnMut(u64, &Subtree, &Subtree) + 'f;
+
+impl TrieBuilder {
+    pub fn new() -> TrieBuilder {
+        TrieBuilder::default()
+    }
+
+    pub fn push(&mut self, hash: Blake2b256Hash, depth: u64, on_branch: &mut OnBranch) {
+        if let Some(mut right) = self.last.take() {
+            while let Some((left, split)) = self.spine.last().copied() {
+                if split <= depth {
+                    break;
+                }
+                self.spine.pop();
+                right = join(split, &left, &right, on_branch);
+            }
+            self.spine.push((right, depth));
+        }
+
+        self.last = Some(Subtree {
+            hash,
+            start: self.pushed,
+            end: self.pushed + 1,
+        });
+        self.pushed += 1;
+    }
+
+    // The root of everything pushed, the hash of nothing if that was nothing
+    pub fn finish(mut self, on_branch: &mut OnBranch) -> Blake2b256Hash {
+        let Some(mut right) = self.last else {
+            return hash(&[]);
+        };
+        while let Some((left, split)) = self.spine.pop() {
+            right = join(split, &left, &right, on_branch);
+        }
+        right.hash
+    }
+}
+
+fn join(depth: u64, left: &Subtree, right: &Subtree, on_branch: &mut OnBranch) -> Subtree {
+    on_branch(depth, left, right);
+    Subtree {
+        hash: branch_hash(depth, &left.hash, &right.hash),
+        start: left.start,
+        end: right.end,
+    }
+}
@@ -0,0 +1,960 @@
+#![allow(dead_code)]
+
+use crate::dump;
+use crate::exporter::{self, Blake2b256Hash, RSpaceExporter};
+use crate::history::{to_hex, HistoryRepository};
+use crate::hotstore::{HistoryStore, HotStoreTrieAction};
+use crate::importer::RSpaceImporter;
+use crate::keys::{self, Key, Kind};
+use crate::keyvaluestore::{Conflict, KeyValueRead, KeyValueStore, Storage};
+use crate::matcher;
+use crate::rho;
+use crate::rtypes::rtypes;
+use crate::trace::Event;
+use crate::tracked::{Tracked, TrackedWriteTxn};
+use crate::verify;
+use crate::watch::{Watch, Watchers};
+use prost::Message;
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::io::{BufRead, Write};
+use std::marker::PhantomData;
+use std::sync::Mutex;
+use tracing::{debug, debug_span, error, warn};
+
+/*
+Produce and consume, written once against KeyValueStore. The storage a TupleSpace
+is created with decides whether it is durable.
+See RSpace.scala and Tuplespace.scala in rspace/
+
+Entries are stored under their channel and an id from the hash of their content
+and a copy count, so identical ones stay apart, with an index key per entry under its channel and match key, so a lookup only visits
+candidates that can match. See keys.rs for the layout
+*/
+pub struct TupleSpace<S: Storage, D: Message, K: Message> {
+    store: Tracked<S>,
+    event_log: Mutex<Option<Vec<Event>>>,
+    watchers: Watchers,
+    fired: Mutex<Vec<FiredHook>>,
+    phantom: PhantomData<(D, K)>,
+}
+
+// Called with the key of each continuation a produce fires and what it got, see on_fired
+type FiredHook = Box<dyn Fn(&[u8], &rtypes::OptionResult) + Send + Sync>;
+
+// What a consume matched, else the keys of the continuation copies it stored
+pub(crate) type Consumed = (Option<Vec<rtypes::OptionResult>>, Vec<Vec<u8>>);
+
+// A fired continuation's key and what it got
+pub(crate) type KeyedResult = (Vec<u8>, rtypes::OptionResult);
+
+// Everything stored at one channel
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChannelEntries {
+    pub data: Vec<rtypes::RetrieveContinuation>,
+    pub continuations: Vec<rtypes::CommitContinuation>,
+}
+
+impl ChannelEntries {
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty() && self.continuations.is_empty()
+    }
+
+    // Channels of every join waiting at this channel
+    pub fn joins(&self) -> Vec<Vec<String>> {
+        let mut joins: Vec<Vec<String>> = self
+            .continuations
+            .iter()
+            .filter(|ccdata| ccdata.channels.len() > 1)
+            .map(|ccdata| ccdata.channels.clone())
+            .collect();
+        joins.sort();
+        joins.dedup();
+        joins
+    }
+}
+
+impl<
+        S: Storage,
+        D: Clone + std::hash::Hash + std::fmt::Debug + std::default::Default + prost::Message,
+        K: Clone + std::hash::Hash + std::fmt::Debug + std::default::Default + prost::Message,
+    > TupleSpace<S, D, K>
+{
+    pub fn new(store: S) -> TupleSpace<S, D, K> {
+        TupleSpace {
+            store: Tracked::new(store),
+            event_log: Mutex::new(None),
+            watchers: Watchers::new(),
+            fired: Mutex::new(vec![]),
+            phantom: PhantomData,
+        }
+    }
+
+    // Events are only kept once asked for, so a long running space does not pile them up
+    pub fn start_event_log(&self) {
+        let mut event_log = self.event_log.lock().unwrap();
+        if event_log.is_none() {
+            *event_log = Some(vec![]);
+        }
+    }
+
+    // Everything logged since the last take, logging carries on
+    pub fn take_event_log(&self) -> Vec<Event> {
+        self.event_log
+            .lock()
+            .unwrap()
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    // Changes at channel from now on, see watch.rs
+    pub fn watch(&self, channel: &str) -> Watch {
+        let (watch, sender) = Watch::new(channel);
+        self.watchers.add(channel, watch.id(), sender);
+        watch
+    }
+
+    pub fn unwatch(&self, id: u64) -> bool {
+        self.watchers.remove(id)
+    }
+
+    pub(crate) fn watchers(&self) -> &Watchers {
+        &self.watchers
+    }
+
+    // Runs hook after every committed produce that fires a continuation, see asyncspace.rs
+    pub(crate) fn on_fired(
+        &self,
+        hook: impl Fn(&[u8], &rtypes::OptionResult) + Send + Sync + 'static,
+    ) {
+        self.fired.lock().unwrap().push(Box::new(hook));
+    }
+
+    // Runs the on_fired hooks, once the produce that fired the continuation at key committed
+    pub(crate) fn fire(&self, key: &[u8], result: &rtypes::OptionResult) {
+        for hook in self.fired.lock().unwrap().iter() {
+            hook(key, result);
+        }
+    }
+
+    // Called with what each committed write did, watches see it too
+    pub fn log_events(&self, events: Vec<Event>) {
+        self.watchers.publish(&events);
+        if let Some(event_log) = self.event_log.lock().unwrap().as_mut() {
+            event_log.extend(events);
+        }
+    }
+
+    /*
+    Matching, deleting the matched entries and storing the new continuation all
+    happen in one write transaction, so a crash never leaves half a join stored.
+    */
+    pub fn consume(
+        &self,
+        commit: rtypes::Commit,
+        persistent: bool,
+    ) -> Result<Option<Vec<rtypes::OptionResult>>, Box<dyn Error>> {
+        Ok(self.consume_keyed(commit, persistent)?.0)
+    }
+
+    // Like consume, also returning the keys the continuation was stored under
+    pub(crate) fn consume_keyed(
+        &self,
+        commit: rtypes::Commit,
+        persistent: bool,
+    ) -> Result<Consumed, Box<dyn Error>> {
+        let _entered = debug_span!(
+            "consume",
+            channels = ?commit.channels,
+            persistent
+        )
+        .entered();
+
+        if let Err(err) = check_commit(&commit) {
+            warn!("{}", err);
+            return Err(err);
+        }
+
+        let mut events = vec![];
+        let (results, keys) = retrying(|| {
+            events.clear();
+            let mut txn = self.store.write_txn()?;
+            let consumed = consume_keyed_in_txn(&mut txn, &commit, persistent, &mut events)?;
+            txn.commit()?;
+            Ok(consumed)
+        })
+        // dropping the transaction aborts it
+        .inspect_err(|err| error!(%err, "consume failed"))?;
+
+        self.log_events(events);
+        debug!(matched = results.is_some(), "consumed");
+        Ok((results, keys))
+    }
+
+    pub fn produce(
+        &self,
+        retrieve: rtypes::Retrieve,
+        persistent: bool,
+    ) -> Result<Option<rtypes::OptionResult>, Box<dyn Error>> {
+        Ok(self
+            .produce_fired(retrieve, persistent)?
+            .map(|(result, _)| result))
+    }
+
+    // Like produce, also returning the stored continuation the match took
+    pub fn produce_fired(
+        &self,
+        retrieve: rtypes::Retrieve,
+        persistent: bool,
+    ) -> Result<Option<(rtypes::OptionResult, rtypes::CommitContinuation)>, Box<dyn Error>> {
+        let _entered = debug_span!(
+            "produce",
+            channel = %retrieve.chan,
+            persistent
+        )
+        .entered();
+
+        let mut events = vec![];
+        let matched = retrying(|| {
+            events.clear();
+            let mut txn = self.store.write_txn()?;
+            let matched = produce_keyed_in_txn(&mut txn, &retrieve, persistent, &mut events)?;
+            txn.commit()?;
+            Ok(matched)
+        })
+        // dropping the transaction aborts it
+        .inspect_err(|err| error!(%err, "produce failed"))?;
+
+        let fired = match events.first() {
+            Some(Event::Comm { continuations, .. }) => {
+                continuations.first().map(|(_, ccdata)| ccdata.clone())
+            }
+            _ => None,
+        };
+        self.log_events(events);
+        if let Some((key, result)) = &matched {
+            self.fire(key, result);
+        }
+        let result = matched.map(|(_, result)| result);
+        debug!(matched = result.is_some(), "produced");
+        Ok(result.zip(fired))
+    }
+
+    /*
+    Stores a persistent continuation for a system process, before anything is sent
+    to it. Installing where data already matches is refused and consumes nothing.
+    See install in RSpace.scala in rspace/
+    */
+    pub fn install(&self, commit: rtypes::Commit) -> Result<(), Box<dyn Error>> {
+        let _entered = debug_span!("install", channels = ?commit.channels).entered();
+
+        let mut events = vec![];
+        retrying(|| {
+            events.clear();
+            let mut txn = self.store.write_txn()?;
+            if consume_in_txn(&mut txn, &commit, true, &mut events)?.is_some() {
+                // dropping the transaction leaves the matched data where it was
+                return Err("installing can be done only on startup".into());
+            }
+            txn.commit()
+        })?;
+        self.log_events(events);
+        debug!("installed");
+        Ok(())
+    }
+
+    pub fn write_txn(&self) -> Result<TrackedWriteTxn<'_, S>, Box<dyn Error>> {
+        self.store.write_txn()
+    }
+
+    pub fn print_channel(&self, channel: &str) -> Result<(), Box<dyn Error>> {
+        if !self.store.is_empty()? {
+            let txn = self.store.read_txn()?;
+            println!("\nCurrent channel state for \"{}\":", channel);
+
+            for (key, ccdata) in
+                channel_entries::<rtypes::CommitContinuation>(&txn, channel, Kind::Continuation)?
+            {
+                println!("KEY: {} VALUE: {:?}", to_hex(&key), ccdata);
+            }
+
+            for (key, rcdata) in
+                channel_entries::<rtypes::RetrieveContinuation>(&txn, channel, Kind::Data)?
+            {
+                println!("KEY: {} VALUE: {:?}", to_hex(&key), rcdata);
+            }
+        } else {
+            println!("\nDatabase is empty")
+        }
+
+        Ok(())
+    }
+
+    // Inspection, see ISpace.scala in rspace/
+
+    // Every channel holding data or continuations, in order
+    pub fn channels(&self) -> Result<Vec<String>, Box<dyn Error>> {
+        let txn = self.store.read_txn()?;
+        Ok(list_channels(&txn)?.into_iter().collect())
+    }
+
+    // Data and continuations stored, counted once per channel they are stored on
+    pub fn entry_count(&self) -> Result<usize, Box<dyn Error>> {
+        let txn = self.store.read_txn()?;
+        let mut count = 0;
+        for tag in [keys::DATA, keys::CONTINUATION] {
+            txn.iterate_prefix(&[tag], &mut |_, _| {
+                count += 1;
+                Ok(true)
+            })?;
+        }
+
+        Ok(count)
+    }
+
+    pub fn get_data(
+        &self,
+        channel: &str,
+    ) -> Result<Vec<rtypes::RetrieveContinuation>, Box<dyn Error>> {
+        let txn = self.store.read_txn()?;
+        Ok(read_channel(&txn, channel)?.data)
+    }
+
+    // Continuations stored by a consume on exactly these channels, one per channel it waits on
+    pub fn get_waiting_continuations(
+        &self,
+        channels: &[String],
+    ) -> Result<Vec<rtypes::CommitContinuation>, Box<dyn Error>> {
+        let txn = self.store.read_txn()?;
+        let mut continuations = vec![];
+
+        for channel in channels.iter().collect::<BTreeSet<_>>() {
+            continuations.extend(
+                read_channel(&txn, channel)?
+                    .continuations
+                    .into_iter()
+                    .filter(|ccdata| ccdata.channels == channels),
+            );
+        }
+
+        Ok(continuations)
+    }
+
+    pub fn get_joins(&self, channel: &str) -> Result<Vec<Vec<String>>, Box<dyn Error>> {
+        let txn = self.store.read_txn()?;
+        Ok(read_channel(&txn, channel)?.joins())
+    }
+
+    /*
+    The whole store keyed by the channels things were stored under: data by its
+    channel, a continuation by every channel of its consume. A join shows up once
+    per channel it waits on, each copy with that channel's pattern.
+    */
+    pub fn to_map(&self) -> Result<BTreeMap<Vec<String>, ChannelEntries>, Box<dyn Error>> {
+        let txn = self.store.read_txn()?;
+        let mut map: BTreeMap<Vec<String>, ChannelEntries> = BTreeMap::new();
+
+        for channel in list_channels(&txn)? {
+            let entries = read_channel(&txn, &channel)?;
+            if !entries.data.is_empty() {
+                map.entry(vec![channel])
+                    .or_default()
+                    .data
+                    .extend(entries.data);
+            }
+            for ccdata in entries.continuations {
+                map.entry(ccdata.channels.clone())
+                    .or_default()
+                    .continuations
+                    .push(ccdata);
+            }
+        }
+
+        Ok(map)
+    }
+
+    // State export, see exporter.rs
+    pub fn root(&self) -> Result<Blake2b256Hash, Box<dyn Error>> {
+        exporter::state_root(&self.store.read_txn()?)
+    }
+
+    pub fn exporter(&self, chunk_size: usize) -> Result<RSpaceExporter<'_>, Box<dyn Error>> {
+        RSpaceExporter::new(&self.store, chunk_size)
+    }
+
+    pub fn importer(
+        &self,
+        root: Blake2b256Hash,
+    ) -> Result<RSpaceImporter<'_, Tracked<S>>, Box<dyn Error>> {
+        RSpaceImporter::new(&self.store, root)
+    }
+
+    // JSON lines of every entry and back, see dump.rs
+    pub fn dump(&self, output: &mut impl Write) -> Result<usize, Box<dyn Error>> {
+        dump::dump(&self.store, output)
+    }
+
+    pub fn restore(&self, input: impl BufRead) -> Result<usize, Box<dyn Error>> {
+        dump::restore(&self.store, input)
+    }
+
+    // Checks every key, and fixes what it can with repair, see verify.rs
+    pub fn verify(&self, repair: bool) -> Result<rtypes::VerifyReport, Box<dyn Error>> {
+        retrying(|| verify::verify(&self.store, repair))
+    }
+
+    // Checkpoint history, see history.rs
+    pub fn checkpoint<H: Storage>(
+        &self,
+        history: &HistoryRepository<H>,
+    ) -> Result<Blake2b256Hash, Box<dyn Error>> {
+        let _entered = debug_span!("checkpoint").entered();
+
+        let (txn, changes, epoch) = self.store.snapshot()?;
+        let root = history.checkpoint(&txn, &changes)?;
+        self.store.checkpointed(root, epoch)?;
+        debug!(root = %to_hex(&root), "checkpointed");
+        Ok(root)
+    }
+
+    // Replaces everything the store holds with what it held at a checkpoint
+    pub fn reset<H: Storage>(
+        &self,
+        history: &HistoryRepository<H>,
+        root: &Blake2b256Hash,
+    ) -> Result<(), Box<dyn Error>> {
+        let pairs = history.pairs(root)?;
+
+        let mut txn = self.store.write_txn()?;
+        let mut keys = vec![];
+        txn.iterate_prefix(&[], &mut |key, _| {
+            keys.push(key.to_vec());
+            Ok(true)
+        })?;
+        for key in keys {
+            txn.delete(&key)?;
+        }
+        for pair in pairs {
+            txn.put(&pair.key, &pair.value)?;
+        }
+        txn.commit_at(*root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.store.is_empty().unwrap()
+    }
+
+    pub fn clear(&self) -> Result<(), Box<dyn Error>> {
+        self.store.clear()
+    }
+
+    pub fn flush(&self) -> Result<(), Box<dyn Error>> {
+        self.store.flush()
+    }
+}
+
+impl<
+        S: Storage,
+        D: Clone + std::hash::Hash + std::fmt::Debug + std::default::Default + prost::Message,
+        K: Clone + std::hash::Hash + std::fmt::Debug + std::default::Default + prost::Message,
+    > HistoryStore for TupleSpace<S, D, K>
+{
+    fn read_channel(&self, channel: &str) -> Result<ChannelEntries, Box<dyn Error>> {
+        let txn = self.store.read_txn()?;
+        read_channel(&txn, channel)
+    }
+
+    // Joins need no action of their own, they are rebuilt from the continuations
+    fn apply_actions(&self, actions: &[HotStoreTrieAction]) -> Result<(), Box<dyn Error>> {
+        let mut txn = self.store.write_txn()?;
+
+        for action in actions {
+            match action {
+                HotStoreTrieAction::TrieInsertProduce { channel, data } => {
+                    delete_channel_entries(&mut txn, channel, Kind::Data)?;
+                    for rcdata in data {
+                        put_datum(&mut txn, channel, rcdata)?;
+                    }
+                }
+                HotStoreTrieAction::TrieInsertConsume {
+                    channel,
+                    continuations,
+                } => {
+                    delete_channel_entries(&mut txn, channel, Kind::Continuation)?;
+                    for ccdata in continuations {
+                        put_continuation(&mut txn, channel, ccdata)?;
+                    }
+                }
+                HotStoreTrieAction::TrieDeleteProduce { channel } => {
+                    delete_channel_entries(&mut txn, channel, Kind::Data)?;
+                }
+                HotStoreTrieAction::TrieDeleteConsume { channel } => {
+                    delete_channel_entries(&mut txn, channel, Kind::Continuation)?;
+                }
+                HotStoreTrieAction::TrieInsertJoins { .. }
+                | HotStoreTrieAction::TrieDeleteJoins { .. } => {}
+            }
+        }
+
+        txn.commit()
+    }
+}
+
+// Runs f again for as long as its write transaction conflicts, see MemStore::concurrent
+fn retrying<T>(mut f: impl FnMut() -> Result<T, Box<dyn Error>>) -> Result<T, Box<dyn Error>> {
+    loop {
+        match f() {
+            Err(err) if err.is::<Conflict>() => debug!("write transaction conflicted, retrying"),
+            result => return result,
+        }
+    }
+}
+
+pub fn check_commit(commit: &rtypes::Commit) -> Result<(), Box<dyn Error>> {
+    let commit = rho::named_commit(commit);
+    if commit.channels.len() != commit.patterns.len() {
+        return Err("channel and pattern vectors are not equal length!".into());
+    }
+    if !commit.bind_patterns.is_empty() && commit.bind_patterns.len() != commit.channels.len() {
+        return Err("channel and bind pattern vectors are not equal length!".into());
+    }
+    Ok(())
+}
+
+// What consume stores under each channel when nothing matches
+pub fn commit_continuations(
+    commit: &rtypes::Commit,
+    persistent: bool,
+) -> Vec<(String, rtypes::CommitContinuation)> {
+    let commit = rho::named_commit(commit);
+    commit
+        .channels
+        .iter()
+        .zip(commit.patterns.iter())
+        .enumerate()
+        .map(|(i, (channel, pattern))| {
+            let ccdata = rtypes::CommitContinuation {
+                pattern: pattern.clone(),
+                continuation: commit.continuation.clone(),
+                persistent,
+                channels: commit.channels.clone(),
+                bind_pattern: commit.bind_patterns.get(i).cloned(),
+            };
+            (channel.clone(), ccdata)
+        })
+        .collect()
+}
+
+// Both verbs append what they did to events, see trace.rs
+pub fn consume_in_txn(
+    txn: &mut impl KeyValueStore,
+    commit: &rtypes::Commit,
+    persistent: bool,
+    events: &mut Vec<Event>,
+) -> Result<Option<Vec<rtypes::OptionResult>>, Box<dyn Error>> {
+    Ok(consume_keyed_in_txn(txn, commit, persistent, events)?.0)
+}
+
+// Like consume_in_txn, also returning the key of each copy of the continuation it stored
+pub(crate) fn consume_keyed_in_txn(
+    txn: &mut impl KeyValueStore,
+    commit: &rtypes::Commit,
+    persistent: bool,
+    events: &mut Vec<Event>,
+) -> Result<Consumed, Box<dyn Error>> {
+    check_commit(commit)?;
+    let commit = rho::named_commit(commit);
+
+    let mut results: Vec<rtypes::OptionResult> = vec![];
+    let mut taken = vec![];
+
+    for (i, (channel, pattern)) in commit
+        .channels
+        .iter()
+        .zip(commit.patterns.iter())
+        .enumerate()
+    {
+        let channel_hash = keys::channel_hash(channel);
+        let matched = find_match(
+            txn,
+            &keys::index_prefix(Kind::Data, &channel_hash, pattern),
+            |rcdata: &rtypes::RetrieveContinuation| {
+                bind(commit.bind_patterns.get(i), rcdata.pars.as_ref())
+            },
+        )?;
+
+        if let Some(entry) = matched {
+            debug!(%channel, key = %to_hex(&entry.key), "matched datum");
+            if !entry.value.persistent {
+                delete_entry(txn, &channel_hash, &entry.key, &entry.index_key)?;
+            }
+
+            results.push(rtypes::OptionResult {
+                continuation: commit.continuation.clone(),
+                data: entry.value.data.clone(),
+                pars: entry.value.pars.clone(),
+                bindings: entry.matched,
+            });
+            taken.push((channel.clone(), entry.value));
+        }
+    }
+
+    if !results.is_empty() {
+        events.push(Event::Comm {
+            data: taken,
+            continuations: vec![],
+        });
+        return Ok((Some(results), vec![]));
+    }
+
+    let stored = commit_continuations(&commit, persistent);
+    let mut keys = vec![];
+    for (channel, commitcont_data) in &stored {
+        keys.push(put_continuation(txn, channel, commitcont_data)?);
+    }
+
+    events.push(Event::Consume {
+        continuations: stored,
+    });
+    Ok((None, keys))
+}
+
+pub fn produce_in_txn(
+    txn: &mut impl KeyValueStore,
+    retrieve: &rtypes::Retrieve,
+    persistent: bool,
+    events: &mut Vec<Event>,
+) -> Result<Option<rtypes::OptionResult>, Box<dyn Error>> {
+    Ok(produce_keyed_in_txn(txn, retrieve, persistent, events)?.map(|(_, result)| result))
+}
+
+// Like produce_in_txn, also returning the key the continuation it fired was stored under
+pub(crate) fn produce_keyed_in_txn(
+    txn: &mut impl KeyValueStore,
+    retrieve: &rtypes::Retrieve,
+    persistent: bool,
+    events: &mut Vec<Event>,
+) -> Result<Option<KeyedResult>, Box<dyn Error>> {
+    let retrieve = rho::named_retrieve(retrieve);
+    let channel_hash = keys::channel_hash(&retrieve.chan);
+    let matched = find_match(
+        txn,
+        &keys::index_prefix(Kind::Continuation, &channel_hash, &retrieve.match_case),
+        |ccdata: &rtypes::CommitContinuation| {
+            bind(ccdata.bind_pattern.as_ref(), retrieve.pars.as_ref())
+        },
+    )?;
+
+    if let Some(entry) = matched {
+        debug!(channel = %retrieve.chan, key = %to_hex(&entry.key), "matched continuation");
+        let mut taken = vec![(retrieve.chan.clone(), entry.value.clone())];
+        if !entry.value.persistent {
+            delete_entry(txn, &channel_hash, &entry.key, &entry.index_key)?;
+            taken.extend(take_join_copies(txn, &retrieve.chan, &entry.value)?);
+        }
+
+        let result = rtypes::OptionResult {
+            continuation: entry.value.continuation.clone(),
+            data: retrieve.data.clone(),
+            pars: retrieve.pars.clone(),
+            bindings: entry.matched,
+        };
+        events.push(Event::Comm {
+            data: vec![],
+            continuations: taken,
+        });
+        return Ok(Some((entry.key, result)));
+    }
+
+    let retrievecont_data = rtypes::RetrieveContinuation {
+        data: retrieve.data.clone(),
+        match_case: retrieve.match_case.clone(),
+        persistent,
+        pars: retrieve.pars.clone(),
+    };
+
+    put_datum(txn, &retrieve.chan, &retrievecont_data)?;
+
+    events.push(Event::Produce {
+        channel: retrieve.chan.clone(),
+        datum: Box::new(retrievecont_data),
+    });
+    Ok(None)
+}
+
+pub fn read_channel(
+    txn: &impl KeyValueRead,
+    channel: &str,
+) -> Result<ChannelEntries, Box<dyn Error>> {
+    let data = channel_entries::<rtypes::RetrieveContinuation>(txn, channel, Kind::Data)?;
+    let continuations =
+        channel_entries::<rtypes::CommitContinuation>(txn, channel, Kind::Continuation)?;
+
+    Ok(ChannelEntries {
+        data: data.into_iter().map(|(_, rcdata)| rcdata).collect(),
+        continuations: continuations
+            .into_iter()
+            .map(|(_, ccdata)| ccdata)
+            .collect(),
+    })
+}
+
+// Stores every entry of a channel next to whatever is already there
+pub fn put_channel(
+    txn: &mut impl KeyValueStore,
+    channel: &str,
+    entries: &ChannelEntries,
+) -> Result<(), Box<dyn Error>> {
+    for rcdata in &entries.data {
+        put_datum(txn, channel, rcdata)?;
+    }
+    for ccdata in &entries.continuations {
+        put_continuation(txn, channel, ccdata)?;
+    }
+    Ok(())
+}
+
+struct IndexedEntry<T, R> {
+    index_key: Vec<u8>,
+    key: Vec<u8>,
+    value: T,
+    // what the match predicate returned for value
+    matched: R,
+}
+
+/*
+Whether pars fit a continuation's bind pattern: None if not, else what the
+pattern bound. Without a bind pattern the match key alone decides
+*/
+pub(crate) fn bind(
+    bind_pattern: Option<&rtypes::BindPattern>,
+    pars: Option<&rtypes::ListParWithRandom>,
+) -> Option<Option<rtypes::ListParWithRandom>> {
+    match (bind_pattern, pars) {
+        (None, _) => Some(None),
+        (Some(bind_pattern), Some(pars)) => matcher::match_list_par(bind_pattern, pars).map(Some),
+        (Some(_), None) => None,
+    }
+}
+
+// Returns the first entry under index_prefix whose decoded value matches
+fn find_match<T: Message + Default, R>(
+    txn: &impl KeyValueRead,
+    index_prefix: &[u8],
+    matches: impl Fn(&T) -> Option<R>,
+) -> Result<Option<IndexedEntry<T, R>>, Box<dyn Error>> {
+    let mut found = None;
+
+    txn.iterate_prefix(index_prefix, &mut |index_key, entry_key| {
+        let buf = txn
+            .get(entry_key)?
+            .ok_or_else(|| format!("match index points at missing key {}", to_hex(entry_key)))?;
+        let value = T::decode(buf.as_slice())?;
+
+        if let Some(matched) = matches(&value) {
+            found = Some(IndexedEntry {
+                index_key: index_key.to_vec(),
+                key: entry_key.to_vec(),
+                value,
+                matched,
+            });
+            return Ok(false);
+        }
+        Ok(true)
+    })?;
+
+    Ok(found)
+}
+
+fn put_datum(
+    txn: &mut impl KeyValueStore,
+    channel: &str,
+    rcdata: &rtypes::RetrieveContinuation,
+) -> Result<(), Box<dyn Error>> {
+    let channel_hash = keys::channel_hash(channel);
+    let value = rcdata.encode_to_vec();
+    let (key, id) = free_entry_key(txn, Kind::Data, &channel_hash, &value)?;
+    let index_key = keys::index_key(Kind::Data, &channel_hash, &rcdata.match_case, id);
+
+    debug!(%channel, key = %to_hex(&key), "stored datum");
+    txn.put(&keys::channel_key(&channel_hash), channel.as_bytes())?;
+    txn.put(&key, &value)?;
+    txn.put(&index_key, &key)
+}
+
+// Returns the key it is stored under
+fn put_continuation(
+    txn: &mut impl KeyValueStore,
+    channel: &str,
+    ccdata: &rtypes::CommitContinuation,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let channel_hash = keys::channel_hash(channel);
+    let value = ccdata.encode_to_vec();
+    let (key, id) = free_entry_key(txn, Kind::Continuation, &channel_hash, &value)?;
+    let index_key = keys::index_key(Kind::Continuation, &channel_hash, &ccdata.pattern, id);
+
+    debug!(%channel, key = %to_hex(&key), "stored continuation");
+    txn.put(&keys::channel_key(&channel_hash), channel.as_bytes())?;
+    txn.put(&key, &value)?;
+    txn.put(&index_key, &key)?;
+    Ok(key)
+}
+
+// Key and id of the first copy of value at the channel not stored yet, see keys::entry_id
+fn free_entry_key(
+    txn: &impl KeyValueRead,
+    kind: Kind,
+    channel_hash: &Blake2b256Hash,
+    value: &[u8],
+) -> Result<(Vec<u8>, u64), Box<dyn Error>> {
+    for copy in 0..=u16::MAX {
+        let id = keys::entry_id(value, copy);
+        let key = keys::entry_key(kind, channel_hash, id);
+        if txn.get(&key)?.is_none() {
+            return Ok((key, id));
+        }
+    }
+    Err(format!(
+        "channel {} holds too many identical entries",
+        to_hex(channel_hash)
+    )
+    .into())
+}
+
+/*
+A join fires once, so when one of its copies is taken the copies on its other
+channels go too, one per channel. The fired copy is already deleted from channel
+*/
+fn take_join_copies(
+    txn: &mut impl KeyValueStore,
+    channel: &str,
+    ccdata: &rtypes::CommitContinuation,
+) -> Result<Vec<(String, rtypes::CommitContinuation)>, Box<dyn Error>> {
+    let mut others = ccdata.channels.clone();
+    if let Some(fired) = others.iter().position(|other| other == channel) {
+        others.remove(fired);
+    }
+
+    let mut taken = vec![];
+    for other in others {
+        let channel_hash = keys::channel_hash(&other);
+        let mut found = None;
+        txn.iterate_prefix(
+            &keys::entry_prefix(Kind::Continuation, &channel_hash),
+            &mut |key, value| {
+                let copy = rtypes::CommitContinuation::decode(value)?;
+                if copy.continuation == ccdata.continuation
+                    && copy.persistent == ccdata.persistent
+                    && copy.channels == ccdata.channels
+                {
+                    found = Some((key.to_vec(), copy));
+                    return Ok(false);
+                }
+                Ok(true)
+            },
+        )?;
+
+        if let Some((key, copy)) = found {
+            let Some(Key::Entry(_, _, id)) = keys::parse(&key) else {
+                continue;
+            };
+            let index_key = keys::index_key(Kind::Continuation, &channel_hash, &copy.pattern, id);
+            debug!(channel = %other, key = %to_hex(&key), "took join copy");
+            delete_entry(txn, &channel_hash, &key, &index_key)?;
+            taken.push((other, copy));
+        }
+    }
+
+    Ok(taken)
+}
+
+// Deletes an entry with its index, and the channel's name once nothing is left there
+fn delete_entry(
+    txn: &mut impl KeyValueStore,
+    channel_hash: &Blake2b256Hash,
+    key: &[u8],
+    index_key: &[u8],
+) -> Result<(), Box<dyn Error>> {
+    txn.delete(key)?;
+    txn.delete(index_key)?;
+    forget_if_empty(txn, channel_hash)
+}
+
+fn forget_if_empty(
+    txn: &mut impl KeyValueStore,
+    channel_hash: &Blake2b256Hash,
+) -> Result<(), Box<dyn Error>> {
+    let mut empty = true;
+    for kind in [Kind::Data, Kind::Continuation] {
+        txn.iterate_prefix(&keys::entry_prefix(kind, channel_hash), &mut |_, _| {
+            empty = false;
+            Ok(false)
+        })?;
+    }
+
+    if empty {
+        txn.delete(&keys::channel_key(channel_hash))?;
+    }
+    Ok(())
+}
+
+// An entry with the key it is stored under
+type KeyedEntry<T> = (Vec<u8>, T);
+
+// Entries of one kind stored at channel, with their keys
+fn channel_entries<T: Message + Default>(
+    txn: &impl KeyValueRead,
+    channel: &str,
+    kind: Kind,
+) -> Result<Vec<KeyedEntry<T>>, Box<dyn Error>> {
+    let mut entries = vec![];
+
+    txn.iterate_prefix(
+        &keys::entry_prefix(kind, &keys::channel_hash(channel)),
+        &mut |key, buf| {
+            entries.push((key.to_vec(), T::decode(buf)?));
+            Ok(true)
+        },
+    )?;
+
+    Ok(entries)
+}
+
+// Names of the channels with stored entries
+pub(crate) fn list_channels(txn: &impl KeyValueRead) -> Result<BTreeSet<String>, Box<dyn Error>> {
+    let mut channels = BTreeSet::new();
+
+    txn.iterate_prefix(&[keys::CHANNEL], &mut |_, name| {
+        channels.insert(String::from_utf8(name.to_vec())?);
+        Ok(true)
+    })?;
+
+    Ok(channels)
+}
+
+fn delete_channel_entries(
+    txn: &mut impl KeyValueStore,
+    channel: &str,
+    kind: Kind,
+) -> Result<(), Box<dyn Error>> {
+    let channel_hash = keys::channel_hash(channel);
+    let entry_prefix = keys::entry_prefix(kind, &channel_hash);
+    let mut stale = vec![];
+
+    txn.iterate_prefix(
+        &keys::index_channel_prefix(kind, &channel_hash),
+        &mut |index_key, entry_key| {
+            if entry_key.starts_with(&entry_prefix) {
+                stale.push(index_key.to_vec());
+                stale.push(entry_key.to_vec());
+            }
+            Ok(true)
+        },
+    )?;
+
+    for key in stale {
+        txn.delete(&key)?;
+    }
+    forget_if_empty(txn, &channel_hash)
+}
@@ -0,0 +1,360 @@
+#![allow(dead_code)]
+
+use crate::exporter::{read_pairs, Blake2b256Hash};
+use crate::history::to_hex;
+use crate::keys::{self, Key, Kind};
+use crate::keyvaluestore::{KeyValueRead, KeyValueStore, Storage};
+use crate::rtypes::rtypes::{
+    CommitContinuation, Problem, ProblemKind, RetrieveContinuation, VerifyReport,
+};
+use prost::Message;
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+
+// What every copy of a join shares: its continuation, persistence and channels
+type Join = (Vec<u8>, bool, Vec<String>);
+
+// An entry as it should be stored: its channel, index key and encoded value
+struct Expected {
+    channel_hash: Blake2b256Hash,
+    index_key: Vec<u8>,
+    value: Vec<u8>,
+    continuation: Option<CommitContinuation>,
+    // not in the store yet, repair writes it with its index
+    missing: bool,
+    // to be deleted by repair, with its index
+    removed: bool,
+}
+
+/*
+Walks every key of a store, see the key layout in keys.rs. Keys have to parse,
+entry values decode and hash to the key's id, a continuation has to be stored on
+every channel it waits on and on no other, every entry has exactly one index key
+pointing at it, and a channel holds a name exactly while it holds entries. With
+repair, bad keys and incomplete joins are deleted and missing indexes and names
+written, in one transaction, so a second run comes back clean. A repair scans in
+that same transaction, so nothing committed meanwhile is undone from a stale scan.
+*/
+pub fn verify<S: Storage>(store: &S, repair: bool) -> Result<VerifyReport, Box<dyn Error>> {
+    if !repair {
+        return Ok(scan(&store.read_txn()?, false)?.0);
+    }
+
+    let mut txn = store.write_txn()?;
+    let (report, writes) = scan(&txn, true)?;
+    if !report.problems.is_empty() {
+        for key in &writes.deletes {
+            txn.delete(key)?;
+        }
+        for (key, value) in &writes.puts {
+            txn.put(key, value)?;
+        }
+        txn.commit()?;
+    }
+
+    Ok(report)
+}
+
+// What a repair deletes, then writes
+#[derive(Default)]
+struct Writes {
+    deletes: Vec<Vec<u8>>,
+    puts: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+fn scan(txn: &impl KeyValueRead, repair: bool) -> Result<(VerifyReport, Writes), Box<dyn Error>> {
+    let pairs = read_pairs(txn)?;
+    let mut report = VerifyReport {
+        keys: pairs.len() as u64,
+        problems: vec![],
+    };
+    let mut problem = |key: &[u8], kind: ProblemKind, detail: String| {
+        report.problems.push(Problem {
+            key: key.to_vec(),
+            kind: kind as i32,
+            detail,
+            repaired: repair,
+        })
+    };
+
+    let mut expected: BTreeMap<Vec<u8>, Expected> = BTreeMap::new();
+    let mut deletes: Vec<Vec<u8>> = vec![];
+    let mut indexes: Vec<(&[u8], &[u8])> = vec![];
+    let mut names: BTreeMap<Blake2b256Hash, String> = BTreeMap::new();
+    let stored_keys: BTreeSet<&[u8]> = pairs.iter().map(|pair| pair.key.as_slice()).collect();
+
+    for pair in &pairs {
+        let key = pair.key.as_slice();
+        let (kind, channel_hash, id) = match keys::parse(key) {
+            Some(Key::Entry(kind, channel_hash, id)) => (kind, channel_hash, id),
+            Some(Key::Index(..)) => {
+                indexes.push((key, &pair.value));
+                continue;
+            }
+            Some(Key::Channel(channel_hash)) => {
+                match String::from_utf8(pair.value.clone()) {
+                    Ok(name) if keys::channel_hash(&name) == channel_hash => {
+                        names.insert(channel_hash, name);
+                    }
+                    Ok(name) => {
+                        problem(
+                            key,
+                            ProblemKind::HashMismatch,
+                            format!("{} is not the name of this channel", name),
+                        );
+                        deletes.push(key.to_vec());
+                    }
+                    Err(err) => {
+                        problem(key, ProblemKind::UndecodableValue, err.to_string());
+                        deletes.push(key.to_vec());
+                    }
+                }
+                continue;
+            }
+            None => {
+                problem(
+                    key,
+                    ProblemKind::MalformedKey,
+                    String::from("not a tuplespace key"),
+                );
+                deletes.push(key.to_vec());
+                continue;
+            }
+        };
+
+        let decoded = match kind {
+            Kind::Data => RetrieveContinuation::decode(pair.value.as_slice())
+                .map(|datum| (datum.match_case, None)),
+            Kind::Continuation => CommitContinuation::decode(pair.value.as_slice())
+                .map(|k| (k.pattern.clone(), Some(k))),
+        };
+        let (match_key, continuation) = match decoded {
+            Ok(decoded) => decoded,
+            Err(err) => {
+                problem(key, ProblemKind::UndecodableValue, err.to_string());
+                deletes.push(key.to_vec());
+                continue;
+            }
+        };
+
+        if let Some(k) = &continuation {
+            if !k.channels.is_empty()
+                && !k
+                    .channels
+                    .iter()
+                    .any(|c| keys::channel_hash(c) == channel_hash)
+            {
+                problem(
+                    key,
+                    ProblemKind::StrayContinuation,
+                    format!(
+                        "continuation waits on {:?}, not channel {}",
+                        k.channels,
+                        to_hex(&channel_hash)
+                    ),
+                );
+                deletes.push(key.to_vec());
+                continue;
+            }
+        }
+
+        // under an id that is not its own, the entry moves to the first copy free for it
+        let mismatch = keys::entry_id(&pair.value, keys::entry_copy(id)) != id;
+        let (entry_key, id) = match mismatch {
+            false => (key.to_vec(), id),
+            true => (0..=u16::MAX)
+                .map(|copy| keys::entry_id(&pair.value, copy))
+                .map(|id| (keys::entry_key(kind, &channel_hash, id), id))
+                .find(|(key, _)| {
+                    !expected.contains_key(key) && !stored_keys.contains(key.as_slice())
+                })
+                .ok_or("no free id left for a moved entry")?,
+        };
+        if mismatch {
+            problem(
+                key,
+                ProblemKind::HashMismatch,
+                format!("content belongs at {}", to_hex(&entry_key)),
+            );
+            deletes.push(key.to_vec());
+        }
+        expected.insert(
+            entry_key,
+            Expected {
+                channel_hash,
+                index_key: keys::index_key(kind, &channel_hash, &match_key, id),
+                value: pair.value.clone(),
+                continuation,
+                missing: mismatch,
+                removed: false,
+            },
+        );
+    }
+
+    // a join is stored once per channel it waits on, each copy with that channel's pattern
+    let mut joins: BTreeMap<Join, Vec<Vec<u8>>> = BTreeMap::new();
+    for (key, entry) in &expected {
+        if let Some(k) = entry
+            .continuation
+            .as_ref()
+            .filter(|k| !k.channels.is_empty())
+        {
+            joins
+                .entry((
+                    k.continuation
+                        .as_ref()
+                        .map(Message::encode_to_vec)
+                        .unwrap_or_default(),
+                    k.persistent,
+                    k.channels.clone(),
+                ))
+                .or_default()
+                .push(key.clone());
+        }
+    }
+    // a copy missing can not be rebuilt without its pattern, so the rest go too
+    for ((_, _, channels), copies) in joins {
+        let stored: BTreeSet<Blake2b256Hash> = copies
+            .iter()
+            .map(|key| expected[key].channel_hash)
+            .collect();
+        let missing: Vec<&str> = channels
+            .iter()
+            .map(String::as_str)
+            .filter(|channel| !stored.contains(&keys::channel_hash(channel)))
+            .collect();
+        if missing.is_empty() {
+            continue;
+        }
+        for key in copies {
+            problem(
+                &key,
+                ProblemKind::MissingJoinEntry,
+                format!(
+                    "join on {:?} has nothing stored on {}",
+                    channels,
+                    missing.join(", ")
+                ),
+            );
+            if let Some(entry) = expected.get_mut(&key) {
+                if !entry.missing {
+                    deletes.push(key.clone());
+                }
+                entry.removed = true;
+            }
+        }
+    }
+
+    // a lost name comes back from a continuation waiting on the channel, if there is one
+    let mut name_writes: BTreeMap<Blake2b256Hash, String> = BTreeMap::new();
+    let unnamed: BTreeSet<Blake2b256Hash> = expected
+        .values()
+        .filter(|entry| !entry.removed && !names.contains_key(&entry.channel_hash))
+        .map(|entry| entry.channel_hash)
+        .collect();
+    for channel_hash in unnamed {
+        let name = expected
+            .values()
+            .filter_map(|entry| entry.continuation.as_ref())
+            .flat_map(|k| k.channels.iter())
+            .find(|channel| keys::channel_hash(channel) == channel_hash);
+        let channel_key = keys::channel_key(&channel_hash);
+        match name {
+            Some(name) => {
+                problem(
+                    &channel_key,
+                    ProblemKind::MissingChannelName,
+                    format!("channel {} has entries but no name", name),
+                );
+                name_writes.insert(channel_hash, name.clone());
+            }
+            None => {
+                problem(
+                    &channel_key,
+                    ProblemKind::MissingChannelName,
+                    String::from("nothing names this channel, its entries go"),
+                );
+                for (key, entry) in expected
+                    .iter_mut()
+                    .filter(|(_, entry)| entry.channel_hash == channel_hash)
+                {
+                    if !entry.missing {
+                        deletes.push(key.clone());
+                    }
+                    entry.removed = true;
+                }
+            }
+        }
+    }
+    // a name outlives its channel's entries only by mistake, or because repair takes them
+    let stored: BTreeSet<Blake2b256Hash> = expected
+        .values()
+        .filter(|entry| !entry.missing)
+        .map(|entry| entry.channel_hash)
+        .collect();
+    for (channel_hash, name) in &names {
+        if expected
+            .values()
+            .any(|entry| !entry.removed && entry.channel_hash == *channel_hash)
+        {
+            continue;
+        }
+        let channel_key = keys::channel_key(channel_hash);
+        if !stored.contains(channel_hash) {
+            problem(
+                &channel_key,
+                ProblemKind::DanglingChannelName,
+                format!("nothing is stored at channel {}", name),
+            );
+        }
+        deletes.push(channel_key);
+    }
+
+    let mut indexed = BTreeSet::new();
+    for (index_key, entry_key) in indexes {
+        match expected.get(entry_key) {
+            Some(entry) if entry.index_key == index_key && entry.removed => {
+                deletes.push(index_key.to_vec());
+            }
+            Some(entry) if entry.index_key == index_key && !entry.missing => {
+                indexed.insert(entry_key.to_vec());
+            }
+            _ => {
+                problem(
+                    index_key,
+                    ProblemKind::DanglingIndex,
+                    format!(
+                        "points at {}, not an entry indexed under this key",
+                        to_hex(entry_key)
+                    ),
+                );
+                deletes.push(index_key.to_vec());
+            }
+        }
+    }
+    // entries written by the repair get their index with them
+    for (key, entry) in &expected {
+        if !entry.missing && !entry.removed && !indexed.contains(key) {
+            problem(
+                &entry.index_key,
+                ProblemKind::MissingIndex,
+                format!("{} has no index", to_hex(key)),
+            );
+        }
+    }
+
+    let mut puts = vec![];
+    for (key, entry) in expected.iter().filter(|(_, entry)| !entry.removed) {
+        if entry.missing {
+            puts.push((key.clone(), entry.value.clone()));
+        }
+        if entry.missing || !indexed.contains(key) {
+            puts.push((entry.index_key.clone(), key.clone()));
+        }
+    }
+    for (channel_hash, name) in name_writes {
+        puts.push((keys::channel_key(&channel_hash), name.into_bytes()));
+    }
+
+    Ok((report, Writes { deletes, puts }))
+}
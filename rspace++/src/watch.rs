@@ -0,0 +1,202 @@
+#![allow(dead_code)]
+
+use crate::rtypes::rtypes::{self, channel_change};
+use crate::trace::Event;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Mutex;
+use std::time::Duration;
+
+// One change at a watched channel
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChannelChange {
+    DatumAdded(rtypes::RetrieveContinuation),
+    // Taken by a match. Persistent data is never removed
+    DatumRemoved(rtypes::RetrieveContinuation),
+    ContinuationAdded(rtypes::CommitContinuation),
+    // A match involving the channel, with what it took from the channel
+    CommFired {
+        data: Vec<rtypes::RetrieveContinuation>,
+        continuations: Vec<rtypes::CommitContinuation>,
+    },
+}
+
+impl ChannelChange {
+    // Every change an event made, by channel, in order
+    pub fn from_event(event: &Event) -> Vec<(String, ChannelChange)> {
+        match event {
+            Event::Produce { channel, datum } => {
+                vec![(
+                    channel.clone(),
+                    ChannelChange::DatumAdded((**datum).clone()),
+                )]
+            }
+            Event::Consume { continuations } => continuations
+                .iter()
+                .map(|(channel, k)| (channel.clone(), ChannelChange::ContinuationAdded(k.clone())))
+                .collect(),
+            Event::Comm {
+                data,
+                continuations,
+            } => {
+                let mut changes: Vec<(String, ChannelChange)> = event
+                    .channels()
+                    .into_iter()
+                    .map(|channel| {
+                        let fired = ChannelChange::CommFired {
+                            data: taken_at(data, &channel),
+                            continuations: taken_at(continuations, &channel),
+                        };
+                        (channel, fired)
+                    })
+                    .collect();
+                changes.extend(data.iter().filter(|(_, datum)| !datum.persistent).map(
+                    |(channel, datum)| {
+                        (channel.clone(), ChannelChange::DatumRemoved(datum.clone()))
+                    },
+                ));
+                changes
+            }
+        }
+    }
+
+    pub fn to_proto(&self, channel: &str) -> rtypes::ChannelChange {
+        let change = match self {
+            ChannelChange::DatumAdded(datum) => channel_change::Change::DatumAdded(datum.clone()),
+            ChannelChange::DatumRemoved(datum) => {
+                channel_change::Change::DatumRemoved(datum.clone())
+            }
+            ChannelChange::ContinuationAdded(k) => {
+                channel_change::Change::ContinuationAdded(k.clone())
+            }
+            ChannelChange::CommFired {
+                data,
+                continuations,
+            } => channel_change::Change::CommFired(rtypes::CommFired {
+                data: data.clone(),
+                continuations: continuations.clone(),
+            }),
+        };
+
+        rtypes::ChannelChange {
+            channel: channel.to_string(),
+            change: Some(change),
+        }
+    }
+}
+
+fn taken_at<T: Clone>(taken: &[(String, T)], channel: &str) -> Vec<T> {
+    taken
+        .iter()
+        .filter(|(at, _)| at == channel)
+        .map(|(_, entry)| entry.clone())
+        .collect()
+}
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/*
+The changes at one channel, from when it was watched. Iterating blocks for the
+next change and ends once the space is gone or the watch is removed with
+unwatch. Changes queue up until read, so a watch nobody reads should be dropped.
+*/
+pub struct Watch {
+    id: u64,
+    channel: String,
+    receiver: Receiver<ChannelChange>,
+}
+
+impl Watch {
+    // The watch, and the sender every watched space publishes to
+    pub(crate) fn new(channel: &str) -> (Watch, Sender<ChannelChange>) {
+        let (sender, receiver) = mpsc::channel();
+        let watch = Watch {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            channel: channel.to_string(),
+            receiver,
+        };
+        (watch, sender)
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn channel(&self) -> &str {
+        &self.channel
+    }
+
+    // The next change if there is one already, without waiting
+    pub fn try_next(&self) -> Option<ChannelChange> {
+        self.receiver.try_recv().ok()
+    }
+
+    // Waits at most timeout for the next change. None on timeout and when the watch has ended
+    pub fn next_timeout(&self, timeout: Duration) -> Option<ChannelChange> {
+        self.receiver.recv_timeout(timeout).ok()
+    }
+}
+
+impl Iterator for Watch {
+    type Item = ChannelChange;
+
+    fn next(&mut self) -> Option<ChannelChange> {
+        self.receiver.recv().ok()
+    }
+}
+
+// Each watch's id and sender
+type Senders = Vec<(u64, Sender<ChannelChange>)>;
+
+// A space's watches by channel. Publishing never blocks, and drops watches whose receiver is gone
+#[derive(Default)]
+pub struct Watchers {
+    senders: Mutex<HashMap<String, Senders>>,
+}
+
+impl Watchers {
+    pub fn new() -> Watchers {
+        Watchers::default()
+    }
+
+    pub(crate) fn add(&self, channel: &str, id: u64, sender: Sender<ChannelChange>) {
+        let mut senders = self.senders.lock().unwrap();
+        senders
+            .entry(channel.to_string())
+            .or_default()
+            .push((id, sender));
+    }
+
+    // Ends the watch as far as this space goes. False if it was not watching here
+    pub fn remove(&self, id: u64) -> bool {
+        let mut senders = self.senders.lock().unwrap();
+        let mut removed = false;
+        senders.retain(|_, watches| {
+            let before = watches.len();
+            watches.retain(|(watch, _)| *watch != id);
+            removed |= watches.len() != before;
+            !watches.is_empty()
+        });
+        removed
+    }
+
+    pub fn publish(&self, events: &[Event]) {
+        let mut senders = self.senders.lock().unwrap();
+        if senders.is_empty() {
+            return;
+        }
+
+        for (channel, change) in events.iter().flat_map(ChannelChange::from_event) {
+            if let Some(watches) = senders.get_mut(&channel) {
+                watches.retain(|(_, sender)| sender.send(change.clone()).is_ok());
+                if watches.is_empty() {
+                    senders.remove(&channel);
+                }
+            }
+        }
+    }
+}
+
+// An encoded ChannelChange of len bytes, only valid during the call
+pub type WatchCallback = extern "C" fn(change: *const u8, len: usize);
@@ -0,0 +1,286 @@
+#[cfg(all(test, feature = "async"))]
+mod tests {
+    use rspace_plus_plus::asyncspace::AsyncSpace;
+    use rspace_plus_plus::hotstore::HistoryStore;
+    use rspace_plus_plus::lmdbstore::LmdbStore;
+    use rspace_plus_plus::memorystore::MemStore;
+    use rspace_plus_plus::rtypes::rtypes::{Commit, Retrieve};
+    use rspace_plus_plus::setup::Setup;
+    use rspace_plus_plus::transaction::Transaction;
+    use rspace_plus_plus::tuplespace::TupleSpace;
+    use std::path::Path;
+    use std::time::Duration;
+
+    fn space() -> AsyncSpace<MemStore> {
+        AsyncSpace::new(TupleSpace::new(MemStore::new()))
+    }
+
+    fn retrieve(channel: &str) -> Retrieve {
+        let setup = Setup::new();
+        Setup::create_retrieve(
+            String::from(channel),
+            setup.alice.clone(),
+            Setup::get_city_field(setup.alice),
+        )
+    }
+
+    fn commit(channels: &[&str], continuation: &str) -> Commit {
+        Setup::create_commit(
+            channels.iter().map(|channel| channel.to_string()).collect(),
+            channels
+                .iter()
+                .map(|_| Setup::new().city_match_case)
+                .collect(),
+            continuation,
+        )
+    }
+
+    #[tokio::test]
+    async fn asyncspace_test_produce_consume() {
+        let space = space();
+
+        assert_eq!(
+            space
+                .produce(retrieve("async-friends"), false)
+                .await
+                .unwrap(),
+            None
+        );
+        let results = space
+            .consume(commit(&["async-friends"], "k"), false)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].continuation, Some("k".into()));
+
+        let mut uneven = commit(&["async-friends"], "k");
+        uneven.patterns.clear();
+        assert!(space.consume(uneven.clone(), false).await.is_err());
+        assert!(space.consume_waiting(uneven, false).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn asyncspace_test_consume_waiting_matches_at_once() {
+        let space = space();
+        space
+            .produce(retrieve("async-colleagues"), false)
+            .await
+            .unwrap();
+
+        let fired = space
+            .consume_waiting(commit(&["async-colleagues"], "k"), false)
+            .await
+            .unwrap();
+        assert!(fired.is_fired());
+        assert_eq!(fired.await[0].continuation, Some("k".into()));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn asyncspace_test_produce_wakes_waiting_consume() {
+        let space = space();
+        let fired = space
+            .consume_waiting(commit(&["async-family"], "I am waiting"), false)
+            .await
+            .unwrap();
+        assert!(!fired.is_fired());
+
+        let waiting = tokio::spawn(fired);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiting.is_finished());
+
+        let result = space
+            .produce(retrieve("async-family"), false)
+            .await
+            .unwrap()
+            .unwrap();
+        let results = tokio::time::timeout(Duration::from_secs(5), waiting)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(results, vec![result]);
+        assert_eq!(results[0].continuation, Some("I am waiting".into()));
+    }
+
+    #[tokio::test]
+    async fn asyncspace_test_waiters_wake_one_per_produce() {
+        let space = space();
+        let first = space
+            .consume_waiting(commit(&["async-order"], "first"), false)
+            .await
+            .unwrap();
+        let second = space
+            .consume_waiting(commit(&["async-order"], "second"), false)
+            .await
+            .unwrap();
+        // a dropped future leaves its continuation stored, the produce it takes wakes no one
+        let dropped = space
+            .consume_waiting(commit(&["async-dropped"], "dropped"), false)
+            .await
+            .unwrap();
+        drop(dropped);
+
+        space
+            .produce(retrieve("async-dropped"), false)
+            .await
+            .unwrap()
+            .unwrap();
+        space
+            .produce(retrieve("async-order"), false)
+            .await
+            .unwrap()
+            .unwrap();
+        let fired = [first.is_fired(), second.is_fired()];
+        assert_eq!(fired.iter().filter(|fired| **fired).count(), 1);
+        space
+            .produce(retrieve("async-order"), false)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let mut continuations = vec![first.await[0].continuation.clone().unwrap().to_string()];
+        continuations.push(second.await[0].continuation.clone().unwrap().to_string());
+        continuations.sort();
+        assert_eq!(continuations, vec!["first", "second"]);
+    }
+
+    #[tokio::test]
+    async fn asyncspace_test_identical_waiters_fire_apart() {
+        let space = space();
+        let first = space
+            .consume_waiting(commit(&["async-identical"], "same"), false)
+            .await
+            .unwrap();
+        let second = space
+            .consume_waiting(commit(&["async-identical"], "same"), false)
+            .await
+            .unwrap();
+
+        for _ in 0..2 {
+            space
+                .produce(retrieve("async-identical"), false)
+                .await
+                .unwrap()
+                .unwrap();
+        }
+        assert!(first.is_fired() && second.is_fired());
+        assert!(space
+            .space()
+            .read_channel("async-identical")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn asyncspace_test_waiting_stores_what_consume_stores() {
+        let waiting = space();
+        let plain = space();
+        let _fired = waiting
+            .consume_waiting(commit(&["async-root-a", "async-root-b"], "k"), false)
+            .await
+            .unwrap();
+        plain
+            .consume(commit(&["async-root-a", "async-root-b"], "k"), false)
+            .await
+            .unwrap();
+
+        // nothing about the waiter is stored, so the state roots agree
+        assert_eq!(
+            waiting.space().root().unwrap(),
+            plain.space().root().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn asyncspace_test_transaction_produce_wakes_on_commit() {
+        let space = space();
+        let fired = space
+            .consume_waiting(commit(&["async-transaction"], "k"), false)
+            .await
+            .unwrap();
+        let other = TupleSpace::new(MemStore::new());
+
+        let mut tx = Transaction::new(&other, &other, space.space(), &other);
+        let result = tx
+            .get_once_non_durable_sequential(retrieve("async-transaction"))
+            .unwrap()
+            .unwrap();
+        assert!(!fired.is_fired());
+        tx.commit().unwrap();
+        assert!(fired.is_fired());
+        assert_eq!(fired.await, vec![result]);
+    }
+
+    #[tokio::test]
+    async fn asyncspace_test_direct_produce_wakes_waiting_consume() {
+        let space = space();
+        let fired = space
+            .consume_waiting(commit(&["async-direct"], "k"), false)
+            .await
+            .unwrap();
+
+        let result = space
+            .space()
+            .produce(retrieve("async-direct"), false)
+            .unwrap()
+            .unwrap();
+        assert!(fired.is_fired());
+        assert_eq!(fired.await, vec![result]);
+    }
+
+    #[tokio::test]
+    async fn asyncspace_test_persistent_and_join() {
+        let space = space();
+        let persistent = space
+            .consume_waiting(commit(&["async-persistent"], "always"), true)
+            .await
+            .unwrap();
+        for _ in 0..2 {
+            let result = space
+                .produce(retrieve("async-persistent"), false)
+                .await
+                .unwrap();
+            assert!(result.is_some());
+        }
+        assert_eq!(persistent.await.len(), 1);
+
+        let join = space
+            .consume_waiting(commit(&["async-join-a", "async-join-b"], "join"), false)
+            .await
+            .unwrap();
+        space
+            .produce(retrieve("async-join-b"), false)
+            .await
+            .unwrap();
+        assert_eq!(join.await[0].continuation, Some("join".into()));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn asyncspace_test_lmdb() {
+        let dir = Path::new("target")
+            .join("AsyncDB")
+            .join(std::process::id().to_string());
+        let _ = std::fs::remove_dir_all(&dir);
+        let space = AsyncSpace::new(TupleSpace::new(LmdbStore::open(&dir).unwrap()));
+
+        let fired = space
+            .consume_waiting(commit(&["async-disk"], "k"), false)
+            .await
+            .unwrap();
+        let producer = space.clone();
+        tokio::spawn(async move {
+            producer
+                .produce(retrieve("async-disk"), false)
+                .await
+                .is_ok()
+        });
+        let results = tokio::time::timeout(Duration::from_secs(5), fired)
+            .await
+            .unwrap();
+        assert_eq!(results[0].continuation, Some("k".into()));
+        assert!(space.space().read_channel("async-disk").unwrap().is_empty());
+
+        drop(space);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,244 @@
+#[cfg(test)]
+mod tests {
+    use rspace_plus_plus::cli::{Format, Session};
+    use rspace_plus_plus::keyvaluestore::Storage;
+    use rspace_plus_plus::rtypes::rtypes::TaggedContinuation;
+    use serde_json::{json, Value};
+    use std::fs;
+    use std::io::Cursor;
+    use std::path::Path;
+    use std::process::Command;
+
+    const ALICE: &str =
+        r#"'{"name":{"first":"Alice","last":"Lincoln"},"address":{"city":"Boston"}}'"#;
+
+    fn json(session: &Session<impl Storage, impl Storage>, line: &str) -> Value {
+        serde_json::from_str(&session.execute(line).unwrap().unwrap()).unwrap()
+    }
+
+    // A continuation given as text, as JSON
+    fn continuation(text: &str) -> Value {
+        json!(TaggedContinuation::from(text))
+    }
+
+    fn run(
+        session: &Session<impl Storage, impl Storage>,
+        script: &str,
+        interactive: bool,
+    ) -> (Result<(), String>, String) {
+        let mut output = vec![];
+        let result = session
+            .run(Cursor::new(script), &mut output, interactive)
+            .map_err(|err| err.to_string());
+        (result, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn cli_test_verbs() {
+        let session = Session::in_memory(Format::Json);
+
+        assert_eq!(
+            json(
+                &session,
+                "consume cli-friends Boston 'I am the continuation'"
+            ),
+            json!({ "matched": null })
+        );
+        let matched = json(&session, &format!("produce cli-friends Boston {}", ALICE));
+        assert_eq!(
+            matched["matched"]["continuation"],
+            continuation("I am the continuation")
+        );
+        assert_eq!(matched["matched"]["data"]["name"]["first"], "Alice");
+        assert_eq!(matched["matched"]["data"]["email"], "");
+
+        session
+            .execute(&format!("produce cli-friends Boston {} --persist", ALICE))
+            .unwrap();
+        session
+            .execute("consume cli-friends,cli-colleagues Idaho,Nevada \"the \\\"join\\\"\"")
+            .unwrap();
+
+        let peek = json(&session, "peek cli-friends");
+        assert_eq!(peek["data"][0]["persistent"], true);
+        assert_eq!(
+            peek["continuations"][0]["continuation"],
+            continuation("the \"join\"")
+        );
+        assert_eq!(
+            json(&session, "channels"),
+            json!(["cli-colleagues", "cli-friends"])
+        );
+        assert_eq!(json(&session, "dump").as_array().unwrap().len(), 2);
+
+        let stats = json(&session, "stats");
+        assert_eq!(stats["channels"], 2);
+        assert_eq!(stats["data"], 1);
+        assert_eq!(stats["continuations"], 2);
+        assert_eq!(stats["checkpoints"], 0);
+
+        assert_eq!(
+            json(&session, "verify --repair"),
+            json!({ "keys": 8, "problems": [] })
+        );
+    }
+
+    #[test]
+    fn cli_test_checkpoint_and_reset() {
+        let session = Session::in_memory(Format::Json);
+
+        let empty = json(&session, "checkpoint")["root"].clone();
+        session
+            .execute(&format!("produce cli-friends Boston {}", ALICE))
+            .unwrap();
+        let full = json(&session, "checkpoint")["root"].clone();
+        assert_ne!(empty, full);
+
+        assert_eq!(
+            json(&session, &format!("reset {}", empty.as_str().unwrap()))["root"],
+            empty
+        );
+        assert_eq!(json(&session, "channels"), json!([]));
+
+        json(&session, &format!("reset {}", full.as_str().unwrap()));
+        let stats = json(&session, "stats");
+        assert_eq!(stats["root"], full);
+        assert_eq!(stats["checkpoints"], 2);
+
+        assert!(session.execute("reset 1234").is_err());
+        assert!(session
+            .execute(&format!("reset {}", "ab".repeat(32)))
+            .is_err());
+    }
+
+    #[test]
+    fn cli_test_human_output() {
+        let session = Session::in_memory(Format::Human);
+
+        assert_eq!(
+            session
+                .execute("consume cli-friends Boston k")
+                .unwrap()
+                .unwrap(),
+            "stored"
+        );
+        assert_eq!(
+            session.execute("peek cli-colleagues").unwrap().unwrap(),
+            "nothing stored"
+        );
+        assert_eq!(session.execute("channels").unwrap().unwrap(), "cli-friends");
+        assert!(session
+            .execute("dump")
+            .unwrap()
+            .unwrap()
+            .starts_with("cli-friends\n  continuation {"));
+        assert!(session
+            .execute(&format!("produce cli-friends Boston {}", ALICE))
+            .unwrap()
+            .unwrap()
+            .starts_with(
+                "matched {\"bindings\":null,\"continuation\":{\"tagged_cont\":{\"opaque\":[107]}}"
+            ));
+        assert!(session
+            .execute("help")
+            .unwrap()
+            .unwrap()
+            .contains("checkpoint"));
+        assert_eq!(session.execute("   ").unwrap(), None);
+        assert_eq!(session.execute("# produce it ' later").unwrap(), None);
+    }
+
+    #[test]
+    fn cli_test_errors() {
+        let session = Session::in_memory(Format::Human);
+
+        for line in [
+            "launch",
+            "peek",
+            "dump everything",
+            "consume a,b Boston k",
+            "produce cli-friends Boston '{\"name\":",
+            "produce cli-friends Boston 'unterminated",
+            "channels --persist",
+        ] {
+            assert!(session.execute(line).is_err(), "{}", line);
+        }
+
+        // interactive runs carry on, scripts stop
+        let (result, output) = run(&session, "launch\nconsume cli-friends Boston k\n", true);
+        assert!(result.is_ok());
+        assert_eq!(
+            output,
+            "> error: unknown command launch, see help\n> stored\n> "
+        );
+
+        let (result, output) = run(&session, "channels\n\nlaunch\nchannels\n", false);
+        assert_eq!(
+            result,
+            Err(String::from("line 3: unknown command launch, see help"))
+        );
+        assert_eq!(output, "cli-friends\n");
+
+        let (result, output) = run(&session, "channels\nquit\nlaunch\n", false);
+        assert!(result.is_ok());
+        assert_eq!(output, "cli-friends\n");
+    }
+
+    #[test]
+    fn cli_test_binary() {
+        let dir = Path::new("target")
+            .join("CliDB")
+            .join(std::process::id().to_string());
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("script.txt");
+        fs::write(
+            &script,
+            format!(
+                "# fixture\nproduce cli-friends Boston {} --persist\ncheckpoint\n",
+                ALICE
+            ),
+        )
+        .unwrap();
+
+        let stats_script = dir.join("stats.txt");
+        fs::write(&stats_script, "stats\n").unwrap();
+
+        let cli = env!("CARGO_BIN_EXE_rspace-cli");
+        let first = Command::new(cli)
+            .arg("--json")
+            .arg(&dir)
+            .arg("--script")
+            .arg(&script)
+            .output()
+            .unwrap();
+        assert!(first.status.success());
+        let lines: Vec<Value> = String::from_utf8(first.stdout)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines[0], json!({ "matched": null }));
+
+        // the store and its history are still there for the next run
+        let second = Command::new(cli)
+            .arg("--json")
+            .arg(&dir)
+            .stdin(fs::File::open(&stats_script).unwrap())
+            .output()
+            .unwrap();
+        assert!(second.status.success());
+        let stats: Value = serde_json::from_slice(&second.stdout).unwrap();
+        assert_eq!(stats["data"], 1);
+        assert_eq!(stats["checkpoints"], 1);
+        assert_eq!(stats["root"], lines[1]["root"]);
+
+        let usage = Command::new(cli).output().unwrap();
+        assert!(!usage.status.success());
+        assert!(String::from_utf8(usage.stderr)
+            .unwrap()
+            .starts_with("usage:"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
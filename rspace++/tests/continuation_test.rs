@@ -0,0 +1,175 @@
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+    use rspace_plus_plus::memorystore::MemStore;
+    use rspace_plus_plus::rspace::RSpace;
+    use rspace_plus_plus::rtypes::rtypes::{
+        expr::ExprInstance, tagged_continuation::TaggedCont, Commit, Expr, OptionResult, Par,
+        ParWithRandom, Retrieve, Store, TaggedContinuation,
+    };
+    use rspace_plus_plus::setup::Setup;
+    use rspace_plus_plus::{
+        space_free_bytes, space_get_once_non_durable_sequential, space_new,
+        space_put_once_non_durable_sequential,
+    };
+
+    fn par_with_random(random_state: Vec<u8>) -> ParWithRandom {
+        ParWithRandom {
+            body: Some(Par {
+                exprs: vec![Expr {
+                    expr_instance: Some(ExprInstance::GInt(7)),
+                }],
+                ..Default::default()
+            }),
+            random_state,
+        }
+    }
+
+    fn retrieve(channel: &str) -> Retrieve {
+        let setup = Setup::new();
+        Setup::create_retrieve(
+            String::from(channel),
+            setup.alice.clone(),
+            Setup::get_city_field(setup.alice),
+        )
+    }
+
+    fn commit(channel: &str, continuation: TaggedContinuation) -> Commit {
+        Setup::create_commit(
+            vec![String::from(channel)],
+            vec![Setup::new().city_match_case],
+            continuation,
+        )
+    }
+
+    fn kinds() -> Vec<TaggedContinuation> {
+        vec![
+            TaggedContinuation::par_body(par_with_random(vec![7; 8])),
+            TaggedContinuation::scala_body_ref(3),
+            TaggedContinuation::opaque(vec![0, 255, 1]),
+        ]
+    }
+
+    #[test]
+    fn continuation_test_decodes_scala_encoding() {
+        // TaggedContinuation(ParBody(ParWithRandom(GInt(7), randomState = 010203))) from the Scala models
+        let scala = [
+            0x0a, 0x0b, 0x0a, 0x04, 0x2a, 0x02, 0x10, 0x0e, 0x12, 0x03, 0x01, 0x02, 0x03,
+        ];
+
+        let decoded = TaggedContinuation::decode(scala.as_slice()).unwrap();
+        assert_eq!(
+            decoded,
+            TaggedContinuation::par_body(par_with_random(vec![1, 2, 3]))
+        );
+        assert_eq!(decoded.encode_to_vec(), scala);
+    }
+
+    #[test]
+    fn continuation_test_every_kind_in_every_store() {
+        let rspace = RSpace::<Retrieve, Commit, MemStore>::create_with_storage(
+            MemStore::new(),
+            MemStore::new(),
+        )
+        .unwrap();
+
+        for (i, continuation) in kinds().into_iter().enumerate() {
+            let channel = format!("continuation-kind-{}", i);
+            let expected = Some(continuation.clone());
+
+            assert!(rspace
+                .put_once_non_durable_sequential(commit(&channel, continuation.clone()))
+                .unwrap()
+                .is_none());
+            let result = rspace
+                .get_once_non_durable_sequential(retrieve(&channel))
+                .unwrap()
+                .unwrap();
+            assert_eq!(result.continuation, expected);
+
+            assert!(rspace
+                .put_once_durable_concurrent(commit(&channel, continuation.clone()))
+                .unwrap()
+                .is_none());
+            let stored = rspace
+                .get_waiting_continuations(Store::DiskConc, std::slice::from_ref(&channel))
+                .unwrap();
+            assert_eq!(stored[0].continuation, expected);
+            let result = rspace
+                .get_once_durable_concurrent(retrieve(&channel))
+                .unwrap()
+                .unwrap();
+            assert_eq!(result.continuation, expected);
+
+            rspace
+                .get_once_durable_sequential(retrieve(&channel))
+                .unwrap();
+            let results = rspace
+                .put_once_durable_sequential(commit(&channel, continuation))
+                .unwrap()
+                .unwrap();
+            assert_eq!(results[0].continuation, expected);
+        }
+    }
+
+    #[test]
+    fn continuation_test_ffi() {
+        let rspace = space_new();
+        let continuation = TaggedContinuation::scala_body_ref(42);
+
+        let mut len = 0;
+        let mut error = false;
+        let buf = commit("continuation-ffi", continuation.clone()).encode_to_vec();
+        assert!(unsafe {
+            space_put_once_non_durable_sequential(
+                rspace,
+                buf.as_ptr(),
+                buf.len(),
+                &mut len,
+                &mut error,
+            )
+        }
+        .is_null());
+        let buf = retrieve("continuation-ffi").encode_to_vec();
+        let pres = unsafe {
+            space_get_once_non_durable_sequential(
+                rspace,
+                buf.as_ptr(),
+                buf.len(),
+                &mut len,
+                &mut error,
+            )
+        };
+        assert!(!pres.is_null());
+
+        let result =
+            OptionResult::decode(unsafe { std::slice::from_raw_parts(pres, len) }).unwrap();
+        unsafe { space_free_bytes(pres as *mut u8, len) };
+        assert_eq!(result.continuation, Some(continuation));
+    }
+
+    #[test]
+    fn continuation_test_display() {
+        assert_eq!(
+            TaggedContinuation::from("I am the continuation").to_string(),
+            "I am the continuation"
+        );
+        assert_eq!(
+            TaggedContinuation::scala_body_ref(5).to_string(),
+            "scala_body_ref 5"
+        );
+        assert_eq!(
+            TaggedContinuation::par_body(par_with_random(vec![1, 2])).to_string(),
+            "par_body (10 bytes)"
+        );
+        assert_eq!(
+            TaggedContinuation::opaque(vec![0xff]).to_string(),
+            "opaque (1 bytes)"
+        );
+        assert_eq!(TaggedContinuation::default().to_string(), "empty");
+        assert!(matches!(
+            TaggedContinuation::from("k").tagged_cont,
+            Some(TaggedCont::Opaque(_))
+        ));
+    }
+}
@@ -0,0 +1,283 @@
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+    use rspace_plus_plus::exporter::Blake2b256Hash;
+    use rspace_plus_plus::history::to_hex;
+    use rspace_plus_plus::keys::{self, Key, Kind};
+    use rspace_plus_plus::keyvaluestore::{KeyValueRead, Storage};
+    use rspace_plus_plus::lmdbstore::LmdbStore;
+    use rspace_plus_plus::rtypes::rtypes::{
+        Address, Commit, CommitContinuation, Entry, Retrieve, RetrieveContinuation,
+    };
+    use rspace_plus_plus::setup::Setup;
+    use rspace_plus_plus::tuplespace::TupleSpace;
+    use std::collections::{HashMap, HashSet};
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+    use std::path::{Path, PathBuf};
+    use std::process::{Command, Stdio};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+    use std::{env, thread};
+
+    const ROUNDS: usize = 8;
+    const CHILD_OPS: usize = 2_000;
+    const CRASH_DIR: &str = "RSPACE_CRASH_DIR";
+    const STATES: [&str; 2] = ["Idaho", "Nevada"];
+
+    // xorshift, enough to vary the traffic and the kill points between runs
+    struct Rng(u64);
+
+    impl Rng {
+        fn new() -> Rng {
+            let nanos = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos() as u64;
+            Rng((nanos ^ std::process::id() as u64) | 1)
+        }
+
+        fn next(&mut self, bound: u64) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0 % bound
+        }
+    }
+
+    fn matched_log(dir: &Path) -> PathBuf {
+        dir.join("matched.log")
+    }
+
+    /*
+    Runs in the child process only. Each datum carries a unique email, and every
+    datum a consume takes is appended to the log after its transaction commits.
+    Joins only go to channels nothing is produced on, so they are always stored.
+    */
+    #[test]
+    #[ignore]
+    fn crash_test_child_traffic() {
+        let dir = match env::var(CRASH_DIR) {
+            Ok(dir) => PathBuf::from(dir),
+            Err(_) => return,
+        };
+        let space: TupleSpace<LmdbStore, Retrieve, Commit> =
+            TupleSpace::new(LmdbStore::open(&dir).unwrap());
+        let mut log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(matched_log(&dir))
+            .unwrap();
+        let mut rng = Rng::new();
+
+        for n in 0..CHILD_OPS {
+            let state = STATES[rng.next(2) as usize].to_string();
+
+            match rng.next(10) {
+                0..=4 => {
+                    let entry = Entry {
+                        email: format!("crash-{}-{}@example.org", std::process::id(), n),
+                        address: Some(Address {
+                            state: state.clone(),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    };
+                    let channel = format!("crash-{}", rng.next(3));
+                    space
+                        .produce(Setup::create_retrieve(channel, entry, state), false)
+                        .unwrap();
+                }
+                5..=8 => {
+                    let commit = Setup::create_commit(
+                        vec![format!("crash-{}", rng.next(3))],
+                        vec![state],
+                        format!("consume-{}-{}", std::process::id(), n),
+                    );
+                    for result in space.consume(commit, false).unwrap().unwrap_or_default() {
+                        if let Some(data) = result.data {
+                            writeln!(log, "{}", data.email).unwrap();
+                        }
+                    }
+                }
+                _ => {
+                    let first = rng.next(4);
+                    let second = (first + 1 + rng.next(3)) % 4;
+                    let commit = Setup::create_commit(
+                        vec![
+                            format!("crash-join-{}", first),
+                            format!("crash-join-{}", second),
+                        ],
+                        vec![state.clone(), state],
+                        format!("join-{}-{}", std::process::id(), n),
+                    );
+                    space.consume(commit, false).unwrap();
+                }
+            }
+        }
+
+        // wait to be killed
+        loop {
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    // Every violated invariant, after the store has been reopened
+    fn check_store(store: &LmdbStore, dir: &Path) -> Vec<String> {
+        let mut violations = vec![];
+        let txn = store.read_txn().unwrap();
+
+        let mut entries: HashMap<Vec<u8>, Vec<u8>> = HashMap::new();
+        let mut index_targets: Vec<Vec<u8>> = vec![];
+        let mut names: HashMap<Blake2b256Hash, Vec<u8>> = HashMap::new();
+        txn.iterate_prefix(&[], &mut |key, value| {
+            match keys::parse(key) {
+                Some(Key::Index(..)) => index_targets.push(value.to_vec()),
+                Some(Key::Channel(channel_hash)) => {
+                    names.insert(channel_hash, value.to_vec());
+                }
+                _ => {
+                    entries.insert(key.to_vec(), value.to_vec());
+                }
+            }
+            Ok(true)
+        })
+        .unwrap();
+
+        let mut data: Vec<RetrieveContinuation> = vec![];
+        let mut continuations: HashMap<Blake2b256Hash, Vec<CommitContinuation>> = HashMap::new();
+        let mut stored_at: HashSet<Blake2b256Hash> = HashSet::new();
+        for (key, value) in &entries {
+            match keys::parse(key) {
+                Some(Key::Entry(Kind::Data, channel_hash, _)) => {
+                    stored_at.insert(channel_hash);
+                    match RetrieveContinuation::decode(value.as_slice()) {
+                        Ok(rcdata) => data.push(rcdata),
+                        Err(err) => {
+                            violations.push(format!("{} does not decode: {}", to_hex(key), err))
+                        }
+                    }
+                }
+                Some(Key::Entry(Kind::Continuation, channel_hash, _)) => {
+                    stored_at.insert(channel_hash);
+                    match CommitContinuation::decode(value.as_slice()) {
+                        Ok(ccdata) => continuations.entry(channel_hash).or_default().push(ccdata),
+                        Err(err) => {
+                            violations.push(format!("{} does not decode: {}", to_hex(key), err))
+                        }
+                    }
+                }
+                _ => violations.push(format!("unexpected key {}", to_hex(key))),
+            }
+        }
+
+        // a channel has a name exactly while something is stored there
+        for channel_hash in &stored_at {
+            if !names.contains_key(channel_hash) {
+                violations.push(format!("channel {} has no name", to_hex(channel_hash)));
+            }
+        }
+        for (channel_hash, name) in &names {
+            if !stored_at.contains(channel_hash) {
+                violations.push(format!(
+                    "{} is named with nothing stored",
+                    String::from_utf8_lossy(name)
+                ));
+            }
+        }
+
+        // every entry is indexed exactly once and every index entry points at an entry
+        let mut indexed: HashMap<&[u8], usize> = HashMap::new();
+        for target in &index_targets {
+            if !entries.contains_key(target) {
+                violations.push(format!("index points at missing {}", to_hex(target)));
+            }
+            *indexed.entry(target).or_insert(0) += 1;
+        }
+        for key in entries.keys() {
+            if indexed.get(key.as_slice()) != Some(&1) {
+                violations.push(format!(
+                    "{} is indexed {:?} times",
+                    to_hex(key),
+                    indexed.get(key.as_slice())
+                ));
+            }
+        }
+
+        // a join is stored on every one of its channels or on none
+        for waiting in continuations.values() {
+            for ccdata in waiting.iter().filter(|ccdata| ccdata.channels.len() > 1) {
+                for channel in &ccdata.channels {
+                    let stored =
+                        continuations
+                            .get(&keys::channel_hash(channel))
+                            .is_some_and(|others| {
+                                others
+                                    .iter()
+                                    .any(|other| other.continuation == ccdata.continuation)
+                            });
+                    if !stored {
+                        violations.push(format!(
+                            "join {} is missing on {}",
+                            ccdata.continuation.clone().unwrap_or_default(),
+                            channel
+                        ));
+                    }
+                }
+            }
+        }
+
+        // a datum a consume took is gone, and no datum was taken twice
+        let log = fs::read_to_string(matched_log(dir)).unwrap_or_default();
+        let mut matched: HashSet<&str> = HashSet::new();
+        for email in log.lines() {
+            if !matched.insert(email) {
+                violations.push(format!("{} was matched twice", email));
+            }
+        }
+        for rcdata in &data {
+            let email = rcdata
+                .data
+                .as_ref()
+                .map_or("", |entry| entry.email.as_str());
+            if matched.contains(email) {
+                violations.push(format!("{} was matched but is still stored", email));
+            }
+        }
+
+        violations
+    }
+
+    #[test]
+    fn crash_test_lmdb_survives_kill() {
+        let dir = Path::new("target")
+            .join("CrashDB")
+            .join(std::process::id().to_string());
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let store = LmdbStore::open(&dir).unwrap();
+        let mut rng = Rng::new();
+
+        for round in 0..ROUNDS {
+            let mut child = Command::new(env::current_exe().unwrap())
+                .args(["--ignored", "--exact", "tests::crash_test_child_traffic"])
+                .env(CRASH_DIR, &dir)
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .unwrap();
+
+            thread::sleep(Duration::from_millis(20 + rng.next(200)));
+            child.kill().unwrap();
+            child.wait().unwrap();
+
+            let violations = check_store(&store, &dir);
+            assert!(violations.is_empty(), "round {}: {:?}", round, violations);
+
+            // start the next round empty, so the store stays within the LMDB map size
+            store.clear().unwrap();
+            let _ = fs::remove_file(matched_log(&dir));
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
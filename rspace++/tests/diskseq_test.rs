@@ -1,281 +1,538 @@
 #[cfg(test)]
 mod tests {
-    use rspace_plus_plus::setup::Setup;
-
-    #[test]
-    fn diskseq_test_produce_match() {
-        let setup = Setup::new();
-        let diskseq = setup.diskseq;
-
-        let commit = Setup::create_commit(
-            vec![String::from("friends")],
-            vec![setup.city_match_case],
-            String::from("I am the continuation, for now..."),
-        );
-        let cres = diskseq.consume(commit, false);
-
-        let retrieve = Setup::create_retrieve(
-            String::from("friends"),
-            setup.alice.clone(),
-            Setup::get_city_field(setup.alice),
-        );
-        let pres = diskseq.produce(retrieve, false);
-
-        assert!(cres.is_none());
-        assert!(pres.is_some());
-        assert!(diskseq.is_empty());
-
-        let _ = diskseq.clear();
-    }
-
-    #[test]
-    fn diskseq_test_produce_no_match() {
-        let setup = Setup::new();
-        let diskseq = setup.diskseq;
-
-        let commit = Setup::create_commit(
-            vec![String::from("friends")],
-            vec![setup.city_match_case],
-            String::from("I am the continuation, for now..."),
-        );
-        let cres = diskseq.consume(commit, false);
-
-        let retrieve = Setup::create_retrieve(
-            String::from("friends"),
-            setup.carol.clone(),
-            Setup::get_city_field(setup.carol),
-        );
-        let pres = diskseq.produce(retrieve, false);
-
-        assert!(cres.is_none());
-        assert!(pres.is_none());
-        assert!(!diskseq.is_empty());
-
-        let _ = diskseq.clear();
+    use rspace_plus_plus::keyvaluestore::{Durability, SyncPolicy};
+    use std::time::Duration;
+
+    // The suite run against each disk backend: module, store, Setup field and database name prefix
+    macro_rules! diskseq_suite {
+        ($backend:ident, $store:ty, $diskseq:ident, $db:literal) => {
+            mod $backend {
+                use rspace_plus_plus::keyvaluestore::{Durability, KeyValueStore, Storage};
+                use rspace_plus_plus::rspace::RSpace;
+                use rspace_plus_plus::rtypes::rtypes::{Commit, Retrieve};
+                use rspace_plus_plus::setup::Setup;
+                use rspace_plus_plus::tuplespace::TupleSpace;
+                use std::path::Path;
+                use std::time::Duration;
+
+                #[test]
+                fn diskseq_test_produce_match() {
+                    let setup = Setup::new();
+                    let diskseq = setup.$diskseq;
+
+                    let commit = Setup::create_commit(
+                        vec![String::from("friends")],
+                        vec![setup.city_match_case],
+                        String::from("I am the continuation, for now..."),
+                    );
+                    let cres = diskseq.consume(commit, false).unwrap();
+
+                    let retrieve = Setup::create_retrieve(
+                        String::from("friends"),
+                        setup.alice.clone(),
+                        Setup::get_city_field(setup.alice),
+                    );
+                    let pres = diskseq.produce(retrieve, false).unwrap();
+
+                    assert!(cres.is_none());
+                    assert!(pres.is_some());
+                    assert!(diskseq.is_empty());
+
+                    let _ = diskseq.clear();
+                }
+
+                #[test]
+                fn diskseq_test_produce_no_match() {
+                    let setup = Setup::new();
+                    let diskseq = setup.$diskseq;
+
+                    let commit = Setup::create_commit(
+                        vec![String::from("friends")],
+                        vec![setup.city_match_case],
+                        String::from("I am the continuation, for now..."),
+                    );
+                    let cres = diskseq.consume(commit, false).unwrap();
+
+                    let retrieve = Setup::create_retrieve(
+                        String::from("friends"),
+                        setup.carol.clone(),
+                        Setup::get_city_field(setup.carol),
+                    );
+                    let pres = diskseq.produce(retrieve, false).unwrap();
+
+                    assert!(cres.is_none());
+                    assert!(pres.is_none());
+                    assert!(!diskseq.is_empty());
+
+                    let _ = diskseq.clear();
+                }
+
+                #[test]
+                fn diskseq_test_consume_match() {
+                    let setup = Setup::new();
+                    let diskseq = setup.$diskseq;
+
+                    let retrieve = Setup::create_retrieve(
+                        String::from("friends"),
+                        setup.bob.clone(),
+                        Setup::get_last_name_field(setup.bob),
+                    );
+                    let pres = diskseq.produce(retrieve, false).unwrap();
+
+                    let commit = Setup::create_commit(
+                        vec![String::from("friends")],
+                        vec![setup.name_match_case],
+                        String::from("I am the continuation, for now..."),
+                    );
+                    let cres = diskseq.consume(commit, false).unwrap();
+
+                    assert!(pres.is_none());
+                    assert!(cres.is_some());
+                    assert!(diskseq.is_empty());
+
+                    let _ = diskseq.clear();
+                }
+
+                #[test]
+                fn diskseq_test_multiple_channels_consume_match() {
+                    let setup = Setup::new();
+                    let diskseq = setup.$diskseq;
+
+                    let retrieve1 = Setup::create_retrieve(
+                        String::from("colleagues"),
+                        setup.dan.clone(),
+                        Setup::get_state_field(setup.dan),
+                    );
+                    let pres1 = diskseq.produce(retrieve1, false).unwrap();
+
+                    let retrieve2 = Setup::create_retrieve(
+                        String::from("friends"),
+                        setup.erin.clone(),
+                        Setup::get_state_field(setup.erin),
+                    );
+                    let pres2 = diskseq.produce(retrieve2, false).unwrap();
+
+                    let commit = Setup::create_commit(
+                        vec![String::from("friends"), String::from("colleagues")],
+                        vec![setup.state_match_case.clone(), setup.state_match_case],
+                        String::from("I am the continuation, for now..."),
+                    );
+                    let cres = diskseq.consume(commit, false).unwrap();
+
+                    assert!(pres1.is_none());
+                    assert!(pres2.is_none());
+                    assert!(cres.is_some());
+                    assert_eq!(cres.unwrap().len(), 2);
+                    assert!(diskseq.is_empty());
+
+                    let _ = diskseq.clear();
+                }
+
+                #[test]
+                fn diskseq_test_consume_persist() {
+                    let setup = Setup::new();
+                    let diskseq = setup.$diskseq;
+
+                    let commit = Setup::create_commit(
+                        vec![String::from("friends")],
+                        vec![setup.city_match_case],
+                        String::from("I am the continuation, for now..."),
+                    );
+                    let cres = diskseq.consume(commit, true).unwrap();
+
+                    assert!(cres.is_none());
+                    assert!(!diskseq.is_empty());
+
+                    let retrieve = Setup::create_retrieve(
+                        String::from("friends"),
+                        setup.alice.clone(),
+                        Setup::get_city_field(setup.alice),
+                    );
+                    let pres = diskseq.produce(retrieve, false).unwrap();
+
+                    assert!(pres.is_some());
+                    assert!(!diskseq.is_empty());
+
+                    let _ = diskseq.clear();
+                }
+
+                #[test]
+                fn diskseq_test_consume_persist_existing_matches() {
+                    let setup = Setup::new();
+                    let diskseq = setup.$diskseq;
+
+                    let retrieve1 = Setup::create_retrieve(
+                        String::from("friends"),
+                        setup.alice.clone(),
+                        Setup::get_city_field(setup.alice.clone()),
+                    );
+                    let _pres1 = diskseq.produce(retrieve1, false).unwrap();
+
+                    let retrieve2 = Setup::create_retrieve(
+                        String::from("friends"),
+                        setup.bob.clone(),
+                        Setup::get_city_field(setup.bob),
+                    );
+                    let _pres2 = diskseq.produce(retrieve2, false).unwrap();
+
+                    let commit1 = Setup::create_commit(
+                        vec![String::from("friends")],
+                        vec![setup.city_match_case.clone()],
+                        String::from("I am the continuation, for now..."),
+                    );
+                    let cres1 = diskseq.consume(commit1, true).unwrap();
+
+                    assert_eq!(cres1.unwrap().len(), 1);
+                    assert!(!diskseq.is_empty());
+
+                    let commit2 = Setup::create_commit(
+                        vec![String::from("friends")],
+                        vec![setup.city_match_case.clone()],
+                        String::from("I am the continuation, for now..."),
+                    );
+                    let cres2 = diskseq.consume(commit2, true).unwrap();
+
+                    assert_eq!(cres2.unwrap().len(), 1);
+                    assert!(diskseq.is_empty());
+
+                    let commit3 = Setup::create_commit(
+                        vec![String::from("friends")],
+                        vec![setup.city_match_case],
+                        String::from("I am the continuation, for now..."),
+                    );
+                    let cres3 = diskseq.consume(commit3, true).unwrap();
+
+                    assert!(cres3.is_none());
+                    assert!(!diskseq.is_empty());
+
+                    let retrieve3 = Setup::create_retrieve(
+                        String::from("friends"),
+                        setup.alice.clone(),
+                        Setup::get_city_field(setup.alice),
+                    );
+                    let pres3 = diskseq.produce(retrieve3, false).unwrap();
+
+                    assert!(pres3.is_some());
+                    assert!(!diskseq.is_empty());
+
+                    let _ = diskseq.clear();
+                }
+
+                #[test]
+                fn diskseq_test_produce_persist() {
+                    let setup = Setup::new();
+                    let diskseq = setup.$diskseq;
+
+                    let retrieve = Setup::create_retrieve(
+                        String::from("friends"),
+                        setup.alice.clone(),
+                        Setup::get_city_field(setup.alice),
+                    );
+                    let pres = diskseq.produce(retrieve, true).unwrap();
+
+                    assert!(pres.is_none());
+                    assert!(!diskseq.is_empty());
+
+                    let commit = Setup::create_commit(
+                        vec![String::from("friends")],
+                        vec![setup.city_match_case],
+                        String::from("I am the continuation, for now..."),
+                    );
+                    let cres = diskseq.consume(commit, false).unwrap();
+
+                    assert!(cres.is_some());
+                    assert_eq!(cres.unwrap().len(), 1);
+                    assert!(!diskseq.is_empty());
+
+                    let _ = diskseq.clear();
+                }
+
+                #[test]
+                fn diskseq_test_produce_persist_existing_matches() {
+                    let setup = Setup::new();
+                    let diskseq = setup.$diskseq;
+
+                    let commit1 = Setup::create_commit(
+                        vec![String::from("friends")],
+                        vec![setup.city_match_case.clone()],
+                        String::from("I am the continuation, for now..."),
+                    );
+                    let cres1 = diskseq.consume(commit1, false).unwrap();
+
+                    assert!(cres1.is_none());
+                    assert!(!diskseq.is_empty());
+
+                    let retrieve1 = Setup::create_retrieve(
+                        String::from("friends"),
+                        setup.alice.clone(),
+                        Setup::get_city_field(setup.alice.clone()),
+                    );
+                    let pres1 = diskseq.produce(retrieve1, true).unwrap();
+
+                    assert!(pres1.is_some());
+                    assert!((diskseq.is_empty()));
+
+                    let retrieve2 = Setup::create_retrieve(
+                        String::from("friends"),
+                        setup.alice.clone(),
+                        Setup::get_city_field(setup.alice),
+                    );
+                    let pres2 = diskseq.produce(retrieve2, true).unwrap();
+
+                    let commit2 = Setup::create_commit(
+                        vec![String::from("friends")],
+                        vec![setup.city_match_case],
+                        String::from("I am the continuation, for now..."),
+                    );
+                    let _cres2 = diskseq.consume(commit2, false).unwrap();
+
+                    assert!(pres2.is_none());
+                    assert!(!diskseq.is_empty());
+
+                    let _ = diskseq.clear();
+                }
+
+                #[test]
+                fn diskseq_test_consume_match_among_other_match_keys() {
+                    let setup = Setup::new();
+                    let diskseq = setup.$diskseq;
+
+                    let retrieve1 = Setup::create_retrieve(
+                        String::from("friends"),
+                        setup.alice.clone(),
+                        Setup::get_city_field(setup.alice),
+                    );
+                    let pres1 = diskseq.produce(retrieve1, false).unwrap();
+
+                    let retrieve2 = Setup::create_retrieve(
+                        String::from("friends"),
+                        setup.carol.clone(),
+                        Setup::get_city_field(setup.carol.clone()),
+                    );
+                    let pres2 = diskseq.produce(retrieve2, false).unwrap();
+
+                    let commit = Setup::create_commit(
+                        vec![String::from("friends")],
+                        vec![Setup::get_city_field(setup.carol.clone())],
+                        String::from("I am the continuation, for now..."),
+                    );
+                    let cres = diskseq.consume(commit, false).unwrap();
+
+                    assert!(pres1.is_none());
+                    assert!(pres2.is_none());
+                    assert!(cres.is_some());
+                    assert_eq!(cres.unwrap()[0].data, Some(setup.carol));
+                    assert!(!diskseq.is_empty());
+
+                    let _ = diskseq.clear();
+                }
+
+                #[test]
+                fn diskseq_test_no_sync_flush() {
+                    let setup = Setup::new();
+                    let path = Path::new("target").join(concat!($db, "NoSyncDB"));
+                    let diskseq: TupleSpace<$store, Retrieve, Commit> = TupleSpace::new(
+                        <$store>::open_with_durability(&path, Durability::NoSync).unwrap(),
+                    );
+                    let _ = diskseq.clear();
+
+                    let retrieve = Setup::create_retrieve(
+                        String::from("friends"),
+                        setup.alice.clone(),
+                        Setup::get_city_field(setup.alice),
+                    );
+                    let pres = diskseq.produce(retrieve, false).unwrap();
+                    assert!(diskseq.flush().is_ok());
+                    drop(diskseq);
+
+                    let reopened: TupleSpace<$store, Retrieve, Commit> =
+                        TupleSpace::new(<$store>::open(&path).unwrap());
+                    let commit = Setup::create_commit(
+                        vec![String::from("friends")],
+                        vec![setup.city_match_case],
+                        String::from("I am the continuation, for now..."),
+                    );
+                    let cres = reopened.consume(commit, false).unwrap();
+
+                    assert!(pres.is_none());
+                    assert!(cres.is_some());
+                    assert!(reopened.is_empty());
+                }
+
+                #[test]
+                fn diskseq_test_inspection() {
+                    let setup = Setup::new();
+                    let diskseq = setup.$diskseq;
+
+                    let retrieve = Setup::create_retrieve(
+                        String::from("diskseq-inspect-friends"),
+                        setup.carol.clone(),
+                        Setup::get_city_field(setup.carol.clone()),
+                    );
+                    let pres = diskseq.produce(retrieve, false).unwrap();
+
+                    let join = vec![
+                        String::from("diskseq-inspect-colleagues"),
+                        String::from("diskseq-inspect-family"),
+                    ];
+                    let commit = Setup::create_commit(
+                        join.clone(),
+                        vec![setup.state_match_case.clone(), setup.name_match_case],
+                        String::from("I am the continuation, for now..."),
+                    );
+                    let cres = diskseq.consume(commit, false).unwrap();
+
+                    assert!(pres.is_none());
+                    assert!(cres.is_none());
+
+                    let channels = diskseq.channels().unwrap();
+                    assert!(channels.contains(&join[0]));
+                    assert!(channels.contains(&join[1]));
+                    assert!(channels.contains(&String::from("diskseq-inspect-friends")));
+
+                    let data = diskseq.get_data("diskseq-inspect-friends").unwrap();
+                    assert_eq!(data.len(), 1);
+                    assert_eq!(data[0].data, Some(setup.carol));
+
+                    let waiting = diskseq.get_waiting_continuations(&join).unwrap();
+                    assert_eq!(waiting.len(), 2);
+                    assert!(diskseq
+                        .get_waiting_continuations(&join[..1])
+                        .unwrap()
+                        .is_empty());
+                    assert_eq!(diskseq.get_joins(&join[1]).unwrap(), vec![join.clone()]);
+
+                    let map = diskseq.to_map().unwrap();
+                    assert_eq!(map[&join].continuations.len(), 2);
+                    assert_eq!(
+                        map[&vec![String::from("diskseq-inspect-friends")]]
+                            .data
+                            .len(),
+                        1
+                    );
+
+                    let _ = diskseq.clear();
+                }
+
+                #[test]
+                fn diskseq_test_idle_group_commit_synced() {
+                    let path = Path::new("target").join(concat!($db, "IdleGroupCommitDB"));
+                    let durability = Durability::GroupCommit {
+                        ops: 1_000,
+                        interval: Duration::from_millis(50),
+                    };
+                    let store = <$store>::open_with_durability(&path, durability).unwrap();
+
+                    let mut txn = store.write_txn().unwrap();
+                    txn.put(b"friends", b"alice").unwrap();
+                    txn.commit().unwrap();
+                    assert_eq!(store.pending().unwrap(), 1);
+
+                    // no further commit comes, the interval alone syncs it
+                    std::thread::sleep(Duration::from_millis(500));
+                    assert_eq!(store.pending().unwrap(), 0);
+                    store.clear().unwrap();
+                }
+
+                #[test]
+                fn diskseq_test_rspace_durable_verbs() {
+                    let setup = Setup::new();
+                    let rspace = RSpace::<Retrieve, Commit, $store>::create_with_storage(
+                        <$store>::open(&Path::new("target").join(concat!($db, "SeqDB"))).unwrap(),
+                        <$store>::open(&Path::new("target").join(concat!($db, "ConcDB"))).unwrap(),
+                    )
+                    .unwrap();
+
+                    let commit = Setup::create_commit(
+                        vec![String::from("diskseq-rspace")],
+                        vec![setup.city_match_case],
+                        String::from("I am the continuation, for now..."),
+                    );
+                    let cres = rspace.put_once_durable_sequential(commit).unwrap();
+
+                    let retrieve = Setup::create_retrieve(
+                        String::from("diskseq-rspace"),
+                        setup.alice.clone(),
+                        Setup::get_city_field(setup.alice),
+                    );
+                    let pres = rspace.get_once_durable_sequential(retrieve).unwrap();
+
+                    assert!(cres.is_none());
+                    assert!(pres.is_some());
+                }
+
+                #[test]
+                fn diskseq_test_group_commit_flush() {
+                    let setup = Setup::new();
+                    let path = Path::new("target").join(concat!($db, "GroupCommitDB"));
+                    let durability = Durability::GroupCommit {
+                        ops: 2,
+                        interval: Duration::from_secs(3600),
+                    };
+                    let diskseq: TupleSpace<$store, Retrieve, Commit> =
+                        TupleSpace::new(<$store>::open_with_durability(&path, durability).unwrap());
+                    let _ = diskseq.clear();
+
+                    let retrieve = Setup::create_retrieve(
+                        String::from("friends"),
+                        setup.alice.clone(),
+                        Setup::get_city_field(setup.alice),
+                    );
+                    let pres = diskseq.produce(retrieve, false).unwrap();
+                    assert!(diskseq.flush().is_ok());
+
+                    let commit = Setup::create_commit(
+                        vec![String::from("friends")],
+                        vec![setup.city_match_case],
+                        String::from("I am the continuation, for now..."),
+                    );
+                    let cres = diskseq.consume(commit, false).unwrap();
+
+                    assert!(pres.is_none());
+                    assert!(cres.is_some());
+                    assert!(diskseq.is_empty());
+                }
+            }
+        };
     }
 
-    #[test]
-    fn diskseq_test_consume_match() {
-        let setup = Setup::new();
-        let diskseq = setup.diskseq;
-
-        let retrieve = Setup::create_retrieve(
-            String::from("friends"),
-            setup.bob.clone(),
-            Setup::get_last_name_field(setup.bob),
-        );
-        let pres = diskseq.produce(retrieve, false);
-
-        let commit = Setup::create_commit(
-            vec![String::from("friends")],
-            vec![setup.name_match_case],
-            String::from("I am the continuation, for now..."),
-        );
-        let cres = diskseq.consume(commit, false);
-
-        assert!(pres.is_none());
-        assert!(cres.is_some());
-        assert!(diskseq.is_empty());
-
-        let _ = diskseq.clear();
-    }
-
-    #[test]
-    fn diskseq_test_multiple_channels_consume_match() {
-        let setup = Setup::new();
-        let diskseq = setup.diskseq;
-
-        let retrieve1 = Setup::create_retrieve(
-            String::from("colleagues"),
-            setup.dan.clone(),
-            Setup::get_state_field(setup.dan),
-        );
-        let pres1 = diskseq.produce(retrieve1, false);
-
-        let retrieve2 = Setup::create_retrieve(
-            String::from("friends"),
-            setup.erin.clone(),
-            Setup::get_state_field(setup.erin),
-        );
-        let pres2 = diskseq.produce(retrieve2, false);
-
-        let commit = Setup::create_commit(
-            vec![String::from("friends"), String::from("colleagues")],
-            vec![setup.state_match_case.clone(), setup.state_match_case],
-            String::from("I am the continuation, for now..."),
-        );
-        let cres = diskseq.consume(commit, false);
-
-        assert!(pres1.is_none());
-        assert!(pres2.is_none());
-        assert!(cres.is_some());
-        assert_eq!(cres.unwrap().len(), 2);
-        assert!(diskseq.is_empty());
-
-        let _ = diskseq.clear();
-    }
-
-    #[test]
-    fn diskseq_test_consume_persist() {
-        let setup = Setup::new();
-        let diskseq = setup.diskseq;
-
-        let commit = Setup::create_commit(
-            vec![String::from("friends")],
-            vec![setup.city_match_case],
-            String::from("I am the continuation, for now..."),
-        );
-        let cres = diskseq.consume(commit, true);
-
-        assert!(cres.is_none());
-        assert!(!diskseq.is_empty());
-
-        let retrieve = Setup::create_retrieve(
-            String::from("friends"),
-            setup.alice.clone(),
-            Setup::get_city_field(setup.alice),
-        );
-        let pres = diskseq.produce(retrieve, false);
-
-        assert!(pres.is_some());
-        assert!(!diskseq.is_empty());
-
-        let _ = diskseq.clear();
-    }
-
-    #[test]
-    fn diskseq_test_consume_persist_existing_matches() {
-        let setup = Setup::new();
-        let diskseq = setup.diskseq;
-
-        let retrieve1 = Setup::create_retrieve(
-            String::from("friends"),
-            setup.alice.clone(),
-            Setup::get_city_field(setup.alice.clone()),
-        );
-        let _pres1 = diskseq.produce(retrieve1, false);
-
-        let retrieve2 = Setup::create_retrieve(
-            String::from("friends"),
-            setup.bob.clone(),
-            Setup::get_city_field(setup.bob),
-        );
-        let _pres2 = diskseq.produce(retrieve2, false);
-
-        let commit1 = Setup::create_commit(
-            vec![String::from("friends")],
-            vec![setup.city_match_case.clone()],
-            String::from("I am the continuation, for now..."),
-        );
-        let cres1 = diskseq.consume(commit1, true);
-
-        assert_eq!(cres1.unwrap().len(), 1);
-        assert!(!diskseq.is_empty());
-
-        let commit2 = Setup::create_commit(
-            vec![String::from("friends")],
-            vec![setup.city_match_case.clone()],
-            String::from("I am the continuation, for now..."),
-        );
-        let cres2 = diskseq.consume(commit2, true);
-
-        assert_eq!(cres2.unwrap().len(), 1);
-        assert!(diskseq.is_empty());
-
-        let commit3 = Setup::create_commit(
-            vec![String::from("friends")],
-            vec![setup.city_match_case],
-            String::from("I am the continuation, for now..."),
-        );
-        let cres3 = diskseq.consume(commit3, true);
-
-        assert!(cres3.is_none());
-        assert!(!diskseq.is_empty());
-
-        let retrieve3 = Setup::create_retrieve(
-            String::from("friends"),
-            setup.alice.clone(),
-            Setup::get_city_field(setup.alice),
-        );
-        let pres3 = diskseq.produce(retrieve3, false);
-
-        assert!(pres3.is_some());
-        assert!(!diskseq.is_empty());
-
-        let _ = diskseq.clear();
-    }
-
-    #[test]
-    fn diskseq_test_produce_persist() {
-        let setup = Setup::new();
-        let diskseq = setup.diskseq;
-
-        let retrieve = Setup::create_retrieve(
-            String::from("friends"),
-            setup.alice.clone(),
-            Setup::get_city_field(setup.alice),
-        );
-        let pres = diskseq.produce(retrieve, true);
-
-        assert!(pres.is_none());
-        assert!(!diskseq.is_empty());
-
-        let commit = Setup::create_commit(
-            vec![String::from("friends")],
-            vec![setup.city_match_case],
-            String::from("I am the continuation, for now..."),
-        );
-        let cres = diskseq.consume(commit, false);
-
-        assert!(cres.is_some());
-        assert_eq!(cres.unwrap().len(), 1);
-        assert!(!diskseq.is_empty());
-
-        let _ = diskseq.clear();
-    }
+    diskseq_suite!(
+        lmdb,
+        rspace_plus_plus::lmdbstore::LmdbStore,
+        diskseq,
+        "Disk"
+    );
+    #[cfg(feature = "redb")]
+    diskseq_suite!(
+        redb,
+        rspace_plus_plus::redbstore::RedbStore,
+        redbseq,
+        "Redb"
+    );
 
     #[test]
-    fn diskseq_test_produce_persist_existing_matches() {
-        let setup = Setup::new();
-        let diskseq = setup.diskseq;
-
-        let commit1 = Setup::create_commit(
-            vec![String::from("friends")],
-            vec![setup.city_match_case.clone()],
-            String::from("I am the continuation, for now..."),
-        );
-        let cres1 = diskseq.consume(commit1, false);
-
-        assert!(cres1.is_none());
-        assert!(!diskseq.is_empty());
-
-        let retrieve1 = Setup::create_retrieve(
-            String::from("friends"),
-            setup.alice.clone(),
-            Setup::get_city_field(setup.alice.clone()),
-        );
-        let pres1 = diskseq.produce(retrieve1, true);
-
-        assert!(pres1.is_some());
-        assert!((diskseq.is_empty()));
-
-        let retrieve2 = Setup::create_retrieve(
-            String::from("friends"),
-            setup.alice.clone(),
-            Setup::get_city_field(setup.alice),
-        );
-        let pres2 = diskseq.produce(retrieve2, true);
-
-        let commit2 = Setup::create_commit(
-            vec![String::from("friends")],
-            vec![setup.city_match_case],
-            String::from("I am the continuation, for now..."),
-        );
-        let _cres2 = diskseq.consume(commit2, false);
-
-        assert!(pres2.is_none());
-        assert!(!diskseq.is_empty());
-
-        let _ = diskseq.clear();
+    fn diskseq_test_sync_policy() {
+        let group = SyncPolicy::new(Durability::GroupCommit {
+            ops: 3,
+            interval: Duration::from_secs(3600),
+        });
+        let due: Vec<bool> = (0..7).map(|_| group.commit_is_due().unwrap()).collect();
+        assert_eq!(due, vec![false, false, true, false, false, true, false]);
+        assert_eq!(group.pending().unwrap(), 1);
+
+        let elapsed = SyncPolicy::new(Durability::GroupCommit {
+            ops: 1_000,
+            interval: Duration::ZERO,
+        });
+        assert!(elapsed.commit_is_due().unwrap());
+
+        let sync = SyncPolicy::new(Durability::Sync);
+        assert!(sync.commit_is_due().unwrap());
+
+        let no_sync = SyncPolicy::new(Durability::NoSync);
+        assert!(!no_sync.commit_is_due().unwrap());
+        assert!(!no_sync.commit_is_due().unwrap());
+        no_sync.synced().unwrap();
+        assert_eq!(no_sync.pending().unwrap(), 0);
     }
 }
@@ -0,0 +1,199 @@
+#[cfg(test)]
+mod tests {
+    use rspace_plus_plus::dump::{DumpEntry, DumpLine};
+    use rspace_plus_plus::keyvaluestore::Storage;
+    use rspace_plus_plus::lmdbstore::LmdbStore;
+    use rspace_plus_plus::memorystore::MemStore;
+    use rspace_plus_plus::rtypes::rtypes::{Commit, Retrieve};
+    use rspace_plus_plus::setup::Setup;
+    use rspace_plus_plus::tuplespace::TupleSpace;
+    use std::fs;
+    use std::io::Cursor;
+    use std::path::{Path, PathBuf};
+    use std::process::Command;
+
+    fn mem_space() -> TupleSpace<MemStore, Retrieve, Commit> {
+        TupleSpace::new(MemStore::new())
+    }
+
+    fn dir(name: &str) -> PathBuf {
+        let dir =
+            Path::new("target")
+                .join("DumpDB")
+                .join(format!("{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    // A persistent datum and a join waiting on two channels
+    fn fill<S: Storage>(space: &TupleSpace<S, Retrieve, Commit>) {
+        let setup = Setup::new();
+        space
+            .produce(
+                Setup::create_retrieve(
+                    String::from("dump-friends"),
+                    setup.alice.clone(),
+                    Setup::get_city_field(setup.alice),
+                ),
+                true,
+            )
+            .unwrap();
+        space
+            .consume(
+                Setup::create_commit(
+                    vec![String::from("dump-colleagues"), String::from("dump-family")],
+                    vec![String::from("Idaho"), String::from("Nevada")],
+                    String::from("I am the continuation, for now..."),
+                ),
+                false,
+            )
+            .unwrap();
+    }
+
+    fn dump_lines<S: Storage>(space: &TupleSpace<S, Retrieve, Commit>) -> String {
+        let mut output = vec![];
+        space.dump(&mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn dump_test_round_trip() {
+        let space = mem_space();
+        fill(&space);
+
+        let mut output = vec![];
+        assert_eq!(space.dump(&mut output).unwrap(), 3);
+        let lines: Vec<DumpLine> = String::from_utf8(output.clone())
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let kinds: Vec<(&str, bool, bool)> = lines
+            .iter()
+            .map(|line| {
+                (
+                    line.channel.as_str(),
+                    line.persistent,
+                    matches!(line.entry, DumpEntry::Data(_)),
+                )
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("dump-colleagues", false, false),
+                ("dump-family", false, false),
+                ("dump-friends", true, true),
+            ]
+        );
+
+        let text = String::from_utf8(output.clone()).unwrap();
+        let first: serde_json::Value = serde_json::from_str(text.lines().next().unwrap()).unwrap();
+        assert_eq!(first["kind"], "continuation");
+        assert_eq!(first["payload"]["pattern"], "Idaho");
+
+        // the restored store has the same entries, indexes and root
+        let restored = mem_space();
+        assert_eq!(restored.restore(Cursor::new(&output)).unwrap(), 3);
+        assert_eq!(restored.root().unwrap(), space.root().unwrap());
+        assert_eq!(restored.to_map().unwrap(), space.to_map().unwrap());
+        assert_eq!(
+            restored.get_joins("dump-family").unwrap(),
+            space.get_joins("dump-family").unwrap()
+        );
+    }
+
+    #[test]
+    fn dump_test_restore_rejects() {
+        let space = mem_space();
+        fill(&space);
+        let dump = dump_lines(&space);
+
+        assert!(space.restore(Cursor::new(&dump)).is_err());
+
+        let fresh = mem_space();
+        let broken = format!("{}{{\"channel\":", dump);
+        let err = fresh.restore(Cursor::new(&broken)).unwrap_err();
+        assert!(err.to_string().starts_with("line 4:"), "{}", err);
+        // nothing of a bad dump is kept
+        assert_eq!(fresh.entry_count().unwrap(), 0);
+
+        let flipped = dump.replacen("\"persistent\":true", "\"persistent\":false", 1);
+        assert!(fresh.restore(Cursor::new(&flipped)).is_err());
+
+        let moved = dump.replacen("\"channel\":\"dump-family\"", "\"channel\":\"dump-x\"", 1);
+        assert!(fresh.restore(Cursor::new(&moved)).is_err());
+
+        assert_eq!(fresh.restore(Cursor::new("\n\n")).unwrap(), 0);
+    }
+
+    // heed hands this process back the writable environment for dir, so only reading is checked
+    #[test]
+    fn dump_test_read_only_lmdb() {
+        let dir = dir("read-only");
+        let expected = {
+            let space = TupleSpace::<_, Retrieve, Commit>::new(LmdbStore::open(&dir).unwrap());
+            fill(&space);
+            dump_lines(&space)
+        };
+
+        let store = LmdbStore::open_read_only(&dir).unwrap();
+        let space = TupleSpace::<_, Retrieve, Commit>::new(store);
+        assert_eq!(dump_lines(&space), expected);
+
+        assert!(LmdbStore::open_read_only(&dir.join("missing")).is_err());
+        drop(space);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn dump_test_binary() {
+        let source = dir("source");
+        let target = dir("target");
+        let file = dir("dump.jsonl");
+        {
+            let space = TupleSpace::<_, Retrieve, Commit>::new(LmdbStore::open(&source).unwrap());
+            fill(&space);
+        }
+
+        let cli = env!("CARGO_BIN_EXE_rspace-cli");
+        let dumped = Command::new(cli)
+            .args(["dump".as_ref(), source.as_os_str(), "--out".as_ref()])
+            .arg(&file)
+            .output()
+            .unwrap();
+        assert!(dumped.status.success());
+        assert_eq!(
+            String::from_utf8(dumped.stderr).unwrap(),
+            "dumped 3 entries\n"
+        );
+
+        let restored = Command::new(cli)
+            .arg("restore")
+            .arg(&target)
+            .stdin(fs::File::open(&file).unwrap())
+            .output()
+            .unwrap();
+        assert!(restored.status.success());
+
+        let again = Command::new(cli).arg("dump").arg(&target).output().unwrap();
+        assert!(again.status.success());
+        assert_eq!(again.stdout, fs::read(&file).unwrap());
+
+        // restoring over something already there is refused
+        let twice = Command::new(cli)
+            .args(["restore".as_ref(), target.as_os_str(), "--in".as_ref()])
+            .arg(&file)
+            .output()
+            .unwrap();
+        assert!(!twice.status.success());
+
+        let usage = Command::new(cli).args(["dump"]).output().unwrap();
+        assert!(!usage.status.success());
+
+        for path in [source, target, file] {
+            let _ = fs::remove_dir_all(&path);
+            let _ = fs::remove_file(&path);
+        }
+    }
+}
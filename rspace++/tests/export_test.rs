@@ -0,0 +1,233 @@
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+    use rspace_plus_plus::importer::empty_root;
+    use rspace_plus_plus::keyvaluestore::Storage;
+    use rspace_plus_plus::lmdbstore::LmdbStore;
+    use rspace_plus_plus::memorystore::MemStore;
+    use rspace_plus_plus::rspace::RSpace;
+    use rspace_plus_plus::rtypes::rtypes::{Commit, Retrieve, Store};
+    use rspace_plus_plus::setup::Setup;
+    use rspace_plus_plus::tuplespace::TupleSpace;
+    use std::path::Path;
+
+    fn mem_space() -> TupleSpace<MemStore, Retrieve, Commit> {
+        TupleSpace::new(MemStore::new())
+    }
+
+    // Data on two channels, a waiting continuation and a join
+    fn fill<S: Storage>(space: &TupleSpace<S, Retrieve, Commit>) {
+        let setup = Setup::new();
+
+        for (channel, entry) in [
+            ("export-friends", setup.alice.clone()),
+            ("export-friends", setup.bob.clone()),
+            ("export-colleagues", setup.carol.clone()),
+            ("export-colleagues", setup.dan.clone()),
+            ("export-friends", setup.erin.clone()),
+        ] {
+            let retrieve = Setup::create_retrieve(
+                String::from(channel),
+                entry.clone(),
+                Setup::get_city_field(entry),
+            );
+            space.produce(retrieve, false).unwrap();
+        }
+
+        let commit = Setup::create_commit(
+            vec![String::from("export-family")],
+            vec![setup.city_match_case.clone()],
+            String::from("I am the continuation, for now..."),
+        );
+        space.consume(commit, true).unwrap();
+
+        let join = Setup::create_commit(
+            vec![
+                String::from("export-family"),
+                String::from("export-cousins"),
+            ],
+            vec![setup.state_match_case, setup.name_match_case],
+            String::from("I am the join, for now..."),
+        );
+        space.consume(join, false).unwrap();
+    }
+
+    #[test]
+    fn export_test_round_trip() {
+        let source = mem_space();
+        fill(&source);
+
+        for chunk_size in [1, 2, 3, 5, 1_000] {
+            let exporter = source.exporter(chunk_size).unwrap();
+            assert_eq!(exporter.root(), source.root().unwrap());
+
+            let target = mem_space();
+            let mut importer = target.importer(exporter.root()).unwrap();
+            for chunk in exporter.chunks() {
+                importer.import_chunk(&chunk.unwrap()).unwrap();
+            }
+            importer.finish().unwrap();
+
+            assert_eq!(target.root().unwrap(), source.root().unwrap());
+            assert_eq!(target.to_map().unwrap(), source.to_map().unwrap());
+        }
+    }
+
+    #[test]
+    fn export_test_deterministic() {
+        let first = mem_space();
+        let second = mem_space();
+        fill(&first);
+        fill(&second);
+
+        let first: Vec<Vec<u8>> = first
+            .exporter(3)
+            .unwrap()
+            .chunks()
+            .map(|chunk| chunk.unwrap().encode_to_vec())
+            .collect();
+        let second: Vec<Vec<u8>> = second
+            .exporter(3)
+            .unwrap()
+            .chunks()
+            .map(|chunk| chunk.unwrap().encode_to_vec())
+            .collect();
+
+        assert!(!first.is_empty());
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn export_test_lmdb_to_memory() {
+        let source: TupleSpace<LmdbStore, Retrieve, Commit> =
+            TupleSpace::new(LmdbStore::open(&Path::new("target").join("ExportLmdbDB")).unwrap());
+        let _ = source.clear();
+        fill(&source);
+
+        let exporter = source.exporter(4).unwrap();
+        let target = mem_space();
+        let mut importer = target.importer(exporter.root()).unwrap();
+        for chunk in exporter.chunks() {
+            importer.import_chunk(&chunk.unwrap()).unwrap();
+        }
+        importer.finish().unwrap();
+
+        assert_eq!(target.root().unwrap(), source.root().unwrap());
+        assert_eq!(target.to_map().unwrap(), source.to_map().unwrap());
+
+        let _ = source.clear();
+    }
+
+    #[test]
+    fn export_test_serves_its_snapshot() {
+        let source: TupleSpace<LmdbStore, Retrieve, Commit> = TupleSpace::new(
+            LmdbStore::open(&Path::new("target").join("ExportSnapshotDB")).unwrap(),
+        );
+        let _ = source.clear();
+        fill(&source);
+        let expected = mem_space();
+        fill(&expected);
+
+        let exporter = source.exporter(2).unwrap();
+        let _ = source.clear();
+
+        let target = mem_space();
+        let mut importer = target.importer(exporter.root()).unwrap();
+        for chunk in exporter.chunks() {
+            importer.import_chunk(&chunk.unwrap()).unwrap();
+        }
+        importer.finish().unwrap();
+
+        assert!(source.is_empty());
+        assert_eq!(target.to_map().unwrap(), expected.to_map().unwrap());
+    }
+
+    #[test]
+    fn export_test_rejects_bad_chunks() {
+        let source = mem_space();
+        fill(&source);
+        let exporter = source.exporter(2).unwrap();
+        let root = exporter.root();
+
+        let mut tampered = exporter.chunk(1).unwrap().unwrap();
+        tampered.pairs[0].value[0] ^= 1;
+        let mut short_proof = exporter.chunk(1).unwrap().unwrap();
+        short_proof.before.pop();
+        let mut moved = exporter.chunk(1).unwrap().unwrap();
+        moved.start += 1;
+
+        let target = mem_space();
+        let mut importer = target.importer(root).unwrap();
+        assert!(importer
+            .import_chunk(&exporter.chunk(1).unwrap().unwrap())
+            .is_err());
+        importer
+            .import_chunk(&exporter.chunk(0).unwrap().unwrap())
+            .unwrap();
+        assert!(importer.import_chunk(&tampered).is_err());
+        assert!(importer.import_chunk(&short_proof).is_err());
+        assert!(importer.import_chunk(&moved).is_err());
+        importer
+            .import_chunk(&exporter.chunk(1).unwrap().unwrap())
+            .unwrap();
+
+        // stopping early does not reproduce the root, and nothing of it is kept
+        assert!(importer.finish().is_err());
+        assert!(target.is_empty());
+
+        let mut importer = target.importer(root).unwrap();
+        for chunk in exporter.chunks() {
+            importer.import_chunk(&chunk.unwrap()).unwrap();
+        }
+        importer.finish().unwrap();
+        assert_eq!(target.root().unwrap(), root);
+    }
+
+    #[test]
+    fn export_test_empty_store() {
+        let source = mem_space();
+        let exporter = source.exporter(3).unwrap();
+
+        assert_eq!(exporter.chunk_count(), 0);
+        assert_eq!(exporter.root(), empty_root());
+
+        let target = mem_space();
+        target.importer(exporter.root()).unwrap().finish().unwrap();
+        assert!(target.is_empty());
+    }
+
+    #[test]
+    fn export_test_rspace_import() {
+        let setup = Setup::new();
+        let source = RSpace::<Retrieve, Commit>::create().unwrap();
+        let target = RSpace::<Retrieve, Commit>::create().unwrap();
+
+        let retrieve = Setup::create_retrieve(
+            String::from("export-rspace"),
+            setup.alice.clone(),
+            Setup::get_city_field(setup.alice),
+        );
+        source.get_once_non_durable_sequential(retrieve).unwrap();
+
+        let exporter = source.exporter(Store::MemSeq, 1).unwrap();
+        target
+            .import(
+                Store::MemSeq,
+                exporter.root(),
+                exporter.chunks().map(Result::unwrap),
+            )
+            .unwrap();
+
+        assert_eq!(
+            target.root(Store::MemSeq).unwrap(),
+            source.root(Store::MemSeq).unwrap()
+        );
+        assert_eq!(
+            target
+                .get_data(Store::MemSeq, "export-rspace")
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,343 @@
+#[cfg(test)]
+mod tests {
+    use rspace_plus_plus::gc::{GarbageCollector, GcProgress};
+    use rspace_plus_plus::history::HistoryRepository;
+    use rspace_plus_plus::importer::empty_root;
+    use rspace_plus_plus::keyvaluestore::{KeyValueRead, KeyValueStore, Storage, Visitor};
+    use rspace_plus_plus::lmdbstore::LmdbStore;
+    use rspace_plus_plus::memorystore::MemStore;
+    use rspace_plus_plus::rspace::RSpace;
+    use rspace_plus_plus::rtypes::rtypes::{Commit, Entry, Retrieve, Store};
+    use rspace_plus_plus::setup::Setup;
+    use rspace_plus_plus::tuplespace::TupleSpace;
+    use std::error::Error;
+    use std::path::Path;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn mem_space() -> TupleSpace<MemStore, Retrieve, Commit> {
+        TupleSpace::new(MemStore::new())
+    }
+
+    fn produce<S: Storage>(space: &TupleSpace<S, Retrieve, Commit>, channel: &str, entry: Entry) {
+        let retrieve = Setup::create_retrieve(
+            String::from(channel),
+            entry.clone(),
+            Setup::get_city_field(entry),
+        );
+        space.produce(retrieve, false).unwrap();
+    }
+
+    // A store that counts the puts committed to it
+    struct Counted {
+        store: MemStore,
+        puts: Arc<AtomicUsize>,
+    }
+
+    struct CountedTxn<'a> {
+        txn: <MemStore as Storage>::WriteTxn<'a>,
+        puts: &'a AtomicUsize,
+        pending: usize,
+    }
+
+    impl KeyValueRead for CountedTxn<'_> {
+        fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+            self.txn.get(key)
+        }
+
+        fn iterate_prefix(&self, prefix: &[u8], f: &mut Visitor) -> Result<(), Box<dyn Error>> {
+            self.txn.iterate_prefix(prefix, f)
+        }
+    }
+
+    impl KeyValueStore for CountedTxn<'_> {
+        fn put(&mut self, key: &[u8], value: &[u8]) -> Result<(), Box<dyn Error>> {
+            self.pending += 1;
+            self.txn.put(key, value)
+        }
+
+        fn delete(&mut self, key: &[u8]) -> Result<(), Box<dyn Error>> {
+            self.txn.delete(key)
+        }
+
+        fn commit(self) -> Result<(), Box<dyn Error>> {
+            self.txn.commit()?;
+            self.puts.fetch_add(self.pending, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    impl Storage for Counted {
+        type ReadTxn<'a> = <MemStore as Storage>::ReadTxn<'a>;
+        type WriteTxn<'a> = CountedTxn<'a>;
+
+        fn read_txn(&self) -> Result<Self::ReadTxn<'_>, Box<dyn Error>> {
+            self.store.read_txn()
+        }
+
+        fn write_txn(&self) -> Result<Self::WriteTxn<'_>, Box<dyn Error>> {
+            Ok(CountedTxn {
+                txn: self.store.write_txn()?,
+                puts: &self.puts,
+                pending: 0,
+            })
+        }
+
+        fn is_empty(&self) -> Result<bool, Box<dyn Error>> {
+            self.store.is_empty()
+        }
+
+        fn clear(&self) -> Result<(), Box<dyn Error>> {
+            self.store.clear()
+        }
+
+        fn flush(&self) -> Result<(), Box<dyn Error>> {
+            self.store.flush()
+        }
+    }
+
+    // Four checkpoints, each with one more datum than the last
+    fn checkpoints<S: Storage, H: Storage>(
+        space: &TupleSpace<S, Retrieve, Commit>,
+        history: &HistoryRepository<H>,
+    ) -> Vec<[u8; 32]> {
+        let setup = Setup::new();
+        let mut roots = vec![];
+
+        for (channel, entry) in [
+            ("gc-friends", setup.alice),
+            ("gc-friends", setup.bob),
+            ("gc-colleagues", setup.carol),
+            ("gc-colleagues", setup.dan),
+        ] {
+            produce(space, channel, entry);
+            roots.push(space.checkpoint(history).unwrap());
+        }
+
+        roots
+    }
+
+    #[test]
+    fn gc_test_checkpoint_and_reset() {
+        let space = mem_space();
+        let history = HistoryRepository::new(MemStore::new());
+        let roots = checkpoints(&space, &history);
+        let latest = space.to_map().unwrap();
+
+        assert_eq!(history.roots().unwrap(), roots);
+        assert_eq!(roots[3], space.root().unwrap());
+
+        space.reset(&history, &roots[1]).unwrap();
+        assert_eq!(space.root().unwrap(), roots[1]);
+        assert_eq!(space.get_data("gc-friends").unwrap().len(), 2);
+        assert!(space.get_data("gc-colleagues").unwrap().is_empty());
+
+        space.reset(&history, &roots[3]).unwrap();
+        assert_eq!(space.to_map().unwrap(), latest);
+
+        space.reset(&history, &empty_root()).unwrap();
+        assert!(space.is_empty());
+        assert!(space.reset(&history, &[7u8; 32]).is_err());
+    }
+
+    #[test]
+    fn gc_test_checkpoint_follows_changes() {
+        let space = mem_space();
+        let history = HistoryRepository::new(MemStore::new());
+        let keys: Vec<&[u8]> = vec![b"", b"a", b"ab", b"abc", b"b", b"\x00", b"\xff", b"a\x80"];
+        let mut seed = 7u64;
+
+        for round in 0..40 {
+            let mut txn = space.write_txn().unwrap();
+            for _ in 0..3 {
+                seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                let key = keys[(seed >> 33) as usize % keys.len()];
+                match seed >> 62 {
+                    0 => txn.delete(key).unwrap(),
+                    _ => txn
+                        .put(key, &seed.to_be_bytes()[..(seed >> 61) as usize])
+                        .unwrap(),
+                }
+            }
+            txn.commit().unwrap();
+
+            let root = space.checkpoint(&history).unwrap();
+            assert_eq!(root, space.root().unwrap(), "round {}", round);
+        }
+    }
+
+    #[test]
+    fn gc_test_checkpoint_updates_changed_paths() {
+        let puts = Arc::new(AtomicUsize::new(0));
+        let history = HistoryRepository::new(Counted {
+            store: MemStore::new(),
+            puts: puts.clone(),
+        });
+        let space = mem_space();
+        let setup = Setup::new();
+
+        for n in 0..32 {
+            produce(&space, &format!("gc-paths-{}", n), setup.alice.clone());
+        }
+        space.checkpoint(&history).unwrap();
+        let full = puts.swap(0, Ordering::SeqCst);
+
+        produce(&space, "gc-paths-7", setup.bob.clone());
+        let root = space.checkpoint(&history).unwrap();
+        assert_eq!(root, space.root().unwrap());
+        assert!(puts.load(Ordering::SeqCst) * 4 < full);
+
+        // a reset updates from the root it went to, a clear from nothing
+        space.reset(&history, &root).unwrap();
+        produce(&space, "gc-paths-8", setup.carol.clone());
+        puts.store(0, Ordering::SeqCst);
+        assert_eq!(space.checkpoint(&history).unwrap(), space.root().unwrap());
+        assert!(puts.load(Ordering::SeqCst) * 4 < full);
+
+        space.clear().unwrap();
+        produce(&space, "gc-paths-9", setup.dan.clone());
+        let root = space.checkpoint(&history).unwrap();
+        assert_eq!(root, space.root().unwrap());
+        space.reset(&history, &root).unwrap();
+        assert_eq!(space.get_data("gc-paths-9").unwrap().len(), 1);
+        assert!(space.get_data("gc-paths-7").unwrap().is_empty());
+    }
+
+    #[test]
+    fn gc_test_keeps_live_roots() {
+        let space = mem_space();
+        let history = HistoryRepository::new(MemStore::new());
+        let roots = checkpoints(&space, &history);
+
+        let reclaimed = GarbageCollector::new(&history, &roots[2..])
+            .unwrap()
+            .run(1_000)
+            .unwrap();
+
+        assert!(reclaimed > 0);
+        assert_eq!(history.roots().unwrap(), roots[2..].to_vec());
+        for root in &roots[..2] {
+            assert!(!history.contains(root).unwrap());
+            assert!(space.reset(&history, root).is_err());
+        }
+        for root in &roots[2..] {
+            space.reset(&history, root).unwrap();
+            assert_eq!(space.root().unwrap(), *root);
+        }
+
+        // nothing more to take
+        let reclaimed = GarbageCollector::new(&history, &roots[2..])
+            .unwrap()
+            .run(1_000)
+            .unwrap();
+        assert_eq!(reclaimed, 0);
+    }
+
+    #[test]
+    fn gc_test_no_live_roots() {
+        let space = mem_space();
+        let history = HistoryRepository::new(MemStore::new());
+        checkpoints(&space, &history);
+
+        GarbageCollector::new(&history, &[])
+            .unwrap()
+            .run(3)
+            .unwrap();
+        assert!(history.is_empty().unwrap());
+    }
+
+    #[test]
+    fn gc_test_incremental_with_checkpoint_in_between() {
+        let space = mem_space();
+        let history = HistoryRepository::new(MemStore::new());
+        let roots = checkpoints(&space, &history);
+
+        let mut gc = GarbageCollector::new(&history, &roots[3..]).unwrap();
+        let mut steps = 0;
+        let mut checkpointed = None;
+        loop {
+            steps += 1;
+            match gc.step(1).unwrap() {
+                GcProgress::Done { .. } => break,
+                GcProgress::Sweeping { .. } if checkpointed.is_none() => {
+                    // the second root is not live, checkpointing it again revives its nodes
+                    space.reset(&history, &roots[1]).unwrap();
+                    checkpointed = Some(space.checkpoint(&history).unwrap());
+                }
+                _ => {}
+            }
+        }
+        drop(gc);
+
+        assert!(steps > 4);
+        assert_eq!(checkpointed, Some(roots[1]));
+        assert_eq!(history.roots().unwrap(), vec![roots[3], roots[1]]);
+        space.reset(&history, &roots[3]).unwrap();
+        space.reset(&history, &roots[1]).unwrap();
+        assert_eq!(space.get_data("gc-friends").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn gc_test_rejects_bad_runs() {
+        let space = mem_space();
+        let history = HistoryRepository::new(MemStore::new());
+        let roots = checkpoints(&space, &history);
+
+        assert!(GarbageCollector::new(&history, &[[7u8; 32]]).is_err());
+
+        let mut gc = GarbageCollector::new(&history, &roots).unwrap();
+        assert!(gc.step(0).is_err());
+        assert!(GarbageCollector::new(&history, &roots).is_err());
+        drop(gc);
+
+        assert_eq!(
+            GarbageCollector::new(&history, &roots)
+                .unwrap()
+                .run(2)
+                .unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn gc_test_lmdb_history() {
+        let store = LmdbStore::open(&Path::new("target").join("GcHistoryDB")).unwrap();
+        store.clear().unwrap();
+        let history = HistoryRepository::new(store);
+        let setup = Setup::new();
+        let rspace = RSpace::<Retrieve, Commit>::create().unwrap();
+
+        let retrieve = Setup::create_retrieve(
+            String::from("gc-rspace"),
+            setup.alice.clone(),
+            Setup::get_city_field(setup.alice),
+        );
+        rspace.get_once_non_durable_sequential(retrieve).unwrap();
+        let first = rspace.checkpoint(Store::MemSeq, &history).unwrap();
+        rspace
+            .reset(Store::MemSeq, &history, &empty_root())
+            .unwrap();
+        let second = rspace.checkpoint(Store::MemSeq, &history).unwrap();
+
+        GarbageCollector::new(&history, &[first])
+            .unwrap()
+            .run(10)
+            .unwrap();
+        assert!(history.roots().unwrap().iter().all(|root| *root == first));
+        assert_eq!(second, empty_root());
+
+        rspace.reset(Store::MemSeq, &history, &first).unwrap();
+        assert_eq!(
+            rspace.get_data(Store::MemSeq, "gc-rspace").unwrap().len(),
+            1
+        );
+
+        GarbageCollector::new(&history, &[])
+            .unwrap()
+            .run(10)
+            .unwrap();
+        assert!(history.is_empty().unwrap());
+    }
+}
@@ -0,0 +1,237 @@
+#[cfg(all(test, feature = "grpc"))]
+mod tests {
+    use rspace_plus_plus::grpc::RSpaceServer;
+    use rspace_plus_plus::history::HistoryRepository;
+    use rspace_plus_plus::memorystore::MemStore;
+    use rspace_plus_plus::rtypes::rtypes::r_space_service_client::RSpaceServiceClient;
+    use rspace_plus_plus::rtypes::rtypes::{
+        CheckpointRequest, Commit, ConsumeRequest, InstallRequest, PeekRequest, ProduceRequest,
+        ResetRequest,
+    };
+    use rspace_plus_plus::setup::Setup;
+    use rspace_plus_plus::tuplespace::TupleSpace;
+    use tokio::net::TcpListener;
+    use tokio_stream::wrappers::TcpListenerStream;
+    use tokio_stream::StreamExt;
+    use tonic::transport::{Channel, Server};
+    use tonic::Code;
+
+    // An in-memory space served on a free local port, and a client for it
+    async fn serve() -> RSpaceServiceClient<Channel> {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = RSpaceServer::new(
+            TupleSpace::new(MemStore::new()),
+            HistoryRepository::new(MemStore::new()),
+        );
+        tokio::spawn(
+            Server::builder()
+                .add_service(server.into_service())
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+
+        RSpaceServiceClient::connect(format!("http://{}", addr))
+            .await
+            .unwrap()
+    }
+
+    fn produce(channel: &str, persistent: bool) -> ProduceRequest {
+        let setup = Setup::new();
+        ProduceRequest {
+            retrieve: Some(Setup::create_retrieve(
+                String::from(channel),
+                setup.alice.clone(),
+                Setup::get_city_field(setup.alice),
+            )),
+            persistent,
+        }
+    }
+
+    fn commit(channel: &str) -> Commit {
+        Setup::create_commit(
+            vec![String::from(channel)],
+            vec![Setup::new().city_match_case],
+            String::from("I am the continuation, for now..."),
+        )
+    }
+
+    #[tokio::test]
+    async fn grpc_test_produce_consume_peek() {
+        let mut client = serve().await;
+
+        let stored = client
+            .consume(ConsumeRequest {
+                commit: Some(commit("grpc-friends")),
+                persistent: false,
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(stored.results.is_empty());
+
+        let peek = client
+            .peek(PeekRequest {
+                channel: String::from("grpc-friends"),
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(peek.continuations.len(), 1);
+        assert!(peek.data.is_empty());
+
+        let matched = client
+            .produce(produce("grpc-friends", false))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(
+            matched.result.unwrap().continuation,
+            Some("I am the continuation, for now...".into())
+        );
+
+        let missing = client.produce(ProduceRequest::default()).await.unwrap_err();
+        assert_eq!(missing.code(), Code::InvalidArgument);
+        let mut uneven = commit("grpc-friends");
+        uneven.patterns.clear();
+        let uneven = client
+            .consume(ConsumeRequest {
+                commit: Some(uneven),
+                persistent: false,
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(uneven.code(), Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn grpc_test_install() {
+        let mut client = serve().await;
+
+        client
+            .install(InstallRequest {
+                commit: Some(commit("grpc-system")),
+            })
+            .await
+            .unwrap();
+        // an installed continuation stays for every produce
+        for _ in 0..2 {
+            let matched = client
+                .produce(produce("grpc-system", false))
+                .await
+                .unwrap()
+                .into_inner();
+            assert!(matched.result.is_some());
+        }
+
+        client.produce(produce("grpc-data", true)).await.unwrap();
+        let refused = client
+            .install(InstallRequest {
+                commit: Some(commit("grpc-data")),
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(refused.code(), Code::FailedPrecondition);
+        let peek = client
+            .peek(PeekRequest {
+                channel: String::from("grpc-data"),
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!((peek.data.len(), peek.continuations.len()), (1, 0));
+    }
+
+    #[tokio::test]
+    async fn grpc_test_checkpoint_and_reset() {
+        let mut client = serve().await;
+
+        let empty = client
+            .checkpoint(CheckpointRequest {})
+            .await
+            .unwrap()
+            .into_inner()
+            .root;
+        client.produce(produce("grpc-family", false)).await.unwrap();
+        let full = client
+            .checkpoint(CheckpointRequest {})
+            .await
+            .unwrap()
+            .into_inner()
+            .root;
+        assert_ne!(empty, full);
+
+        client.reset(ResetRequest { root: empty }).await.unwrap();
+        let peek = client
+            .peek(PeekRequest {
+                channel: String::from("grpc-family"),
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(peek.data.is_empty());
+
+        let short = client
+            .reset(ResetRequest { root: vec![1, 2] })
+            .await
+            .unwrap_err();
+        assert_eq!(short.code(), Code::InvalidArgument);
+        let unknown = client
+            .reset(ResetRequest { root: vec![7; 32] })
+            .await
+            .unwrap_err();
+        assert_eq!(unknown.code(), Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn grpc_test_streams() {
+        let mut client = serve().await;
+
+        let mut second = commit("grpc-colleagues");
+        second.continuation = Some("and I am the next one".into());
+        let consumes =
+            tokio_stream::iter(
+                [commit("grpc-colleagues"), second].map(|commit| ConsumeRequest {
+                    commit: Some(commit),
+                    persistent: false,
+                }),
+            );
+        let stored: Vec<_> = client
+            .consume_stream(consumes)
+            .await
+            .unwrap()
+            .into_inner()
+            .collect()
+            .await;
+        assert_eq!(stored.len(), 2);
+        assert!(stored.iter().all(|response| response
+            .as_ref()
+            .is_ok_and(|response| response.results.is_empty())));
+
+        // two produces fire the two continuations, the third is stored, then the bad one ends the stream
+        let produces = tokio_stream::iter(vec![
+            produce("grpc-colleagues", false),
+            produce("grpc-colleagues", false),
+            produce("grpc-colleagues", false),
+            ProduceRequest::default(),
+            produce("grpc-colleagues", false),
+        ]);
+        let responses: Vec<_> = client
+            .produce_stream(produces)
+            .await
+            .unwrap()
+            .into_inner()
+            .collect()
+            .await;
+        let matched: Vec<bool> = responses
+            .iter()
+            .take(3)
+            .map(|response| response.as_ref().unwrap().result.is_some())
+            .collect();
+        assert_eq!(matched, vec![true, true, false]);
+        assert_eq!(responses.len(), 4);
+        assert_eq!(
+            responses[3].as_ref().unwrap_err().code(),
+            Code::InvalidArgument
+        );
+    }
+}
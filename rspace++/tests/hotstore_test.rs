@@ -0,0 +1,131 @@
+#[cfg(test)]
+mod tests {
+    use rspace_plus_plus::hotstore::HotStoreTrieAction;
+    use rspace_plus_plus::setup::Setup;
+
+    #[test]
+    fn hotstore_test_reads_fall_back_to_history() {
+        let setup = Setup::new();
+        let rspace = setup.rspace;
+
+        let retrieve = Setup::create_retrieve(
+            String::from("hotstore-fallback"),
+            setup.dan.clone(),
+            Setup::get_state_field(setup.dan.clone()),
+        );
+        let commit = Setup::create_commit(
+            vec![String::from("hotstore-fallback")],
+            vec![setup.state_match_case],
+            String::from("I am the continuation, for now..."),
+        );
+        let pres = rspace.get_once_durable_sequential(retrieve).unwrap();
+        assert!(pres.is_none());
+
+        let hot_store = rspace.hot_store_diskseq();
+        let data = hot_store.get_data("hotstore-fallback").unwrap();
+        assert_eq!(data.len(), 1);
+        assert_eq!(data[0].data, Some(setup.dan));
+
+        let cres = hot_store.consume(&commit, false).unwrap();
+        assert!(cres.is_some());
+        assert!(hot_store.get_data("hotstore-fallback").unwrap().is_empty());
+
+        // the history store is untouched until checkpoint
+        let other = rspace.hot_store_diskseq();
+        assert_eq!(other.get_data("hotstore-fallback").unwrap().len(), 1);
+
+        let actions = hot_store.checkpoint().unwrap();
+        assert_eq!(
+            actions,
+            vec![HotStoreTrieAction::TrieDeleteProduce {
+                channel: String::from("hotstore-fallback")
+            }]
+        );
+
+        let after = rspace.hot_store_diskseq();
+        assert!(after.get_data("hotstore-fallback").unwrap().is_empty());
+    }
+
+    #[test]
+    fn hotstore_test_changes_track_joins() {
+        let setup = Setup::new();
+        let rspace = setup.rspace;
+
+        let channels = vec![
+            String::from("hotstore-join-a"),
+            String::from("hotstore-join-b"),
+        ];
+        let commit = Setup::create_commit(
+            channels.clone(),
+            vec![setup.city_match_case.clone(), setup.city_match_case],
+            String::from("I am the continuation, for now..."),
+        );
+        let retrieve = Setup::create_retrieve(
+            String::from("hotstore-join-a"),
+            setup.alice.clone(),
+            Setup::get_city_field(setup.alice),
+        );
+
+        let hot_store = rspace.hot_store_diskconc();
+        let cres = hot_store.consume(&commit, false).unwrap();
+        assert!(cres.is_none());
+        assert_eq!(
+            hot_store.get_joins("hotstore-join-b").unwrap(),
+            vec![channels.clone()]
+        );
+
+        let actions = hot_store.changes().unwrap();
+        assert_eq!(actions.len(), 4);
+        assert!(actions.contains(&HotStoreTrieAction::TrieInsertJoins {
+            channel: String::from("hotstore-join-a"),
+            joins: vec![channels.clone()],
+        }));
+
+        let pres = hot_store.produce(&retrieve, false).unwrap();
+        assert!(pres.is_some());
+
+        // the join fired with both its copies, so both channels are back to what history holds
+        assert!(hot_store.changes().unwrap().is_empty());
+
+        hot_store.clear().unwrap();
+        assert!(hot_store.changes().unwrap().is_empty());
+    }
+
+    #[test]
+    fn hotstore_test_checkpoint_persists() {
+        let setup = Setup::new();
+        let rspace = setup.rspace;
+
+        let retrieve = Setup::create_retrieve(
+            String::from("hotstore-checkpoint"),
+            setup.erin.clone(),
+            Setup::get_state_field(setup.erin.clone()),
+        );
+        let commit = Setup::create_commit(
+            vec![String::from("hotstore-checkpoint")],
+            vec![setup.state_match_case],
+            String::from("I am the continuation, for now..."),
+        );
+
+        let hot_store = rspace.hot_store_diskseq();
+        let pres = hot_store.produce(&retrieve, false).unwrap();
+        assert!(pres.is_none());
+
+        let actions = hot_store.checkpoint().unwrap();
+        assert_eq!(actions.len(), 1);
+        assert!(matches!(
+            &actions[0],
+            HotStoreTrieAction::TrieInsertProduce { channel, data }
+                if channel == "hotstore-checkpoint" && data.len() == 1
+        ));
+        assert!(hot_store.changes().unwrap().is_empty());
+
+        // a produce on disk now finds the checkpointed datum
+        let cres = rspace.put_once_durable_sequential(commit).unwrap();
+        assert_eq!(cres.unwrap()[0].data, Some(setup.erin));
+        assert!(hot_store
+            .get_data("hotstore-checkpoint")
+            .unwrap()
+            .is_empty());
+    }
+}
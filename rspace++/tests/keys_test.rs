@@ -0,0 +1,221 @@
+#[cfg(test)]
+mod tests {
+    use rspace_plus_plus::keys::{self, Key, Kind};
+    use rspace_plus_plus::keyvaluestore::{KeyValueRead, Storage};
+    use rspace_plus_plus::lmdbstore::LmdbStore;
+    use rspace_plus_plus::memorystore::MemStore;
+    use rspace_plus_plus::rtypes::rtypes::{Commit, Retrieve};
+    use rspace_plus_plus::setup::Setup;
+    use rspace_plus_plus::tuplespace::TupleSpace;
+    use std::path::{Path, PathBuf};
+
+    // Names that ran into each other's keys when keys were built by joining strings with '-'
+    const CHANNELS: [&str; 8] = [
+        "",
+        "-",
+        "friends",
+        "friends-",
+        "friends-data",
+        "friends-data-1",
+        "friends-data-x",
+        "friends-continuation-2",
+    ];
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = Path::new("target")
+            .join(name)
+            .join(std::process::id().to_string());
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn commit(channel: &str, pattern: &str) -> Commit {
+        Setup::create_commit(
+            vec![channel.to_string()],
+            vec![pattern.to_string()],
+            String::from("I am the continuation, for now..."),
+        )
+    }
+
+    fn collisions<S: Storage>(space: &TupleSpace<S, Retrieve, Commit>) {
+        let setup = Setup::new();
+        for channel in CHANNELS {
+            space
+                .produce(
+                    Setup::create_retrieve(
+                        channel.to_string(),
+                        setup.alice.clone(),
+                        Setup::get_city_field(setup.alice.clone()),
+                    ),
+                    false,
+                )
+                .unwrap();
+        }
+
+        let mut sorted = CHANNELS.map(String::from).to_vec();
+        sorted.sort();
+        assert_eq!(space.channels().unwrap(), sorted);
+        assert_eq!(space.entry_count().unwrap(), CHANNELS.len());
+        for channel in CHANNELS {
+            assert_eq!(space.get_data(channel).unwrap().len(), 1, "{:?}", channel);
+        }
+
+        // taking the datum at friends leaves every other channel alone
+        assert!(space
+            .consume(commit("friends", &setup.city_match_case), false)
+            .unwrap()
+            .is_some());
+        assert!(space.get_data("friends").unwrap().is_empty());
+        assert!(!space.channels().unwrap().contains(&String::from("friends")));
+        for channel in CHANNELS.iter().filter(|channel| **channel != "friends") {
+            assert_eq!(space.get_data(channel).unwrap().len(), 1, "{:?}", channel);
+        }
+
+        // a match key that starts another one only finds its own
+        space
+            .produce(
+                Setup::create_retrieve(
+                    String::from("friends"),
+                    setup.bob.clone(),
+                    String::from("Crystal-Lake"),
+                ),
+                false,
+            )
+            .unwrap();
+        space
+            .produce(
+                Setup::create_retrieve(
+                    String::from("friends"),
+                    setup.carol.clone(),
+                    String::from("Crystal"),
+                ),
+                false,
+            )
+            .unwrap();
+        let results = space
+            .consume(commit("friends", "Crystal"), false)
+            .unwrap()
+            .unwrap();
+        assert_eq!(results[0].data, Some(setup.carol));
+        assert!(space
+            .consume(commit("friends", "Crystal"), false)
+            .unwrap()
+            .is_none());
+        assert!(space
+            .produce(
+                Setup::create_retrieve(
+                    String::from("friends"),
+                    setup.alice,
+                    String::from("Crystal"),
+                ),
+                false,
+            )
+            .unwrap()
+            .is_some());
+
+        // emptied channels keep no keys behind
+        assert!(space
+            .consume(commit("friends", "Crystal-Lake"), false)
+            .unwrap()
+            .is_some());
+        for channel in CHANNELS.iter().filter(|channel| **channel != "friends") {
+            assert!(space
+                .consume(commit(channel, &setup.city_match_case), false)
+                .unwrap()
+                .is_some());
+        }
+        assert!(space.is_empty());
+        assert_eq!(space.verify(false).unwrap().problems, vec![]);
+    }
+
+    #[test]
+    fn keys_test_memory_channel_collisions() {
+        collisions(&TupleSpace::new(MemStore::new()));
+    }
+
+    #[test]
+    fn keys_test_lmdb_channel_collisions() {
+        let dir = scratch("KeysDB");
+        collisions(&TupleSpace::new(LmdbStore::open(&dir).unwrap()));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(feature = "redb")]
+    #[test]
+    fn keys_test_redb_channel_collisions() {
+        use rspace_plus_plus::redbstore::RedbStore;
+
+        let dir = scratch("KeysRedb");
+        collisions(&TupleSpace::new(RedbStore::open(&dir).unwrap()));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn keys_test_parse() {
+        let friends = keys::channel_hash("friends");
+        assert_eq!(
+            keys::parse(&keys::channel_key(&friends)),
+            Some(Key::Channel(friends))
+        );
+        assert_eq!(
+            keys::parse(&keys::entry_key(Kind::Continuation, &friends, 7)),
+            Some(Key::Entry(Kind::Continuation, friends, 7))
+        );
+        let index_key = keys::index_key(Kind::Data, &friends, "Crystal-Lake", 7);
+        assert_eq!(
+            keys::parse(&index_key),
+            Some(Key::Index(
+                Kind::Data,
+                friends,
+                String::from("Crystal-Lake"),
+                7
+            ))
+        );
+        assert!(!index_key.starts_with(&keys::index_prefix(Kind::Data, &friends, "Crystal")));
+
+        // truncated, trailing bytes, unknown tags
+        assert_eq!(keys::parse(&index_key[..index_key.len() - 1]), None);
+        assert_eq!(keys::parse(&[index_key.as_slice(), &[0]].concat()), None);
+        assert_eq!(keys::parse(b"channel-friends-data-7"), None);
+        assert_eq!(keys::parse(&[]), None);
+    }
+
+    #[test]
+    fn keys_test_identical_entries_stored_apart() {
+        let setup = Setup::new();
+        let space = TupleSpace::<MemStore, Retrieve, Commit>::new(MemStore::new());
+        let retrieve = Setup::create_retrieve(
+            String::from("friends"),
+            setup.alice.clone(),
+            Setup::get_city_field(setup.alice),
+        );
+        for _ in 0..3 {
+            assert!(space.produce(retrieve.clone(), false).unwrap().is_none());
+        }
+        assert_eq!(space.get_data("friends").unwrap().len(), 3);
+
+        // the copies share the hash part of their ids
+        let txn = space.write_txn().unwrap();
+        let mut ids = vec![];
+        txn.iterate_prefix(
+            &keys::entry_prefix(Kind::Data, &keys::channel_hash("friends")),
+            &mut |key, value| {
+                if let Some(Key::Entry(_, _, id)) = keys::parse(key) {
+                    assert_eq!(keys::entry_id(value, keys::entry_copy(id)), id);
+                    ids.push(id >> 16);
+                }
+                Ok(true)
+            },
+        )
+        .unwrap();
+        drop(txn);
+        ids.dedup();
+        assert_eq!(ids.len(), 1);
+
+        for _ in 0..3 {
+            let commit = commit("friends", &setup.city_match_case);
+            assert!(space.consume(commit, false).unwrap().is_some());
+        }
+        assert!(space.is_empty());
+    }
+}
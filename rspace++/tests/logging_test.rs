@@ -0,0 +1,154 @@
+#[cfg(test)]
+mod tests {
+    use rspace_plus_plus::history::to_hex;
+    use rspace_plus_plus::keys::{self, Kind};
+    use rspace_plus_plus::logging;
+    use rspace_plus_plus::memorystore::MemStore;
+    use rspace_plus_plus::rtypes::rtypes::{Commit, Retrieve};
+    use rspace_plus_plus::setup::Setup;
+    use rspace_plus_plus::tuplespace::TupleSpace;
+    use rspace_plus_plus::{space_set_log_callback, space_set_log_filter};
+    use std::ffi::{c_char, CStr, CString};
+    use std::sync::{Mutex, MutexGuard};
+
+    static LINES: Mutex<Vec<(i32, String, String)>> = Mutex::new(vec![]);
+
+    // The subscriber and callback are global, so these tests take turns
+    static TURN: Mutex<()> = Mutex::new(());
+
+    extern "C" fn collect(level: i32, target: *const c_char, message: *const c_char) {
+        let target = unsafe { CStr::from_ptr(target) }
+            .to_string_lossy()
+            .to_string();
+        let message = unsafe { CStr::from_ptr(message) }
+            .to_string_lossy()
+            .to_string();
+        LINES.lock().unwrap().push((level, target, message));
+    }
+
+    fn collecting(filter: &str) -> MutexGuard<'static, ()> {
+        let turn = TURN.lock().unwrap_or_else(|err| err.into_inner());
+        logging::set_callback(Some(collect)).unwrap();
+        logging::set_filter(filter).unwrap();
+        LINES.lock().unwrap().clear();
+        turn
+    }
+
+    fn lines() -> Vec<(i32, String, String)> {
+        LINES.lock().unwrap().clone()
+    }
+
+    fn space() -> TupleSpace<MemStore, Retrieve, Commit> {
+        TupleSpace::new(MemStore::new())
+    }
+
+    fn retrieve(channel: &str) -> Retrieve {
+        let setup = Setup::new();
+        Setup::create_retrieve(
+            String::from(channel),
+            setup.alice.clone(),
+            Setup::get_city_field(setup.alice),
+        )
+    }
+
+    fn commit(channels: Vec<&str>, patterns: Vec<String>) -> Commit {
+        Setup::create_commit(
+            channels.into_iter().map(String::from).collect(),
+            patterns,
+            String::from("I am the continuation, for now..."),
+        )
+    }
+
+    #[test]
+    fn logging_test_spans_carry_channel_match_and_key() {
+        let _turn = collecting("rspace_plus_plus=debug");
+        let space = space();
+        let setup = Setup::new();
+
+        space.produce(retrieve("logging-friends"), false).unwrap();
+        space
+            .consume(
+                commit(vec!["logging-friends"], vec![setup.city_match_case]),
+                false,
+            )
+            .unwrap();
+
+        let lines = lines();
+        let messages: Vec<&str> = lines.iter().map(|(_, _, line)| line.as_str()).collect();
+        assert!(lines
+            .iter()
+            .all(|(level, target, _)| *level == 4 && target == "rspace_plus_plus::tuplespace"));
+        let key = to_hex(&keys::entry_prefix(
+            Kind::Data,
+            &keys::channel_hash("logging-friends"),
+        ));
+        assert!(messages[0].starts_with(&format!(
+            "produce{{channel=logging-friends persistent=false}}: stored datum \
+             channel=logging-friends key={}",
+            key
+        )));
+        assert_eq!(
+            messages[1],
+            "produce{channel=logging-friends persistent=false}: produced matched=false"
+        );
+        assert!(messages[2].starts_with(&format!(
+            "consume{{channels=[\"logging-friends\"] persistent=false}}: matched datum \
+             channel=logging-friends key={}",
+            key
+        )));
+        assert_eq!(
+            messages[3],
+            "consume{channels=[\"logging-friends\"] persistent=false}: consumed matched=true"
+        );
+        assert_eq!(messages.len(), 4);
+    }
+
+    #[test]
+    fn logging_test_filter_changes_at_runtime() {
+        let _turn = collecting("error");
+        let space = space();
+
+        space
+            .produce(retrieve("logging-colleagues"), false)
+            .unwrap();
+        space
+            .consume(commit(vec!["logging-colleagues"], vec![]), false)
+            .unwrap_err();
+        assert!(lines().is_empty());
+
+        logging::set_filter("warn").unwrap();
+        space
+            .consume(commit(vec!["logging-colleagues"], vec![]), false)
+            .unwrap_err();
+        assert_eq!(
+            lines(),
+            vec![(
+                2,
+                String::from("rspace_plus_plus::tuplespace"),
+                // the consume span is debug, so filtered out too
+                String::from("channel and pattern vectors are not equal length!")
+            )]
+        );
+
+        assert!(logging::set_filter("rspace_plus_plus=loud").is_err());
+    }
+
+    #[test]
+    fn logging_test_ffi() {
+        let _turn = collecting("error");
+        let space = space();
+
+        let filter = CString::new("rspace_plus_plus::tuplespace=debug").unwrap();
+        assert!(unsafe { space_set_log_filter(filter.as_ptr()) });
+        space.produce(retrieve("logging-family"), true).unwrap();
+        assert_eq!(lines().len(), 2);
+
+        // back to stderr
+        assert!(space_set_log_callback(None));
+        space.produce(retrieve("logging-family"), true).unwrap();
+        assert_eq!(lines().len(), 2);
+
+        let filter = CString::new("rspace_plus_plus=[").unwrap();
+        assert!(!unsafe { space_set_log_filter(filter.as_ptr()) });
+    }
+}
@@ -0,0 +1,1011 @@
+#[cfg(test)]
+mod tests {
+    use rspace_plus_plus::matcher::{self, FreeMap};
+    use rspace_plus_plus::rtypes::rtypes::{
+        connective::ConnectiveInstance, expr::ExprInstance, g_unforgeable::UnfInstance,
+        var::VarInstance, var::WildcardMsg, BindPattern, Bundle, Connective, ConnectiveBody, EList,
+        EMap, EMinusMinus, EPercentPercent, EPlusPlus, ESet, EVar, Expr, GPrivate, GUnforgeable,
+        KeyValuePair, ListParWithRandom, Match, MatchCase, New, Par, Receive, ReceiveBind, Send,
+        Var, VarRef,
+    };
+
+    /*
+    The cases of MatchTest.scala in rholang/interpreter/matcher/. The builders do
+    what the implicits in models/rholang/implicits.scala do, and terms are given
+    in the order the Scala sorter puts them
+    */
+
+    // A Scala BitSet's bytes
+    fn bits(indices: &[i32]) -> Vec<u8> {
+        let mut bytes = vec![];
+        for index in indices {
+            let (byte, bit) = (*index as usize / 8, index % 8);
+            if bytes.len() <= byte {
+                bytes.resize(byte + 1, 0);
+            }
+            bytes[byte] |= 1 << bit;
+        }
+        bytes
+    }
+
+    fn or(a: &[u8], b: &[u8]) -> Vec<u8> {
+        (0..a.len().max(b.len()))
+            .map(|i| a.get(i).unwrap_or(&0) | b.get(i).unwrap_or(&0))
+            .collect()
+    }
+
+    fn expr(instance: ExprInstance) -> Expr {
+        Expr {
+            expr_instance: Some(instance),
+        }
+    }
+
+    fn int(value: i64) -> Expr {
+        expr(ExprInstance::GInt(value))
+    }
+
+    fn string(value: &str) -> Expr {
+        expr(ExprInstance::GString(value.to_string()))
+    }
+
+    fn var(instance: VarInstance) -> Var {
+        Var {
+            var_instance: Some(instance),
+        }
+    }
+
+    fn free_var(level: i32) -> Var {
+        var(VarInstance::FreeVar(level))
+    }
+
+    fn wildcard_var() -> Var {
+        var(VarInstance::Wildcard(WildcardMsg {}))
+    }
+
+    fn evar(v: Var) -> Expr {
+        expr(ExprInstance::EVarBody(EVar { v: Some(v) }))
+    }
+
+    fn free(level: i32) -> Expr {
+        evar(free_var(level))
+    }
+
+    fn wildcard() -> Expr {
+        evar(wildcard_var())
+    }
+
+    fn bound(level: i32) -> Expr {
+        evar(var(VarInstance::BoundVar(level)))
+    }
+
+    // ExprLocallyFree in implicits.scala, for the exprs used here
+    fn expr_locally_free(e: &Expr, depth: i32) -> Vec<u8> {
+        match e.expr_instance.as_ref().unwrap() {
+            ExprInstance::EListBody(list) => list.locally_free.clone(),
+            ExprInstance::ESetBody(set) => set.locally_free.clone(),
+            ExprInstance::EMapBody(map) => map.locally_free.clone(),
+            ExprInstance::EVarBody(EVar { v }) => match v.as_ref().unwrap().var_instance {
+                Some(VarInstance::BoundVar(level)) if depth == 0 => bits(&[level]),
+                _ => vec![],
+            },
+            _ => vec![],
+        }
+    }
+
+    fn expr_connective_used(e: &Expr) -> bool {
+        let used = |p: &Option<Par>| p.as_ref().unwrap().connective_used;
+        match e.expr_instance.as_ref().unwrap() {
+            ExprInstance::EListBody(list) => list.connective_used,
+            ExprInstance::ESetBody(set) => set.connective_used,
+            ExprInstance::EMapBody(map) => map.connective_used,
+            ExprInstance::EVarBody(EVar { v }) => !matches!(
+                v.as_ref().unwrap().var_instance,
+                Some(VarInstance::BoundVar(_))
+            ),
+            ExprInstance::EPlusPlusBody(EPlusPlus { p1, p2 })
+            | ExprInstance::EPercentPercentBody(EPercentPercent { p1, p2 })
+            | ExprInstance::EMinusMinusBody(EMinusMinus { p1, p2 }) => used(p1) || used(p2),
+            _ => false,
+        }
+    }
+
+    fn par(e: Expr) -> Par {
+        prepend(Par::default(), e, 0)
+    }
+
+    fn prepend(p: Par, e: Expr, depth: i32) -> Par {
+        let mut exprs = vec![e.clone()];
+        exprs.extend(p.exprs);
+        Par {
+            exprs,
+            locally_free: or(&p.locally_free, &expr_locally_free(&e, depth)),
+            connective_used: p.connective_used || expr_connective_used(&e),
+            ..p
+        }
+    }
+
+    fn prepend_send(p: Par, s: Send) -> Par {
+        let mut sends = vec![s.clone()];
+        sends.extend(p.sends);
+        Par {
+            sends,
+            locally_free: or(&p.locally_free, &s.locally_free),
+            connective_used: p.connective_used || s.connective_used,
+            ..p
+        }
+    }
+
+    fn send_par(s: Send) -> Par {
+        prepend_send(Par::default(), s)
+    }
+
+    fn receive_par(r: Receive) -> Par {
+        Par {
+            locally_free: r.locally_free.clone(),
+            connective_used: r.connective_used,
+            receives: vec![r],
+            ..Default::default()
+        }
+    }
+
+    fn new_par(n: New) -> Par {
+        Par {
+            locally_free: n.locally_free.clone(),
+            connective_used: n.p.as_ref().unwrap().connective_used,
+            news: vec![n],
+            ..Default::default()
+        }
+    }
+
+    fn match_par(m: Match) -> Par {
+        Par {
+            locally_free: m.locally_free.clone(),
+            connective_used: m.connective_used,
+            matches: vec![m],
+            ..Default::default()
+        }
+    }
+
+    fn bundle_par(b: Bundle) -> Par {
+        Par {
+            locally_free: b.body.as_ref().unwrap().locally_free.clone(),
+            bundles: vec![b],
+            ..Default::default()
+        }
+    }
+
+    fn connective(instance: ConnectiveInstance) -> Connective {
+        Connective {
+            connective_instance: Some(instance),
+        }
+    }
+
+    fn conn_par(connectives: Vec<Connective>) -> Par {
+        Par {
+            connective_used: connectives.iter().any(|c| {
+                !matches!(
+                    c.connective_instance,
+                    Some(ConnectiveInstance::VarRefBody(_)) | None
+                )
+            }),
+            connectives,
+            ..Default::default()
+        }
+    }
+
+    fn and(ps: Vec<Par>) -> Connective {
+        connective(ConnectiveInstance::ConnAndBody(ConnectiveBody { ps }))
+    }
+
+    fn or_(ps: Vec<Par>) -> Connective {
+        connective(ConnectiveInstance::ConnOrBody(ConnectiveBody { ps }))
+    }
+
+    fn not(p: Par) -> Connective {
+        connective(ConnectiveInstance::ConnNotBody(p))
+    }
+
+    // GPrivateBuilder(id)
+    fn private(id: &str) -> Par {
+        Par {
+            unforgeables: vec![GUnforgeable {
+                unf_instance: Some(UnfInstance::GPrivateBody(GPrivate {
+                    id: id.as_bytes().to_vec(),
+                })),
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn send(chan: Par, data: Vec<Par>, connective_used: bool) -> Send {
+        Send {
+            chan: Some(chan),
+            data,
+            persistent: false,
+            locally_free: vec![],
+            connective_used,
+        }
+    }
+
+    fn list(ps: Vec<Par>, connective_used: bool) -> Expr {
+        expr(ExprInstance::EListBody(EList {
+            ps,
+            connective_used,
+            ..Default::default()
+        }))
+    }
+
+    fn list_rem(ps: Vec<Par>, remainder: Var) -> Expr {
+        expr(ExprInstance::EListBody(EList {
+            ps,
+            connective_used: true,
+            remainder: Some(remainder),
+            ..Default::default()
+        }))
+    }
+
+    // ParSet(ps, remainder), with ps sorted
+    fn set(ps: Vec<Par>, remainder: Option<Var>) -> Expr {
+        expr(ExprInstance::ESetBody(ESet {
+            connective_used: ps.iter().any(|p| p.connective_used) || remainder.is_some(),
+            locally_free: ps.iter().fold(vec![], |acc, p| or(&acc, &p.locally_free)),
+            ps,
+            remainder,
+        }))
+    }
+
+    fn map(kvs: Vec<(Par, Par)>, remainder: Option<Var>) -> Expr {
+        let kvs: Vec<KeyValuePair> = kvs
+            .into_iter()
+            .map(|(key, value)| KeyValuePair {
+                key: Some(key),
+                value: Some(value),
+            })
+            .collect();
+        expr(ExprInstance::EMapBody(EMap {
+            connective_used: kvs.iter().any(|kv| {
+                kv.key.as_ref().unwrap().connective_used
+                    || kv.value.as_ref().unwrap().connective_used
+            }) || remainder.is_some(),
+            kvs,
+            locally_free: vec![],
+            remainder,
+        }))
+    }
+
+    fn ints(values: &[i64]) -> Vec<Par> {
+        values.iter().map(|value| par(int(*value))).collect()
+    }
+
+    fn pairs(values: &[(i64, i64)]) -> Vec<(Par, Par)> {
+        values
+            .iter()
+            .map(|(key, value)| (par(int(*key)), par(int(*value))))
+            .collect()
+    }
+
+    fn erased(mut par: Par) -> Par {
+        matcher::erase_locally_free(&mut par);
+        par
+    }
+
+    fn assert_spatial_match(target: &Par, pattern: &Par, expected: Option<Vec<(i32, Par)>>) {
+        let result = matcher::spatial_match(target, pattern)
+            .map(|captures| captures.into_iter().map(|(l, p)| (l, erased(p))).collect());
+        let expected: Option<FreeMap> =
+            expected.map(|captures| captures.into_iter().map(|(l, p)| (l, erased(p))).collect());
+        assert_eq!(result, expected, "matching {:?} to {:?}", pattern, target);
+    }
+
+    #[test]
+    fn matcher_test_ground_with_var() {
+        assert_spatial_match(&par(int(7)), &par(free(0)), Some(vec![(0, par(int(7)))]));
+    }
+
+    #[test]
+    fn matcher_test_bound_var() {
+        // bound var with free var fails, with a wildcard succeeds
+        assert_spatial_match(&par(bound(0)), &par(free(0)), None);
+        assert_spatial_match(&par(bound(0)), &par(wildcard()), Some(vec![]));
+    }
+
+    #[test]
+    fn matcher_test_lists_of_grounds_with_lists_of_vars() {
+        let target = par(list(
+            vec![par(string("add")), par(int(7)), par(int(8))],
+            false,
+        ));
+        let pattern = par(list(
+            vec![par(string("add")), par(free(0)), par(free(1))],
+            true,
+        ));
+        assert_spatial_match(
+            &target,
+            &pattern,
+            Some(vec![(0, par(int(7))), (1, par(int(8)))]),
+        );
+    }
+
+    #[test]
+    fn matcher_test_two_lists_in_parallel() {
+        let target = prepend(
+            par(list(ints(&[7, 9]), false)),
+            list(ints(&[7, 8]), false),
+            0,
+        );
+        let pattern = prepend(
+            par(list(vec![par(free(0)), par(int(9))], true)),
+            list(vec![par(int(7)), par(free(1))], true),
+            1,
+        );
+        assert_spatial_match(
+            &target,
+            &pattern,
+            Some(vec![(0, par(int(7))), (1, par(int(8)))]),
+        );
+    }
+
+    #[test]
+    fn matcher_test_huge_list_of_targets() {
+        let target = Par {
+            exprs: vec![int(1); 1000],
+            ..Default::default()
+        };
+        assert_spatial_match(&target, &par(free(0)), Some(vec![(0, target.clone())]));
+    }
+
+    #[test]
+    fn matcher_test_send() {
+        let target = send_par(send(private("unforgeable"), ints(&[7, 8]), false));
+
+        // the channel
+        let pattern = send_par(send(par(free(0)), vec![par(wildcard()), par(int(8))], true));
+        assert_spatial_match(&target, &pattern, Some(vec![(0, private("unforgeable"))]));
+
+        // the body
+        let pattern = send_par(send(par(wildcard()), vec![par(free(0)), par(int(8))], true));
+        assert_spatial_match(&target, &pattern, Some(vec![(0, par(int(7)))]));
+
+        // arity has to match
+        let target = send_par(send(private("unforgeable"), ints(&[7, 8, 9]), false));
+        let pattern = send_par(send(
+            par(wildcard()),
+            vec![par(free(0)), par(wildcard())],
+            true,
+        ));
+        assert_spatial_match(&target, &pattern, None);
+    }
+
+    #[test]
+    fn matcher_test_extras() {
+        let target = prepend(prepend(par(int(9)), int(8), 0), int(7), 0);
+        let rest = prepend(par(int(9)), int(7), 0);
+
+        // with a free variable
+        let pattern = prepend(par(free(0)), int(8), 1);
+        assert_spatial_match(&target, &pattern, Some(vec![(0, rest.clone())]));
+
+        // with a wildcard
+        let pattern = prepend(par(wildcard()), int(8), 1);
+        assert_spatial_match(&target, &pattern, Some(vec![]));
+
+        // with both, the free variable captures them
+        let pattern = prepend(prepend(par(wildcard()), free(0), 1), int(8), 1);
+        assert_spatial_match(&target, &pattern, Some(vec![(0, rest)]));
+    }
+
+    #[test]
+    fn matcher_test_singleton_list() {
+        let target = par(list(ints(&[1]), false));
+        let pattern = par(list(vec![par(free(0))], true));
+        assert_spatial_match(&target, &pattern, Some(vec![(0, par(int(1)))]));
+    }
+
+    #[test]
+    fn matcher_test_patterns_to_equal_targets() {
+        // free0 | free1 against 1 | 1
+        let target = prepend(par(int(1)), int(1), 0);
+        let pattern = prepend(par(free(1)), free(0), 0);
+        assert_spatial_match(&target, &pattern, Some(vec![(0, target.clone())]));
+    }
+
+    #[test]
+    fn matcher_test_multiple_remainders() {
+        // free0 | free1 | _ against 1 | 2 | 3, the first one takes them all
+        let target = prepend(prepend(par(int(3)), int(2), 0), int(1), 0);
+        let pattern = prepend(prepend(par(wildcard()), free(1), 0), free(0), 0);
+        assert_spatial_match(&target, &pattern, Some(vec![(0, target.clone())]));
+    }
+
+    #[test]
+    fn matcher_test_revision_of_prior_matches() {
+        let t1 = list(vec![par(int(1)), par(list(ints(&[2]), false))], false);
+        let t2 = list(vec![par(int(1)), par(list(ints(&[3]), false))], false);
+        let target = prepend(par(t2), t1, 0);
+
+        // [1, [free0]] | [free1, [2]]
+        let p1 = list(vec![par(int(1)), par(list(vec![par(free(0))], true))], true);
+        let p2 = list(vec![par(free(1)), par(list(ints(&[2]), false))], true);
+        let pattern = prepend(par(p2.clone()), p1, 0);
+        assert_spatial_match(
+            &target,
+            &pattern,
+            Some(vec![(0, par(int(3))), (1, par(int(1)))]),
+        );
+
+        // [1, [...free0]] | [free1, [2]]
+        let p1 = list(vec![par(int(1)), par(list_rem(vec![], free_var(0)))], true);
+        let pattern = prepend(par(p2), p1, 0);
+        assert_spatial_match(
+            &target,
+            &pattern,
+            Some(vec![(0, par(list(ints(&[3]), false))), (1, par(int(1)))]),
+        );
+
+        // [1, [free0, 2]] | [free1, [2, 2]] against [1, [2, 2]] | [1, [3, 2]]
+        let t1 = list(vec![par(int(1)), par(list(ints(&[2, 2]), false))], false);
+        let t2 = list(vec![par(int(1)), par(list(ints(&[3, 2]), false))], false);
+        let target = prepend(par(t2), t1, 0);
+        let p1 = list(
+            vec![
+                par(int(1)),
+                par(list(vec![par(free(0)), par(int(2))], true)),
+            ],
+            true,
+        );
+        let p2 = list(vec![par(free(1)), par(list(ints(&[2, 2]), false))], true);
+        let pattern = prepend(par(p2), p1, 0);
+        assert_spatial_match(
+            &target,
+            &pattern,
+            Some(vec![(0, par(int(3))), (1, par(int(1)))]),
+        );
+    }
+
+    #[test]
+    fn matcher_test_remainders_and_wildcards() {
+        // [_] | free0 against [1] | [x0]
+        let t2 = expr(ExprInstance::EListBody(EList {
+            ps: vec![par(bound(0))],
+            locally_free: bits(&[0]),
+            ..Default::default()
+        }));
+        let target = prepend(par(t2), list(ints(&[1]), false), 0);
+        let pattern = prepend(par(free(0)), list(vec![par(wildcard())], true), 0);
+        assert_spatial_match(
+            &target,
+            &pattern,
+            Some(vec![(0, par(list(ints(&[1]), false)))]),
+        );
+    }
+
+    #[test]
+    fn matcher_test_send_with_free_channel_and_data() {
+        let target = send_par(send(
+            private("zero"),
+            vec![par(int(7)), private("one")],
+            false,
+        ));
+        let pattern = send_par(send(par(free(0)), vec![par(int(7)), par(free(1))], true));
+        assert_spatial_match(
+            &target,
+            &pattern,
+            Some(vec![(0, private("zero")), (1, private("one"))]),
+        );
+    }
+
+    #[test]
+    fn matcher_test_receive() {
+        let bind = |source: Par| ReceiveBind {
+            patterns: vec![par(free(0)), par(free(1))],
+            source: Some(source),
+            remainder: None,
+            free_count: 0,
+        };
+        let body = send(private("unforgeable"), ints(&[9, 10]), false);
+        let target = receive_par(Receive {
+            binds: vec![bind(par(int(7))), bind(par(int(8)))],
+            body: Some(send_par(body.clone())),
+            bind_count: 4,
+            ..Default::default()
+        });
+        let pattern = receive_par(Receive {
+            binds: vec![bind(par(int(7))), bind(par(free(0)))],
+            body: Some(par(free(1))),
+            bind_count: 4,
+            connective_used: true,
+            ..Default::default()
+        });
+        assert_spatial_match(
+            &target,
+            &pattern,
+            Some(vec![(0, par(int(8))), (1, send_par(body))]),
+        );
+    }
+
+    #[test]
+    fn matcher_test_eval_with_no_free_variables() {
+        assert_spatial_match(&par(bound(0)), &par(bound(0)), Some(vec![]));
+    }
+
+    #[test]
+    fn matcher_test_news() {
+        let target = new_par(New {
+            bind_count: 2,
+            p: Some(prepend_send(
+                send_par(Send {
+                    locally_free: bits(&[0]),
+                    ..send(par(bound(0)), ints(&[43]), false)
+                }),
+                send(par(int(7)), ints(&[42]), false),
+            )),
+            ..Default::default()
+        });
+        let pattern = new_par(New {
+            bind_count: 2,
+            p: Some(prepend(
+                send_par(send(par(int(7)), vec![par(free(0))], true)),
+                wildcard(),
+                1,
+            )),
+            ..Default::default()
+        });
+        assert_spatial_match(&target, &pattern, Some(vec![(0, par(int(42)))]));
+    }
+
+    #[test]
+    fn matcher_test_matches() {
+        let case_pattern = par(list(vec![par(free(0)), par(free(1))], false));
+        let target = match_par(Match {
+            target: Some(par(list(ints(&[4, 20]), false))),
+            cases: vec![
+                MatchCase {
+                    pattern: Some(case_pattern.clone()),
+                    source: Some(send_par(Send {
+                        locally_free: bits(&[0, 1]),
+                        ..send(par(bound(1)), vec![par(bound(0))], false)
+                    })),
+                    free_count: 0,
+                },
+                MatchCase {
+                    pattern: Some(par(wildcard())),
+                    source: Some(Par::default()),
+                    free_count: 0,
+                },
+            ],
+            ..Default::default()
+        });
+        let pattern = match_par(Match {
+            target: Some(par(free(0))),
+            cases: vec![
+                MatchCase {
+                    pattern: Some(case_pattern),
+                    source: Some(par(wildcard())),
+                    free_count: 0,
+                },
+                MatchCase {
+                    pattern: Some(par(wildcard())),
+                    source: Some(par(free(1))),
+                    free_count: 0,
+                },
+            ],
+            connective_used: true,
+            ..Default::default()
+        });
+        assert_spatial_match(
+            &target,
+            &pattern,
+            Some(vec![
+                (0, par(list(ints(&[4, 20]), false))),
+                (1, Par::default()),
+            ]),
+        );
+    }
+
+    #[test]
+    fn matcher_test_list_remainder() {
+        let target = par(list(ints(&[1, 2]), false));
+        let pattern = par(list_rem(ints(&[1]), free_var(0)));
+        assert_spatial_match(
+            &target,
+            &pattern,
+            Some(vec![(0, par(list(ints(&[2]), false)))]),
+        );
+
+        // a whole list
+        let target = par(list(ints(&[1, 2, 3]), false));
+        let pattern = par(list_rem(vec![], free_var(0)));
+        assert_spatial_match(&target, &pattern, Some(vec![(0, target.clone())]));
+    }
+
+    #[test]
+    fn matcher_test_sets() {
+        let target = par(set(ints(&[1, 2, 3]), None));
+        assert_spatial_match(&target, &target, Some(vec![]));
+
+        let target = par(set(ints(&[1, 2, 3, 4, 5]), None));
+        let pattern = par(set(
+            vec![
+                par(int(2)),
+                par(int(5)),
+                par(free(0)),
+                par(free(1)),
+                par(wildcard()),
+            ],
+            None,
+        ));
+        assert_spatial_match(
+            &target,
+            &pattern,
+            Some(vec![(0, par(int(4))), (1, par(int(3)))]),
+        );
+
+        let pattern = par(set(
+            vec![par(int(2)), par(int(5)), par(free(0)), par(wildcard())],
+            None,
+        ));
+        assert_spatial_match(&target, &pattern, None);
+    }
+
+    #[test]
+    fn matcher_test_set_remainders() {
+        let elements = ints(&[1, 2, 3, 4, 5]);
+        let target = par(set(elements.clone(), None));
+
+        for pattern in [
+            set(ints(&[1, 4]), Some(wildcard_var())),
+            set(elements.clone(), Some(wildcard_var())),
+            set(vec![], Some(wildcard_var())),
+        ] {
+            assert_spatial_match(&target, &par(pattern), Some(vec![]));
+        }
+
+        let pattern = par(set(
+            vec![par(int(1)), par(int(4)), par(free(0))],
+            Some(free_var(1)),
+        ));
+        assert_spatial_match(
+            &target,
+            &pattern,
+            Some(vec![(0, par(int(2))), (1, par(set(ints(&[3, 5]), None)))]),
+        );
+
+        let pattern = par(set(elements.clone(), Some(free_var(0))));
+        assert_spatial_match(&target, &pattern, Some(vec![(0, par(set(vec![], None)))]));
+
+        let pattern = par(set(vec![], Some(free_var(0))));
+        assert_spatial_match(&target, &pattern, Some(vec![(0, target.clone())]));
+    }
+
+    #[test]
+    fn matcher_test_maps() {
+        let target = par(map(pairs(&[(1, 2), (3, 4)]), None));
+        assert_spatial_match(&target, &target, Some(vec![]));
+
+        let target = par(map(pairs(&[(1, 2), (3, 4), (5, 6)]), None));
+        let pattern = par(map(
+            vec![
+                (par(int(1)), par(free(1))),
+                (par(int(3)), par(int(4))),
+                (par(free(0)), par(wildcard())),
+            ],
+            None,
+        ));
+        assert_spatial_match(
+            &target,
+            &pattern,
+            Some(vec![(0, par(int(5))), (1, par(int(2)))]),
+        );
+
+        let pattern = par(map(
+            vec![
+                (par(int(3)), par(free(1))),
+                (par(free(0)), par(wildcard())),
+                (par(wildcard()), par(int(4))),
+            ],
+            None,
+        ));
+        assert_spatial_match(&target, &pattern, None);
+    }
+
+    #[test]
+    fn matcher_test_map_remainders() {
+        let elements = pairs(&[(1, 2), (3, 4), (5, 6)]);
+        let target = par(map(elements.clone(), None));
+
+        for pattern in [
+            map(pairs(&[(3, 4)]), Some(wildcard_var())),
+            map(elements.clone(), Some(wildcard_var())),
+            map(vec![], Some(wildcard_var())),
+        ] {
+            assert_spatial_match(&target, &par(pattern), Some(vec![]));
+        }
+
+        let pattern = par(map(vec![(par(free(0)), par(int(4)))], Some(free_var(1))));
+        assert_spatial_match(
+            &target,
+            &pattern,
+            Some(vec![
+                (0, par(int(3))),
+                (1, par(map(pairs(&[(1, 2), (5, 6)]), None))),
+            ]),
+        );
+
+        let pattern = par(map(elements.clone(), Some(free_var(0))));
+        assert_spatial_match(&target, &pattern, Some(vec![(0, par(map(vec![], None)))]));
+
+        let pattern = par(map(vec![], Some(free_var(0))));
+        assert_spatial_match(&target, &pattern, Some(vec![(0, target.clone())]));
+    }
+
+    #[test]
+    fn matcher_test_bundles() {
+        let body = prepend_send(
+            send_par(send(private("0"), ints(&[43]), false)),
+            send(par(int(7)), ints(&[42]), false),
+        );
+        let target = bundle_par(Bundle {
+            body: Some(body),
+            ..Default::default()
+        });
+
+        // nothing inside a bundle is matched
+        let pattern = bundle_par(Bundle {
+            body: Some(prepend(
+                send_par(send(par(int(7)), vec![par(free(0))], false)),
+                wildcard(),
+                1,
+            )),
+            ..Default::default()
+        });
+        assert_spatial_match(&target, &pattern, None);
+
+        // but the whole bundle is
+        assert_spatial_match(&target, &par(free(0)), Some(vec![(0, target.clone())]));
+    }
+
+    #[test]
+    fn matcher_test_and() {
+        let target = send_par(send(par(int(7)), ints(&[8]), false));
+        let fail_target = send_par(send(par(int(7)), ints(&[9]), false));
+        // @7!(x) /\ @y!(8)
+        let pattern = conn_par(vec![and(vec![
+            send_par(send(par(int(7)), vec![par(free(0))], true)),
+            send_par(send(par(free(1)), ints(&[8]), true)),
+        ])]);
+
+        assert_spatial_match(
+            &target,
+            &pattern,
+            Some(vec![(0, par(int(8))), (1, par(int(7)))]),
+        );
+        assert_spatial_match(&fail_target, &pattern, None);
+    }
+
+    #[test]
+    fn matcher_test_or() {
+        let target = send_par(send(par(int(7)), ints(&[8]), false));
+        let fail_target = send_par(send(par(int(7)), ints(&[9]), false));
+        // @9!(x) \/ @x!(8), binds nothing
+        let pattern = conn_par(vec![or_(vec![
+            send_par(send(par(int(9)), vec![par(free(0))], true)),
+            send_par(send(par(free(0)), ints(&[8]), true)),
+        ])]);
+
+        assert_spatial_match(&target, &pattern, Some(vec![]));
+        assert_spatial_match(&fail_target, &pattern, None);
+    }
+
+    #[test]
+    fn matcher_test_negation() {
+        let target = Par {
+            sends: vec![
+                send(par(int(1)), ints(&[2]), false),
+                send(par(int(2)), ints(&[3]), false),
+                send(par(int(3)), ints(&[4]), false),
+            ],
+            ..Default::default()
+        };
+
+        // ~Nil, up to three times, as there are three sends to split between them
+        for n in 1..=3 {
+            let pattern = conn_par(vec![not(Par::default()); n]);
+            assert_spatial_match(&target, &pattern, Some(vec![]));
+        }
+        let pattern = conn_par(vec![not(Par::default()); 4]);
+        assert_spatial_match(&target, &pattern, None);
+    }
+
+    #[test]
+    fn matcher_test_complicated_connective() {
+        let sends = |data: [i64; 3]| Par {
+            sends: (1..=3)
+                .zip(data)
+                .map(|(chan, datum)| send(par(int(chan)), ints(&[datum]), false))
+                .collect(),
+            ..Default::default()
+        };
+        let target = sends([6, 7, 8]);
+        let fail_target = sends([6, 9, 8]);
+
+        let non_null = not(Par::default());
+        // ~{ ~Nil | ~Nil }
+        let single_factor = not(conn_par(vec![non_null.clone(), non_null.clone()]));
+        // x /\ y!(7)
+        let capture = and(vec![
+            par(free(0)),
+            send_par(send(par(free(1)), ints(&[7]), true)),
+        ]);
+        // ~{ ~Nil | ~Nil } /\ ~Nil
+        let prime = and(vec![
+            conn_par(vec![non_null]),
+            conn_par(vec![single_factor]),
+        ]);
+        // x!(7) \/ x!(8)
+        let alternative = or_(vec![
+            send_par(send(par(free(0)), ints(&[7]), true)),
+            send_par(send(par(free(0)), ints(&[8]), true)),
+        ]);
+        let pattern = conn_par(vec![capture, prime, alternative]);
+
+        assert_spatial_match(
+            &target,
+            &pattern,
+            Some(vec![
+                (0, send_par(send(par(int(2)), ints(&[7]), false))),
+                (1, par(int(2))),
+            ]),
+        );
+        assert_spatial_match(&fail_target, &pattern, None);
+    }
+
+    #[test]
+    fn matcher_test_var_ref_ignores_locally_free() {
+        let var_ref = connective(ConnectiveInstance::VarRefBody(VarRef {
+            index: 0,
+            depth: 1,
+        }));
+        let new = |bind_pattern: Par, locally_free: Vec<u8>| {
+            new_par(New {
+                bind_count: 1,
+                p: Some(receive_par(Receive {
+                    binds: vec![ReceiveBind {
+                        patterns: vec![bind_pattern],
+                        source: Some(Par::default()),
+                        remainder: None,
+                        free_count: 0,
+                    }],
+                    body: Some(Par::default()),
+                    locally_free: locally_free.clone(),
+                    ..Default::default()
+                }))
+                .map(|p| Par { locally_free, ..p }),
+                ..Default::default()
+            })
+        };
+        // the target's bind pattern was prepended at depth 1, so it has index 0 locally free
+        let target = new(
+            Par {
+                locally_free: bits(&[0]),
+                ..conn_par(vec![var_ref.clone()])
+            },
+            bits(&[0]),
+        );
+        let pattern = new(conn_par(vec![var_ref]), vec![]);
+        assert_spatial_match(&target, &pattern, Some(vec![]));
+    }
+
+    #[test]
+    fn matcher_test_operators() {
+        // "abc" ++ "def" against x ++ y
+        let target = par(expr(ExprInstance::EPlusPlusBody(EPlusPlus {
+            p1: Some(par(string("abc"))),
+            p2: Some(par(string("def"))),
+        })));
+        let pattern = par(expr(ExprInstance::EPlusPlusBody(EPlusPlus {
+            p1: Some(par(free(0))),
+            p2: Some(par(free(1))),
+        })));
+        assert_spatial_match(
+            &target,
+            &pattern,
+            Some(vec![(0, par(string("abc"))), (1, par(string("def")))]),
+        );
+
+        // "${name}" %% {"name" : "a"} against x %% y
+        let names = map(vec![(par(string("name")), par(string("a")))], None);
+        let target = par(expr(ExprInstance::EPercentPercentBody(EPercentPercent {
+            p1: Some(par(string("${name}"))),
+            p2: Some(par(names.clone())),
+        })));
+        let pattern = par(expr(ExprInstance::EPercentPercentBody(EPercentPercent {
+            p1: Some(par(free(0))),
+            p2: Some(par(free(1))),
+        })));
+        assert_spatial_match(
+            &target,
+            &pattern,
+            Some(vec![(0, par(string("${name}"))), (1, par(names))]),
+        );
+
+        // {1, 2, 3} -- {1, 2} against x -- y
+        let (lhs, rhs) = (set(ints(&[1, 2, 3]), None), set(ints(&[1, 2]), None));
+        let target = par(expr(ExprInstance::EMinusMinusBody(EMinusMinus {
+            p1: Some(par(lhs.clone())),
+            p2: Some(par(rhs.clone())),
+        })));
+        let pattern = par(expr(ExprInstance::EMinusMinusBody(EMinusMinus {
+            p1: Some(par(free(0))),
+            p2: Some(par(free(1))),
+        })));
+        assert_spatial_match(&target, &pattern, Some(vec![(0, par(lhs)), (1, par(rhs))]));
+    }
+
+    #[test]
+    fn matcher_test_ground_type_connectives() {
+        let cases = [
+            (
+                ConnectiveInstance::ConnBool(true),
+                expr(ExprInstance::GBool(false)),
+            ),
+            (ConnectiveInstance::ConnInt(true), int(7)),
+            (ConnectiveInstance::ConnString(true), string("Match me!")),
+            (
+                ConnectiveInstance::ConnUri(true),
+                expr(ExprInstance::GUri("rho:io:stdout".to_string())),
+            ),
+            (
+                ConnectiveInstance::ConnByteArray(true),
+                expr(ExprInstance::GByteArray(vec![74, 75])),
+            ),
+        ];
+
+        for (instance, matching) in cases {
+            let pattern = conn_par(vec![connective(instance.clone())]);
+            let fail = if matches!(instance, ConnectiveInstance::ConnString(_)) {
+                int(42)
+            } else {
+                string("Fail")
+            };
+            assert_spatial_match(&par(matching), &pattern, Some(vec![]));
+            assert_spatial_match(&par(fail), &pattern, None);
+        }
+    }
+
+    #[test]
+    fn matcher_test_match_list_par() {
+        let data = ListParWithRandom {
+            pars: ints(&[1, 2, 3]),
+            random_state: vec![7; 4],
+        };
+
+        // for (@1, @x, ...@rest <- channel)
+        let pattern = BindPattern {
+            patterns: vec![par(int(1)), par(free(0))],
+            remainder: Some(free_var(1)),
+            free_count: 3,
+        };
+        let bindings = matcher::match_list_par(&pattern, &data).unwrap();
+        assert_eq!(bindings.random_state, data.random_state);
+        assert_eq!(
+            bindings.pars,
+            vec![
+                par(int(2)),
+                Par {
+                    exprs: vec![list(ints(&[3]), false)],
+                    ..Default::default()
+                },
+                // nothing bound at level 2
+                Par::default(),
+            ]
+        );
+
+        // for (@2, ...@rest <- channel)
+        let pattern = BindPattern {
+            patterns: vec![par(int(2))],
+            remainder: Some(free_var(0)),
+            free_count: 1,
+        };
+        assert!(matcher::match_list_par(&pattern, &data).is_none());
+    }
+}
@@ -0,0 +1,160 @@
+#[cfg(test)]
+mod tests {
+    use rspace_plus_plus::keyvaluestore::{Conflict, KeyValueRead, KeyValueStore, Storage};
+    use rspace_plus_plus::memorystore::MemStore;
+    use rspace_plus_plus::rtypes::rtypes::{Commit, Retrieve};
+    use rspace_plus_plus::setup::Setup;
+    use rspace_plus_plus::tuplespace::TupleSpace;
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn key(i: u16) -> Vec<u8> {
+        [b"k".as_slice(), &i.to_be_bytes()].concat()
+    }
+
+    #[test]
+    fn memorystore_test_iterate_merges_writes() {
+        let store = MemStore::new();
+        let mut expected = BTreeMap::new();
+
+        // more committed pairs than one batch
+        let mut txn = store.write_txn().unwrap();
+        for i in (0..1200).step_by(2) {
+            txn.put(&key(i), b"committed").unwrap();
+            expected.insert(key(i), b"committed".to_vec());
+        }
+        txn.put(b"other", b"committed").unwrap();
+        txn.commit().unwrap();
+
+        let mut txn = store.write_txn().unwrap();
+        for i in (0..1200).step_by(5) {
+            if i % 2 == 0 && i % 3 == 0 {
+                txn.delete(&key(i)).unwrap();
+                expected.remove(&key(i));
+            } else {
+                txn.put(&key(i), b"written").unwrap();
+                expected.insert(key(i), b"written".to_vec());
+            }
+        }
+
+        let mut seen = BTreeMap::new();
+        txn.iterate_prefix(b"k", &mut |key, value| {
+            // reading through the transaction from inside f does not block
+            assert_eq!(txn.get(key)?.as_deref(), Some(value));
+            seen.insert(key.to_vec(), value.to_vec());
+            Ok(true)
+        })
+        .unwrap();
+        assert_eq!(seen, expected);
+
+        let mut first = vec![];
+        txn.iterate_prefix(b"k", &mut |key, _| {
+            first.push(key.to_vec());
+            Ok(first.len() < 300)
+        })
+        .unwrap();
+        assert_eq!(
+            first,
+            expected.keys().take(300).cloned().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn memorystore_test_clear_waits_for_writer() {
+        let store = Arc::new(MemStore::new());
+
+        let mut txn = store.write_txn().unwrap();
+        let clearing = std::thread::spawn({
+            let store = store.clone();
+            move || store.clear().unwrap()
+        });
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(!clearing.is_finished());
+
+        txn.put(b"friends", b"alice").unwrap();
+        txn.commit().unwrap();
+        clearing.join().unwrap();
+        assert!(store.is_empty().unwrap());
+    }
+
+    #[test]
+    fn memorystore_test_concurrent_overlapping_writes_conflict() {
+        let store = MemStore::concurrent();
+        let mut first = store.write_txn().unwrap();
+        let mut second = store.write_txn().unwrap();
+
+        first.iterate_prefix(b"k", &mut |_, _| Ok(true)).unwrap();
+        first.put(&key(1), b"first").unwrap();
+        second.iterate_prefix(b"k", &mut |_, _| Ok(true)).unwrap();
+        second.put(&key(2), b"second").unwrap();
+
+        first.commit().unwrap();
+        let err = second.commit().unwrap_err();
+        assert!(err.is::<Conflict>());
+        assert_eq!(store.read_txn().unwrap().get(&key(2)).unwrap(), None);
+    }
+
+    #[test]
+    fn memorystore_test_concurrent_disjoint_writes_commit() {
+        let store = MemStore::concurrent();
+        let mut first = store.write_txn().unwrap();
+        let mut second = store.write_txn().unwrap();
+
+        assert_eq!(first.get(&key(1)).unwrap(), None);
+        first.put(&key(1), b"first").unwrap();
+        assert_eq!(second.get(&key(2)).unwrap(), None);
+        second.put(&key(2), b"second").unwrap();
+
+        second.commit().unwrap();
+        first.commit().unwrap();
+        let txn = store.read_txn().unwrap();
+        assert_eq!(
+            txn.get(&key(1)).unwrap().as_deref(),
+            Some(b"first".as_slice())
+        );
+        assert_eq!(
+            txn.get(&key(2)).unwrap().as_deref(),
+            Some(b"second".as_slice())
+        );
+    }
+
+    #[test]
+    fn memorystore_test_concurrent_threads_on_one_channel() {
+        let space = Arc::new(TupleSpace::<MemStore, Retrieve, Commit>::new(
+            MemStore::concurrent(),
+        ));
+
+        // every produce is matched by exactly one consume, whatever the interleaving
+        let threads: Vec<_> = (0..8)
+            .map(|i| {
+                let space = space.clone();
+                std::thread::spawn(move || {
+                    let setup = Setup::new();
+                    for j in 0..100 {
+                        if i % 2 == 0 {
+                            let mut alice = setup.alice.clone();
+                            alice.email = format!("alice-{}-{}@example.org", i, j);
+                            let city = Setup::get_city_field(alice.clone());
+                            let retrieve =
+                                Setup::create_retrieve(String::from("friends"), alice, city);
+                            space.produce(retrieve, false).unwrap();
+                        } else {
+                            let commit = Setup::create_commit(
+                                vec![String::from("friends")],
+                                vec![setup.city_match_case.clone()],
+                                format!("continuation {} {}", i, j),
+                            );
+                            space.consume(commit, false).unwrap();
+                        }
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        assert!(space.is_empty());
+    }
+}
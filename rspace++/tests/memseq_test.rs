@@ -12,14 +12,14 @@ mod tests {
             vec![setup.city_match_case],
             String::from("I am the continuation, for now..."),
         );
-        let cres = memseq.consume(commit, false);
+        let cres = memseq.consume(commit, false).unwrap();
 
         let retrieve = Setup::create_retrieve(
             String::from("friends"),
             setup.alice.clone(),
             Setup::get_city_field(setup.alice),
         );
-        let pres = memseq.produce(retrieve, false);
+        let pres = memseq.produce(retrieve, false).unwrap();
 
         assert!(cres.is_none());
         assert!(pres.is_some());
@@ -38,14 +38,14 @@ mod tests {
             vec![setup.city_match_case],
             String::from("I am the continuation, for now..."),
         );
-        let cres = memseq.consume(commit, false);
+        let cres = memseq.consume(commit, false).unwrap();
 
         let retrieve = Setup::create_retrieve(
             String::from("friends"),
             setup.carol.clone(),
             Setup::get_city_field(setup.carol),
         );
-        let pres = memseq.produce(retrieve, false);
+        let pres = memseq.produce(retrieve, false).unwrap();
 
         assert!(cres.is_none());
         assert!(pres.is_none());
@@ -64,14 +64,14 @@ mod tests {
             setup.bob.clone(),
             Setup::get_last_name_field(setup.bob),
         );
-        let pres = memseq.produce(retrieve, false);
+        let pres = memseq.produce(retrieve, false).unwrap();
 
         let commit = Setup::create_commit(
             vec![String::from("friends")],
             vec![setup.name_match_case],
             String::from("I am the continuation, for now..."),
         );
-        let cres = memseq.consume(commit, false);
+        let cres = memseq.consume(commit, false).unwrap();
 
         assert!(pres.is_none());
         assert!(cres.is_some());
@@ -90,21 +90,21 @@ mod tests {
             setup.dan.clone(),
             Setup::get_state_field(setup.dan),
         );
-        let pres1 = memseq.produce(retrieve1, false);
+        let pres1 = memseq.produce(retrieve1, false).unwrap();
 
         let retrieve2 = Setup::create_retrieve(
             String::from("friends"),
             setup.erin.clone(),
             Setup::get_state_field(setup.erin),
         );
-        let pres2 = memseq.produce(retrieve2, false);
+        let pres2 = memseq.produce(retrieve2, false).unwrap();
 
         let commit = Setup::create_commit(
             vec![String::from("friends"), String::from("colleagues")],
             vec![setup.state_match_case.clone(), setup.state_match_case],
             String::from("I am the continuation, for now..."),
         );
-        let cres = memseq.consume(commit, false);
+        let cres = memseq.consume(commit, false).unwrap();
 
         assert!(pres1.is_none());
         assert!(pres2.is_none());
@@ -125,7 +125,7 @@ mod tests {
             vec![setup.city_match_case],
             String::from("I am the continuation, for now..."),
         );
-        let cres = memseq.consume(commit, true);
+        let cres = memseq.consume(commit, true).unwrap();
 
         assert!(cres.is_none());
         assert!(!memseq.is_empty());
@@ -135,7 +135,7 @@ mod tests {
             setup.alice.clone(),
             Setup::get_city_field(setup.alice),
         );
-        let pres = memseq.produce(retrieve, false);
+        let pres = memseq.produce(retrieve, false).unwrap();
 
         assert!(pres.is_some());
         assert!(!memseq.is_empty());
@@ -153,21 +153,21 @@ mod tests {
             setup.alice.clone(),
             Setup::get_city_field(setup.alice.clone()),
         );
-        let _pres1 = memseq.produce(retrieve1, false);
+        let _pres1 = memseq.produce(retrieve1, false).unwrap();
 
         let retrieve2 = Setup::create_retrieve(
             String::from("friends"),
             setup.bob.clone(),
             Setup::get_city_field(setup.bob),
         );
-        let _pres2 = memseq.produce(retrieve2, false);
+        let _pres2 = memseq.produce(retrieve2, false).unwrap();
 
         let commit1 = Setup::create_commit(
             vec![String::from("friends")],
             vec![setup.city_match_case.clone()],
             String::from("I am the continuation, for now..."),
         );
-        let cres1 = memseq.consume(commit1, true);
+        let cres1 = memseq.consume(commit1, true).unwrap();
 
         assert_eq!(cres1.unwrap().len(), 1);
         assert!(!memseq.is_empty());
@@ -177,7 +177,7 @@ mod tests {
             vec![setup.city_match_case.clone()],
             String::from("I am the continuation, for now..."),
         );
-        let cres2 = memseq.consume(commit2, true);
+        let cres2 = memseq.consume(commit2, true).unwrap();
 
         assert_eq!(cres2.unwrap().len(), 1);
         assert!(memseq.is_empty());
@@ -187,7 +187,7 @@ mod tests {
             vec![setup.city_match_case],
             String::from("I am the continuation, for now..."),
         );
-        let cres3 = memseq.consume(commit3, true);
+        let cres3 = memseq.consume(commit3, true).unwrap();
 
         assert!(cres3.is_none());
         assert!(!memseq.is_empty());
@@ -197,7 +197,7 @@ mod tests {
             setup.alice.clone(),
             Setup::get_city_field(setup.alice),
         );
-        let pres3 = memseq.produce(retrieve3, false);
+        let pres3 = memseq.produce(retrieve3, false).unwrap();
 
         assert!(pres3.is_some());
         assert!(!memseq.is_empty());
@@ -215,7 +215,7 @@ mod tests {
             setup.alice.clone(),
             Setup::get_city_field(setup.alice),
         );
-        let pres = memseq.produce(retrieve, true);
+        let pres = memseq.produce(retrieve, true).unwrap();
 
         assert!(pres.is_none());
         assert!(!memseq.is_empty());
@@ -225,7 +225,7 @@ mod tests {
             vec![setup.city_match_case],
             String::from("I am the continuation, for now..."),
         );
-        let cres = memseq.consume(commit, false);
+        let cres = memseq.consume(commit, false).unwrap();
 
         assert!(cres.is_some());
         assert_eq!(cres.unwrap().len(), 1);
@@ -244,7 +244,7 @@ mod tests {
             vec![setup.city_match_case.clone()],
             String::from("I am the continuation, for now..."),
         );
-        let cres1 = memseq.consume(commit1, false);
+        let cres1 = memseq.consume(commit1, false).unwrap();
 
         assert!(cres1.is_none());
         assert!(!memseq.is_empty());
@@ -254,7 +254,7 @@ mod tests {
             setup.alice.clone(),
             Setup::get_city_field(setup.alice.clone()),
         );
-        let pres1 = memseq.produce(retrieve1, true);
+        let pres1 = memseq.produce(retrieve1, true).unwrap();
 
         assert!(pres1.is_some());
         assert!((memseq.is_empty()));
@@ -264,18 +264,147 @@ mod tests {
             setup.alice.clone(),
             Setup::get_city_field(setup.alice),
         );
-        let pres2 = memseq.produce(retrieve2, true);
+        let pres2 = memseq.produce(retrieve2, true).unwrap();
 
         let commit2 = Setup::create_commit(
             vec![String::from("friends")],
             vec![setup.city_match_case],
             String::from("I am the continuation, for now..."),
         );
-        let _cres2 = memseq.consume(commit2, false);
+        let _cres2 = memseq.consume(commit2, false).unwrap();
 
         assert!(pres2.is_none());
         assert!(!memseq.is_empty());
 
         let _ = memseq.clear();
     }
+
+    #[test]
+    fn memseq_test_consume_match_among_other_match_keys() {
+        let setup = Setup::new();
+        let memseq = setup.memseq;
+
+        let retrieve1 = Setup::create_retrieve(
+            String::from("friends"),
+            setup.alice.clone(),
+            Setup::get_city_field(setup.alice),
+        );
+        let pres1 = memseq.produce(retrieve1, false).unwrap();
+
+        let retrieve2 = Setup::create_retrieve(
+            String::from("friends"),
+            setup.carol.clone(),
+            Setup::get_city_field(setup.carol.clone()),
+        );
+        let pres2 = memseq.produce(retrieve2, false).unwrap();
+
+        let commit = Setup::create_commit(
+            vec![String::from("friends")],
+            vec![Setup::get_city_field(setup.carol.clone())],
+            String::from("I am the continuation, for now..."),
+        );
+        let cres = memseq.consume(commit, false).unwrap();
+
+        assert!(pres1.is_none());
+        assert!(pres2.is_none());
+        assert!(cres.is_some());
+        assert_eq!(cres.unwrap()[0].data, Some(setup.carol));
+        assert!(!memseq.is_empty());
+
+        let _ = memseq.clear();
+    }
+
+    #[test]
+    fn memseq_test_inspection() {
+        let setup = Setup::new();
+        let memseq = setup.memseq;
+
+        let retrieve = Setup::create_retrieve(
+            String::from("memseq-inspect-friends"),
+            setup.carol.clone(),
+            Setup::get_city_field(setup.carol.clone()),
+        );
+        let pres = memseq.produce(retrieve, false).unwrap();
+
+        let join = vec![
+            String::from("memseq-inspect-colleagues"),
+            String::from("memseq-inspect-family"),
+        ];
+        let commit = Setup::create_commit(
+            join.clone(),
+            vec![setup.state_match_case.clone(), setup.name_match_case],
+            String::from("I am the continuation, for now..."),
+        );
+        let cres = memseq.consume(commit, false).unwrap();
+
+        assert!(pres.is_none());
+        assert!(cres.is_none());
+
+        let channels = memseq.channels().unwrap();
+        assert!(channels.contains(&join[0]));
+        assert!(channels.contains(&join[1]));
+        assert!(channels.contains(&String::from("memseq-inspect-friends")));
+
+        let data = memseq.get_data("memseq-inspect-friends").unwrap();
+        assert_eq!(data.len(), 1);
+        assert_eq!(data[0].data, Some(setup.carol));
+
+        let waiting = memseq.get_waiting_continuations(&join).unwrap();
+        assert_eq!(waiting.len(), 2);
+        assert!(memseq
+            .get_waiting_continuations(&join[..1])
+            .unwrap()
+            .is_empty());
+        assert_eq!(memseq.get_joins(&join[1]).unwrap(), vec![join.clone()]);
+
+        let map = memseq.to_map().unwrap();
+        assert_eq!(map[&join].continuations.len(), 2);
+        assert_eq!(
+            map[&vec![String::from("memseq-inspect-friends")]]
+                .data
+                .len(),
+            1
+        );
+
+        let _ = memseq.clear();
+    }
+    #[test]
+    fn memseq_test_install() {
+        let setup = Setup::new();
+        let memseq = setup.memseq;
+
+        let commit = Setup::create_commit(
+            vec![String::from("memseq-install-system")],
+            vec![setup.city_match_case.clone()],
+            String::from("I am the continuation, for now..."),
+        );
+        memseq.install(commit).unwrap();
+
+        // installed continuations are persistent
+        for _ in 0..2 {
+            let retrieve = Setup::create_retrieve(
+                String::from("memseq-install-system"),
+                setup.alice.clone(),
+                Setup::get_city_field(setup.alice.clone()),
+            );
+            assert!(memseq.produce(retrieve, false).unwrap().is_some());
+        }
+
+        let retrieve = Setup::create_retrieve(
+            String::from("memseq-install-late"),
+            setup.bob.clone(),
+            Setup::get_city_field(setup.bob),
+        );
+        memseq.produce(retrieve, false).unwrap();
+        let late = Setup::create_commit(
+            vec![String::from("memseq-install-late")],
+            vec![setup.city_match_case],
+            String::from("I am the continuation, for now..."),
+        );
+        assert!(memseq.install(late).is_err());
+        // the refused install consumed nothing
+        assert_eq!(memseq.get_data("memseq-install-late").unwrap().len(), 1);
+
+        let _ = memseq.clear();
+    }
 }
@@ -0,0 +1,286 @@
+#[cfg(test)]
+mod tests {
+    use rspace_plus_plus::memorystore::MemStore;
+    use rspace_plus_plus::merger::{self, ConflictReason, MergeOutcome};
+    use rspace_plus_plus::rho;
+    use rspace_plus_plus::rtypes::rtypes::{
+        expr::ExprInstance, BindPattern, Commit, Entry, Expr, ListParWithRandom, Par, Retrieve,
+        TaggedContinuation,
+    };
+    use rspace_plus_plus::setup::Setup;
+    use rspace_plus_plus::trace::Event;
+    use rspace_plus_plus::tuplespace::TupleSpace;
+
+    type Space = TupleSpace<MemStore, Retrieve, Commit>;
+
+    // alice waits on friends and bob on colleagues
+    fn base() -> Space {
+        let setup = Setup::new();
+        let base = TupleSpace::new(MemStore::new());
+        base.produce(retrieve("friends", &setup.alice), false)
+            .unwrap();
+        base.produce(retrieve("colleagues", &setup.bob), false)
+            .unwrap();
+        base
+    }
+
+    fn copy(space: &Space) -> Space {
+        let copy = TupleSpace::new(MemStore::new());
+        let exporter = space.exporter(10).unwrap();
+        let mut importer = copy.importer(exporter.root()).unwrap();
+        for chunk in exporter.chunks() {
+            importer.import_chunk(&chunk.unwrap()).unwrap();
+        }
+        importer.finish().unwrap();
+        copy
+    }
+
+    fn retrieve(channel: &str, entry: &Entry) -> Retrieve {
+        Setup::create_retrieve(
+            String::from(channel),
+            entry.clone(),
+            Setup::get_city_field(entry.clone()),
+        )
+    }
+
+    fn commit(channels: &[&str], entry: &Entry) -> Commit {
+        Setup::create_commit(
+            channels.iter().map(|channel| channel.to_string()).collect(),
+            channels
+                .iter()
+                .map(|_| Setup::get_city_field(entry.clone()))
+                .collect(),
+            String::from("I am the continuation, for now..."),
+        )
+    }
+
+    // Runs f on a copy of base and returns what it logged
+    fn branch(base: &Space, f: impl Fn(&Space)) -> Vec<Event> {
+        let branch = copy(base);
+        branch.start_event_log();
+        f(&branch);
+        branch.take_event_log()
+    }
+
+    #[test]
+    fn merger_test_non_conflicting() {
+        let setup = Setup::new();
+        let base = base();
+
+        let left_ops = |space: &Space| {
+            assert!(space
+                .consume(commit(&["friends"], &setup.alice), false)
+                .unwrap()
+                .is_some());
+            space
+                .produce(retrieve("family", &setup.carol), false)
+                .unwrap();
+        };
+        let right_ops = |space: &Space| {
+            assert!(space
+                .consume(commit(&["colleagues"], &setup.bob), false)
+                .unwrap()
+                .is_some());
+            space
+                .consume(commit(&["neighbors", "cousins"], &setup.dan), false)
+                .unwrap();
+        };
+        let left = branch(&base, left_ops);
+        let right = branch(&base, right_ops);
+
+        let sequential = copy(&base);
+        left_ops(&sequential);
+        right_ops(&sequential);
+
+        let outcome = merger::merge(&base, &left, &right).unwrap();
+
+        assert_eq!(outcome, MergeOutcome::Merged(sequential.root().unwrap()));
+        assert_eq!(base.to_map().unwrap(), sequential.to_map().unwrap());
+    }
+
+    #[test]
+    fn merger_test_same_datum_taken() {
+        let setup = Setup::new();
+        let base = base();
+
+        let take_alice = |space: &Space| {
+            assert!(space
+                .consume(commit(&["friends"], &setup.alice), false)
+                .unwrap()
+                .is_some());
+        };
+        let left = branch(&base, take_alice);
+        let right = branch(&base, take_alice);
+        let root = base.root().unwrap();
+
+        match merger::merge(&base, &left, &right).unwrap() {
+            MergeOutcome::Conflicting(conflicts) => {
+                assert_eq!(conflicts.len(), 1);
+                assert_eq!(conflicts[0].left, vec![String::from("friends")]);
+                assert_eq!(conflicts[0].right, vec![String::from("friends")]);
+                assert_eq!(conflicts[0].reason, ConflictReason::SameEntryTaken);
+            }
+            outcome => panic!("expected a conflict, got {:?}", outcome),
+        }
+        assert_eq!(base.root().unwrap(), root);
+    }
+
+    #[test]
+    fn merger_test_potential_comm() {
+        let setup = Setup::new();
+        let base = base();
+
+        let left = branch(&base, |space| {
+            space
+                .produce(retrieve("family", &setup.carol), false)
+                .unwrap();
+        });
+        let matching = branch(&base, |space| {
+            space
+                .consume(commit(&["family", "cousins"], &setup.carol), false)
+                .unwrap();
+        });
+        let other_pattern = branch(&base, |space| {
+            space
+                .consume(commit(&["family"], &setup.dan), false)
+                .unwrap();
+        });
+
+        let conflicts = merger::conflicts(
+            &merger::EventLogIndex::new(&left),
+            &merger::EventLogIndex::new(&matching),
+        );
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].left, vec![String::from("family")]);
+        assert_eq!(
+            conflicts[0].right,
+            vec![String::from("cousins"), String::from("family")]
+        );
+        assert_eq!(conflicts[0].reason, ConflictReason::PotentialComm);
+
+        assert!(merger::conflicts(
+            &merger::EventLogIndex::new(&left),
+            &merger::EventLogIndex::new(&other_pattern),
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn merger_test_potential_comm_needs_a_bind() {
+        let base = base();
+        let int = |value: i64| Par {
+            exprs: vec![Expr {
+                expr_instance: Some(ExprInstance::GInt(value)),
+            }],
+            ..Default::default()
+        };
+        let send = |value: i64| {
+            branch(&base, |space| {
+                let pars = ListParWithRandom {
+                    pars: vec![int(value)],
+                    random_state: vec![],
+                };
+                space
+                    .produce(rho::retrieve(int(7), pars, ""), false)
+                    .unwrap();
+            })
+        };
+        // for (@2 <- @7)
+        let receive = branch(&base, |space| {
+            space
+                .consume(
+                    rho::commit(
+                        vec![int(7)],
+                        vec![String::new()],
+                        vec![BindPattern {
+                            patterns: vec![int(2)],
+                            remainder: None,
+                            free_count: 0,
+                        }],
+                        TaggedContinuation::scala_body_ref(1),
+                    ),
+                    false,
+                )
+                .unwrap();
+        });
+        let receive = merger::EventLogIndex::new(&receive);
+
+        // same channel and match key, but @1 does not fit the pattern
+        assert!(merger::conflicts(&merger::EventLogIndex::new(&send(1)), &receive).is_empty());
+        let conflicts = merger::conflicts(&merger::EventLogIndex::new(&send(2)), &receive);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].reason, ConflictReason::PotentialComm);
+    }
+
+    #[test]
+    fn merger_test_conflicts_are_unique() {
+        let setup = Setup::new();
+        let base = base();
+
+        let left = branch(&base, |space| {
+            space
+                .produce(retrieve("family", &setup.carol), false)
+                .unwrap();
+            space
+                .produce(retrieve("family", &setup.carol), false)
+                .unwrap();
+        });
+        let right = branch(&base, |space| {
+            space
+                .consume(commit(&["family"], &setup.carol), false)
+                .unwrap();
+            space
+                .consume(commit(&["family", "cousins"], &setup.carol), false)
+                .unwrap();
+        });
+
+        // each datum meets each continuation, the two data alike
+        let conflicts = merger::conflicts(
+            &merger::EventLogIndex::new(&left),
+            &merger::EventLogIndex::new(&right),
+        );
+        assert_eq!(conflicts.len(), 2);
+        assert_ne!(conflicts[0], conflicts[1]);
+    }
+
+    #[test]
+    fn merger_test_log_cancels_own_entries() {
+        let setup = Setup::new();
+        let base = base();
+        let root = base.root().unwrap();
+
+        let left = branch(&base, |space| {
+            space
+                .produce(retrieve("family", &setup.carol), false)
+                .unwrap();
+            assert!(space
+                .consume(commit(&["family"], &setup.carol), false)
+                .unwrap()
+                .is_some());
+        });
+        let index = merger::EventLogIndex::new(&left);
+
+        assert_eq!(left.len(), 2);
+        assert!(index.changes().is_empty());
+        assert_eq!(
+            merger::merge(&base, &left, &[]).unwrap(),
+            MergeOutcome::Merged(root)
+        );
+    }
+
+    #[test]
+    fn merger_test_rejects_other_base() {
+        let setup = Setup::new();
+        let base = base();
+
+        let left = branch(&base, |space| {
+            assert!(space
+                .consume(commit(&["friends"], &setup.alice), false)
+                .unwrap()
+                .is_some());
+        });
+        let other: Space = TupleSpace::new(MemStore::new());
+
+        assert!(merger::merge(&other, &left, &[]).is_err());
+    }
+}
@@ -0,0 +1,187 @@
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+    use rspace_plus_plus::metrics::{self, InMemoryMetrics, Metrics};
+    use rspace_plus_plus::rspace::RSpace;
+    use rspace_plus_plus::rtypes::rtypes::{Commit, MetricsSnapshot, Retrieve};
+    use rspace_plus_plus::setup::Setup;
+    use rspace_plus_plus::{
+        space_enable_metrics, space_free_bytes, space_metrics_snapshot, space_new,
+    };
+    use std::sync::{Arc, Mutex, MutexGuard};
+
+    // The recorder is global, so these tests take turns
+    static TURN: Mutex<()> = Mutex::new(());
+
+    fn with_recorder() -> (MutexGuard<'static, ()>, Arc<InMemoryMetrics>) {
+        let turn = TURN.lock().unwrap_or_else(|err| err.into_inner());
+        let recorder = Arc::new(InMemoryMetrics::new());
+        metrics::set_recorder(recorder.clone()).unwrap();
+        (turn, recorder)
+    }
+
+    fn retrieve(channel: &str) -> Retrieve {
+        let setup = Setup::new();
+        Setup::create_retrieve(
+            String::from(channel),
+            setup.alice.clone(),
+            Setup::get_city_field(setup.alice),
+        )
+    }
+
+    fn commit(channel: &str) -> Commit {
+        let setup = Setup::new();
+        Setup::create_commit(
+            vec![String::from(channel)],
+            vec![setup.city_match_case],
+            String::from("I am the continuation, for now..."),
+        )
+    }
+
+    #[test]
+    fn metrics_test_counts_verbs() {
+        let (_turn, recorder) = with_recorder();
+        let rspace = RSpace::<Retrieve, Commit>::create().unwrap();
+
+        rspace
+            .get_once_non_durable_sequential(retrieve("metrics-friends"))
+            .unwrap();
+        rspace
+            .put_once_non_durable_concurrent(commit("metrics-friends"))
+            .unwrap();
+        rspace
+            .put_once_non_durable_sequential(commit("metrics-friends"))
+            .unwrap();
+        rspace
+            .put_always_non_durable_sequential(commit("metrics-colleagues"))
+            .unwrap();
+
+        assert_eq!(recorder.counter("rspace.produce.once.non_durable.calls"), 1);
+        assert_eq!(recorder.counter("rspace.produce.once.non_durable.miss"), 1);
+        assert_eq!(recorder.counter("rspace.consume.once.non_durable.calls"), 2);
+        assert_eq!(recorder.counter("rspace.consume.once.non_durable.hit"), 1);
+        assert_eq!(recorder.counter("rspace.consume.once.non_durable.miss"), 1);
+        assert_eq!(
+            recorder.counter("rspace.consume.always.non_durable.miss"),
+            1
+        );
+        assert_eq!(
+            recorder.counter("rspace.produce.always.non_durable.calls"),
+            0
+        );
+
+        let latency = recorder
+            .histogram("rspace.consume.once.non_durable.latency")
+            .unwrap();
+        assert_eq!(latency.count, 2);
+        assert!(latency.min_micros <= latency.max_micros);
+        assert_eq!(
+            latency
+                .buckets
+                .iter()
+                .map(|bucket| bucket.count)
+                .sum::<u64>(),
+            2
+        );
+
+        metrics::clear_recorder().unwrap();
+    }
+
+    #[test]
+    fn metrics_test_durable_verbs_and_lmdb() {
+        let (_turn, recorder) = with_recorder();
+        let rspace = RSpace::<Retrieve, Commit>::create().unwrap();
+        rspace.clear_store();
+
+        rspace
+            .get_always_durable_sequential(retrieve("metrics-durable"))
+            .unwrap();
+        rspace
+            .put_once_durable_sequential(commit("metrics-durable"))
+            .unwrap();
+
+        assert_eq!(recorder.counter("rspace.produce.always.durable.calls"), 1);
+        assert_eq!(recorder.counter("rspace.consume.once.durable.hit"), 1);
+        assert!(recorder.histogram("lmdb.write_txn").unwrap().count >= 2);
+
+        rspace.record_store_sizes().unwrap();
+        assert_eq!(recorder.gauge("rspace.store.diskseq.entries"), Some(1));
+        assert_eq!(recorder.gauge("rspace.store.memseq.entries"), Some(0));
+
+        rspace.clear_store();
+        metrics::clear_recorder().unwrap();
+    }
+
+    #[test]
+    fn metrics_test_counts_transaction_verbs() {
+        let (_turn, recorder) = with_recorder();
+        let rspace = RSpace::<Retrieve, Commit>::create().unwrap();
+
+        rspace
+            .transaction(|tx| {
+                tx.get_once_non_durable_sequential(retrieve("metrics-transaction"))?;
+                tx.put_always_non_durable_sequential(commit("metrics-transaction"))?;
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(recorder.counter("rspace.produce.once.non_durable.miss"), 1);
+        assert_eq!(recorder.counter("rspace.consume.always.non_durable.hit"), 1);
+        assert_eq!(
+            recorder
+                .histogram("rspace.consume.always.non_durable.latency")
+                .unwrap()
+                .count,
+            1
+        );
+
+        metrics::clear_recorder().unwrap();
+    }
+
+    #[test]
+    fn metrics_test_nothing_without_recorder() {
+        let _turn = TURN.lock().unwrap_or_else(|err| err.into_inner());
+        metrics::clear_recorder().unwrap();
+        let recorder = InMemoryMetrics::new();
+        let rspace = RSpace::<Retrieve, Commit>::create().unwrap();
+
+        rspace
+            .get_once_non_durable_sequential(retrieve("metrics-unrecorded"))
+            .unwrap();
+
+        assert_eq!(metrics::snapshot(), MetricsSnapshot::default());
+        assert_eq!(recorder.snapshot(), MetricsSnapshot::default());
+    }
+
+    #[test]
+    fn metrics_test_ffi_snapshot() {
+        let _turn = TURN.lock().unwrap_or_else(|err| err.into_inner());
+        metrics::clear_recorder().unwrap();
+        let space = space_new();
+        assert!(space_enable_metrics());
+
+        let mut len = 0;
+        let ptr = unsafe { space_metrics_snapshot(space, &mut len) };
+        assert!(!ptr.is_null());
+        let snapshot =
+            MetricsSnapshot::decode(unsafe { std::slice::from_raw_parts(ptr, len) }).unwrap();
+        unsafe { space_free_bytes(ptr as *mut u8, len) };
+
+        let names: Vec<&str> = snapshot
+            .gauges
+            .iter()
+            .map(|gauge| gauge.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "rspace.store.diskconc.entries",
+                "rspace.store.diskseq.entries",
+                "rspace.store.memconc.entries",
+                "rspace.store.memseq.entries",
+            ]
+        );
+
+        metrics::clear_recorder().unwrap();
+    }
+}
@@ -0,0 +1,73 @@
+#![cfg(feature = "redb")]
+
+#[cfg(test)]
+mod tests {
+    use rspace_plus_plus::keyvaluestore::{KeyValueRead, KeyValueStore, Storage};
+    use rspace_plus_plus::redbstore::RedbStore;
+    use std::path::{Path, PathBuf};
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = Path::new("target")
+            .join(name)
+            .join(std::process::id().to_string());
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn key(i: u16) -> Vec<u8> {
+        [b"k".as_slice(), &i.to_be_bytes()].concat()
+    }
+
+    #[test]
+    fn redbstore_test_iterate_prefix_in_batches() {
+        let dir = scratch("RedbIterateDB");
+        let store = RedbStore::open(&dir).unwrap();
+
+        // more pairs than one batch
+        let mut txn = store.write_txn().unwrap();
+        for i in 0..1000 {
+            txn.put(&key(i), &i.to_be_bytes()).unwrap();
+        }
+        txn.put(b"other", b"?").unwrap();
+
+        let mut seen = vec![];
+        txn.iterate_prefix(b"k", &mut |key, value| {
+            // reading through the transaction from inside f works
+            assert_eq!(txn.get(key)?.as_deref(), Some(value));
+            seen.push(key.to_vec());
+            Ok(true)
+        })
+        .unwrap();
+        assert_eq!(seen, (0..1000).map(key).collect::<Vec<_>>());
+
+        let mut first = vec![];
+        txn.iterate_prefix(b"k", &mut |key, _| {
+            first.push(key.to_vec());
+            Ok(first.len() < 300)
+        })
+        .unwrap();
+        assert_eq!(first, (0..300).map(key).collect::<Vec<_>>());
+        txn.commit().unwrap();
+
+        drop(store);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn redbstore_test_last_drop_closes_database() {
+        let dir = scratch("RedbCloseDB");
+        let first = RedbStore::open(&dir).unwrap();
+        let second = RedbStore::open(&dir).unwrap();
+
+        drop(first);
+        assert!(redb::Database::create(dir.join("data.redb")).is_err());
+        drop(second);
+        drop(redb::Database::create(dir.join("data.redb")).unwrap());
+
+        // and opening it again starts over
+        let reopened = RedbStore::open(&dir).unwrap();
+        assert!(reopened.is_empty().unwrap());
+        drop(reopened);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
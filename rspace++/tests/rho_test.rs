@@ -0,0 +1,318 @@
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+    use rspace_plus_plus::memorystore::MemStore;
+    use rspace_plus_plus::rho;
+    use rspace_plus_plus::rspace::RSpace;
+    use rspace_plus_plus::rtypes::rtypes::{
+        expr::ExprInstance, var::VarInstance, BindPattern, Commit, EVar, Expr, ListParWithRandom,
+        OptionResult, Par, Retrieve, Store, TaggedContinuation, Var,
+    };
+    use rspace_plus_plus::{
+        space_free_bytes, space_get_once_non_durable_sequential, space_new,
+        space_put_once_non_durable_sequential,
+    };
+    use std::collections::BTreeSet;
+    use std::path::Path;
+
+    // (message, type, field, number) of every field declared in a proto file
+    fn fields(path: &Path) -> BTreeSet<(String, String, String, String)> {
+        let text = std::fs::read_to_string(path).unwrap();
+        let mut fields = BTreeSet::new();
+        let mut message = String::new();
+
+        for line in text.lines() {
+            let line = line.split("//").next().unwrap().trim();
+            if let Some(name) = line.strip_prefix("message ") {
+                // messages nested in one line, like Var.WildcardMsg, have no fields
+                if !line.ends_with("{}") {
+                    message = name.trim_end_matches(" {").to_string();
+                }
+                continue;
+            }
+            let Some((declaration, number)) = line.split_once(" = ") else {
+                continue;
+            };
+            if line.starts_with("option") || !line.ends_with(';') {
+                continue;
+            }
+            let Some((kind, name)) = declaration.rsplit_once(' ') else {
+                continue;
+            };
+            let number: String = number.chars().take_while(char::is_ascii_digit).collect();
+            fields.insert((message.clone(), kind.to_string(), name.to_string(), number));
+        }
+        fields
+    }
+
+    fn int(value: i64) -> Par {
+        Par {
+            exprs: vec![Expr {
+                expr_instance: Some(ExprInstance::GInt(value)),
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn string(value: &str) -> Par {
+        Par {
+            exprs: vec![Expr {
+                expr_instance: Some(ExprInstance::GString(value.to_string())),
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn pars(values: &[i64]) -> ListParWithRandom {
+        ListParWithRandom {
+            pars: values.iter().map(|value| int(*value)).collect(),
+            random_state: vec![9; 4],
+        }
+    }
+
+    // for (...@rest <- channel)
+    fn bind_pattern() -> BindPattern {
+        BindPattern {
+            patterns: vec![],
+            remainder: Some(Var {
+                var_instance: Some(VarInstance::FreeVar(0)),
+            }),
+            free_count: 1,
+        }
+    }
+
+    fn space() -> RSpace<Retrieve, Commit, MemStore> {
+        RSpace::create_with_storage(MemStore::new(), MemStore::new()).unwrap()
+    }
+
+    #[test]
+    fn rho_test_fields_match_scala_models() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let scala = fields(&dir.join("../models/src/main/protobuf/RhoTypes.proto"));
+        let ours: BTreeSet<_> = fields(&dir.join("src/main/protobuf/rhotypes.proto"))
+            .union(&fields(&dir.join("src/main/protobuf/rtypes.proto")))
+            .cloned()
+            .collect();
+
+        assert!(scala.len() > 100);
+        let missing: Vec<_> = scala.difference(&ours).collect();
+        assert!(missing.is_empty(), "missing {:?}", missing);
+    }
+
+    #[test]
+    fn rho_test_scala_encoding() {
+        // Par(exprs = Seq(Expr(GInt(7)))) and Par(exprs = Seq(Expr(GString("a")))) from the Scala models
+        assert_eq!(int(7).encode_to_vec(), vec![0x2a, 0x02, 0x10, 0x0e]);
+        assert_eq!(
+            string("a").encode_to_vec(),
+            vec![0x2a, 0x03, 0x1a, 0x01, 0x61]
+        );
+        assert_eq!(
+            Par::decode([0x2a, 0x02, 0x10, 0x0e].as_slice()).unwrap(),
+            int(7)
+        );
+    }
+
+    #[test]
+    fn rho_test_channel_name() {
+        let name = rho::channel_name(&int(7));
+        assert_eq!(name.len(), 64);
+        assert_eq!(name, rho::channel_name(&int(7)));
+        assert_ne!(name, rho::channel_name(&int(8)));
+        assert_ne!(name, rho::channel_name(&string("7")));
+
+        let retrieve = rho::retrieve(int(7), pars(&[1]), "");
+        assert_eq!(retrieve.chan, name);
+        // chan is named after the Par channel even when the sender left it out
+        let unnamed = Retrieve {
+            chan: String::new(),
+            ..retrieve.clone()
+        };
+        assert_eq!(rho::named_retrieve(&unnamed).chan, name);
+        assert_eq!(*rho::named_retrieve(&retrieve), retrieve);
+
+        let commit = Commit {
+            channels: vec![],
+            ..rho::commit(vec![int(7)], vec![String::new()], vec![], "k".into())
+        };
+        assert_eq!(rho::named_commit(&commit).channels, vec![name]);
+    }
+
+    #[test]
+    fn rho_test_pars_through_the_store() {
+        let rspace = space();
+        let channel = int(1);
+
+        assert!(rspace
+            .get_once_non_durable_sequential(rho::retrieve(channel.clone(), pars(&[10, 11]), ""))
+            .unwrap()
+            .is_none());
+        let data = rspace
+            .get_data(Store::MemSeq, &rho::channel_name(&channel))
+            .unwrap();
+        assert_eq!(data[0].pars, Some(pars(&[10, 11])));
+
+        let results = rspace
+            .put_once_non_durable_sequential(rho::commit(
+                vec![channel.clone()],
+                vec![String::new()],
+                vec![bind_pattern()],
+                TaggedContinuation::scala_body_ref(2),
+            ))
+            .unwrap()
+            .unwrap();
+        assert_eq!(results[0].pars, Some(pars(&[10, 11])));
+
+        // a waiting continuation keeps its bind pattern, and a send it matches hands over its pars
+        assert!(rspace
+            .put_once_non_durable_sequential(rho::commit(
+                vec![channel.clone()],
+                vec![String::new()],
+                vec![bind_pattern()],
+                TaggedContinuation::scala_body_ref(3),
+            ))
+            .unwrap()
+            .is_none());
+        let stored = rspace
+            .get_waiting_continuations(Store::MemSeq, &[rho::channel_name(&channel)])
+            .unwrap();
+        assert_eq!(stored[0].bind_pattern, Some(bind_pattern()));
+
+        let result = rspace
+            .get_once_non_durable_sequential(rho::retrieve(channel, pars(&[12]), ""))
+            .unwrap()
+            .unwrap();
+        assert_eq!(result.pars, Some(pars(&[12])));
+        assert_eq!(
+            result.continuation,
+            Some(TaggedContinuation::scala_body_ref(3))
+        );
+    }
+
+    #[test]
+    fn rho_test_bindings() {
+        let rspace = space();
+        let channel = int(1);
+        let free = |level| Par {
+            exprs: vec![Expr {
+                expr_instance: Some(ExprInstance::EVarBody(EVar {
+                    v: Some(Var {
+                        var_instance: Some(VarInstance::FreeVar(level)),
+                    }),
+                })),
+            }],
+            connective_used: true,
+            ..Default::default()
+        };
+        // for (@1, @x <- channel)
+        let one_and_x = BindPattern {
+            patterns: vec![int(1), free(0)],
+            remainder: None,
+            free_count: 1,
+        };
+        // for (@2, @x <- channel)
+        let two_and_x = BindPattern {
+            patterns: vec![int(2), free(0)],
+            ..one_and_x.clone()
+        };
+        let commit = |bind_pattern: &BindPattern| {
+            rho::commit(
+                vec![channel.clone()],
+                vec![String::new()],
+                vec![bind_pattern.clone()],
+                TaggedContinuation::scala_body_ref(1),
+            )
+        };
+
+        assert!(rspace
+            .get_once_non_durable_sequential(rho::retrieve(channel.clone(), pars(&[1, 7]), ""))
+            .unwrap()
+            .is_none());
+        // the datum does not fit, so the consume waits
+        assert!(rspace
+            .put_once_non_durable_sequential(commit(&two_and_x))
+            .unwrap()
+            .is_none());
+
+        let results = rspace
+            .put_once_non_durable_sequential(commit(&one_and_x))
+            .unwrap()
+            .unwrap();
+        assert_eq!(results[0].pars, Some(pars(&[1, 7])));
+        assert_eq!(results[0].bindings, Some(pars(&[7])));
+
+        // a send passes over the continuation it does not fit
+        assert!(rspace
+            .get_once_non_durable_sequential(rho::retrieve(channel.clone(), pars(&[1, 8]), ""))
+            .unwrap()
+            .is_none());
+        let result = rspace
+            .get_once_non_durable_sequential(rho::retrieve(channel, pars(&[2, 9]), ""))
+            .unwrap()
+            .unwrap();
+        assert_eq!(result.bindings, Some(pars(&[9])));
+    }
+
+    #[test]
+    fn rho_test_uneven_bind_patterns() {
+        let rspace = space();
+        let commit = rho::commit(
+            vec![int(1), int(2)],
+            vec![String::new(), String::new()],
+            vec![bind_pattern()],
+            "k".into(),
+        );
+
+        assert!(rspace.put_once_non_durable_sequential(commit).is_err());
+        assert!(rspace.channels(Store::MemSeq).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rho_test_ffi() {
+        let rspace = space_new();
+        let channel = string("rho-ffi");
+
+        let buf = rho::commit(
+            vec![channel.clone()],
+            vec![String::new()],
+            vec![bind_pattern()],
+            "k".into(),
+        )
+        .encode_to_vec();
+        let mut len = 0;
+        let mut error = false;
+        assert!(unsafe {
+            space_put_once_non_durable_sequential(
+                rspace,
+                buf.as_ptr(),
+                buf.len(),
+                &mut len,
+                &mut error,
+            )
+        }
+        .is_null());
+
+        // Scala sends the Par channel alone and leaves naming it to the space
+        let retrieve = Retrieve {
+            chan: String::new(),
+            ..rho::retrieve(channel, pars(&[5]), "")
+        };
+        let buf = retrieve.encode_to_vec();
+        let pres = unsafe {
+            space_get_once_non_durable_sequential(
+                rspace,
+                buf.as_ptr(),
+                buf.len(),
+                &mut len,
+                &mut error,
+            )
+        };
+        assert!(!pres.is_null());
+
+        let result =
+            OptionResult::decode(unsafe { std::slice::from_raw_parts(pres, len) }).unwrap();
+        unsafe { space_free_bytes(pres as *mut u8, len) };
+        assert_eq!(result.pars, Some(pars(&[5])));
+        assert_eq!(result.continuation, Some("k".into()));
+    }
+}
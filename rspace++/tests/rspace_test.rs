@@ -1,6 +1,11 @@
 #[cfg(test)]
 mod tests {
+    use prost::Message;
+    use rspace_plus_plus::rtypes::rtypes::{Store, StoreMap};
     use rspace_plus_plus::setup::Setup;
+    use rspace_plus_plus::{
+        space_free_bytes, space_new, space_put_once_non_durable_sequential, space_to_map,
+    };
 
     //memseq
     #[test]
@@ -39,28 +44,28 @@ mod tests {
             String::from("I am the continuation, for now..."),
         );
 
-        let _pres1 = rspace.get_once_non_durable_sequential(retrieve1);
-        let _pres2 = rspace.get_once_non_durable_sequential(retrieve2);
-        let cres1 = rspace.put_always_non_durable_sequential(commit1);
+        let _pres1 = rspace.get_once_non_durable_sequential(retrieve1).unwrap();
+        let _pres2 = rspace.get_once_non_durable_sequential(retrieve2).unwrap();
+        let cres1 = rspace.put_always_non_durable_sequential(commit1).unwrap();
         assert_eq!(cres1.unwrap().len(), 1);
         assert!(!rspace.is_memseq_empty());
 
-        let cres2 = rspace.put_always_non_durable_sequential(commit2);
+        let cres2 = rspace.put_always_non_durable_sequential(commit2).unwrap();
 
         assert_eq!(cres2.unwrap().len(), 1);
         assert!(rspace.is_memseq_empty());
 
-        let cres3 = rspace.put_always_non_durable_sequential(commit3);
+        let cres3 = rspace.put_always_non_durable_sequential(commit3).unwrap();
 
         assert!(cres3.is_none());
         assert!(!rspace.is_memseq_empty());
 
-        let pres3 = rspace.get_once_non_durable_sequential(retrieve3);
+        let pres3 = rspace.get_once_non_durable_sequential(retrieve3).unwrap();
 
         assert!(pres3.is_some());
         assert!(!rspace.is_memseq_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     #[test]
@@ -84,10 +89,10 @@ mod tests {
             String::from("I am the continuation, for now..."),
         );
 
-        let pres1 = rspace.get_once_non_durable_sequential(retrieve1);
-        let pres2 = rspace.get_once_non_durable_sequential(retrieve2);
+        let pres1 = rspace.get_once_non_durable_sequential(retrieve1).unwrap();
+        let pres2 = rspace.get_once_non_durable_sequential(retrieve2).unwrap();
 
-        let cres = rspace.put_once_non_durable_sequential(commit);
+        let cres = rspace.put_once_non_durable_sequential(commit).unwrap();
 
         assert!(pres1.is_none());
         assert!(pres2.is_none());
@@ -95,7 +100,7 @@ mod tests {
         assert_eq!(cres.unwrap().len(), 2);
         assert!(rspace.is_memseq_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     #[test]
@@ -114,14 +119,14 @@ mod tests {
             String::from("I am the continuation, for now..."),
         );
 
-        let pres = rspace.get_once_non_durable_sequential(retrieve);
-        let cres = rspace.put_once_non_durable_sequential(commit);
+        let pres = rspace.get_once_non_durable_sequential(retrieve).unwrap();
+        let cres = rspace.put_once_non_durable_sequential(commit).unwrap();
 
         assert!(pres.is_none());
         assert!(cres.is_some());
         assert!(rspace.is_memseq_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     #[test]
@@ -139,14 +144,14 @@ mod tests {
             setup.alice.clone(),
             Setup::get_city_field(setup.alice),
         );
-        let cres = rspace.put_once_non_durable_sequential(commit);
-        let pres = rspace.get_once_non_durable_sequential(retrieve);
+        let cres = rspace.put_once_non_durable_sequential(commit).unwrap();
+        let pres = rspace.get_once_non_durable_sequential(retrieve).unwrap();
 
         assert!(cres.is_none());
         assert!(pres.is_some());
         assert!(rspace.is_memseq_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     #[test]
@@ -165,14 +170,14 @@ mod tests {
             Setup::get_city_field(setup.carol),
         );
 
-        let cres = rspace.put_once_non_durable_sequential(commit);
-        let pres = rspace.get_once_non_durable_sequential(retrieve);
+        let cres = rspace.put_once_non_durable_sequential(commit).unwrap();
+        let pres = rspace.get_once_non_durable_sequential(retrieve).unwrap();
 
         assert!(cres.is_none());
         assert!(pres.is_none());
         assert!(!rspace.is_memseq_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     #[test]
@@ -191,17 +196,17 @@ mod tests {
             Setup::get_city_field(setup.alice),
         );
 
-        let cres = rspace.put_always_non_durable_sequential(commit);
+        let cres = rspace.put_always_non_durable_sequential(commit).unwrap();
 
         assert!(cres.is_none());
         assert!(!rspace.is_memseq_empty());
 
-        let pres = rspace.get_once_non_durable_sequential(retrieve);
+        let pres = rspace.get_once_non_durable_sequential(retrieve).unwrap();
 
         assert!(pres.is_some());
         assert!(!rspace.is_memseq_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     #[test]
@@ -220,18 +225,18 @@ mod tests {
             String::from("I am the continuation, for now..."),
         );
 
-        let pres = rspace.get_always_non_durable_sequential(retrieve);
+        let pres = rspace.get_always_non_durable_sequential(retrieve).unwrap();
 
         assert!(pres.is_none());
         assert!(!rspace.is_memseq_empty());
 
-        let cres = rspace.put_once_non_durable_sequential(commit);
+        let cres = rspace.put_once_non_durable_sequential(commit).unwrap();
 
         assert!(cres.is_some());
         assert_eq!(cres.unwrap().len(), 1);
         assert!(!rspace.is_memseq_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     #[test]
@@ -260,23 +265,23 @@ mod tests {
             String::from("I am the continuation, for now..."),
         );
 
-        let cres1 = rspace.put_once_non_durable_sequential(commit1);
+        let cres1 = rspace.put_once_non_durable_sequential(commit1).unwrap();
 
         assert!(cres1.is_none());
         assert!(!rspace.is_memseq_empty());
 
-        let pres1 = rspace.get_always_non_durable_sequential(retrieve1);
+        let pres1 = rspace.get_always_non_durable_sequential(retrieve1).unwrap();
 
         assert!(pres1.is_some());
         assert!((rspace.is_memseq_empty()));
 
-        let pres2 = rspace.get_always_non_durable_sequential(retrieve2);
-        let _cres2 = rspace.put_once_non_durable_sequential(commit2);
+        let pres2 = rspace.get_always_non_durable_sequential(retrieve2).unwrap();
+        let _cres2 = rspace.put_once_non_durable_sequential(commit2).unwrap();
 
         assert!(pres2.is_none());
         assert!(!rspace.is_memseq_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     //memconc
@@ -315,29 +320,29 @@ mod tests {
             setup.alice.clone(),
             Setup::get_city_field(setup.alice),
         );
-        let _pres1 = rspace.get_once_non_durable_concurrent(retrieve1);
-        let _pres2 = rspace.get_once_non_durable_concurrent(retrieve2);
-        let cres1 = rspace.put_always_non_durable_concurrent(commit1);
+        let _pres1 = rspace.get_once_non_durable_concurrent(retrieve1).unwrap();
+        let _pres2 = rspace.get_once_non_durable_concurrent(retrieve2).unwrap();
+        let cres1 = rspace.put_always_non_durable_concurrent(commit1).unwrap();
 
         assert_eq!(cres1.unwrap().len(), 1);
         assert!(!rspace.is_memconc_empty());
 
-        let cres2 = rspace.put_always_non_durable_concurrent(commit2);
+        let cres2 = rspace.put_always_non_durable_concurrent(commit2).unwrap();
 
         assert_eq!(cres2.unwrap().len(), 1);
         assert!(rspace.is_memconc_empty());
 
-        let cres3 = rspace.put_always_non_durable_concurrent(commit3);
+        let cres3 = rspace.put_always_non_durable_concurrent(commit3).unwrap();
 
         assert!(cres3.is_none());
         assert!(!rspace.is_memconc_empty());
 
-        let pres3 = rspace.get_once_non_durable_concurrent(retrieve3);
+        let pres3 = rspace.get_once_non_durable_concurrent(retrieve3).unwrap();
 
         assert!(pres3.is_some());
         assert!(!rspace.is_memconc_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     #[test]
@@ -361,10 +366,10 @@ mod tests {
             String::from("I am the continuation, for now..."),
         );
 
-        let pres1 = rspace.get_once_non_durable_concurrent(retrieve1);
-        let pres2 = rspace.get_once_non_durable_concurrent(retrieve2);
+        let pres1 = rspace.get_once_non_durable_concurrent(retrieve1).unwrap();
+        let pres2 = rspace.get_once_non_durable_concurrent(retrieve2).unwrap();
 
-        let cres = rspace.put_once_non_durable_concurrent(commit);
+        let cres = rspace.put_once_non_durable_concurrent(commit).unwrap();
 
         assert!(pres1.is_none());
         assert!(pres2.is_none());
@@ -372,7 +377,7 @@ mod tests {
         assert_eq!(cres.unwrap().len(), 2);
         assert!(rspace.is_memconc_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     #[test]
@@ -390,14 +395,14 @@ mod tests {
             vec![setup.name_match_case],
             String::from("I am the continuation, for now..."),
         );
-        let pres = rspace.get_once_non_durable_concurrent(retrieve);
-        let cres = rspace.put_once_non_durable_concurrent(commit);
+        let pres = rspace.get_once_non_durable_concurrent(retrieve).unwrap();
+        let cres = rspace.put_once_non_durable_concurrent(commit).unwrap();
 
         assert!(pres.is_none());
         assert!(cres.is_some());
         assert!(rspace.is_memconc_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     #[test]
@@ -416,14 +421,14 @@ mod tests {
             Setup::get_city_field(setup.alice),
         );
 
-        let cres = rspace.put_once_non_durable_concurrent(commit);
-        let pres = rspace.get_once_non_durable_concurrent(retrieve);
+        let cres = rspace.put_once_non_durable_concurrent(commit).unwrap();
+        let pres = rspace.get_once_non_durable_concurrent(retrieve).unwrap();
 
         assert!(cres.is_none());
         assert!(pres.is_some());
         assert!(rspace.is_memconc_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     #[test]
@@ -442,14 +447,14 @@ mod tests {
             Setup::get_city_field(setup.carol),
         );
 
-        let cres = rspace.put_once_non_durable_concurrent(commit);
-        let pres = rspace.get_once_non_durable_concurrent(retrieve);
+        let cres = rspace.put_once_non_durable_concurrent(commit).unwrap();
+        let pres = rspace.get_once_non_durable_concurrent(retrieve).unwrap();
 
         assert!(cres.is_none());
         assert!(pres.is_none());
         assert!(!rspace.is_memconc_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     #[test]
@@ -468,17 +473,17 @@ mod tests {
             Setup::get_city_field(setup.alice),
         );
 
-        let cres = rspace.put_always_non_durable_concurrent(commit);
+        let cres = rspace.put_always_non_durable_concurrent(commit).unwrap();
 
         assert!(cres.is_none());
         assert!(!rspace.is_memconc_empty());
 
-        let pres = rspace.get_once_non_durable_concurrent(retrieve);
+        let pres = rspace.get_once_non_durable_concurrent(retrieve).unwrap();
 
         assert!(pres.is_some());
         assert!(!rspace.is_memconc_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     #[test]
@@ -497,18 +502,18 @@ mod tests {
             String::from("I am the continuation, for now..."),
         );
 
-        let pres = rspace.get_always_non_durable_concurrent(retrieve);
+        let pres = rspace.get_always_non_durable_concurrent(retrieve).unwrap();
 
         assert!(pres.is_none());
         assert!(!rspace.is_memconc_empty());
 
-        let cres = rspace.put_once_non_durable_concurrent(commit);
+        let cres = rspace.put_once_non_durable_concurrent(commit).unwrap();
 
         assert!(cres.is_some());
         assert_eq!(cres.unwrap().len(), 1);
         assert!(!rspace.is_memconc_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     #[test]
@@ -537,23 +542,23 @@ mod tests {
             String::from("I am the continuation, for now..."),
         );
 
-        let cres1 = rspace.put_once_non_durable_concurrent(commit1);
+        let cres1 = rspace.put_once_non_durable_concurrent(commit1).unwrap();
 
         assert!(cres1.is_none());
         assert!(!rspace.is_memconc_empty());
 
-        let pres1 = rspace.get_always_non_durable_concurrent(retrieve1);
+        let pres1 = rspace.get_always_non_durable_concurrent(retrieve1).unwrap();
 
         assert!(pres1.is_some());
         assert!((rspace.is_memconc_empty()));
 
-        let pres2 = rspace.get_always_non_durable_concurrent(retrieve2);
-        let _cres2 = rspace.put_once_non_durable_concurrent(commit2);
+        let pres2 = rspace.get_always_non_durable_concurrent(retrieve2).unwrap();
+        let _cres2 = rspace.put_once_non_durable_concurrent(commit2).unwrap();
 
         assert!(pres2.is_none());
         assert!(!rspace.is_memconc_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     //diskconc
@@ -593,30 +598,30 @@ mod tests {
             Setup::get_city_field(setup.alice),
         );
 
-        let _pres1 = rspace.get_once_durable_concurrent(retrieve1);
-        let _pres2 = rspace.get_once_durable_concurrent(retrieve2);
+        let _pres1 = rspace.get_once_durable_concurrent(retrieve1).unwrap();
+        let _pres2 = rspace.get_once_durable_concurrent(retrieve2).unwrap();
         rspace.print_data("friends");
-        let cres1 = rspace.put_always_durable_concurrent(commit1);
+        let cres1 = rspace.put_always_durable_concurrent(commit1).unwrap();
         rspace.print_data("friends");
         assert_eq!(cres1.unwrap().len(), 1);
         assert!(!rspace.is_diskconc_empty());
 
-        let cres2 = rspace.put_always_durable_concurrent(commit2);
+        let cres2 = rspace.put_always_durable_concurrent(commit2).unwrap();
 
         assert_eq!(cres2.unwrap().len(), 1);
         assert!(rspace.is_diskconc_empty());
 
-        let cres3 = rspace.put_always_durable_concurrent(commit3);
+        let cres3 = rspace.put_always_durable_concurrent(commit3).unwrap();
 
         assert!(cres3.is_none());
         assert!(!rspace.is_diskconc_empty());
 
-        let pres3 = rspace.get_once_durable_concurrent(retrieve3);
+        let pres3 = rspace.get_once_durable_concurrent(retrieve3).unwrap();
 
         assert!(pres3.is_some());
         assert!(!rspace.is_diskconc_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     #[test]
@@ -640,10 +645,10 @@ mod tests {
             String::from("I am the continuation, for now..."),
         );
 
-        let pres1 = rspace.get_once_durable_concurrent(retrieve1);
-        let pres2 = rspace.get_once_durable_concurrent(retrieve2);
+        let pres1 = rspace.get_once_durable_concurrent(retrieve1).unwrap();
+        let pres2 = rspace.get_once_durable_concurrent(retrieve2).unwrap();
 
-        let cres = rspace.put_once_durable_concurrent(commit);
+        let cres = rspace.put_once_durable_concurrent(commit).unwrap();
 
         assert!(pres1.is_none());
         assert!(pres2.is_none());
@@ -651,7 +656,7 @@ mod tests {
         assert_eq!(cres.unwrap().len(), 2);
         assert!(rspace.is_diskconc_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     #[test]
@@ -670,14 +675,14 @@ mod tests {
             String::from("I am the continuation, for now..."),
         );
 
-        let pres = rspace.get_once_durable_concurrent(retrieve);
-        let cres = rspace.put_once_durable_concurrent(commit);
+        let pres = rspace.get_once_durable_concurrent(retrieve).unwrap();
+        let cres = rspace.put_once_durable_concurrent(commit).unwrap();
 
         assert!(pres.is_none());
         assert!(cres.is_some());
         assert!(rspace.is_diskconc_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     #[test]
@@ -696,14 +701,14 @@ mod tests {
             Setup::get_city_field(setup.alice),
         );
 
-        let cres = rspace.put_once_durable_concurrent(commit);
-        let pres = rspace.get_once_durable_concurrent(retrieve);
+        let cres = rspace.put_once_durable_concurrent(commit).unwrap();
+        let pres = rspace.get_once_durable_concurrent(retrieve).unwrap();
 
         assert!(cres.is_none());
         assert!(pres.is_some());
         assert!(rspace.is_diskconc_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     #[test]
@@ -721,14 +726,14 @@ mod tests {
             setup.carol.clone(),
             Setup::get_city_field(setup.carol),
         );
-        let cres = rspace.put_once_durable_concurrent(commit);
-        let pres = rspace.get_once_durable_concurrent(retrieve);
+        let cres = rspace.put_once_durable_concurrent(commit).unwrap();
+        let pres = rspace.get_once_durable_concurrent(retrieve).unwrap();
 
         assert!(cres.is_none());
         assert!(pres.is_none());
         assert!(!rspace.is_diskconc_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     #[test]
@@ -747,17 +752,17 @@ mod tests {
             Setup::get_city_field(setup.alice),
         );
 
-        let cres = rspace.put_always_durable_concurrent(commit);
+        let cres = rspace.put_always_durable_concurrent(commit).unwrap();
 
         assert!(cres.is_none());
         assert!(!rspace.is_diskconc_empty());
 
-        let pres = rspace.get_once_durable_concurrent(retrieve);
+        let pres = rspace.get_once_durable_concurrent(retrieve).unwrap();
 
         assert!(pres.is_some());
         assert!(!rspace.is_diskconc_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     #[test]
@@ -776,18 +781,18 @@ mod tests {
             String::from("I am the continuation, for now..."),
         );
 
-        let pres = rspace.get_always_durable_concurrent(retrieve);
+        let pres = rspace.get_always_durable_concurrent(retrieve).unwrap();
 
         assert!(pres.is_none());
         assert!(!rspace.is_diskconc_empty());
 
-        let cres = rspace.put_once_durable_concurrent(commit);
+        let cres = rspace.put_once_durable_concurrent(commit).unwrap();
 
         assert!(cres.is_some());
         assert_eq!(cres.unwrap().len(), 1);
         assert!(!rspace.is_diskconc_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     #[test]
@@ -816,23 +821,23 @@ mod tests {
             String::from("I am the continuation, for now..."),
         );
 
-        let cres1 = rspace.put_once_durable_concurrent(commit1);
+        let cres1 = rspace.put_once_durable_concurrent(commit1).unwrap();
 
         assert!(cres1.is_none());
         assert!(!rspace.is_diskconc_empty());
 
-        let pres1 = rspace.get_always_durable_concurrent(retrieve1);
+        let pres1 = rspace.get_always_durable_concurrent(retrieve1).unwrap();
 
         assert!(pres1.is_some());
         assert!((rspace.is_diskconc_empty()));
 
-        let pres2 = rspace.get_always_durable_concurrent(retrieve2);
-        let _cres2 = rspace.put_once_durable_concurrent(commit2);
+        let pres2 = rspace.get_always_durable_concurrent(retrieve2).unwrap();
+        let _cres2 = rspace.put_once_durable_concurrent(commit2).unwrap();
 
         assert!(pres2.is_none());
         assert!(!rspace.is_diskconc_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     //diskseq
@@ -872,29 +877,29 @@ mod tests {
             Setup::get_city_field(setup.alice),
         );
 
-        let _pres1 = rspace.get_once_durable_sequential(retrieve1);
-        let _pres2 = rspace.get_once_durable_sequential(retrieve2);
-        let cres1 = rspace.put_always_durable_sequential(commit1);
+        let _pres1 = rspace.get_once_durable_sequential(retrieve1).unwrap();
+        let _pres2 = rspace.get_once_durable_sequential(retrieve2).unwrap();
+        let cres1 = rspace.put_always_durable_sequential(commit1).unwrap();
 
         assert_eq!(cres1.unwrap().len(), 1);
         assert!(!rspace.is_diskseq_empty());
 
-        let cres2 = rspace.put_always_durable_sequential(commit2);
+        let cres2 = rspace.put_always_durable_sequential(commit2).unwrap();
 
         assert_eq!(cres2.unwrap().len(), 1);
         assert!(rspace.is_diskseq_empty());
 
-        let cres3 = rspace.put_always_durable_sequential(commit3);
+        let cres3 = rspace.put_always_durable_sequential(commit3).unwrap();
 
         assert!(cres3.is_none());
         assert!(!rspace.is_diskseq_empty());
 
-        let pres3 = rspace.get_once_durable_sequential(retrieve3);
+        let pres3 = rspace.get_once_durable_sequential(retrieve3).unwrap();
 
         assert!(pres3.is_some());
         assert!(!rspace.is_diskseq_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     #[test]
@@ -917,10 +922,10 @@ mod tests {
             vec![setup.state_match_case.clone(), setup.state_match_case],
             String::from("I am the continuation, for now..."),
         );
-        let pres1 = rspace.get_once_durable_sequential(retrieve1);
-        let pres2 = rspace.get_once_durable_sequential(retrieve2);
+        let pres1 = rspace.get_once_durable_sequential(retrieve1).unwrap();
+        let pres2 = rspace.get_once_durable_sequential(retrieve2).unwrap();
 
-        let cres = rspace.put_once_durable_sequential(commit);
+        let cres = rspace.put_once_durable_sequential(commit).unwrap();
 
         assert!(pres1.is_none());
         assert!(pres2.is_none());
@@ -928,7 +933,7 @@ mod tests {
         assert_eq!(cres.unwrap().len(), 2);
         assert!(rspace.is_diskseq_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     #[test]
@@ -946,14 +951,14 @@ mod tests {
             vec![setup.name_match_case],
             String::from("I am the continuation, for now..."),
         );
-        let pres = rspace.get_once_durable_sequential(retrieve);
-        let cres = rspace.put_once_durable_sequential(commit);
+        let pres = rspace.get_once_durable_sequential(retrieve).unwrap();
+        let cres = rspace.put_once_durable_sequential(commit).unwrap();
 
         assert!(pres.is_none());
         assert!(cres.is_some());
         assert!(rspace.is_diskseq_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     #[test]
@@ -971,14 +976,14 @@ mod tests {
             setup.alice.clone(),
             Setup::get_city_field(setup.alice),
         );
-        let cres = rspace.put_once_durable_sequential(commit);
-        let pres = rspace.get_once_durable_sequential(retrieve);
+        let cres = rspace.put_once_durable_sequential(commit).unwrap();
+        let pres = rspace.get_once_durable_sequential(retrieve).unwrap();
 
         assert!(cres.is_none());
         assert!(pres.is_some());
         assert!(rspace.is_diskseq_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     #[test]
@@ -997,14 +1002,14 @@ mod tests {
             Setup::get_city_field(setup.carol),
         );
 
-        let cres = rspace.put_once_durable_sequential(commit);
-        let pres = rspace.get_once_durable_sequential(retrieve);
+        let cres = rspace.put_once_durable_sequential(commit).unwrap();
+        let pres = rspace.get_once_durable_sequential(retrieve).unwrap();
 
         assert!(cres.is_none());
         assert!(pres.is_none());
         assert!(!rspace.is_diskseq_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     #[test]
@@ -1022,17 +1027,17 @@ mod tests {
             setup.alice.clone(),
             Setup::get_city_field(setup.alice),
         );
-        let cres = rspace.put_always_durable_sequential(commit);
+        let cres = rspace.put_always_durable_sequential(commit).unwrap();
 
         assert!(cres.is_none());
         assert!(!rspace.is_diskseq_empty());
 
-        let pres = rspace.get_once_durable_sequential(retrieve);
+        let pres = rspace.get_once_durable_sequential(retrieve).unwrap();
 
         assert!(pres.is_some());
         assert!(!rspace.is_diskseq_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     #[test]
@@ -1051,18 +1056,18 @@ mod tests {
             String::from("I am the continuation, for now..."),
         );
 
-        let pres = rspace.get_always_durable_sequential(retrieve);
+        let pres = rspace.get_always_durable_sequential(retrieve).unwrap();
 
         assert!(pres.is_none());
         assert!(!rspace.is_diskseq_empty());
 
-        let cres = rspace.put_once_durable_sequential(commit);
+        let cres = rspace.put_once_durable_sequential(commit).unwrap();
 
         assert!(cres.is_some());
         assert_eq!(cres.unwrap().len(), 1);
         assert!(!rspace.is_diskseq_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
     }
 
     #[test]
@@ -1090,23 +1095,120 @@ mod tests {
             vec![setup.city_match_case],
             String::from("I am the continuation, for now..."),
         );
-        let cres1 = rspace.put_once_durable_sequential(commit1);
+        let cres1 = rspace.put_once_durable_sequential(commit1).unwrap();
 
         assert!(cres1.is_none());
         assert!(!rspace.is_diskseq_empty());
 
-        let pres1 = rspace.get_always_durable_sequential(retrieve1);
+        let pres1 = rspace.get_always_durable_sequential(retrieve1).unwrap();
 
         assert!(pres1.is_some());
         assert!((rspace.is_diskseq_empty()));
 
-        let pres2 = rspace.get_always_durable_sequential(retrieve2);
-        let _cres2 = rspace.put_once_durable_sequential(commit2);
+        let pres2 = rspace.get_always_durable_sequential(retrieve2).unwrap();
+        let _cres2 = rspace.put_once_durable_sequential(commit2).unwrap();
 
         assert!(pres2.is_none());
         assert!(!rspace.is_diskseq_empty());
 
-        let _ = rspace.clear_store();
+        rspace.clear_store();
+    }
+
+    #[test]
+    fn rspace_test_flush() {
+        let setup = Setup::new();
+        let rspace = setup.rspace;
+
+        let commit = Setup::create_commit(
+            vec![String::from("rspace-flush")],
+            vec![setup.city_match_case],
+            String::from("I am the continuation, for now..."),
+        );
+        let cres = rspace.put_once_durable_sequential(commit).unwrap();
+
+        assert!(cres.is_none());
+        assert!(rspace.flush().is_ok());
+
+        let retrieve = Setup::create_retrieve(
+            String::from("rspace-flush"),
+            setup.alice.clone(),
+            Setup::get_city_field(setup.alice),
+        );
+        let pres = rspace.get_once_durable_sequential(retrieve).unwrap();
+
+        assert!(pres.is_some());
+    }
+
+    #[test]
+    fn rspace_test_ffi_to_map() {
+        let setup = Setup::new();
+        let space = space_new();
+
+        let commit = Setup::create_commit(
+            vec![String::from("rspace-ffi-friends")],
+            vec![setup.city_match_case],
+            String::from("I am the continuation, for now..."),
+        );
+        let buf = commit.encode_to_vec();
+        let mut len = 0;
+        let mut error = false;
+        let cres = unsafe {
+            space_put_once_non_durable_sequential(
+                space,
+                buf.as_ptr(),
+                buf.len(),
+                &mut len,
+                &mut error,
+            )
+        };
+        assert!(cres.is_null());
+        assert!(!error);
+
+        let ptr = unsafe { space_to_map(space, Store::MemSeq as i32, &mut len) };
+        assert!(!ptr.is_null());
+
+        let map = StoreMap::decode(unsafe { std::slice::from_raw_parts(ptr, len) }).unwrap();
+        unsafe { space_free_bytes(ptr as *mut u8, len) };
+
+        assert_eq!(map.rows.len(), 1);
+        assert_eq!(
+            map.rows[0].channels,
+            vec![String::from("rspace-ffi-friends")]
+        );
+        assert_eq!(map.rows[0].continuations.len(), 1);
+        assert!(map.rows[0].data.is_empty());
+    }
+
+    #[test]
+    fn rspace_test_ffi_error() {
+        let setup = Setup::new();
+        let space = space_new();
+
+        // one channel and no pattern is refused, not stored
+        let commit = Setup::create_commit(
+            vec![String::from("rspace-ffi-uneven")],
+            vec![],
+            String::from("I am the continuation, for now..."),
+        );
+        assert!(setup
+            .rspace
+            .put_once_non_durable_sequential(commit.clone())
+            .is_err());
+
+        let buf = commit.encode_to_vec();
+        let mut len = 0;
+        let mut error = false;
+        let cres = unsafe {
+            space_put_once_non_durable_sequential(
+                space,
+                buf.as_ptr(),
+                buf.len(),
+                &mut len,
+                &mut error,
+            )
+        };
+        assert!(cres.is_null());
+        assert!(error);
     }
 }
 
@@ -1118,9 +1220,9 @@ mod tests {
 //     let binding = "chan1".to_string();
 //     let channels: Vec<&str> = vec! [&binding,&binding,&binding];
 
-//     let cres = rspace.put_once_non_durable_sequential(channels, vec! [city_match,state_match,name_match], Printer);
+//     let cres = rspace.put_once_non_durable_sequential(channels, vec! [city_match,state_match,name_match], Printer).unwrap();
 //     rspace.print_data(&binding);
-//     let pres = rspace.get_once_non_durable_sequential(&binding, setup.emptyEntry);
+//     let pres = rspace.get_once_non_durable_sequential(&binding, setup.emptyEntry).unwrap();
 //     rspace.print_data(&binding);
 
 //     println!("\npres {:?}", pres);
@@ -1151,11 +1253,11 @@ mod tests {
 //         let rspace_clone = Arc::clone(&rspace);
 //         let handle = thread::spawn(move || {
 //             //putting this one on allows the output to go through but its nonsense
-//             //let pres = rspace_clone.put_once_non_durable_concurrent(vec! [&binding], vec! [pos_match],Printer);
+//             //let pres = rspace_clone.put_once_non_durable_concurrent(vec! [&binding], vec! [pos_match],Printer).unwrap();
 
 //             //this one makes sense to put in the data by calling get knowing it puts the entry in the db
 //             //printouts during test show them going as threaded and out of order
-//             let pres = rspace_clone.get_once_non_durable_concurrent(&binding, e);
+//             let pres = rspace_clone.get_once_non_durable_concurrent(&binding, e).unwrap();
 //             //println!("pres value: {:?}", pres);
 
 //             //println!("value: {}", pres.unwrap().data.posStr);
@@ -1184,7 +1286,7 @@ mod tests {
 //     //     let mut e: Entry = setup.emptyEntry.clone();
 //     //     e.pos = i;
 //     //     e.posStr = i.to_string();
-//     //     let pres = rspace.get_once_non_durable_sequential(&binding, e);
+//     //     let pres = rspace.get_once_non_durable_sequential(&binding, e).unwrap();
 //     //     // let v= pres.data;
 //     //     // println!("i: {}", i);
 //     //     // println!("v pos: {}", v.pos);
@@ -1203,11 +1305,11 @@ mod tests {
 //         let rspace_clone = Arc::clone(&rspace);
 //         let handle = thread::spawn(move || {
 //             //putting this one on allows the output to go through but its nonsense
-//             let pres = rspace_clone.put_once_non_durable_concurrent(vec! [&binding], vec! [pos_match],Printer);
+//             let pres = rspace_clone.put_once_non_durable_concurrent(vec! [&binding], vec! [pos_match],Printer).unwrap();
 
 //             //this one makes sense to put in the data by calling get knowing it puts the entry in the db
 //             //printouts during test show them going as threaded and out of order
-//             //let pres = rspace_clone.get_once_non_durable_concurrent(&binding, e);
+//             //let pres = rspace_clone.get_once_non_durable_concurrent(&binding, e).unwrap();
 
 //             //println!("pres value: {:?}", pres);
 //             // let v= pres.unwrap().data;
@@ -1225,9 +1327,9 @@ mod tests {
 //     }
 //     println!("\nended gets\n");
 
-//     //     let cres = rspace.put_once_non_durable_concurrent(channels, vec! [city_match], Printer);
+//     //     let cres = rspace.put_once_non_durable_concurrent(channels, vec! [city_match], Printer).unwrap();
 //     //     rspace.print_data(&binding);
-//     //     let pres = rspace.get_once_non_durable_sequential(&binding, setup.emptyEntry);
+//     //     let pres = rspace.get_once_non_durable_sequential(&binding, setup.emptyEntry).unwrap();
 
 //     println!("\nbegin print state of db\n");
 //     rspace.print_data(&"chan1".to_string());
@@ -0,0 +1,256 @@
+#[cfg(test)]
+mod tests {
+    use rspace_plus_plus::rtypes::rtypes::Store;
+    use rspace_plus_plus::setup::Setup;
+    use rspace_plus_plus::trace::Event;
+
+    #[test]
+    fn transaction_test_commit_returns_comms() {
+        let setup = Setup::new();
+        let rspace = setup.rspace;
+
+        let retrieve1 = Setup::create_retrieve(
+            String::from("friends"),
+            setup.alice.clone(),
+            Setup::get_city_field(setup.alice.clone()),
+        );
+        let retrieve2 = Setup::create_retrieve(
+            String::from("friends"),
+            setup.carol.clone(),
+            Setup::get_city_field(setup.carol),
+        );
+        let commit = Setup::create_commit(
+            vec![String::from("friends")],
+            vec![setup.city_match_case],
+            String::from("I am the continuation, for now..."),
+        );
+
+        let comms = rspace
+            .transaction(|tx| {
+                let pres1 = tx.get_once_non_durable_sequential(retrieve1)?;
+                let pres2 = tx.get_once_non_durable_sequential(retrieve2)?;
+                let cres = tx.put_once_non_durable_sequential(commit)?;
+
+                assert!(pres1.is_none());
+                assert!(pres2.is_none());
+                assert!(cres.is_some());
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(comms.len(), 1);
+        assert_eq!(comms[0].channels, vec![String::from("friends")]);
+        assert_eq!(comms[0].results[0].data, Some(setup.alice));
+        assert!(!rspace.is_memseq_empty());
+    }
+
+    #[test]
+    fn transaction_test_rollback_on_error() {
+        let setup = Setup::new();
+        let rspace = setup.rspace;
+
+        let retrieve = Setup::create_retrieve(
+            String::from("friends"),
+            setup.alice.clone(),
+            Setup::get_city_field(setup.alice),
+        );
+
+        let res = rspace.transaction(|tx| {
+            let pres = tx.get_once_non_durable_concurrent(retrieve)?;
+            assert!(pres.is_none());
+            Err("deploy failed".into())
+        });
+
+        assert!(res.is_err());
+        assert!(rspace.is_memconc_empty());
+    }
+
+    #[test]
+    fn transaction_test_staged_until_commit() {
+        let setup = Setup::new();
+        let rspace = setup.rspace;
+
+        let retrieve = Setup::create_retrieve(
+            String::from("friends"),
+            setup.bob.clone(),
+            Setup::get_last_name_field(setup.bob),
+        );
+        let commit = Setup::create_commit(
+            vec![String::from("friends")],
+            vec![setup.name_match_case],
+            String::from("I am the continuation, for now..."),
+        );
+
+        let pres = rspace.get_once_non_durable_sequential(retrieve).unwrap();
+        assert!(pres.is_none());
+
+        let mut tx = rspace.begin_transaction();
+        let cres = tx.put_once_non_durable_sequential(commit.clone()).unwrap();
+        assert!(cres.is_some());
+        assert!(!rspace.is_memseq_empty());
+        tx.rollback();
+
+        assert!(!rspace.is_memseq_empty());
+
+        let mut tx = rspace.begin_transaction();
+        let _ = tx.put_once_non_durable_sequential(commit).unwrap();
+        let comms = tx.commit().unwrap();
+
+        assert_eq!(comms.len(), 1);
+        assert!(rspace.is_memseq_empty());
+    }
+
+    #[test]
+    fn transaction_test_durable_rollback() {
+        let setup = Setup::new();
+        let rspace = setup.rspace;
+
+        let retrieve = Setup::create_retrieve(
+            String::from("transaction-durable-rollback"),
+            setup.dan.clone(),
+            Setup::get_state_field(setup.dan.clone()),
+        );
+        let commit = Setup::create_commit(
+            vec![String::from("transaction-durable-rollback")],
+            vec![setup.state_match_case],
+            String::from("I am the continuation, for now..."),
+        );
+
+        let res = rspace.transaction(|tx| {
+            let pres = tx.get_once_durable_sequential(retrieve.clone())?;
+            assert!(pres.is_none());
+            Err("deploy failed".into())
+        });
+        assert!(res.is_err());
+
+        // nothing was stored, so the consume waits and the next produce fires it
+        let cres = rspace.put_once_durable_sequential(commit).unwrap();
+        let pres = rspace.get_once_durable_sequential(retrieve).unwrap();
+
+        assert!(cres.is_none());
+        assert!(pres.is_some());
+    }
+
+    #[test]
+    fn transaction_test_one_store_per_transaction() {
+        let setup = Setup::new();
+        let rspace = setup.rspace;
+
+        let retrieve1 = Setup::create_retrieve(
+            String::from("transaction-durable-commit"),
+            setup.erin.clone(),
+            Setup::get_state_field(setup.erin.clone()),
+        );
+        let retrieve2 = Setup::create_retrieve(
+            String::from("colleagues"),
+            setup.dan.clone(),
+            Setup::get_state_field(setup.dan),
+        );
+        let commit1 = Setup::create_commit(
+            vec![String::from("transaction-durable-commit")],
+            vec![setup.state_match_case.clone()],
+            String::from("I am the continuation, for now..."),
+        );
+        let commit2 = Setup::create_commit(
+            vec![String::from("colleagues")],
+            vec![setup.state_match_case],
+            String::from("I am the continuation, for now..."),
+        );
+
+        let res = rspace.transaction(|tx| {
+            tx.put_once_durable_concurrent(commit1.clone())?;
+            tx.get_once_non_durable_concurrent(retrieve2.clone())?;
+            Ok(())
+        });
+
+        assert!(res.is_err());
+        assert!(rspace.is_diskconc_empty());
+        assert!(rspace.is_memconc_empty());
+
+        let comms = rspace
+            .transaction(|tx| {
+                tx.put_once_durable_concurrent(commit1)?;
+                tx.get_once_durable_concurrent(retrieve1)?;
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(comms.len(), 1);
+        assert_eq!(comms[0].results[0].data, Some(setup.erin));
+
+        let comms = rspace
+            .transaction(|tx| {
+                tx.get_once_non_durable_concurrent(retrieve2)?;
+                tx.put_once_non_durable_concurrent(commit2)?;
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(comms.len(), 1);
+        assert!(rspace.is_diskconc_empty());
+        assert!(rspace.is_memconc_empty());
+    }
+
+    #[test]
+    fn transaction_test_failed_verb_blocks_commit() {
+        let setup = Setup::new();
+        let rspace = setup.rspace;
+
+        let retrieve = Setup::create_retrieve(
+            String::from("transaction-failed"),
+            setup.alice.clone(),
+            Setup::get_city_field(setup.alice),
+        );
+        let uneven = Setup::create_commit(
+            vec![String::from("transaction-failed")],
+            vec![],
+            String::from("I am the continuation, for now..."),
+        );
+
+        let mut tx = rspace.begin_transaction();
+        assert!(tx
+            .get_once_non_durable_sequential(retrieve)
+            .unwrap()
+            .is_none());
+        assert!(tx.put_once_non_durable_sequential(uneven).is_err());
+        assert!(tx.commit().is_err());
+        assert!(rspace.is_memseq_empty());
+    }
+
+    #[test]
+    fn transaction_test_event_log() {
+        let setup = Setup::new();
+        let rspace = setup.rspace;
+        rspace.start_event_log(Store::MemSeq);
+
+        let retrieve = Setup::create_retrieve(
+            String::from("friends"),
+            setup.alice.clone(),
+            Setup::get_city_field(setup.alice),
+        );
+        let commit = Setup::create_commit(
+            vec![String::from("friends")],
+            vec![setup.city_match_case],
+            String::from("I am the continuation, for now..."),
+        );
+
+        let res = rspace.transaction(|tx| {
+            tx.put_once_non_durable_sequential(commit.clone())?;
+            Err("deploy failed".into())
+        });
+        assert!(res.is_err());
+        assert!(rspace.take_event_log(Store::MemSeq).is_empty());
+
+        rspace
+            .transaction(|tx| {
+                tx.put_once_non_durable_sequential(commit)?;
+                tx.get_once_non_durable_sequential(retrieve)?;
+                Ok(())
+            })
+            .unwrap();
+
+        let events = rspace.take_event_log(Store::MemSeq);
+        assert_eq!(events.len(), 2);
+        assert!(matches!(events[0], Event::Consume { .. }));
+        assert!(matches!(events[1], Event::Comm { .. }));
+        assert!(rspace.take_event_log(Store::MemSeq).is_empty());
+    }
+}
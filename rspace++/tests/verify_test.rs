@@ -0,0 +1,359 @@
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+    use rspace_plus_plus::keys::{self, Key, Kind};
+    use rspace_plus_plus::keyvaluestore::{KeyValueRead, KeyValueStore, Storage};
+    use rspace_plus_plus::lmdbstore::LmdbStore;
+    use rspace_plus_plus::memorystore::MemStore;
+    use rspace_plus_plus::rtypes::rtypes::{
+        Commit, ProblemKind, Retrieve, RetrieveContinuation, Store, VerifyReport,
+    };
+    use rspace_plus_plus::setup::Setup;
+    use rspace_plus_plus::tracked::TrackedWriteTxn;
+    use rspace_plus_plus::tuplespace::TupleSpace;
+    use rspace_plus_plus::{space_free_bytes, space_new, space_verify};
+    use std::path::Path;
+
+    fn mem_space() -> TupleSpace<MemStore, Retrieve, Commit> {
+        TupleSpace::new(MemStore::new())
+    }
+
+    // A datum on verify-friends and a join on verify-colleagues and verify-family
+    fn fill<S: Storage>(space: &TupleSpace<S, Retrieve, Commit>) {
+        let setup = Setup::new();
+        space
+            .produce(
+                Setup::create_retrieve(
+                    String::from("verify-friends"),
+                    setup.alice.clone(),
+                    Setup::get_city_field(setup.alice),
+                ),
+                false,
+            )
+            .unwrap();
+        space
+            .consume(
+                Setup::create_commit(
+                    vec![
+                        String::from("verify-colleagues"),
+                        String::from("verify-family"),
+                    ],
+                    vec![String::from("Idaho"), String::from("Nevada")],
+                    String::from("I am the continuation, for now..."),
+                ),
+                true,
+            )
+            .unwrap();
+    }
+
+    fn keys<S: Storage>(space: &TupleSpace<S, Retrieve, Commit>, prefix: &[u8]) -> Vec<Vec<u8>> {
+        let txn = space.write_txn().unwrap();
+        let mut keys = vec![];
+        txn.iterate_prefix(prefix, &mut |key, _| {
+            keys.push(key.to_vec());
+            Ok(true)
+        })
+        .unwrap();
+        keys
+    }
+
+    // Key and id of the one entry of a kind stored at channel
+    fn only_entry(txn: &impl KeyValueRead, channel: &str, kind: Kind) -> (Vec<u8>, u64) {
+        let mut found = vec![];
+        txn.iterate_prefix(
+            &keys::entry_prefix(kind, &keys::channel_hash(channel)),
+            &mut |key, _| {
+                found.push(key.to_vec());
+                Ok(true)
+            },
+        )
+        .unwrap();
+        assert_eq!(found.len(), 1);
+        match keys::parse(&found[0]) {
+            Some(Key::Entry(_, _, id)) => (found.remove(0), id),
+            _ => panic!("not an entry key"),
+        }
+    }
+
+    fn kinds(report: &VerifyReport) -> Vec<ProblemKind> {
+        report
+            .problems
+            .iter()
+            .map(|problem| problem.kind())
+            .collect()
+    }
+
+    // Breaks the store with corrupt, then checks the report, the repair and that a rerun is clean
+    fn check(
+        corrupt: impl FnOnce(&mut TrackedWriteTxn<'_, MemStore>),
+        expected: Vec<ProblemKind>,
+    ) -> TupleSpace<MemStore, Retrieve, Commit> {
+        let space = mem_space();
+        fill(&space);
+        let mut txn = space.write_txn().unwrap();
+        corrupt(&mut txn);
+        txn.commit().unwrap();
+
+        let report = space.verify(false).unwrap();
+        assert_eq!(kinds(&report), expected);
+        assert!(report.problems.iter().all(|problem| !problem.repaired));
+        // reporting alone changes nothing
+        assert_eq!(space.verify(false).unwrap(), report);
+
+        let repaired = space.verify(true).unwrap();
+        assert_eq!(kinds(&repaired), expected);
+        assert!(repaired.problems.iter().all(|problem| problem.repaired));
+        assert_eq!(kinds(&space.verify(false).unwrap()), vec![]);
+        space
+    }
+
+    #[test]
+    fn verify_test_clean_store() {
+        let space = mem_space();
+        fill(&space);
+
+        let report = space.verify(true).unwrap();
+        // three entries, each with its index, on three named channels
+        assert_eq!(report.keys, 9);
+        assert_eq!(report.problems, vec![]);
+    }
+
+    #[test]
+    fn verify_test_bad_keys_and_values() {
+        let original = mem_space();
+        fill(&original);
+
+        let friends = keys::channel_hash("verify-friends");
+        let space = check(
+            |txn| {
+                let mut short_id = keys::entry_prefix(Kind::Data, &friends);
+                short_id.push(b'x');
+                txn.put(&short_id, b"?").unwrap();
+                txn.put(b"joins-verify-friends", b"?").unwrap();
+                txn.put(&keys::entry_key(Kind::Data, &friends, 7), &[0xff, 0xff])
+                    .unwrap();
+            },
+            vec![
+                ProblemKind::UndecodableValue,
+                ProblemKind::MalformedKey,
+                ProblemKind::MalformedKey,
+            ],
+        );
+        assert_eq!(space.root().unwrap(), original.root().unwrap());
+    }
+
+    #[test]
+    fn verify_test_hash_mismatch_is_moved() {
+        let original = mem_space();
+        fill(&original);
+
+        // the datum under a hash that is not its own, with its index pointing there
+        let friends = keys::channel_hash("verify-friends");
+        let space = check(
+            |txn| {
+                let (datum, id) = only_entry(txn, "verify-friends", Kind::Data);
+                let value = txn.get(&datum).unwrap().unwrap();
+                txn.delete(&datum).unwrap();
+                txn.delete(&keys::index_key(Kind::Data, &friends, "Crystal Lake", id))
+                    .unwrap();
+                let moved = keys::entry_key(Kind::Data, &friends, 1);
+                txn.put(&moved, &value).unwrap();
+                txn.put(
+                    &keys::index_key(Kind::Data, &friends, "Crystal Lake", 1),
+                    &moved,
+                )
+                .unwrap();
+            },
+            vec![ProblemKind::HashMismatch, ProblemKind::DanglingIndex],
+        );
+        assert_eq!(space.root().unwrap(), original.root().unwrap());
+    }
+
+    #[test]
+    fn verify_test_indexes() {
+        let original = mem_space();
+        fill(&original);
+
+        // the datum has lost its index, and an index points nowhere
+        let friends = keys::channel_hash("verify-friends");
+        let space = check(
+            |txn| {
+                let (_, id) = only_entry(txn, "verify-friends", Kind::Data);
+                txn.delete(&keys::index_key(Kind::Data, &friends, "Crystal Lake", id))
+                    .unwrap();
+                txn.put(
+                    &keys::index_key(Kind::Data, &friends, "Crystal Lake", 9),
+                    b"nowhere",
+                )
+                .unwrap();
+            },
+            vec![ProblemKind::DanglingIndex, ProblemKind::MissingIndex],
+        );
+        assert_eq!(space.root().unwrap(), original.root().unwrap());
+        assert_eq!(space.to_map().unwrap(), original.to_map().unwrap());
+    }
+
+    #[test]
+    fn verify_test_incomplete_join_is_removed() {
+        // one side of the join is gone, with its index
+        let space = check(
+            |txn| {
+                let (family, id) = only_entry(txn, "verify-family", Kind::Continuation);
+                txn.delete(&family).unwrap();
+                txn.delete(&keys::index_key(
+                    Kind::Continuation,
+                    &keys::channel_hash("verify-family"),
+                    "Nevada",
+                    id,
+                ))
+                .unwrap();
+            },
+            vec![
+                ProblemKind::MissingJoinEntry,
+                ProblemKind::DanglingChannelName,
+            ],
+        );
+        // the channel the join emptied loses its name with it
+        assert_eq!(space.channels().unwrap(), vec!["verify-friends"]);
+        assert_eq!(keys(&space, &[keys::INDEX]).len(), 1);
+    }
+
+    #[test]
+    fn verify_test_fired_join_leaves_no_copies() {
+        let space = mem_space();
+        let setup = Setup::new();
+        space
+            .consume(
+                Setup::create_commit(
+                    vec![String::from("verify-a"), String::from("verify-b")],
+                    vec![setup.city_match_case.clone(), setup.city_match_case.clone()],
+                    String::from("join"),
+                ),
+                false,
+            )
+            .unwrap();
+        space
+            .produce(
+                Setup::create_retrieve(
+                    String::from("verify-a"),
+                    setup.alice.clone(),
+                    Setup::get_city_field(setup.alice),
+                ),
+                false,
+            )
+            .unwrap()
+            .unwrap();
+        space
+            .consume(
+                Setup::create_commit(
+                    vec![String::from("verify-b"), String::from("verify-c")],
+                    vec![String::from("Idaho"), String::from("Nevada")],
+                    String::from("waiting"),
+                ),
+                false,
+            )
+            .unwrap();
+
+        assert!(space.verify(false).unwrap().problems.is_empty());
+        assert!(space.verify(true).unwrap().problems.is_empty());
+        assert_eq!(space.channels().unwrap(), vec!["verify-b", "verify-c"]);
+        // the copy on verify-b went when the join fired on verify-a
+        let join = vec![String::from("verify-a"), String::from("verify-b")];
+        assert!(space.get_waiting_continuations(&join).unwrap().is_empty());
+        assert_eq!(keys(&space, &[keys::INDEX]).len(), 2);
+    }
+
+    #[test]
+    fn verify_test_stray_continuation() {
+        let strangers = keys::channel_hash("verify-strangers");
+        let space = check(
+            |txn| {
+                let (colleagues, id) = only_entry(txn, "verify-colleagues", Kind::Continuation);
+                let value = txn.get(&colleagues).unwrap().unwrap();
+                txn.put(&keys::entry_key(Kind::Continuation, &strangers, id), &value)
+                    .unwrap();
+            },
+            vec![ProblemKind::StrayContinuation],
+        );
+        assert!(keys(&space, &keys::entry_prefix(Kind::Continuation, &strangers)).is_empty());
+    }
+
+    #[test]
+    fn verify_test_channel_names() {
+        let original = mem_space();
+        fill(&original);
+
+        // a join names the channel it lost the name of
+        let space = check(
+            |txn| {
+                txn.delete(&keys::channel_key(&keys::channel_hash("verify-colleagues")))
+                    .unwrap();
+            },
+            vec![ProblemKind::MissingChannelName],
+        );
+        assert_eq!(space.root().unwrap(), original.root().unwrap());
+
+        // a name with nothing stored at its channel
+        let space = check(
+            |txn| {
+                txn.put(
+                    &keys::channel_key(&keys::channel_hash("verify-strangers")),
+                    b"verify-strangers",
+                )
+                .unwrap();
+            },
+            vec![ProblemKind::DanglingChannelName],
+        );
+        assert_eq!(space.root().unwrap(), original.root().unwrap());
+
+        // nothing can name the datum's channel, so the datum goes
+        let space = check(
+            |txn| {
+                txn.delete(&keys::channel_key(&keys::channel_hash("verify-friends")))
+                    .unwrap();
+            },
+            vec![ProblemKind::MissingChannelName],
+        );
+        assert_eq!(
+            space.channels().unwrap(),
+            vec!["verify-colleagues", "verify-family"]
+        );
+        assert_eq!(keys(&space, &[keys::DATA]), Vec::<Vec<u8>>::new());
+    }
+
+    #[test]
+    fn verify_test_disk_and_ffi() {
+        let dir = Path::new("target")
+            .join("VerifyDB")
+            .join(std::process::id().to_string());
+        let _ = std::fs::remove_dir_all(&dir);
+        let space = TupleSpace::<_, Retrieve, Commit>::new(LmdbStore::open(&dir).unwrap());
+        fill(&space);
+        let mut txn = space.write_txn().unwrap();
+        let datum = RetrieveContinuation::default();
+        txn.put(
+            &keys::entry_key(Kind::Data, &keys::channel_hash("verify-friends"), 3),
+            &datum.encode_to_vec(),
+        )
+        .unwrap();
+        txn.commit().unwrap();
+
+        assert_eq!(
+            kinds(&space.verify(true).unwrap()),
+            vec![ProblemKind::HashMismatch]
+        );
+        assert_eq!(kinds(&space.verify(false).unwrap()), vec![]);
+        drop(space);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let rspace = space_new();
+        let mut len = 0;
+        let ptr = unsafe { space_verify(rspace, Store::MemSeq as i32, false, &mut len) };
+        assert!(!ptr.is_null());
+        let report = VerifyReport::decode(unsafe { std::slice::from_raw_parts(ptr, len) }).unwrap();
+        unsafe { space_free_bytes(ptr as *mut u8, len) };
+        assert_eq!(report, VerifyReport::default());
+
+        assert!(unsafe { space_verify(rspace, 9, false, &mut len) }.is_null());
+    }
+}
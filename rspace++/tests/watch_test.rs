@@ -0,0 +1,228 @@
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+    use rspace_plus_plus::memorystore::MemStore;
+    use rspace_plus_plus::rspace::RSpace;
+    use rspace_plus_plus::rtypes::rtypes::{self, channel_change, Commit, Retrieve};
+    use rspace_plus_plus::setup::Setup;
+    use rspace_plus_plus::tuplespace::TupleSpace;
+    use rspace_plus_plus::watch::ChannelChange;
+    use rspace_plus_plus::{
+        space_get_once_non_durable_sequential, space_new, space_unwatch, space_watch,
+    };
+    use std::ffi::CString;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    const WAIT: Duration = Duration::from_secs(5);
+
+    fn space() -> TupleSpace<MemStore, Retrieve, Commit> {
+        TupleSpace::new(MemStore::new())
+    }
+
+    fn retrieve(channel: &str) -> Retrieve {
+        let setup = Setup::new();
+        Setup::create_retrieve(
+            String::from(channel),
+            setup.alice.clone(),
+            Setup::get_city_field(setup.alice),
+        )
+    }
+
+    fn commit(channels: &[&str]) -> Commit {
+        Setup::create_commit(
+            channels.iter().map(|channel| channel.to_string()).collect(),
+            channels
+                .iter()
+                .map(|_| Setup::new().city_match_case)
+                .collect(),
+            String::from("I am the continuation, for now..."),
+        )
+    }
+
+    #[test]
+    fn watch_test_datum_added_and_removed() {
+        let space = space();
+        let watch = space.watch("watch-friends");
+
+        space.produce(retrieve("watch-friends"), false).unwrap();
+        space.produce(retrieve("watch-colleagues"), false).unwrap();
+        let added = match watch.try_next() {
+            Some(ChannelChange::DatumAdded(datum)) => datum,
+            other => panic!("expected a datum, got {:?}", other),
+        };
+        assert!(!added.persistent);
+        assert_eq!(watch.try_next(), None);
+
+        space.consume(commit(&["watch-friends"]), false).unwrap();
+        assert_eq!(
+            watch.try_next(),
+            Some(ChannelChange::CommFired {
+                data: vec![added.clone()],
+                continuations: vec![],
+            })
+        );
+        assert_eq!(watch.try_next(), Some(ChannelChange::DatumRemoved(added)));
+        assert_eq!(watch.try_next(), None);
+    }
+
+    #[test]
+    fn watch_test_continuation_added_and_fired() {
+        let space = space();
+        let watch = space.watch("watch-family");
+
+        space.consume(commit(&["watch-family"]), true).unwrap();
+        let stored = match watch.try_next() {
+            Some(ChannelChange::ContinuationAdded(k)) => k,
+            other => panic!("expected a continuation, got {:?}", other),
+        };
+        assert!(stored.persistent);
+
+        space.produce(retrieve("watch-family"), false).unwrap();
+        assert_eq!(
+            watch.try_next(),
+            Some(ChannelChange::CommFired {
+                data: vec![],
+                continuations: vec![stored],
+            })
+        );
+        // the produced datum was never stored, so nothing is removed
+        assert_eq!(watch.try_next(), None);
+    }
+
+    #[test]
+    fn watch_test_persistent_datum_is_not_removed() {
+        let space = space();
+        let watch = space.watch("watch-persistent");
+
+        space.produce(retrieve("watch-persistent"), true).unwrap();
+        space.consume(commit(&["watch-persistent"]), false).unwrap();
+        let changes: Vec<ChannelChange> = std::iter::from_fn(|| watch.try_next()).collect();
+        assert_eq!(changes.len(), 2);
+        assert!(matches!(changes[0], ChannelChange::DatumAdded(_)));
+        assert!(matches!(changes[1], ChannelChange::CommFired { .. }));
+    }
+
+    #[test]
+    fn watch_test_join_reaches_each_channel() {
+        let space = space();
+        let colleagues = space.watch("watch-join-colleagues");
+        let family = space.watch("watch-join-family");
+
+        space
+            .consume(
+                commit(&["watch-join-colleagues", "watch-join-family"]),
+                false,
+            )
+            .unwrap();
+        for watch in [&colleagues, &family] {
+            match watch.try_next() {
+                Some(ChannelChange::ContinuationAdded(k)) => assert_eq!(k.channels.len(), 2),
+                other => panic!("expected a continuation, got {:?}", other),
+            }
+            assert_eq!(watch.try_next(), None);
+        }
+    }
+
+    #[test]
+    fn watch_test_unwatch_ends_iteration() {
+        let space = space();
+        let first = space.watch("watch-unwatch");
+        let second = space.watch("watch-unwatch");
+        assert_ne!(first.id(), second.id());
+
+        space.produce(retrieve("watch-unwatch"), false).unwrap();
+        assert!(space.unwatch(first.id()));
+        assert!(!space.unwatch(first.id()));
+        space.produce(retrieve("watch-unwatch"), true).unwrap();
+
+        // what was sent before unwatch is still read, then iteration ends
+        assert_eq!(first.collect::<Vec<_>>().len(), 1);
+        assert_eq!(std::iter::from_fn(|| second.try_next()).count(), 2);
+
+        // dropping a watch stops the space sending to it
+        drop(second);
+        space.produce(retrieve("watch-unwatch"), false).unwrap();
+        let third = space.watch("watch-unwatch");
+        space.produce(retrieve("watch-unwatch"), false).unwrap();
+        assert!(third.next_timeout(WAIT).is_some());
+    }
+
+    #[test]
+    fn watch_test_rspace_watches_every_store() {
+        let rspace = RSpace::<Retrieve, Commit, MemStore>::create_with_storage(
+            MemStore::new(),
+            MemStore::new(),
+        )
+        .unwrap();
+        let watch = rspace.watch("watch-stores");
+
+        let watching = std::thread::spawn(move || watch.take(3).collect::<Vec<_>>());
+        rspace
+            .get_once_non_durable_sequential(retrieve("watch-stores"))
+            .unwrap();
+        rspace
+            .get_once_durable_concurrent(retrieve("watch-stores"))
+            .unwrap();
+        rspace
+            .put_once_non_durable_concurrent(commit(&["watch-stores"]))
+            .unwrap();
+
+        let changes = watching.join().unwrap();
+        assert!(matches!(changes[0], ChannelChange::DatumAdded(_)));
+        assert!(matches!(changes[1], ChannelChange::DatumAdded(_)));
+        assert!(matches!(changes[2], ChannelChange::ContinuationAdded(_)));
+
+        let other = rspace.watch("watch-stores");
+        assert!(rspace.unwatch(other.id()));
+        assert!(!rspace.unwatch(other.id()));
+        assert_eq!(other.next_timeout(WAIT), None);
+    }
+
+    static CHANGES: Mutex<Vec<rtypes::ChannelChange>> = Mutex::new(vec![]);
+
+    extern "C" fn collect(change: *const u8, len: usize) {
+        let bytes = unsafe { std::slice::from_raw_parts(change, len) };
+        CHANGES
+            .lock()
+            .unwrap()
+            .push(rtypes::ChannelChange::decode(bytes).unwrap());
+    }
+
+    #[test]
+    fn watch_test_ffi_callback() {
+        let rspace = space_new();
+        let channel = CString::new("watch-ffi-friends").unwrap();
+
+        let id = unsafe { space_watch(rspace, channel.as_ptr(), collect) };
+        assert_ne!(id, 0);
+        let buf = retrieve("watch-ffi-friends").encode_to_vec();
+        let mut len = 0;
+        let mut error = false;
+        let pres = unsafe {
+            space_get_once_non_durable_sequential(
+                rspace,
+                buf.as_ptr(),
+                buf.len(),
+                &mut len,
+                &mut error,
+            )
+        };
+        assert!(pres.is_null());
+
+        let started = std::time::Instant::now();
+        while CHANGES.lock().unwrap().is_empty() && started.elapsed() < WAIT {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let changes = CHANGES.lock().unwrap().clone();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].channel, "watch-ffi-friends");
+        assert!(matches!(
+            changes[0].change,
+            Some(channel_change::Change::DatumAdded(_))
+        ));
+
+        assert!(unsafe { space_unwatch(rspace, id) });
+        assert!(!unsafe { space_unwatch(rspace, id) });
+    }
+}